@@ -39,56 +39,108 @@ mod circuits {
 
     /// Input for fill amount calculation
     pub struct FillInput {
-        /// Encrypted buy order remaining amount
+        /// Encrypted buy order total amount
         buy_amount: u64,
-        /// Encrypted sell order remaining amount
+        /// Encrypted buy order cumulative filled amount
+        buy_filled: u64,
+        /// Encrypted sell order total amount
         sell_amount: u64,
+        /// Encrypted sell order cumulative filled amount
+        sell_filled: u64,
         /// Encrypted buy price
         buy_price: u64,
         /// Encrypted sell price
         sell_price: u64,
     }
 
-    /// Output from fill calculation
+    /// Encrypted output from fill calculation
     pub struct FillOutput {
-        /// Fill amount (min of buy_amount, sell_amount)
+        /// Fill amount for this match (min of both remaining sizes)
         fill_amount: u64,
-        /// Whether buy order is fully filled
-        buy_fully_filled: bool,
-        /// Whether sell order is fully filled
-        sell_fully_filled: bool,
+        /// Updated buy order cumulative filled amount (buy_filled + fill_amount)
+        buy_filled: u64,
+        /// Updated sell order cumulative filled amount (sell_filled + fill_amount)
+        sell_filled: u64,
     }
 
     /// Calculate fill amount for matching orders
     ///
-    /// Returns the minimum of buy/sell amounts and flags indicating
-    /// which orders are fully filled.
+    /// Remaining size is `amount - filled` on each side, so an order can be
+    /// matched several times until it is fully filled. The fill is added to
+    /// each order's encrypted filled amount in the same circuit (equivalent
+    /// to a follow-up add_encrypted, without a second MPC round trip).
+    ///
+    /// `buy_has_fills` / `sell_has_fills` are plaintext flags set by the DEX:
+    /// a freshly placed order stores an all-zero filled field, which is not a
+    /// valid ciphertext, so it is treated as 0 instead of being decrypted.
+    ///
+    /// `buy_fill_or_kill` / `sell_fill_or_kill` (plaintext time-in-force flags)
+    /// reject the match unless that order's whole remaining size is filled.
+    ///
+    /// Returns (encrypted fill/filled values, matched, buy_fully_filled,
+    /// sell_fully_filled). The flags are revealed because order status
+    /// (Active/Inactive) is public anyway.
     #[instruction]
-    pub fn calculate_fill(input: Enc<Shared, FillInput>) -> Enc<Shared, FillOutput> {
+    pub fn calculate_fill(
+        input: Enc<Shared, FillInput>,
+        buy_has_fills: bool,
+        sell_has_fills: bool,
+        buy_fill_or_kill: bool,
+        sell_fill_or_kill: bool,
+    ) -> (Enc<Shared, FillOutput>, bool, bool, bool) {
         let fill = input.to_arcis();
 
-        // Check if prices match
-        let prices_match = fill.buy_price >= fill.sell_price;
+        let buy_filled = if buy_has_fills { fill.buy_filled } else { 0u64 };
+        let sell_filled = if sell_has_fills { fill.sell_filled } else { 0u64 };
 
-        // Calculate fill amount as min of both sides
-        let fill_amount = if fill.buy_amount < fill.sell_amount {
-            fill.buy_amount
+        // Remaining size on each side (saturating - filled never exceeds amount)
+        let buy_remaining = if fill.buy_amount > buy_filled {
+            fill.buy_amount - buy_filled
+        } else {
+            0u64
+        };
+        let sell_remaining = if fill.sell_amount > sell_filled {
+            fill.sell_amount - sell_filled
         } else {
-            fill.sell_amount
+            0u64
         };
 
-        // If prices don't match, fill amount is 0
-        let actual_fill = if prices_match { fill_amount } else { 0u64 };
+        // Fill-or-kill orders must be filled completely by this match
+        let buy_fok_ok = !buy_fill_or_kill || buy_remaining <= sell_remaining;
+        let sell_fok_ok = !sell_fill_or_kill || sell_remaining <= buy_remaining;
 
-        // Determine if orders are fully filled
-        let buy_fully_filled = prices_match && fill.buy_amount <= fill.sell_amount;
-        let sell_fully_filled = prices_match && fill.sell_amount <= fill.buy_amount;
+        // Check if prices match and both sides still have size
+        let prices_match = fill.buy_price >= fill.sell_price;
+        let matched = prices_match
+            && buy_remaining > 0
+            && sell_remaining > 0
+            && buy_fok_ok
+            && sell_fok_ok;
+
+        // Calculate fill amount as min of both remaining sizes
+        let fill_amount = if buy_remaining < sell_remaining {
+            buy_remaining
+        } else {
+            sell_remaining
+        };
 
-        input.owner.from_arcis(FillOutput {
-            fill_amount: actual_fill,
-            buy_fully_filled,
-            sell_fully_filled,
-        })
+        // If the orders don't match, fill amount is 0
+        let actual_fill = if matched { fill_amount } else { 0u64 };
+
+        // Determine if orders are fully filled after this match
+        let buy_fully_filled = matched && buy_remaining <= sell_remaining;
+        let sell_fully_filled = matched && sell_remaining <= buy_remaining;
+
+        (
+            input.owner.from_arcis(FillOutput {
+                fill_amount: actual_fill,
+                buy_filled: buy_filled + actual_fill,
+                sell_filled: sell_filled + actual_fill,
+            }),
+            matched.reveal(),
+            buy_fully_filled.reveal(),
+            sell_fully_filled.reveal(),
+        )
     }
 
     // =============================================================
@@ -137,99 +189,215 @@ mod circuits {
     pub struct BatchFillInput {
         /// Encrypted buy amounts (padded to 5)
         buy_amounts: [u64; 5],
+        /// Encrypted cumulative buy filled amounts (padded to 5)
+        buy_filled: [u64; 5],
         /// Encrypted sell amounts (padded to 5)
         sell_amounts: [u64; 5],
+        /// Encrypted cumulative sell filled amounts (padded to 5)
+        sell_filled: [u64; 5],
         /// Encrypted buy prices (padded to 5)
         buy_prices: [u64; 5],
         /// Encrypted sell prices (padded to 5)
         sell_prices: [u64; 5],
-        /// How many valid pairs in this batch
-        count: u8,
     }
 
-    /// Output from batch fill calculation
+    /// Encrypted output from batch fill calculation
     pub struct BatchFillOutput {
-        /// Fill amounts for each pair (0 if prices don't match or invalid)
+        /// Fill amounts for each pair (0 if the pair didn't match)
         fills: [u64; 5],
-        /// Which buy orders are fully filled
-        buy_filled: [bool; 5],
-        /// Which sell orders are fully filled
-        sell_filled: [bool; 5],
+        /// Updated cumulative buy filled amounts
+        buy_filled: [u64; 5],
+        /// Updated cumulative sell filled amounts
+        sell_filled: [u64; 5],
+    }
+
+    /// Revealed per-pair match flags
+    ///
+    /// Bit 0 = matched, bit 1 = buy fully filled, bit 2 = sell fully filled.
+    pub struct BatchFillFlags {
+        s0: u8,
+        s1: u8,
+        s2: u8,
+        s3: u8,
+        s4: u8,
     }
 
     /// Calculate fill amounts for multiple order pairs in one MPC call
     ///
+    /// Each slot applies the same rules as calculate_fill: remaining size is
+    /// `amount - filled`, fill-or-kill orders only match if fully filled, and
+    /// the fill is added to both cumulative filled amounts. `*_has_fills` and
+    /// `*_fill_or_kill` are plaintext per-slot flags set by the DEX.
+    ///
     /// More efficient than 5 separate calculate_fill calls.
     /// Explicitly unrolled for MPC compatibility (no closures or returns).
     #[instruction]
-    pub fn batch_calculate_fill(input: Enc<Shared, BatchFillInput>) -> Enc<Shared, BatchFillOutput> {
+    pub fn batch_calculate_fill(
+        input: Enc<Shared, BatchFillInput>,
+        buy_has_fills: [bool; 5],
+        sell_has_fills: [bool; 5],
+        buy_fill_or_kill: [bool; 5],
+        sell_fill_or_kill: [bool; 5],
+        count: u8,
+    ) -> (Enc<Shared, BatchFillOutput>, BatchFillFlags) {
         let batch = input.to_arcis();
 
         // Slot 0
-        let valid0 = batch.count > 0;
-        let match0 = valid0 && batch.buy_prices[0] >= batch.sell_prices[0];
-        let min0 = if batch.buy_amounts[0] < batch.sell_amounts[0] {
-            batch.buy_amounts[0]
+        let bfd0 = if buy_has_fills[0] { batch.buy_filled[0] } else { 0u64 };
+        let sfd0 = if sell_has_fills[0] { batch.sell_filled[0] } else { 0u64 };
+        let brem0 = if batch.buy_amounts[0] > bfd0 {
+            batch.buy_amounts[0] - bfd0
+        } else {
+            0u64
+        };
+        let srem0 = if batch.sell_amounts[0] > sfd0 {
+            batch.sell_amounts[0] - sfd0
         } else {
-            batch.sell_amounts[0]
+            0u64
         };
+        let fok0 = (!buy_fill_or_kill[0] || brem0 <= srem0)
+            && (!sell_fill_or_kill[0] || srem0 <= brem0);
+        let match0 = count > 0
+            && batch.buy_prices[0] >= batch.sell_prices[0]
+            && brem0 > 0
+            && srem0 > 0
+            && fok0;
+        let min0 = if brem0 < srem0 { brem0 } else { srem0 };
         let f0 = if match0 { min0 } else { 0u64 };
-        let bf0 = match0 && batch.buy_amounts[0] <= batch.sell_amounts[0];
-        let sf0 = match0 && batch.sell_amounts[0] <= batch.buy_amounts[0];
+        let bff0 = match0 && brem0 <= srem0;
+        let sff0 = match0 && srem0 <= brem0;
 
         // Slot 1
-        let valid1 = batch.count > 1;
-        let match1 = valid1 && batch.buy_prices[1] >= batch.sell_prices[1];
-        let min1 = if batch.buy_amounts[1] < batch.sell_amounts[1] {
-            batch.buy_amounts[1]
+        let bfd1 = if buy_has_fills[1] { batch.buy_filled[1] } else { 0u64 };
+        let sfd1 = if sell_has_fills[1] { batch.sell_filled[1] } else { 0u64 };
+        let brem1 = if batch.buy_amounts[1] > bfd1 {
+            batch.buy_amounts[1] - bfd1
+        } else {
+            0u64
+        };
+        let srem1 = if batch.sell_amounts[1] > sfd1 {
+            batch.sell_amounts[1] - sfd1
         } else {
-            batch.sell_amounts[1]
+            0u64
         };
+        let fok1 = (!buy_fill_or_kill[1] || brem1 <= srem1)
+            && (!sell_fill_or_kill[1] || srem1 <= brem1);
+        let match1 = count > 1
+            && batch.buy_prices[1] >= batch.sell_prices[1]
+            && brem1 > 0
+            && srem1 > 0
+            && fok1;
+        let min1 = if brem1 < srem1 { brem1 } else { srem1 };
         let f1 = if match1 { min1 } else { 0u64 };
-        let bf1 = match1 && batch.buy_amounts[1] <= batch.sell_amounts[1];
-        let sf1 = match1 && batch.sell_amounts[1] <= batch.buy_amounts[1];
+        let bff1 = match1 && brem1 <= srem1;
+        let sff1 = match1 && srem1 <= brem1;
 
         // Slot 2
-        let valid2 = batch.count > 2;
-        let match2 = valid2 && batch.buy_prices[2] >= batch.sell_prices[2];
-        let min2 = if batch.buy_amounts[2] < batch.sell_amounts[2] {
-            batch.buy_amounts[2]
+        let bfd2 = if buy_has_fills[2] { batch.buy_filled[2] } else { 0u64 };
+        let sfd2 = if sell_has_fills[2] { batch.sell_filled[2] } else { 0u64 };
+        let brem2 = if batch.buy_amounts[2] > bfd2 {
+            batch.buy_amounts[2] - bfd2
+        } else {
+            0u64
+        };
+        let srem2 = if batch.sell_amounts[2] > sfd2 {
+            batch.sell_amounts[2] - sfd2
         } else {
-            batch.sell_amounts[2]
+            0u64
         };
+        let fok2 = (!buy_fill_or_kill[2] || brem2 <= srem2)
+            && (!sell_fill_or_kill[2] || srem2 <= brem2);
+        let match2 = count > 2
+            && batch.buy_prices[2] >= batch.sell_prices[2]
+            && brem2 > 0
+            && srem2 > 0
+            && fok2;
+        let min2 = if brem2 < srem2 { brem2 } else { srem2 };
         let f2 = if match2 { min2 } else { 0u64 };
-        let bf2 = match2 && batch.buy_amounts[2] <= batch.sell_amounts[2];
-        let sf2 = match2 && batch.sell_amounts[2] <= batch.buy_amounts[2];
+        let bff2 = match2 && brem2 <= srem2;
+        let sff2 = match2 && srem2 <= brem2;
 
         // Slot 3
-        let valid3 = batch.count > 3;
-        let match3 = valid3 && batch.buy_prices[3] >= batch.sell_prices[3];
-        let min3 = if batch.buy_amounts[3] < batch.sell_amounts[3] {
-            batch.buy_amounts[3]
+        let bfd3 = if buy_has_fills[3] { batch.buy_filled[3] } else { 0u64 };
+        let sfd3 = if sell_has_fills[3] { batch.sell_filled[3] } else { 0u64 };
+        let brem3 = if batch.buy_amounts[3] > bfd3 {
+            batch.buy_amounts[3] - bfd3
         } else {
-            batch.sell_amounts[3]
+            0u64
+        };
+        let srem3 = if batch.sell_amounts[3] > sfd3 {
+            batch.sell_amounts[3] - sfd3
+        } else {
+            0u64
         };
+        let fok3 = (!buy_fill_or_kill[3] || brem3 <= srem3)
+            && (!sell_fill_or_kill[3] || srem3 <= brem3);
+        let match3 = count > 3
+            && batch.buy_prices[3] >= batch.sell_prices[3]
+            && brem3 > 0
+            && srem3 > 0
+            && fok3;
+        let min3 = if brem3 < srem3 { brem3 } else { srem3 };
         let f3 = if match3 { min3 } else { 0u64 };
-        let bf3 = match3 && batch.buy_amounts[3] <= batch.sell_amounts[3];
-        let sf3 = match3 && batch.sell_amounts[3] <= batch.buy_amounts[3];
+        let bff3 = match3 && brem3 <= srem3;
+        let sff3 = match3 && srem3 <= brem3;
 
         // Slot 4
-        let valid4 = batch.count > 4;
-        let match4 = valid4 && batch.buy_prices[4] >= batch.sell_prices[4];
-        let min4 = if batch.buy_amounts[4] < batch.sell_amounts[4] {
-            batch.buy_amounts[4]
+        let bfd4 = if buy_has_fills[4] { batch.buy_filled[4] } else { 0u64 };
+        let sfd4 = if sell_has_fills[4] { batch.sell_filled[4] } else { 0u64 };
+        let brem4 = if batch.buy_amounts[4] > bfd4 {
+            batch.buy_amounts[4] - bfd4
         } else {
-            batch.sell_amounts[4]
+            0u64
         };
+        let srem4 = if batch.sell_amounts[4] > sfd4 {
+            batch.sell_amounts[4] - sfd4
+        } else {
+            0u64
+        };
+        let fok4 = (!buy_fill_or_kill[4] || brem4 <= srem4)
+            && (!sell_fill_or_kill[4] || srem4 <= brem4);
+        let match4 = count > 4
+            && batch.buy_prices[4] >= batch.sell_prices[4]
+            && brem4 > 0
+            && srem4 > 0
+            && fok4;
+        let min4 = if brem4 < srem4 { brem4 } else { srem4 };
         let f4 = if match4 { min4 } else { 0u64 };
-        let bf4 = match4 && batch.buy_amounts[4] <= batch.sell_amounts[4];
-        let sf4 = match4 && batch.sell_amounts[4] <= batch.buy_amounts[4];
-
-        input.owner.from_arcis(BatchFillOutput {
-            fills: [f0, f1, f2, f3, f4],
-            buy_filled: [bf0, bf1, bf2, bf3, bf4],
-            sell_filled: [sf0, sf1, sf2, sf3, sf4],
-        })
+        let bff4 = match4 && brem4 <= srem4;
+        let sff4 = match4 && srem4 <= brem4;
+
+        // Match flags are revealed because order status is public anyway
+        let s0 = (if match0 { 1u8 } else { 0u8 })
+            + (if bff0 { 2u8 } else { 0u8 })
+            + (if sff0 { 4u8 } else { 0u8 });
+        let s1 = (if match1 { 1u8 } else { 0u8 })
+            + (if bff1 { 2u8 } else { 0u8 })
+            + (if sff1 { 4u8 } else { 0u8 });
+        let s2 = (if match2 { 1u8 } else { 0u8 })
+            + (if bff2 { 2u8 } else { 0u8 })
+            + (if sff2 { 4u8 } else { 0u8 });
+        let s3 = (if match3 { 1u8 } else { 0u8 })
+            + (if bff3 { 2u8 } else { 0u8 })
+            + (if sff3 { 4u8 } else { 0u8 });
+        let s4 = (if match4 { 1u8 } else { 0u8 })
+            + (if bff4 { 2u8 } else { 0u8 })
+            + (if sff4 { 4u8 } else { 0u8 });
+
+        (
+            input.owner.from_arcis(BatchFillOutput {
+                fills: [f0, f1, f2, f3, f4],
+                buy_filled: [bfd0 + f0, bfd1 + f1, bfd2 + f2, bfd3 + f3, bfd4 + f4],
+                sell_filled: [sfd0 + f0, sfd1 + f1, sfd2 + f2, sfd3 + f3, sfd4 + f4],
+            }),
+            BatchFillFlags {
+                s0: s0.reveal(),
+                s1: s1.reveal(),
+                s2: s2.reveal(),
+                s3: s3.reveal(),
+                s4: s4.reveal(),
+            },
+        )
     }
 
     // =============================================================
@@ -323,8 +491,9 @@ mod circuits {
         is_long: [bool; 10],
         /// How many valid positions in this batch
         count: u8,
-        /// Current mark price (same for all, plaintext)
-        mark_price: u64,
+        /// Mark price of each position's market (padded to 10, plaintext)
+        /// Positions in one batch may belong to different markets
+        mark_prices: [u64; 10],
     }
 
     /// Output from batch liquidation check
@@ -353,72 +522,72 @@ mod circuits {
 
         // Check each position (fixed for MPC compatibility - no dynamic loops)
         let should_liq_0 = if batch.is_long[0] {
-            batch.mark_price <= batch.thresholds[0]
+            batch.mark_prices[0] <= batch.thresholds[0]
         } else {
-            batch.mark_price >= batch.thresholds[0]
+            batch.mark_prices[0] >= batch.thresholds[0]
         };
         let r0 = (batch.count > 0 && should_liq_0).reveal();
 
         let should_liq_1 = if batch.is_long[1] {
-            batch.mark_price <= batch.thresholds[1]
+            batch.mark_prices[1] <= batch.thresholds[1]
         } else {
-            batch.mark_price >= batch.thresholds[1]
+            batch.mark_prices[1] >= batch.thresholds[1]
         };
         let r1 = (batch.count > 1 && should_liq_1).reveal();
 
         let should_liq_2 = if batch.is_long[2] {
-            batch.mark_price <= batch.thresholds[2]
+            batch.mark_prices[2] <= batch.thresholds[2]
         } else {
-            batch.mark_price >= batch.thresholds[2]
+            batch.mark_prices[2] >= batch.thresholds[2]
         };
         let r2 = (batch.count > 2 && should_liq_2).reveal();
 
         let should_liq_3 = if batch.is_long[3] {
-            batch.mark_price <= batch.thresholds[3]
+            batch.mark_prices[3] <= batch.thresholds[3]
         } else {
-            batch.mark_price >= batch.thresholds[3]
+            batch.mark_prices[3] >= batch.thresholds[3]
         };
         let r3 = (batch.count > 3 && should_liq_3).reveal();
 
         let should_liq_4 = if batch.is_long[4] {
-            batch.mark_price <= batch.thresholds[4]
+            batch.mark_prices[4] <= batch.thresholds[4]
         } else {
-            batch.mark_price >= batch.thresholds[4]
+            batch.mark_prices[4] >= batch.thresholds[4]
         };
         let r4 = (batch.count > 4 && should_liq_4).reveal();
 
         let should_liq_5 = if batch.is_long[5] {
-            batch.mark_price <= batch.thresholds[5]
+            batch.mark_prices[5] <= batch.thresholds[5]
         } else {
-            batch.mark_price >= batch.thresholds[5]
+            batch.mark_prices[5] >= batch.thresholds[5]
         };
         let r5 = (batch.count > 5 && should_liq_5).reveal();
 
         let should_liq_6 = if batch.is_long[6] {
-            batch.mark_price <= batch.thresholds[6]
+            batch.mark_prices[6] <= batch.thresholds[6]
         } else {
-            batch.mark_price >= batch.thresholds[6]
+            batch.mark_prices[6] >= batch.thresholds[6]
         };
         let r6 = (batch.count > 6 && should_liq_6).reveal();
 
         let should_liq_7 = if batch.is_long[7] {
-            batch.mark_price <= batch.thresholds[7]
+            batch.mark_prices[7] <= batch.thresholds[7]
         } else {
-            batch.mark_price >= batch.thresholds[7]
+            batch.mark_prices[7] >= batch.thresholds[7]
         };
         let r7 = (batch.count > 7 && should_liq_7).reveal();
 
         let should_liq_8 = if batch.is_long[8] {
-            batch.mark_price <= batch.thresholds[8]
+            batch.mark_prices[8] <= batch.thresholds[8]
         } else {
-            batch.mark_price >= batch.thresholds[8]
+            batch.mark_prices[8] >= batch.thresholds[8]
         };
         let r8 = (batch.count > 8 && should_liq_8).reveal();
 
         let should_liq_9 = if batch.is_long[9] {
-            batch.mark_price <= batch.thresholds[9]
+            batch.mark_prices[9] <= batch.thresholds[9]
         } else {
-            batch.mark_price >= batch.thresholds[9]
+            batch.mark_prices[9] >= batch.thresholds[9]
         };
         let r9 = (batch.count > 9 && should_liq_9).reveal();
 
@@ -427,6 +596,183 @@ mod circuits {
         }
     }
 
+    /// Input for batch exit trigger check (stop-loss / take-profit)
+    pub struct BatchExitTriggerInput {
+        /// Encrypted stop-loss prices (padded to 10)
+        stop_losses: [u64; 10],
+        /// Encrypted take-profit prices (padded to 10)
+        take_profits: [u64; 10],
+        /// Whether each position has a stop-loss set (padded to 10)
+        has_stop_loss: [bool; 10],
+        /// Whether each position has a take-profit set (padded to 10)
+        has_take_profit: [bool; 10],
+        /// Is each position long? (padded to 10)
+        is_long: [bool; 10],
+        /// How many valid positions in this batch
+        count: u8,
+        /// Current mark price (same for all, plaintext)
+        mark_price: u64,
+    }
+
+    /// Output from batch exit trigger check
+    pub struct BatchExitTriggerOutput {
+        r0: bool,
+        r1: bool,
+        r2: bool,
+        r3: bool,
+        r4: bool,
+        r5: bool,
+        r6: bool,
+        r7: bool,
+        r8: bool,
+        r9: bool,
+    }
+
+    /// Check stop-loss / take-profit triggers for multiple positions in one MPC call
+    ///
+    /// Longs stop out at or below the stop-loss and take profit at or above the
+    /// take-profit; shorts the reverse. Only whether a trigger fired is revealed -
+    /// not which one, so the position's direction of profit stays private.
+    #[instruction]
+    pub fn batch_exit_trigger_check(input: Enc<Shared, BatchExitTriggerInput>) -> BatchExitTriggerOutput {
+        let batch = input.to_arcis();
+
+        // Check each position (fixed for MPC compatibility - no dynamic loops)
+        let sl_hit_0 = if batch.is_long[0] {
+            batch.mark_price <= batch.stop_losses[0]
+        } else {
+            batch.mark_price >= batch.stop_losses[0]
+        };
+        let tp_hit_0 = if batch.is_long[0] {
+            batch.mark_price >= batch.take_profits[0]
+        } else {
+            batch.mark_price <= batch.take_profits[0]
+        };
+        let fired_0 = (batch.has_stop_loss[0] && sl_hit_0) || (batch.has_take_profit[0] && tp_hit_0);
+        let r0 = (batch.count > 0 && fired_0).reveal();
+
+        let sl_hit_1 = if batch.is_long[1] {
+            batch.mark_price <= batch.stop_losses[1]
+        } else {
+            batch.mark_price >= batch.stop_losses[1]
+        };
+        let tp_hit_1 = if batch.is_long[1] {
+            batch.mark_price >= batch.take_profits[1]
+        } else {
+            batch.mark_price <= batch.take_profits[1]
+        };
+        let fired_1 = (batch.has_stop_loss[1] && sl_hit_1) || (batch.has_take_profit[1] && tp_hit_1);
+        let r1 = (batch.count > 1 && fired_1).reveal();
+
+        let sl_hit_2 = if batch.is_long[2] {
+            batch.mark_price <= batch.stop_losses[2]
+        } else {
+            batch.mark_price >= batch.stop_losses[2]
+        };
+        let tp_hit_2 = if batch.is_long[2] {
+            batch.mark_price >= batch.take_profits[2]
+        } else {
+            batch.mark_price <= batch.take_profits[2]
+        };
+        let fired_2 = (batch.has_stop_loss[2] && sl_hit_2) || (batch.has_take_profit[2] && tp_hit_2);
+        let r2 = (batch.count > 2 && fired_2).reveal();
+
+        let sl_hit_3 = if batch.is_long[3] {
+            batch.mark_price <= batch.stop_losses[3]
+        } else {
+            batch.mark_price >= batch.stop_losses[3]
+        };
+        let tp_hit_3 = if batch.is_long[3] {
+            batch.mark_price >= batch.take_profits[3]
+        } else {
+            batch.mark_price <= batch.take_profits[3]
+        };
+        let fired_3 = (batch.has_stop_loss[3] && sl_hit_3) || (batch.has_take_profit[3] && tp_hit_3);
+        let r3 = (batch.count > 3 && fired_3).reveal();
+
+        let sl_hit_4 = if batch.is_long[4] {
+            batch.mark_price <= batch.stop_losses[4]
+        } else {
+            batch.mark_price >= batch.stop_losses[4]
+        };
+        let tp_hit_4 = if batch.is_long[4] {
+            batch.mark_price >= batch.take_profits[4]
+        } else {
+            batch.mark_price <= batch.take_profits[4]
+        };
+        let fired_4 = (batch.has_stop_loss[4] && sl_hit_4) || (batch.has_take_profit[4] && tp_hit_4);
+        let r4 = (batch.count > 4 && fired_4).reveal();
+
+        let sl_hit_5 = if batch.is_long[5] {
+            batch.mark_price <= batch.stop_losses[5]
+        } else {
+            batch.mark_price >= batch.stop_losses[5]
+        };
+        let tp_hit_5 = if batch.is_long[5] {
+            batch.mark_price >= batch.take_profits[5]
+        } else {
+            batch.mark_price <= batch.take_profits[5]
+        };
+        let fired_5 = (batch.has_stop_loss[5] && sl_hit_5) || (batch.has_take_profit[5] && tp_hit_5);
+        let r5 = (batch.count > 5 && fired_5).reveal();
+
+        let sl_hit_6 = if batch.is_long[6] {
+            batch.mark_price <= batch.stop_losses[6]
+        } else {
+            batch.mark_price >= batch.stop_losses[6]
+        };
+        let tp_hit_6 = if batch.is_long[6] {
+            batch.mark_price >= batch.take_profits[6]
+        } else {
+            batch.mark_price <= batch.take_profits[6]
+        };
+        let fired_6 = (batch.has_stop_loss[6] && sl_hit_6) || (batch.has_take_profit[6] && tp_hit_6);
+        let r6 = (batch.count > 6 && fired_6).reveal();
+
+        let sl_hit_7 = if batch.is_long[7] {
+            batch.mark_price <= batch.stop_losses[7]
+        } else {
+            batch.mark_price >= batch.stop_losses[7]
+        };
+        let tp_hit_7 = if batch.is_long[7] {
+            batch.mark_price >= batch.take_profits[7]
+        } else {
+            batch.mark_price <= batch.take_profits[7]
+        };
+        let fired_7 = (batch.has_stop_loss[7] && sl_hit_7) || (batch.has_take_profit[7] && tp_hit_7);
+        let r7 = (batch.count > 7 && fired_7).reveal();
+
+        let sl_hit_8 = if batch.is_long[8] {
+            batch.mark_price <= batch.stop_losses[8]
+        } else {
+            batch.mark_price >= batch.stop_losses[8]
+        };
+        let tp_hit_8 = if batch.is_long[8] {
+            batch.mark_price >= batch.take_profits[8]
+        } else {
+            batch.mark_price <= batch.take_profits[8]
+        };
+        let fired_8 = (batch.has_stop_loss[8] && sl_hit_8) || (batch.has_take_profit[8] && tp_hit_8);
+        let r8 = (batch.count > 8 && fired_8).reveal();
+
+        let sl_hit_9 = if batch.is_long[9] {
+            batch.mark_price <= batch.stop_losses[9]
+        } else {
+            batch.mark_price >= batch.stop_losses[9]
+        };
+        let tp_hit_9 = if batch.is_long[9] {
+            batch.mark_price >= batch.take_profits[9]
+        } else {
+            batch.mark_price <= batch.take_profits[9]
+        };
+        let fired_9 = (batch.has_stop_loss[9] && sl_hit_9) || (batch.has_take_profit[9] && tp_hit_9);
+        let r9 = (batch.count > 9 && fired_9).reveal();
+
+        BatchExitTriggerOutput {
+            r0, r1, r2, r3, r4, r5, r6, r7, r8, r9,
+        }
+    }
+
     /// Input for PnL calculation
     pub struct PnlInput {
         /// Encrypted position size
@@ -540,6 +886,462 @@ mod circuits {
         })
     }
 
+    /// Input for auto-deleverage settlement
+    pub struct AdlInput {
+        /// Encrypted size of the bankrupt position
+        bankrupt_size: u64,
+        /// Encrypted entry price of the bankrupt position
+        bankrupt_entry_price: u64,
+        /// Encrypted collateral of the bankrupt position
+        bankrupt_collateral: u64,
+        /// Encrypted size of the counter-position being deleveraged
+        target_size: u64,
+        /// Encrypted entry price of the counter-position
+        target_entry_price: u64,
+        /// Encrypted collateral of the counter-position
+        target_collateral: u64,
+    }
+
+    /// Updated position values after auto-deleverage
+    pub struct AdlOutput {
+        /// Remaining size of the bankrupt position
+        bankrupt_size: u64,
+        /// Remaining collateral of the bankrupt position
+        bankrupt_collateral: u64,
+        /// Remaining size of the counter-position
+        target_size: u64,
+        /// Remaining collateral of the counter-position
+        target_collateral: u64,
+    }
+
+    /// Auto-deleverage a bankrupt position against an opposite-side position
+    ///
+    /// Both positions are closed by adl_size = min(bankrupt_size, target_size)
+    /// at the bankrupt position's bankruptcy price - the price at which its
+    /// loss equals its collateral (same PnL formula as calculate_pnl):
+    ///   long:  bankruptcy = entry - collateral * entry / size
+    ///   short: bankruptcy = entry + collateral * entry / size
+    ///
+    /// Collateral is released pro rata to the closed size on both sides, so
+    /// leverage (and therefore the liquidation thresholds) is unchanged. The
+    /// bankrupt side's released collateral stays in the vault and covers the
+    /// counter-position's PnL at the bankruptcy price.
+    ///
+    /// Returns (encrypted remaining sizes/collaterals, target_payout, adl_size,
+    /// bankrupt_fully_closed, target_fully_closed). The payout is revealed for
+    /// the vault transfer and adl_size for the open interest update; neither
+    /// is emitted in events.
+    #[instruction]
+    pub fn calculate_adl(
+        input: Enc<Shared, AdlInput>,
+        bankrupt_is_long: bool,
+    ) -> (Enc<Shared, AdlOutput>, u64, u64, bool, bool) {
+        let adl = input.to_arcis();
+
+        let valid = adl.bankrupt_size > 0
+            && adl.target_size > 0
+            && adl.bankrupt_entry_price > 0
+            && adl.target_entry_price > 0;
+
+        let adl_size = if !valid {
+            0u64
+        } else if adl.bankrupt_size < adl.target_size {
+            adl.bankrupt_size
+        } else {
+            adl.target_size
+        };
+
+        // Price move that wipes out the bankrupt position's collateral
+        let bankrupt_move = if valid {
+            (adl.bankrupt_collateral * adl.bankrupt_entry_price) / adl.bankrupt_size
+        } else {
+            0u64
+        };
+        let bankruptcy_price = if bankrupt_is_long {
+            if adl.bankrupt_entry_price > bankrupt_move {
+                adl.bankrupt_entry_price - bankrupt_move
+            } else {
+                0u64
+            }
+        } else {
+            adl.bankrupt_entry_price + bankrupt_move
+        };
+
+        // Counter-position is on the opposite side of the bankrupt position
+        let (target_is_profit, target_diff) = if bankrupt_is_long {
+            // Target is short: profit if bankruptcy price < entry
+            if bankruptcy_price < adl.target_entry_price {
+                (true, adl.target_entry_price - bankruptcy_price)
+            } else {
+                (false, bankruptcy_price - adl.target_entry_price)
+            }
+        } else {
+            // Target is long: profit if bankruptcy price > entry
+            if bankruptcy_price > adl.target_entry_price {
+                (true, bankruptcy_price - adl.target_entry_price)
+            } else {
+                (false, adl.target_entry_price - bankruptcy_price)
+            }
+        };
+
+        let target_pnl = if valid {
+            (adl_size * target_diff) / adl.target_entry_price
+        } else {
+            0u64
+        };
+
+        // Pro-rata collateral released by the closed size
+        let bankrupt_released = if valid {
+            (adl.bankrupt_collateral * adl_size) / adl.bankrupt_size
+        } else {
+            0u64
+        };
+        let target_released = if valid {
+            (adl.target_collateral * adl_size) / adl.target_size
+        } else {
+            0u64
+        };
+
+        let target_payout = if target_is_profit {
+            target_released + target_pnl
+        } else if target_released > target_pnl {
+            target_released - target_pnl
+        } else {
+            0u64
+        };
+
+        let bankrupt_remaining = adl.bankrupt_size - adl_size;
+        let target_remaining = adl.target_size - adl_size;
+
+        let bankrupt_fully_closed = valid && bankrupt_remaining == 0;
+        let target_fully_closed = valid && target_remaining == 0;
+
+        let output = AdlOutput {
+            bankrupt_size: bankrupt_remaining,
+            bankrupt_collateral: adl.bankrupt_collateral - bankrupt_released,
+            target_size: target_remaining,
+            target_collateral: adl.target_collateral - target_released,
+        };
+
+        (
+            input.owner.from_arcis(output),
+            target_payout.reveal(),
+            adl_size.reveal(),
+            bankrupt_fully_closed.reveal(),
+            target_fully_closed.reveal(),
+        )
+    }
+
+    /// Input for ADL candidate ranking (up to 10 positions)
+    pub struct AdlRankInput {
+        /// Encrypted position sizes (padded to 10)
+        sizes: [u64; 10],
+        /// Encrypted entry prices (padded to 10)
+        entry_prices: [u64; 10],
+        /// Encrypted collaterals (padded to 10)
+        collaterals: [u64; 10],
+    }
+
+    /// Output from ADL candidate ranking
+    /// Queue position of each slot (1 = deleveraged first, 0 = not eligible)
+    pub struct AdlRankOutput {
+        r0: u8,
+        r1: u8,
+        r2: u8,
+        r3: u8,
+        r4: u8,
+        r5: u8,
+        r6: u8,
+        r7: u8,
+        r8: u8,
+        r9: u8,
+    }
+
+    /// Rank positions for auto-deleverage by unrealized profitability
+    ///
+    /// Score = leverage * profit_ratio_bps (same formula as the on-chain
+    /// AdlPriority::calculate), where
+    ///   pnl              = size * |mark - entry| / entry (as calculate_pnl)
+    ///   profit_ratio_bps = pnl * 10000 / collateral
+    ///   leverage         = size / collateral
+    /// Losing positions and slots >= count score 0 and are not eligible.
+    ///
+    /// Only the rank of each slot is revealed - scores, PnL and sizes stay
+    /// encrypted. Ties keep batch order. Loops have fixed bounds and are
+    /// unrolled at compile time.
+    #[instruction]
+    pub fn rank_adl_candidates(
+        input: Enc<Shared, AdlRankInput>,
+        is_long: [bool; 10],
+        count: u8,
+        mark_price: u64,
+    ) -> AdlRankOutput {
+        let batch = input.to_arcis();
+
+        let mut scores = [0u64; 10];
+        for i in 0..10 {
+            let size = batch.sizes[i];
+            let entry = batch.entry_prices[i];
+            let collateral = batch.collaterals[i];
+
+            let (is_profit, price_diff) = if is_long[i] {
+                if mark_price > entry {
+                    (true, mark_price - entry)
+                } else {
+                    (false, entry - mark_price)
+                }
+            } else {
+                if mark_price < entry {
+                    (true, entry - mark_price)
+                } else {
+                    (false, mark_price - entry)
+                }
+            };
+
+            let valid = (i as u8) < count && entry > 0 && collateral > 0;
+
+            let pnl = if valid { (size * price_diff) / entry } else { 0u64 };
+            let profit_ratio_bps = if valid { (pnl * 10000) / collateral } else { 0u64 };
+            let leverage = if valid { size / collateral } else { 0u64 };
+
+            scores[i] = if valid && is_profit {
+                leverage * profit_ratio_bps
+            } else {
+                0u64
+            };
+        }
+
+        // rank = 1 + number of eligible positions ahead of this one
+        let mut ranks = [0u8; 10];
+        for i in 0..10 {
+            let mut ahead = 0u8;
+            for j in 0..10 {
+                if j != i {
+                    let is_ahead = scores[j] > scores[i] || (j < i && scores[j] == scores[i]);
+                    ahead = ahead + if is_ahead { 1u8 } else { 0u8 };
+                }
+            }
+            ranks[i] = if scores[i] > 0 { ahead + 1 } else { 0u8 };
+        }
+
+        AdlRankOutput {
+            r0: ranks[0].reveal(),
+            r1: ranks[1].reveal(),
+            r2: ranks[2].reveal(),
+            r3: ranks[3].reveal(),
+            r4: ranks[4].reveal(),
+            r5: ranks[5].reveal(),
+            r6: ranks[6].reveal(),
+            r7: ranks[7].reveal(),
+            r8: ranks[8].reveal(),
+            r9: ranks[9].reveal(),
+        }
+    }
+
+    /// Input for partial liquidation
+    pub struct PartialLiquidationInput {
+        /// Encrypted position size
+        size: u64,
+        /// Encrypted entry price
+        entry_price: u64,
+        /// Encrypted collateral
+        collateral: u64,
+    }
+
+    /// Updated position values after partial liquidation
+    pub struct PartialLiquidationOutput {
+        /// Remaining position size
+        size: u64,
+        /// Remaining collateral (after realized PnL and liquidator bonus)
+        collateral: u64,
+        /// Liquidation threshold for the remaining size/collateral
+        liq_threshold: u64,
+    }
+
+    /// Liquidate the minimum size that restores maintenance margin
+    ///
+    /// Equity at the mark price includes unrealized PnL (calculate_pnl
+    /// formula) and accrued funding (calculate_funding, from the market's
+    /// cumulative funding):
+    ///   equity      = collateral +/- size * |mark - entry| / entry
+    ///                            +/- size * funding_e8 / 1e8
+    ///   maintenance = size * mark / entry * mm_bps / 10000
+    /// Closing x units releases maintenance at mm_bps and costs the liquidator
+    /// bonus at bonus_bps of the closed notional, so the smallest close that
+    /// restores equity >= maintenance is
+    ///   close_notional = (maintenance - equity) * 10000 / (mm_bps - bonus_bps)
+    /// A bonus >= mm_bps closes fully, as does a close that would leave less
+    /// than min_remaining_notional. The close is capped at max_close_notional
+    /// (0 = uncapped).
+    ///
+    /// A bankrupt position (equity <= 0) is closed fully with no bonus and
+    /// its deficit is revealed as bad_debt for the insurance fund to cover.
+    ///
+    /// Otherwise accrued funding and the closed share of PnL are settled into
+    /// collateral and the bonus is taken from it (never more than is left).
+    /// The remaining position keeps its entry price; its new threshold uses
+    /// the verify_position_params formula with leverage = size / collateral:
+    ///   long:  entry - collateral * entry / size + entry * mm / 10000
+    ///   short: entry + collateral * entry / size - entry * mm / 10000
+    ///
+    /// Returns (encrypted remaining size/collateral/threshold, close_size,
+    /// liquidator_bonus, released_collateral, bad_debt, fully_closed).
+    /// close_size is 0 when the position is above maintenance margin.
+    /// released_collateral is the equity left after a full close (0 for a
+    /// partial close). Revealed values are used for transfers and open
+    /// interest, not emitted in events.
+    #[instruction]
+    pub fn calculate_partial_liquidation(
+        input: Enc<Shared, PartialLiquidationInput>,
+        is_long: bool,
+        mark_price: u64,
+        mm_bps: u16,
+        bonus_bps: u16,
+        max_close_notional: u64,
+        min_remaining_notional: u64,
+        funding_e8: u64,
+        funding_is_paying: bool,
+    ) -> (Enc<Shared, PartialLiquidationOutput>, u64, u64, u64, u64, bool) {
+        let pos = input.to_arcis();
+        let mm = mm_bps as u64;
+        let bonus = bonus_bps as u64;
+
+        let valid = pos.size > 0 && pos.entry_price > 0 && mark_price > 0;
+
+        let (is_profit, price_diff) = if is_long {
+            if mark_price > pos.entry_price {
+                (true, mark_price - pos.entry_price)
+            } else {
+                (false, pos.entry_price - mark_price)
+            }
+        } else {
+            if mark_price < pos.entry_price {
+                (true, pos.entry_price - mark_price)
+            } else {
+                (false, mark_price - pos.entry_price)
+            }
+        };
+
+        let pnl = if valid { (pos.size * price_diff) / pos.entry_price } else { 0u64 };
+        let funding = (pos.size * funding_e8) / 100000000u64;
+
+        // Signed equity as credit - debit
+        let credit = pos.collateral
+            + if is_profit { pnl } else { 0u64 }
+            + if funding_is_paying { 0u64 } else { funding };
+        let debit = if is_profit { 0u64 } else { pnl }
+            + if funding_is_paying { funding } else { 0u64 };
+        let equity = if credit > debit { credit - debit } else { 0u64 };
+        let bad_debt = if valid && debit > credit { debit - credit } else { 0u64 };
+        let bankrupt = valid && equity == 0;
+
+        let notional = if valid { (pos.size * mark_price) / pos.entry_price } else { 0u64 };
+        let maintenance = (notional * mm) / 10000;
+        let liquidatable = valid && equity < maintenance;
+
+        // Smallest close that brings equity back to maintenance (+1 rounds up)
+        let shortfall = if liquidatable { maintenance - equity } else { 0u64 };
+        let min_close = if valid && mm > bonus && !bankrupt {
+            let close_notional = (shortfall * 10000) / (mm - bonus);
+            (close_notional * pos.entry_price) / mark_price + 1
+        } else {
+            pos.size
+        };
+        let min_close = if min_close < pos.size { min_close } else { pos.size };
+
+        // Don't leave dust positions behind
+        let remaining_notional = if valid {
+            ((pos.size - min_close) * mark_price) / pos.entry_price
+        } else {
+            0u64
+        };
+        let uncapped_close = if remaining_notional < min_remaining_notional {
+            pos.size
+        } else {
+            min_close
+        };
+
+        // The cap doesn't apply to bankrupt positions - their deficit is
+        // settled in one go
+        let max_close = if valid && max_close_notional > 0 && !bankrupt {
+            (max_close_notional * pos.entry_price) / mark_price
+        } else {
+            pos.size
+        };
+
+        let close_size = if !liquidatable {
+            0u64
+        } else if uncapped_close > max_close {
+            max_close
+        } else {
+            uncapped_close
+        };
+        let fully_closed = liquidatable && close_size == pos.size;
+
+        // Settle all accrued funding and the closed share of PnL
+        let realized = if valid { (pnl * close_size) / pos.size } else { 0u64 };
+        let settled_credit = pos.collateral
+            + if is_profit { realized } else { 0u64 }
+            + if funding_is_paying { 0u64 } else { funding };
+        let settled_debit = if is_profit { 0u64 } else { realized }
+            + if funding_is_paying { funding } else { 0u64 };
+        let collateral_after = if settled_credit > settled_debit {
+            settled_credit - settled_debit
+        } else {
+            0u64
+        };
+
+        // Bonus only from positive equity
+        let closed_notional = if valid { (close_size * mark_price) / pos.entry_price } else { 0u64 };
+        let full_bonus = (closed_notional * bonus) / 10000;
+        let liquidator_bonus = if bankrupt {
+            0u64
+        } else if full_bonus < collateral_after {
+            full_bonus
+        } else {
+            collateral_after
+        };
+        let remaining_collateral = collateral_after - liquidator_bonus;
+
+        let released_collateral = if fully_closed { remaining_collateral } else { 0u64 };
+        let new_size = pos.size - close_size;
+        let new_collateral = if fully_closed { 0u64 } else { remaining_collateral };
+
+        // Threshold for the remaining position
+        let collateral_move = if new_size > 0 {
+            (new_collateral * pos.entry_price) / new_size
+        } else {
+            0u64
+        };
+        let mm_move = (pos.entry_price * mm) / 10000;
+        let liq_threshold = if is_long {
+            if pos.entry_price + mm_move > collateral_move {
+                pos.entry_price + mm_move - collateral_move
+            } else {
+                0u64
+            }
+        } else if pos.entry_price + collateral_move > mm_move {
+            pos.entry_price + collateral_move - mm_move
+        } else {
+            0u64
+        };
+
+        let output = PartialLiquidationOutput {
+            size: new_size,
+            collateral: new_collateral,
+            liq_threshold,
+        };
+
+        (
+            input.owner.from_arcis(output),
+            close_size.reveal(),
+            liquidator_bonus.reveal(),
+            released_collateral.reveal(),
+            bad_debt.reveal(),
+            fully_closed.reveal(),
+        )
+    }
+
     // =============================================================
     // SETTLEMENT CIRCUITS
     // =============================================================
@@ -606,8 +1408,8 @@ mod circuits {
 
     /// Input for balance check with order details
     pub struct OrderBalanceCheckInput {
-        /// User's encrypted balance
-        encrypted_balance: u64,
+        /// User's available balance (plaintext until C-SPL, see UserConfidentialBalance)
+        balance: u64,
         /// Encrypted order amount
         order_amount: u64,
         /// Encrypted order price
@@ -616,29 +1418,99 @@ mod circuits {
         is_buy: bool,
     }
 
+    /// Output from order balance check
+    pub struct OrderBalanceCheckOutput {
+        /// Whether the balance covers the order (revealed)
+        sufficient: bool,
+        /// Amount to escrow from the user's balance (revealed, 0 if insufficient)
+        escrow_amount: u64,
+    }
+
     /// Check if user has sufficient balance for an order
     ///
-    /// For buy orders: need balance >= amount * price / PRICE_SCALE
+    /// For buy orders: need balance >= amount * price / BASE_SCALE
     /// For sell orders: need balance >= amount
     ///
-    /// Uses PRICE_SCALE = 1_000_000 (6 decimals)
+    /// Uses BASE_SCALE = 1_000_000_000 (9 base decimals) to match
+    /// settle_order_callback's fill_value calculation.
+    ///
+    /// The escrow amount is revealed so the DEX callback can lock it.
+    /// SECURITY NOTE: It is NOT emitted in events - only the result flag is logged.
     #[instruction]
-    pub fn check_order_balance(input: Enc<Shared, OrderBalanceCheckInput>) -> bool {
+    pub fn check_order_balance(input: Enc<Shared, OrderBalanceCheckInput>) -> OrderBalanceCheckOutput {
         let check = input.to_arcis();
 
-        const PRICE_SCALE: u64 = 1_000_000;
+        const BASE_SCALE: u64 = 1_000_000_000;
 
         let required = if check.is_buy {
             // Buy order: need quote currency (amount * price / scale)
-            // Overflow-safe: compute (amount * price) / PRICE_SCALE
-            (check.order_amount * check.order_price) / PRICE_SCALE
+            (check.order_amount * check.order_price) / BASE_SCALE
         } else {
             // Sell order: need base currency (amount)
             check.order_amount
         };
 
-        let sufficient = check.encrypted_balance >= required;
-        sufficient.reveal()
+        let sufficient = check.balance >= required;
+        let escrow_amount = if sufficient { required } else { 0u64 };
+
+        OrderBalanceCheckOutput {
+            sufficient: sufficient.reveal(),
+            escrow_amount: escrow_amount.reveal(),
+        }
+    }
+
+    /// Input for an order amend (cancel-replace) balance check
+    pub struct OrderAmendInput {
+        /// Encrypted replacement order amount
+        new_amount: u64,
+        /// Encrypted replacement limit price
+        new_price: u64,
+    }
+
+    /// Output from order amend check
+    pub struct OrderAmendOutput {
+        /// Whether the amend can be applied (revealed)
+        sufficient: bool,
+        /// Escrow required by the replacement order (revealed, 0 if insufficient)
+        escrow_amount: u64,
+    }
+
+    /// Check if a resting, unfilled order can be amended in place
+    ///
+    /// Escrow uses the same scaling as check_order_balance:
+    ///   buy:  amount * price / BASE_SCALE (quote)
+    ///   sell: amount (base)
+    ///
+    /// The current escrow is released back to the balance before the new
+    /// one is taken. The DEX tracks that escrow in plaintext, so `available`
+    /// is balance + the order's remaining escrow and the check is
+    /// available >= required. A zero replacement amount is rejected.
+    ///
+    /// SECURITY NOTE: Revealed amounts are used only for the escrow update.
+    /// They are NOT emitted in events - only the result flag is logged.
+    #[instruction]
+    pub fn check_order_amend(
+        input: Enc<Shared, OrderAmendInput>,
+        available: u64,
+        is_buy: bool,
+    ) -> OrderAmendOutput {
+        let amend = input.to_arcis();
+
+        const BASE_SCALE: u64 = 1_000_000_000;
+
+        let required = if is_buy {
+            (amend.new_amount * amend.new_price) / BASE_SCALE
+        } else {
+            amend.new_amount
+        };
+
+        let sufficient = amend.new_amount > 0u64 && available >= required;
+        let escrow_amount = if sufficient { required } else { 0u64 };
+
+        OrderAmendOutput {
+            sufficient: sufficient.reveal(),
+            escrow_amount: escrow_amount.reveal(),
+        }
     }
 
     // =============================================================
@@ -651,6 +1523,8 @@ mod circuits {
         encrypted_amount: u64,
         /// Encrypted filled amount
         encrypted_filled: u64,
+        /// Encrypted limit price (used to size buy-side quote escrow)
+        encrypted_price: u64,
     }
 
     /// Output from refund calculation
@@ -663,24 +1537,46 @@ mod circuits {
 
     /// Calculate refund amount for order cancellation
     ///
-    /// Computes: refund = encrypted_amount - encrypted_filled
+    /// Computes the unfilled remainder of the escrow locked by
+    /// check_order_balance:
+    ///   sell: amount - filled (base)
+    ///   buy:  (amount - filled) * price / BASE_SCALE (quote)
+    ///
+    /// `has_fills` is false while the order's filled field is still unset
+    /// (all zeros), in which case filled is treated as 0.
+    ///
     /// The result is revealed since the refund transfer amount must be known.
     ///
     /// SECURITY NOTE: The revealed value is used only for the token transfer.
     /// It is NOT emitted in events - only order ID is logged.
     #[instruction]
-    pub fn calculate_refund(input: Enc<Shared, RefundInput>) -> RefundOutput {
+    pub fn calculate_refund(
+        input: Enc<Shared, RefundInput>,
+        is_buy: bool,
+        has_fills: bool,
+    ) -> RefundOutput {
         let refund = input.to_arcis();
 
+        const BASE_SCALE: u64 = 1_000_000_000;
+
+        let filled = if has_fills { refund.encrypted_filled } else { 0u64 };
+
         // Safe subtraction: if filled > amount (shouldn't happen), return 0
-        let refund_amount = if refund.encrypted_amount >= refund.encrypted_filled {
-            refund.encrypted_amount - refund.encrypted_filled
+        let remaining = if refund.encrypted_amount >= filled {
+            refund.encrypted_amount - filled
         } else {
             0u64
         };
 
+        // Refund in the escrowed token (same scaling as check_order_balance)
+        let refund_amount = if is_buy {
+            (remaining * refund.encrypted_price) / BASE_SCALE
+        } else {
+            remaining
+        };
+
         // Track if there were any fills
-        let had_fills = refund.encrypted_filled > 0u64;
+        let had_fills = filled > 0u64;
 
         RefundOutput {
             refund_amount: refund_amount.reveal(),
@@ -688,6 +1584,84 @@ mod circuits {
         }
     }
 
+    /// Input for batch refund calculation (up to 5 orders)
+    pub struct BatchRefundInput {
+        /// Encrypted total order amounts (padded to 5)
+        amounts: [u64; 5],
+        /// Encrypted filled amounts (padded to 5)
+        filled: [u64; 5],
+        /// Encrypted limit prices (padded to 5)
+        prices: [u64; 5],
+    }
+
+    /// Output from batch refund calculation
+    pub struct BatchRefundOutput {
+        /// Refund amount for each order (0 for unused slots)
+        r0: u64,
+        r1: u64,
+        r2: u64,
+        r3: u64,
+        r4: u64,
+    }
+
+    /// Calculate refund amounts for up to 5 orders in one MPC call
+    ///
+    /// Same per-order computation as calculate_refund. `is_buy` and
+    /// `has_fills` are per-slot plaintext flags; slots >= count refund 0.
+    /// Explicitly unrolled for MPC compatibility (no closures or returns).
+    ///
+    /// SECURITY NOTE: Revealed values are used only for the balance refunds.
+    /// They are NOT emitted in events.
+    #[instruction]
+    pub fn batch_calculate_refund(
+        input: Enc<Shared, BatchRefundInput>,
+        is_buy: [bool; 5],
+        has_fills: [bool; 5],
+        count: u8,
+    ) -> BatchRefundOutput {
+        let batch = input.to_arcis();
+
+        const BASE_SCALE: u64 = 1_000_000_000;
+
+        // Slot 0
+        let filled0 = if has_fills[0] { batch.filled[0] } else { 0u64 };
+        let rem0 = if batch.amounts[0] >= filled0 { batch.amounts[0] - filled0 } else { 0u64 };
+        let ref0 = if is_buy[0] { (rem0 * batch.prices[0]) / BASE_SCALE } else { rem0 };
+        let r0 = if count > 0 { ref0 } else { 0u64 };
+
+        // Slot 1
+        let filled1 = if has_fills[1] { batch.filled[1] } else { 0u64 };
+        let rem1 = if batch.amounts[1] >= filled1 { batch.amounts[1] - filled1 } else { 0u64 };
+        let ref1 = if is_buy[1] { (rem1 * batch.prices[1]) / BASE_SCALE } else { rem1 };
+        let r1 = if count > 1 { ref1 } else { 0u64 };
+
+        // Slot 2
+        let filled2 = if has_fills[2] { batch.filled[2] } else { 0u64 };
+        let rem2 = if batch.amounts[2] >= filled2 { batch.amounts[2] - filled2 } else { 0u64 };
+        let ref2 = if is_buy[2] { (rem2 * batch.prices[2]) / BASE_SCALE } else { rem2 };
+        let r2 = if count > 2 { ref2 } else { 0u64 };
+
+        // Slot 3
+        let filled3 = if has_fills[3] { batch.filled[3] } else { 0u64 };
+        let rem3 = if batch.amounts[3] >= filled3 { batch.amounts[3] - filled3 } else { 0u64 };
+        let ref3 = if is_buy[3] { (rem3 * batch.prices[3]) / BASE_SCALE } else { rem3 };
+        let r3 = if count > 3 { ref3 } else { 0u64 };
+
+        // Slot 4
+        let filled4 = if has_fills[4] { batch.filled[4] } else { 0u64 };
+        let rem4 = if batch.amounts[4] >= filled4 { batch.amounts[4] - filled4 } else { 0u64 };
+        let ref4 = if is_buy[4] { (rem4 * batch.prices[4]) / BASE_SCALE } else { rem4 };
+        let r4 = if count > 4 { ref4 } else { 0u64 };
+
+        BatchRefundOutput {
+            r0: r0.reveal(),
+            r1: r1.reveal(),
+            r2: r2.reveal(),
+            r3: r3.reveal(),
+            r4: r4.reveal(),
+        }
+    }
+
     // =============================================================
     // ARITHMETIC HELPERS
     // =============================================================
@@ -48,6 +48,10 @@ const MXE_AUTHORITY_SEED: &[u8] = b"mxe_authority";
 /// sha256("global:finalize_match")[0..8] = 06672f07420155cf
 const DEX_FINALIZE_MATCH_DISCRIMINATOR: [u8; 8] = [0x06, 0x67, 0x2f, 0x07, 0x42, 0x01, 0x55, 0xcf];
 
+/// DEX finalize_match_batch instruction discriminator
+/// sha256("global:finalize_match_batch")[0..8] = 6ca70bc980d45bec
+const DEX_FINALIZE_MATCH_BATCH_DISCRIMINATOR: [u8; 8] = [0x6c, 0xa7, 0x0b, 0xc9, 0x80, 0xd4, 0x5b, 0xec];
+
 // Computation definition offsets (generated from circuit names)
 const COMP_DEF_OFFSET_COMPARE_PRICES: u32 = comp_def_offset("compare_prices");
 const COMP_DEF_OFFSET_CALCULATE_FILL: u32 = comp_def_offset("calculate_fill");
@@ -65,14 +69,52 @@ const COMP_DEF_OFFSET_DECRYPT_FOR_SETTLEMENT: u32 = comp_def_offset("decrypt_for
 const COMP_DEF_OFFSET_CALCULATE_REFUND: u32 = comp_def_offset("calculate_refund");
 const COMP_DEF_OFFSET_BATCH_COMPARE_PRICES: u32 = comp_def_offset("batch_compare_prices");
 const COMP_DEF_OFFSET_BATCH_CALCULATE_FILL: u32 = comp_def_offset("batch_calculate_fill");
+const COMP_DEF_OFFSET_BATCH_CALCULATE_REFUND: u32 = comp_def_offset("batch_calculate_refund");
+const COMP_DEF_OFFSET_CHECK_ORDER_AMEND: u32 = comp_def_offset("check_order_amend");
+const COMP_DEF_OFFSET_CALCULATE_ADL: u32 = comp_def_offset("calculate_adl");
+const COMP_DEF_OFFSET_RANK_ADL_CANDIDATES: u32 = comp_def_offset("rank_adl_candidates");
+const COMP_DEF_OFFSET_CALCULATE_PARTIAL_LIQUIDATION: u32 = comp_def_offset("calculate_partial_liquidation");
+const COMP_DEF_OFFSET_BATCH_EXIT_TRIGGER_CHECK: u32 = comp_def_offset("batch_exit_trigger_check");
 
 /// DEX settle_order_callback instruction discriminator
 /// sha256("global:settle_order_callback")[0..8]
-const DEX_SETTLE_ORDER_CALLBACK_DISCRIMINATOR: [u8; 8] = [0x8d, 0x47, 0x94, 0x0c, 0x9f, 0x81, 0xa2, 0xe3];
+const DEX_SETTLE_ORDER_CALLBACK_DISCRIMINATOR: [u8; 8] = [0x23, 0x21, 0x1d, 0xae, 0x25, 0x7a, 0x63, 0xf1];
 
 /// DEX cancel_order_callback instruction discriminator
-/// sha256("global:cancel_order_callback")[0..8]
-const DEX_CANCEL_ORDER_CALLBACK_DISCRIMINATOR: [u8; 8] = [0xa3, 0xc2, 0x1f, 0x67, 0x8b, 0x4e, 0xd9, 0x12];
+/// sha256("global:cancel_order_callback")[0..8] = 8a221de9f008d32a
+const DEX_CANCEL_ORDER_CALLBACK_DISCRIMINATOR: [u8; 8] = [0x8a, 0x22, 0x1d, 0xe9, 0xf0, 0x08, 0xd3, 0x2a];
+
+/// DEX cancel_orders_batch_callback instruction discriminator
+/// sha256("global:cancel_orders_batch_callback")[0..8] = a84a4a78127ef96c
+const DEX_CANCEL_ORDERS_BATCH_CALLBACK_DISCRIMINATOR: [u8; 8] = [0xa8, 0x4a, 0x4a, 0x78, 0x12, 0x7e, 0xf9, 0x6c];
+
+/// DEX place_order_callback instruction discriminator
+/// sha256("global:place_order_callback")[0..8] = f6be9a922c0b692c
+const DEX_PLACE_ORDER_CALLBACK_DISCRIMINATOR: [u8; 8] = [0xf6, 0xbe, 0x9a, 0x92, 0x2c, 0x0b, 0x69, 0x2c];
+
+/// DEX amend_order_callback instruction discriminator
+/// sha256("global:amend_order_callback")[0..8] = e85dbf1b7136d6b2
+const DEX_AMEND_ORDER_CALLBACK_DISCRIMINATOR: [u8; 8] = [0xe8, 0x5d, 0xbf, 0x1b, 0x71, 0x36, 0xd6, 0xb2];
+
+/// DEX adl_pnl_callback instruction discriminator
+/// sha256("global:adl_pnl_callback")[0..8] = f310ccc0ee1740c8
+const DEX_ADL_PNL_CALLBACK_DISCRIMINATOR: [u8; 8] = [0xf3, 0x10, 0xcc, 0xc0, 0xee, 0x17, 0x40, 0xc8];
+
+/// DEX adl_ranking_callback instruction discriminator
+/// sha256("global:adl_ranking_callback")[0..8] = 2f95f5e26b139abd
+const DEX_ADL_RANKING_CALLBACK_DISCRIMINATOR: [u8; 8] = [0x2f, 0x95, 0xf5, 0xe2, 0x6b, 0x13, 0x9a, 0xbd];
+
+/// DEX partial_liquidation_callback instruction discriminator
+/// sha256("global:partial_liquidation_callback")[0..8] = 7e6d1302e09555f0
+const DEX_PARTIAL_LIQUIDATION_CALLBACK_DISCRIMINATOR: [u8; 8] = [0x7e, 0x6d, 0x13, 0x02, 0xe0, 0x95, 0x55, 0xf0];
+
+/// DEX liquidation_batch_callback instruction discriminator
+/// sha256("global:liquidation_batch_callback")[0..8] = b05d6bddb387853e
+const DEX_LIQUIDATION_BATCH_CALLBACK_DISCRIMINATOR: [u8; 8] = [0xb0, 0x5d, 0x6b, 0xdd, 0xb3, 0x87, 0x85, 0x3e];
+
+/// DEX exit_trigger_callback instruction discriminator
+/// sha256("global:exit_trigger_callback")[0..8] = 8555bcff5f452ddd
+const DEX_EXIT_TRIGGER_CALLBACK_DISCRIMINATOR: [u8; 8] = [0x85, 0x55, 0xbc, 0xff, 0x5f, 0x45, 0x2d, 0xdd];
 
 declare_id!("4pdgnqNQLxocJNo6MrSHKqieUpQ8zx3sxbsTANJFtSNi");
 
@@ -178,6 +220,60 @@ pub mod confidex_mxe {
         Ok(())
     }
 
+    pub fn init_calculate_adl_comp_def(ctx: Context<InitCalculateAdlCompDef>) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: format!("{}/calculate_adl.arcis", CIRCUIT_BASE_URL),
+                hash: circuit_hash!("calculate_adl"),
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_rank_adl_candidates_comp_def(
+        ctx: Context<InitRankAdlCandidatesCompDef>,
+    ) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: format!("{}/rank_adl_candidates.arcis", CIRCUIT_BASE_URL),
+                hash: circuit_hash!("rank_adl_candidates"),
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_calculate_partial_liquidation_comp_def(
+        ctx: Context<InitCalculatePartialLiquidationCompDef>,
+    ) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: format!("{}/calculate_partial_liquidation.arcis", CIRCUIT_BASE_URL),
+                hash: circuit_hash!("calculate_partial_liquidation"),
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_batch_exit_trigger_check_comp_def(
+        ctx: Context<InitBatchExitTriggerCheckCompDef>,
+    ) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: format!("{}/batch_exit_trigger_check.arcis", CIRCUIT_BASE_URL),
+                hash: circuit_hash!("batch_exit_trigger_check"),
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
     pub fn init_add_encrypted_comp_def(ctx: Context<InitAddEncryptedCompDef>) -> Result<()> {
         init_comp_def(
             ctx.accounts,
@@ -296,6 +392,34 @@ pub mod confidex_mxe {
         Ok(())
     }
 
+    pub fn init_batch_calculate_refund_comp_def(
+        ctx: Context<InitBatchCalculateRefundCompDef>,
+    ) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: format!("{}/batch_calculate_refund.arcis", CIRCUIT_BASE_URL),
+                hash: circuit_hash!("batch_calculate_refund"),
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_check_order_amend_comp_def(
+        ctx: Context<InitCheckOrderAmendCompDef>,
+    ) -> Result<()> {
+        init_comp_def(
+            ctx.accounts,
+            Some(CircuitSource::OffChain(OffChainCircuitSource {
+                source: format!("{}/check_order_amend.arcis", CIRCUIT_BASE_URL),
+                hash: circuit_hash!("check_order_amend"),
+            })),
+            None,
+        )?;
+        Ok(())
+    }
+
     // =============================================================
     // SPOT TRADING OPERATIONS
     // =============================================================
@@ -479,14 +603,25 @@ pub mod confidex_mxe {
 
     /// Queue fill amount calculation
     ///
+    /// Remaining size is computed in MPC as amount - filled for each order,
+    /// and the updated cumulative filled amounts are returned encrypted.
+    ///
     /// If buy_order and sell_order are provided, the callback will CPI to DEX.
     pub fn calculate_fill(
         ctx: Context<CalculateFill>,
         computation_offset: u64,
         buy_amount_ciphertext: [u8; 32],
+        buy_filled_ciphertext: [u8; 32],
         sell_amount_ciphertext: [u8; 32],
+        sell_filled_ciphertext: [u8; 32],
         buy_price_ciphertext: [u8; 32],
         sell_price_ciphertext: [u8; 32],
+        // false if the order has never been filled (filled ciphertext unset)
+        buy_has_fills: bool,
+        sell_has_fills: bool,
+        // true if the order's time-in-force is fill-or-kill
+        buy_fill_or_kill: bool,
+        sell_fill_or_kill: bool,
         pub_key: [u8; 32],
         nonce: u128,
         // Optional: DEX order pubkeys for CPI callback
@@ -497,9 +632,15 @@ pub mod confidex_mxe {
             .x25519_pubkey(pub_key)
             .plaintext_u128(nonce)
             .encrypted_u64(buy_amount_ciphertext)
+            .encrypted_u64(buy_filled_ciphertext)
             .encrypted_u64(sell_amount_ciphertext)
+            .encrypted_u64(sell_filled_ciphertext)
             .encrypted_u64(buy_price_ciphertext)
             .encrypted_u64(sell_price_ciphertext)
+            .plaintext_bool(buy_has_fills)
+            .plaintext_bool(sell_has_fills)
+            .plaintext_bool(buy_fill_or_kill)
+            .plaintext_bool(sell_fill_or_kill)
             .build();
 
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
@@ -571,17 +712,24 @@ pub mod confidex_mxe {
             }
         };
 
-        let fill_amount_ciphertext = result.ciphertexts[0];
-        let buy_fully_filled = result.ciphertexts[1][0] != 0;
-        let sell_fully_filled = result.ciphertexts[2][0] != 0;
+        // field_0 = encrypted (fill_amount, buy_filled, sell_filled)
+        // field_1..3 = revealed matched / buy_fully_filled / sell_fully_filled
+        let encrypted = result.field_0;
+        let matched = result.field_1;
+        let buy_fully_filled = result.field_2;
+        let sell_fully_filled = result.field_3;
+
+        let fill_amount_ciphertext = encrypted.ciphertexts[0];
+        let nonce = encrypted.nonce.to_le_bytes();
 
         // Emit event for monitoring
         emit!(FillCalculationResult {
             computation_offset: ctx.accounts.computation_account.key(),
             fill_amount_ciphertext,
+            matched,
             buy_fully_filled,
             sell_fully_filled,
-            nonce: result.nonce.to_le_bytes(),
+            nonce,
         });
 
         // If order accounts are provided, CPI to DEX to update orders
@@ -615,9 +763,16 @@ pub mod confidex_mxe {
 
             let request_id = ctx.accounts.computation_account.key().to_bytes();
 
-            // Build result: 64 bytes fill + 1 byte buy_filled + 1 byte sell_filled
-            let mut result_data = Vec::with_capacity(66);
-            result_data.extend_from_slice(&fill_amount_ciphertext);
+            // Build result: 3x 64-byte encrypted values (fill, buy_filled, sell_filled)
+            // + 1 byte matched + 1 byte buy_fully_filled + 1 byte sell_fully_filled
+            // Each value uses the DEX format: [nonce (16) | ciphertext (32) | pubkey (16)]
+            let mut result_data = Vec::with_capacity(64 * 3 + 3);
+            for ciphertext in encrypted.ciphertexts.iter() {
+                result_data.extend_from_slice(&nonce);
+                result_data.extend_from_slice(ciphertext);
+                result_data.extend_from_slice(&encrypted.encryption_key[0..16]);
+            }
+            result_data.push(if matched { 1u8 } else { 0u8 });
             result_data.push(if buy_fully_filled { 1u8 } else { 0u8 });
             result_data.push(if sell_fully_filled { 1u8 } else { 0u8 });
 
@@ -654,8 +809,8 @@ pub mod confidex_mxe {
                 signer_seeds,
             )?;
 
-            msg!("CPI to DEX finalize_match complete: buy_filled={}, sell_filled={}",
-                 buy_fully_filled, sell_fully_filled);
+            msg!("CPI to DEX finalize_match complete: matched={}, buy_filled={}, sell_filled={}",
+                 matched, buy_fully_filled, sell_fully_filled);
         }
 
         Ok(())
@@ -750,42 +905,92 @@ pub mod confidex_mxe {
 
     /// Queue batch fill calculation for up to 5 order pairs
     ///
-    /// More efficient than 5 separate calculate_fill calls.
+    /// More efficient than 5 separate calculate_fill calls. Each slot takes
+    /// the same inputs as calculate_fill (amounts, cumulative filled amounts,
+    /// prices, has_fills and fill-or-kill flags).
+    ///
+    /// If order_pairs is provided ([buy_0, sell_0, buy_1, sell_1, ...]), the
+    /// callback will CPI to DEX finalize_match_batch to update every order.
     pub fn batch_calculate_fill(
         ctx: Context<BatchCalculateFill>,
         computation_offset: u64,
         buy_amounts: [[u8; 32]; 5],
+        buy_filled: [[u8; 32]; 5],
         sell_amounts: [[u8; 32]; 5],
+        sell_filled: [[u8; 32]; 5],
         buy_prices: [[u8; 32]; 5],
         sell_prices: [[u8; 32]; 5],
+        // false if the order has never been filled (filled ciphertext unset)
+        buy_has_fills: [bool; 5],
+        sell_has_fills: [bool; 5],
+        // true if the order's time-in-force is fill-or-kill
+        buy_fill_or_kill: [bool; 5],
+        sell_fill_or_kill: [bool; 5],
+        count: u8,
         pub_key: [u8; 32],
         nonce: u128,
-        count: u8,
+        // Optional: DEX order pubkeys for CPI callback (empty = event only)
+        order_pairs: Vec<Pubkey>,
     ) -> Result<()> {
         require!(count > 0 && count <= 5, ErrorCode::AbortedComputation);
+        require!(
+            order_pairs.is_empty() || order_pairs.len() == 2 * count as usize,
+            ErrorCode::AbortedComputation
+        );
 
         let mut args = ArgBuilder::new()
             .x25519_pubkey(pub_key)
             .plaintext_u128(nonce);
 
-        // Add buy amounts, sell amounts, buy prices, sell prices
-        for amt in buy_amounts.iter() {
-            args = args.encrypted_u64(*amt);
-        }
-        for amt in sell_amounts.iter() {
-            args = args.encrypted_u64(*amt);
-        }
-        for price in buy_prices.iter() {
-            args = args.encrypted_u64(*price);
+        // Encrypted struct fields, in circuit order
+        for ciphertexts in [
+            &buy_amounts,
+            &buy_filled,
+            &sell_amounts,
+            &sell_filled,
+            &buy_prices,
+            &sell_prices,
+        ] {
+            for ciphertext in ciphertexts.iter() {
+                args = args.encrypted_u64(*ciphertext);
+            }
         }
-        for price in sell_prices.iter() {
-            args = args.encrypted_u64(*price);
+        // Plaintext per-slot flags, then count
+        for flags in [&buy_has_fills, &sell_has_fills, &buy_fill_or_kill, &sell_fill_or_kill] {
+            for flag in flags.iter() {
+                args = args.plaintext_bool(*flag);
+            }
         }
-        // Add count
         args = args.plaintext_u8(count);
 
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
+        // Build callback accounts - include DEX order accounts if provided
+        let mut callback_accounts = Vec::new();
+
+        if !order_pairs.is_empty() {
+            // MXE authority PDA for signing CPI to DEX
+            let (mxe_authority, _) = Pubkey::find_program_address(
+                &[MXE_AUTHORITY_SEED],
+                ctx.program_id,
+            );
+            callback_accounts.push(CallbackAccount {
+                pubkey: mxe_authority,
+                is_writable: false,
+            });
+            // CRITICAL: Include DEX program ID so callback can CPI to it
+            callback_accounts.push(CallbackAccount {
+                pubkey: DEX_PROGRAM_ID,
+                is_writable: false,
+            });
+            for order in order_pairs.iter() {
+                callback_accounts.push(CallbackAccount {
+                    pubkey: *order,
+                    is_writable: true,
+                });
+            }
+        }
+
         queue_computation(
             ctx.accounts,
             computation_offset,
@@ -794,7 +999,7 @@ pub mod confidex_mxe {
             vec![BatchCalculateFillCallback::callback_ix(
                 computation_offset,
                 &ctx.accounts.mxe_account,
-                &[], // No CPI callback - just emit event
+                &callback_accounts,
             )?],
             1,
             0,
@@ -804,6 +1009,9 @@ pub mod confidex_mxe {
     }
 
     /// Callback for batch fill calculation result
+    ///
+    /// SECURITY: This callback performs cryptographic verification via verify_output()
+    /// before CPI-ing to DEX. This ensures MPC results are authentic.
     #[arcium_callback(encrypted_ix = "batch_calculate_fill")]
     pub fn batch_calculate_fill_callback(
         ctx: Context<BatchCalculateFillCallback>,
@@ -820,41 +1028,121 @@ pub mod confidex_mxe {
             }
         };
 
-        // Extract fill amounts (encrypted)
-        let fills = [
-            result.ciphertexts[0],
-            result.ciphertexts[1],
-            result.ciphertexts[2],
-            result.ciphertexts[3],
-            result.ciphertexts[4],
+        // field_0 = encrypted (fills[5], buy_filled[5], sell_filled[5])
+        // field_1 = revealed per-pair flags (bit 0 matched, bit 1 buy full, bit 2 sell full)
+        let encrypted = result.field_0;
+        let flags = [
+            result.field_1.field_0,
+            result.field_1.field_1,
+            result.field_1.field_2,
+            result.field_1.field_3,
+            result.field_1.field_4,
         ];
+        let nonce = encrypted.nonce.to_le_bytes();
 
-        // Extract filled flags from ciphertexts array
-        // The struct has fills[5], buy_filled[5], sell_filled[5]
-        // After fills, we have 5 more for buy_filled, 5 more for sell_filled
-        let buy_filled = [
-            result.ciphertexts[5][0] != 0,
-            result.ciphertexts[6][0] != 0,
-            result.ciphertexts[7][0] != 0,
-            result.ciphertexts[8][0] != 0,
-            result.ciphertexts[9][0] != 0,
-        ];
-        let sell_filled = [
-            result.ciphertexts[10][0] != 0,
-            result.ciphertexts[11][0] != 0,
-            result.ciphertexts[12][0] != 0,
-            result.ciphertexts[13][0] != 0,
-            result.ciphertexts[14][0] != 0,
+        let fills = [
+            encrypted.ciphertexts[0],
+            encrypted.ciphertexts[1],
+            encrypted.ciphertexts[2],
+            encrypted.ciphertexts[3],
+            encrypted.ciphertexts[4],
         ];
 
         emit!(BatchFillCalculationResult {
             computation_offset: ctx.accounts.computation_account.key(),
             fills,
-            buy_filled,
-            sell_filled,
-            nonce: result.nonce.to_le_bytes(),
+            buy_filled: flags.map(|f| f & 0b010 != 0),
+            sell_filled: flags.map(|f| f & 0b100 != 0),
+            nonce,
         });
 
+        // If order accounts are provided, CPI to DEX to update all orders atomically
+        // remaining_accounts[0] = MXE authority account
+        // remaining_accounts[1] = DEX program (CRITICAL: needed for CPI target)
+        // remaining_accounts[2..] = buy_0, sell_0, buy_1, sell_1, ...
+        if ctx.remaining_accounts.len() >= 4 {
+            let mxe_authority_info = &ctx.remaining_accounts[0];
+            let dex_program_info = &ctx.remaining_accounts[1];
+            let order_infos = &ctx.remaining_accounts[2..];
+
+            require!(
+                order_infos.len() % 2 == 0 && order_infos.len() <= 10,
+                ErrorCode::AbortedComputation
+            );
+
+            // Verify DEX program matches expected
+            require!(
+                *dex_program_info.key == DEX_PROGRAM_ID,
+                ErrorCode::AbortedComputation
+            );
+
+            // Derive MXE authority PDA for signing
+            let (expected_mxe_authority, bump) = Pubkey::find_program_address(
+                &[MXE_AUTHORITY_SEED],
+                ctx.program_id,
+            );
+
+            require!(
+                *mxe_authority_info.key == expected_mxe_authority,
+                ErrorCode::AbortedComputation
+            );
+
+            let request_id = ctx.accounts.computation_account.key().to_bytes();
+            let pair_count = order_infos.len() / 2;
+
+            // Build one calculate_fill-format record per pair:
+            // fill (64) | buy_filled (64) | sell_filled (64) | matched | buy_full | sell_full
+            // Each value uses the DEX format: [nonce (16) | ciphertext (32) | pubkey (16)]
+            let mut result_data = Vec::with_capacity(pair_count * (64 * 3 + 3));
+            for (i, flag) in flags.iter().enumerate().take(pair_count) {
+                for ciphertext in [
+                    &encrypted.ciphertexts[i],
+                    &encrypted.ciphertexts[5 + i],
+                    &encrypted.ciphertexts[10 + i],
+                ] {
+                    result_data.extend_from_slice(&nonce);
+                    result_data.extend_from_slice(ciphertext);
+                    result_data.extend_from_slice(&encrypted.encryption_key[0..16]);
+                }
+                result_data.push(flag & 0b001);
+                result_data.push((flag >> 1) & 1);
+                result_data.push((flag >> 2) & 1);
+            }
+
+            // Build CPI data: [discriminator(8) | request_id(32) | result: Vec<u8>]
+            let mut ix_data = Vec::with_capacity(8 + 32 + 4 + result_data.len());
+            ix_data.extend_from_slice(&DEX_FINALIZE_MATCH_BATCH_DISCRIMINATOR);
+            ix_data.extend_from_slice(&request_id);
+            ix_data.extend_from_slice(&(result_data.len() as u32).to_le_bytes());
+            ix_data.extend_from_slice(&result_data);
+
+            let mut accounts = Vec::with_capacity(1 + order_infos.len());
+            accounts.push(AccountMeta::new_readonly(expected_mxe_authority, true)); // MXE authority (signer)
+            for order in order_infos.iter() {
+                accounts.push(AccountMeta::new(*order.key, false));
+            }
+
+            let ix = Instruction {
+                program_id: DEX_PROGRAM_ID,
+                accounts,
+                data: ix_data,
+            };
+
+            let mut account_infos = Vec::with_capacity(2 + order_infos.len());
+            account_infos.push(mxe_authority_info.clone());
+            for order in order_infos.iter() {
+                account_infos.push(order.clone());
+            }
+            account_infos.push(dex_program_info.clone());
+
+            let seeds: &[&[u8]] = &[MXE_AUTHORITY_SEED, &[bump]];
+            let signer_seeds = &[seeds];
+
+            invoke_signed(&ix, &account_infos, signer_seeds)?;
+
+            msg!("CPI to DEX finalize_match_batch complete: {} pairs", pair_count);
+        }
+
         Ok(())
     }
 
@@ -939,27 +1227,34 @@ pub mod confidex_mxe {
 
     /// Queue an order-specific balance check
     ///
-    /// For buy orders: checks balance >= (amount * price / PRICE_SCALE)
+    /// For buy orders: checks balance >= (amount * price / BASE_SCALE)
     /// For sell orders: checks balance >= amount
     ///
     /// This is more efficient than computing the required amount client-side
     /// and then calling check_balance, since it's all done in MPC.
+    ///
+    /// Queued by the DEX's place_order. The callback CPIs to the DEX's
+    /// place_order_callback to activate (and escrow) or reject the order.
     pub fn check_order_balance(
         ctx: Context<CheckOrderBalance>,
         computation_offset: u64,
-        balance_ciphertext: [u8; 32],
+        balance: u64,
         order_amount_ciphertext: [u8; 32],
         order_price_ciphertext: [u8; 32],
         is_buy: bool,
         pub_key: [u8; 32],
         nonce: u128,
-        // Optional: order account for CPI callback
-        order_account: Option<Pubkey>,
+        // Required: Place order accounts for CPI callback
+        order: Pubkey,
+        user_balance: Pubkey,
+        pair: Pubkey,
     ) -> Result<()> {
+        // User balances are still plaintext on the DEX (pre C-SPL),
+        // so the balance goes in as a plaintext input
         let args = ArgBuilder::new()
             .x25519_pubkey(pub_key)
             .plaintext_u128(nonce)
-            .encrypted_u64(balance_ciphertext)
+            .plaintext_u64(balance)
             .encrypted_u64(order_amount_ciphertext)
             .encrypted_u64(order_price_ciphertext)
             .plaintext_bool(is_buy)
@@ -967,13 +1262,18 @@ pub mod confidex_mxe {
 
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
-        let mut callback_accounts = Vec::new();
-        if let Some(order) = order_account {
-            callback_accounts.push(CallbackAccount {
-                pubkey: order,
-                is_writable: true,
-            });
-        }
+        // Build callback accounts for place order CPI
+        let (mxe_authority, _) = Pubkey::find_program_address(
+            &[MXE_AUTHORITY_SEED],
+            ctx.program_id,
+        );
+
+        let callback_accounts = vec![
+            CallbackAccount { pubkey: mxe_authority, is_writable: false },
+            CallbackAccount { pubkey: order, is_writable: true },
+            CallbackAccount { pubkey: user_balance, is_writable: true },
+            CallbackAccount { pubkey: pair, is_writable: true },
+        ];
 
         queue_computation(
             ctx.accounts,
@@ -993,13 +1293,15 @@ pub mod confidex_mxe {
     }
 
     /// Callback for order balance check result
+    ///
+    /// Receives revealed sufficient flag and escrow amount from MPC, then CPIs
+    /// to DEX place_order_callback to activate or reject the pending order.
     #[arcium_callback(encrypted_ix = "check_order_balance")]
     pub fn check_order_balance_callback(
         ctx: Context<CheckOrderBalanceCallback>,
         output: SignedComputationOutputs<CheckOrderBalanceOutput>,
     ) -> Result<()> {
-        // The result is a revealed bool
-        let sufficient = match output.verify_output(
+        let result = match output.verify_output(
             &ctx.accounts.cluster_account,
             &ctx.accounts.computation_account,
         ) {
@@ -1010,44 +1312,267 @@ pub mod confidex_mxe {
             }
         };
 
+        let sufficient = result.field_0;    // sufficient from struct
+        let escrow_amount = result.field_1; // escrow_amount from struct
+
+        // Emit minimal event (NO amounts for privacy)
         emit!(OrderBalanceCheckResult {
             computation_offset: ctx.accounts.computation_account.key(),
             sufficient,
         });
 
-        // TODO: If order account is provided and balance is insufficient,
-        // CPI to DEX to reject/cancel the order
+        // CPI to DEX place_order_callback with revealed values
+        // remaining_accounts[0] = MXE authority
+        // remaining_accounts[1] = order
+        // remaining_accounts[2] = user_balance
+        // remaining_accounts[3] = pair
+        if ctx.remaining_accounts.len() >= 4 {
+            let mxe_authority_info = &ctx.remaining_accounts[0];
+            let order = &ctx.remaining_accounts[1];
+            let user_balance = &ctx.remaining_accounts[2];
+            let pair = &ctx.remaining_accounts[3];
+
+            // Derive MXE authority PDA
+            let (expected_mxe_authority, bump) = Pubkey::find_program_address(
+                &[MXE_AUTHORITY_SEED],
+                ctx.program_id,
+            );
+
+            require!(
+                *mxe_authority_info.key == expected_mxe_authority,
+                ErrorCode::AbortedComputation
+            );
+
+            // Request ID = computation account (the DEX records it on the
+            // order when queuing, so out-of-band results are rejected)
+            let request_id = ctx.accounts.computation_account.key().to_bytes();
+
+            // Build CPI data: [discriminator(8) | request_id(32) | sufficient(1) | escrow_amount(8)]
+            let mut ix_data = Vec::with_capacity(49);
+            ix_data.extend_from_slice(&DEX_PLACE_ORDER_CALLBACK_DISCRIMINATOR);
+            ix_data.extend_from_slice(&request_id);
+            ix_data.push(if sufficient { 1 } else { 0 });
+            ix_data.extend_from_slice(&escrow_amount.to_le_bytes());
+
+            let ix = Instruction {
+                program_id: DEX_PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new_readonly(expected_mxe_authority, true), // MXE authority (signer)
+                    AccountMeta::new(*order.key, false),
+                    AccountMeta::new(*user_balance.key, false),
+                    AccountMeta::new(*pair.key, false),
+                ],
+                data: ix_data,
+            };
+
+            let seeds: &[&[u8]] = &[MXE_AUTHORITY_SEED, &[bump]];
+            let signer_seeds = &[seeds];
+
+            invoke_signed(
+                &ix,
+                &[
+                    mxe_authority_info.clone(),
+                    order.clone(),
+                    user_balance.clone(),
+                    pair.clone(),
+                ],
+                signer_seeds,
+            )?;
+
+            msg!("CPI to DEX place_order_callback complete");
+        } else {
+            msg!("Warning: Not enough remaining accounts for place order CPI");
+        }
 
         Ok(())
     }
 
-    // =============================================================
-    // SETTLEMENT OPERATIONS
-    // =============================================================
-
-    /// Queue settlement decryption
+    /// Queue balance check for an order amend (cancel-replace)
     ///
-    /// Reveals fill amount and price for settlement transfer calculation.
-    /// The callback will CPI to DEX with the revealed values.
-    ///
-    /// SECURITY: Only the MXE authority can trigger settlement, and values
-    /// are NOT emitted in events (only passed via CPI to DEX).
-    pub fn decrypt_for_settlement(
-        ctx: Context<DecryptForSettlement>,
+    /// Computes the escrow required by the replacement and checks it against
+    /// `available` (balance + the order's current escrow, both plaintext on
+    /// the DEX). The result is revealed and passed to amend_order_callback.
+    pub fn check_order_amend(
+        ctx: Context<CheckOrderAmend>,
         computation_offset: u64,
-        fill_ciphertext: [u8; 32],
-        price_ciphertext: [u8; 32],
+        new_amount_ciphertext: [u8; 32],
+        new_price_ciphertext: [u8; 32],
+        available: u64,
+        is_buy: bool,
         pub_key: [u8; 32],
         nonce: u128,
-        // Required: Settlement accounts for CPI callback
-        buy_order: Pubkey,
-        sell_order: Pubkey,
+        // Required: Amend order accounts for CPI callback
+        order: Pubkey,
+        user_balance: Pubkey,
         pair: Pubkey,
-        buyer_base_balance: Pubkey,
-        buyer_quote_balance: Pubkey,
+    ) -> Result<()> {
+        // User balances are still plaintext on the DEX (pre C-SPL),
+        // so the available amount goes in as a plaintext input
+        let args = ArgBuilder::new()
+            .x25519_pubkey(pub_key)
+            .plaintext_u128(nonce)
+            .encrypted_u64(new_amount_ciphertext)
+            .encrypted_u64(new_price_ciphertext)
+            .plaintext_u64(available)
+            .plaintext_bool(is_buy)
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        // Build callback accounts for amend order CPI
+        let (mxe_authority, _) = Pubkey::find_program_address(
+            &[MXE_AUTHORITY_SEED],
+            ctx.program_id,
+        );
+
+        let callback_accounts = vec![
+            CallbackAccount { pubkey: mxe_authority, is_writable: false },
+            CallbackAccount { pubkey: order, is_writable: true },
+            CallbackAccount { pubkey: user_balance, is_writable: true },
+            CallbackAccount { pubkey: pair, is_writable: true },
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![CheckOrderAmendCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &callback_accounts,
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    /// Callback for order amend check result
+    ///
+    /// Receives the revealed result and escrow amount from MPC, then CPIs
+    /// to DEX amend_order_callback to apply or reject the amend.
+    #[arcium_callback(encrypted_ix = "check_order_amend")]
+    pub fn check_order_amend_callback(
+        ctx: Context<CheckOrderAmendCallback>,
+        output: SignedComputationOutputs<CheckOrderAmendOutput>,
+    ) -> Result<()> {
+        let result = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CheckOrderAmendOutput { field_0 }) => field_0,
+            Err(e) => {
+                msg!("Order amend check verification failed: {}", e);
+                return Err(ErrorCode::AbortedComputation.into());
+            }
+        };
+
+        let sufficient = result.field_0;    // sufficient from struct
+        let escrow_amount = result.field_1; // escrow_amount from struct
+
+        // Emit minimal event (NO amounts for privacy)
+        emit!(OrderAmendCheckResult {
+            computation_offset: ctx.accounts.computation_account.key(),
+            sufficient,
+        });
+
+        // CPI to DEX amend_order_callback with revealed values
+        // remaining_accounts[0] = MXE authority
+        // remaining_accounts[1] = order
+        // remaining_accounts[2] = user_balance
+        // remaining_accounts[3] = pair
+        if ctx.remaining_accounts.len() >= 4 {
+            let mxe_authority_info = &ctx.remaining_accounts[0];
+            let order = &ctx.remaining_accounts[1];
+            let user_balance = &ctx.remaining_accounts[2];
+            let pair = &ctx.remaining_accounts[3];
+
+            // Derive MXE authority PDA
+            let (expected_mxe_authority, bump) = Pubkey::find_program_address(
+                &[MXE_AUTHORITY_SEED],
+                ctx.program_id,
+            );
+
+            require!(
+                *mxe_authority_info.key == expected_mxe_authority,
+                ErrorCode::AbortedComputation
+            );
+
+            // Request ID = computation account (the DEX records it on the
+            // order when queuing, so out-of-band results are rejected)
+            let request_id = ctx.accounts.computation_account.key().to_bytes();
+
+            // Build CPI data: [discriminator(8) | request_id(32) | sufficient(1) |
+            //                  escrow_amount(8)]
+            let mut ix_data = Vec::with_capacity(49);
+            ix_data.extend_from_slice(&DEX_AMEND_ORDER_CALLBACK_DISCRIMINATOR);
+            ix_data.extend_from_slice(&request_id);
+            ix_data.push(if sufficient { 1 } else { 0 });
+            ix_data.extend_from_slice(&escrow_amount.to_le_bytes());
+
+            let ix = Instruction {
+                program_id: DEX_PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new_readonly(expected_mxe_authority, true), // MXE authority (signer)
+                    AccountMeta::new(*order.key, false),
+                    AccountMeta::new(*user_balance.key, false),
+                    AccountMeta::new(*pair.key, false),
+                ],
+                data: ix_data,
+            };
+
+            let seeds: &[&[u8]] = &[MXE_AUTHORITY_SEED, &[bump]];
+            let signer_seeds = &[seeds];
+
+            invoke_signed(
+                &ix,
+                &[
+                    mxe_authority_info.clone(),
+                    order.clone(),
+                    user_balance.clone(),
+                    pair.clone(),
+                ],
+                signer_seeds,
+            )?;
+
+            msg!("CPI to DEX amend_order_callback complete");
+        } else {
+            msg!("Warning: Not enough remaining accounts for amend order CPI");
+        }
+
+        Ok(())
+    }
+
+    // =============================================================
+    // SETTLEMENT OPERATIONS
+    // =============================================================
+
+    /// Queue settlement decryption
+    ///
+    /// Reveals fill amount and price for settlement transfer calculation.
+    /// The callback will CPI to DEX with the revealed values.
+    ///
+    /// SECURITY: Only the MXE authority can trigger settlement, and values
+    /// are NOT emitted in events (only passed via CPI to DEX).
+    pub fn decrypt_for_settlement(
+        ctx: Context<DecryptForSettlement>,
+        computation_offset: u64,
+        fill_ciphertext: [u8; 32],
+        price_ciphertext: [u8; 32],
+        pub_key: [u8; 32],
+        nonce: u128,
+        // Required: Settlement accounts for CPI callback
+        buy_order: Pubkey,
+        sell_order: Pubkey,
+        pair: Pubkey,
+        buyer_base_balance: Pubkey,
+        buyer_quote_balance: Pubkey,
         seller_base_balance: Pubkey,
         seller_quote_balance: Pubkey,
         fee_recipient_balance: Pubkey,
+        fee_recipient_base_balance: Pubkey,
         exchange: Pubkey,
     ) -> Result<()> {
         let args = ArgBuilder::new()
@@ -1075,6 +1600,7 @@ pub mod confidex_mxe {
             CallbackAccount { pubkey: seller_base_balance, is_writable: true },
             CallbackAccount { pubkey: seller_quote_balance, is_writable: true },
             CallbackAccount { pubkey: fee_recipient_balance, is_writable: true },
+            CallbackAccount { pubkey: fee_recipient_base_balance, is_writable: true },
             CallbackAccount { pubkey: exchange, is_writable: false },
         ];
 
@@ -1134,9 +1660,10 @@ pub mod confidex_mxe {
         // remaining_accounts[5] = buyer_quote_balance
         // remaining_accounts[6] = seller_base_balance
         // remaining_accounts[7] = seller_quote_balance
-        // remaining_accounts[8] = fee_recipient_balance
-        // remaining_accounts[9] = exchange
-        if ctx.remaining_accounts.len() >= 10 {
+        // remaining_accounts[8] = fee_recipient_balance (quote)
+        // remaining_accounts[9] = fee_recipient_base_balance
+        // remaining_accounts[10] = exchange
+        if ctx.remaining_accounts.len() >= 11 {
             let mxe_authority_info = &ctx.remaining_accounts[0];
             let buy_order = &ctx.remaining_accounts[1];
             let sell_order = &ctx.remaining_accounts[2];
@@ -1146,7 +1673,8 @@ pub mod confidex_mxe {
             let seller_base_balance = &ctx.remaining_accounts[6];
             let seller_quote_balance = &ctx.remaining_accounts[7];
             let fee_recipient_balance = &ctx.remaining_accounts[8];
-            let exchange = &ctx.remaining_accounts[9];
+            let fee_recipient_base_balance = &ctx.remaining_accounts[9];
+            let exchange = &ctx.remaining_accounts[10];
 
             // Derive MXE authority PDA
             let (expected_mxe_authority, bump) = Pubkey::find_program_address(
@@ -1177,6 +1705,7 @@ pub mod confidex_mxe {
                     AccountMeta::new(*seller_base_balance.key, false),
                     AccountMeta::new(*seller_quote_balance.key, false),
                     AccountMeta::new(*fee_recipient_balance.key, false),
+                    AccountMeta::new(*fee_recipient_base_balance.key, false),
                     AccountMeta::new_readonly(*exchange.key, false),
                 ],
                 data: ix_data,
@@ -1197,6 +1726,7 @@ pub mod confidex_mxe {
                     seller_base_balance.clone(),
                     seller_quote_balance.clone(),
                     fee_recipient_balance.clone(),
+                    fee_recipient_base_balance.clone(),
                     exchange.clone(),
                 ],
                 signer_seeds,
@@ -1216,13 +1746,18 @@ pub mod confidex_mxe {
 
     /// Queue refund calculation for order cancellation
     ///
-    /// Computes: refund_amount = encrypted_amount - encrypted_filled
+    /// Computes the unfilled escrow: (amount - filled) for sells, or
+    /// (amount - filled) * price / 1e9 for buys.
     /// The result is revealed and passed to cancel_order_callback.
     pub fn calculate_refund(
         ctx: Context<CalculateRefund>,
         computation_offset: u64,
         amount_ciphertext: [u8; 32],
         filled_ciphertext: [u8; 32],
+        price_ciphertext: [u8; 32],
+        is_buy: bool,
+        // false if the order has never been filled (filled ciphertext unset)
+        has_fills: bool,
         pub_key: [u8; 32],
         nonce: u128,
         // Required: Cancel order accounts for CPI callback
@@ -1237,6 +1772,9 @@ pub mod confidex_mxe {
             .plaintext_u128(nonce)
             .encrypted_u64(amount_ciphertext)
             .encrypted_u64(filled_ciphertext)
+            .encrypted_u64(price_ciphertext)
+            .plaintext_bool(is_buy)
+            .plaintext_bool(has_fills)
             .build();
 
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
@@ -1330,9 +1868,14 @@ pub mod confidex_mxe {
                 ErrorCode::AbortedComputation
             );
 
-            // Build CPI data: [discriminator(8) | refund_amount(8)]
-            let mut ix_data = Vec::with_capacity(16);
+            // Request ID = computation account (the DEX records it on the
+            // order when queuing, so out-of-band refunds are rejected)
+            let request_id = ctx.accounts.computation_account.key().to_bytes();
+
+            // Build CPI data: [discriminator(8) | request_id(32) | refund_amount(8)]
+            let mut ix_data = Vec::with_capacity(48);
             ix_data.extend_from_slice(&DEX_CANCEL_ORDER_CALLBACK_DISCRIMINATOR);
+            ix_data.extend_from_slice(&request_id);
             ix_data.extend_from_slice(&refund_amount.to_le_bytes());
 
             let ix = Instruction {
@@ -1372,41 +1915,87 @@ pub mod confidex_mxe {
         Ok(())
     }
 
-    // =============================================================
-    // PERPETUALS OPERATIONS
-    // =============================================================
-
-    /// Queue position parameter verification
-    pub fn verify_position_params(
-        ctx: Context<VerifyPositionParams>,
+    /// Queue refund calculation for up to 5 orders of one maker on one pair
+    ///
+    /// Same computation as calculate_refund for each slot, in a single MPC
+    /// call. The revealed refunds are passed to the DEX
+    /// cancel_orders_batch_callback, which refunds every order atomically.
+    pub fn batch_calculate_refund(
+        ctx: Context<BatchCalculateRefund>,
         computation_offset: u64,
-        entry_price_ciphertext: [u8; 32],
-        leverage: u8,
-        mm_bps: u16,
-        is_long: bool,
+        amount_ciphertexts: [[u8; 32]; 5],
+        filled_ciphertexts: [[u8; 32]; 5],
+        price_ciphertexts: [[u8; 32]; 5],
+        is_buy: [bool; 5],
+        // false for orders that have never been filled (filled ciphertext unset)
+        has_fills: [bool; 5],
+        count: u8,
         pub_key: [u8; 32],
         nonce: u128,
+        // Required: Cancel accounts for CPI callback
+        orders: Vec<Pubkey>,
+        user_base_balance: Pubkey,
+        user_quote_balance: Pubkey,
+        pair: Pubkey,
+        exchange: Pubkey,
     ) -> Result<()> {
-        let args = ArgBuilder::new()
+        require!(count > 0 && count <= 5, ErrorCode::AbortedComputation);
+        require!(orders.len() == count as usize, ErrorCode::AbortedComputation);
+
+        let mut args = ArgBuilder::new()
             .x25519_pubkey(pub_key)
-            .plaintext_u128(nonce)
-            .encrypted_u64(entry_price_ciphertext)
-            .plaintext_u8(leverage)
-            .plaintext_u16(mm_bps)
-            .plaintext_bool(is_long)
-            .build();
+            .plaintext_u128(nonce);
+
+        for amount in amount_ciphertexts.iter() {
+            args = args.encrypted_u64(*amount);
+        }
+        for filled in filled_ciphertexts.iter() {
+            args = args.encrypted_u64(*filled);
+        }
+        for price in price_ciphertexts.iter() {
+            args = args.encrypted_u64(*price);
+        }
+        for flag in is_buy.iter() {
+            args = args.plaintext_bool(*flag);
+        }
+        for flag in has_fills.iter() {
+            args = args.plaintext_bool(*flag);
+        }
+        args = args.plaintext_u8(count);
 
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
+        // Build callback accounts for the batched cancel CPI
+        let (mxe_authority, _) = Pubkey::find_program_address(
+            &[MXE_AUTHORITY_SEED],
+            ctx.program_id,
+        );
+
+        let mut callback_accounts = vec![
+            CallbackAccount { pubkey: mxe_authority, is_writable: false },
+            // CRITICAL: Include DEX program ID so callback can CPI to it
+            CallbackAccount { pubkey: DEX_PROGRAM_ID, is_writable: false },
+            CallbackAccount { pubkey: user_base_balance, is_writable: true },
+            CallbackAccount { pubkey: user_quote_balance, is_writable: true },
+            CallbackAccount { pubkey: pair, is_writable: true },
+            CallbackAccount { pubkey: exchange, is_writable: false },
+        ];
+        for order in orders.iter() {
+            callback_accounts.push(CallbackAccount {
+                pubkey: *order,
+                is_writable: true,
+            });
+        }
+
         queue_computation(
             ctx.accounts,
             computation_offset,
-            args,
+            args.build(),
             None,
-            vec![VerifyPositionParamsCallback::callback_ix(
+            vec![BatchCalculateRefundCallback::callback_ix(
                 computation_offset,
                 &ctx.accounts.mxe_account,
-                &[],
+                &callback_accounts,
             )?],
             1,
             0,
@@ -1415,60 +2004,224 @@ pub mod confidex_mxe {
         Ok(())
     }
 
-    /// Callback for position parameter verification
-    #[arcium_callback(encrypted_ix = "verify_position_params")]
-    pub fn verify_position_params_callback(
-        ctx: Context<VerifyPositionParamsCallback>,
-        output: SignedComputationOutputs<VerifyPositionParamsOutput>,
+    /// Callback for batch refund calculation
+    ///
+    /// Receives the revealed refund amounts from MPC, then CPIs to DEX
+    /// cancel_orders_batch_callback to cancel every order in the batch.
+    #[arcium_callback(encrypted_ix = "batch_calculate_refund")]
+    pub fn batch_calculate_refund_callback(
+        ctx: Context<BatchCalculateRefundCallback>,
+        output: SignedComputationOutputs<BatchCalculateRefundOutput>,
     ) -> Result<()> {
         let result = match output.verify_output(
             &ctx.accounts.cluster_account,
             &ctx.accounts.computation_account,
         ) {
-            Ok(VerifyPositionParamsOutput { field_0 }) => field_0,
+            Ok(BatchCalculateRefundOutput { field_0 }) => field_0,
             Err(e) => {
-                msg!("Computation verification failed: {}", e);
+                msg!("Batch refund calculation verification failed: {}", e);
                 return Err(ErrorCode::AbortedComputation.into());
             }
         };
 
-        emit!(PositionParamsVerified {
+        let refunds = [
+            result.field_0,
+            result.field_1,
+            result.field_2,
+            result.field_3,
+            result.field_4,
+        ];
+
+        // Emit minimal event (NO amounts for privacy)
+        emit!(BatchRefundCalculationResult {
             computation_offset: ctx.accounts.computation_account.key(),
-            liq_threshold_ciphertext: result.ciphertexts[0],
-            nonce: result.nonce.to_le_bytes(),
         });
 
-        Ok(())
-    }
+        // CPI to DEX cancel_orders_batch_callback with revealed values
+        // remaining_accounts[0] = MXE authority
+        // remaining_accounts[1] = DEX program (CRITICAL: needed for CPI target)
+        // remaining_accounts[2] = user_base_balance
+        // remaining_accounts[3] = user_quote_balance
+        // remaining_accounts[4] = pair
+        // remaining_accounts[5] = exchange
+        // remaining_accounts[6..] = orders
+        if ctx.remaining_accounts.len() >= 7 {
+            let mxe_authority_info = &ctx.remaining_accounts[0];
+            let dex_program_info = &ctx.remaining_accounts[1];
+            let user_base_balance = &ctx.remaining_accounts[2];
+            let user_quote_balance = &ctx.remaining_accounts[3];
+            let pair = &ctx.remaining_accounts[4];
+            let exchange = &ctx.remaining_accounts[5];
+            let order_infos = &ctx.remaining_accounts[6..];
 
-    /// Queue single liquidation check
-    pub fn check_liquidation(
-        ctx: Context<CheckLiquidation>,
-        computation_offset: u64,
-        liq_threshold_ciphertext: [u8; 32],
-        mark_price: u64,
-        is_long: bool,
-        pub_key: [u8; 32],
-        nonce: u128,
-    ) -> Result<()> {
-        let args = ArgBuilder::new()
-            .x25519_pubkey(pub_key)
-            .plaintext_u128(nonce)
-            .encrypted_u64(liq_threshold_ciphertext)
-            .plaintext_u64(mark_price)
-            .plaintext_bool(is_long)
-            .build();
+            require!(order_infos.len() <= 5, ErrorCode::AbortedComputation);
 
-        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+            // Verify DEX program matches expected
+            require!(
+                *dex_program_info.key == DEX_PROGRAM_ID,
+                ErrorCode::AbortedComputation
+            );
 
-        queue_computation(
-            ctx.accounts,
-            computation_offset,
-            args,
-            None,
-            vec![CheckLiquidationCallback::callback_ix(
-                computation_offset,
-                &ctx.accounts.mxe_account,
+            // Derive MXE authority PDA
+            let (expected_mxe_authority, bump) = Pubkey::find_program_address(
+                &[MXE_AUTHORITY_SEED],
+                ctx.program_id,
+            );
+
+            require!(
+                *mxe_authority_info.key == expected_mxe_authority,
+                ErrorCode::AbortedComputation
+            );
+
+            let request_id = ctx.accounts.computation_account.key().to_bytes();
+            let order_count = order_infos.len();
+
+            // Build CPI data: [discriminator(8) | request_id(32) | refund_amounts: Vec<u64>]
+            let mut ix_data = Vec::with_capacity(8 + 32 + 4 + 8 * order_count);
+            ix_data.extend_from_slice(&DEX_CANCEL_ORDERS_BATCH_CALLBACK_DISCRIMINATOR);
+            ix_data.extend_from_slice(&request_id);
+            ix_data.extend_from_slice(&(order_count as u32).to_le_bytes());
+            for refund in refunds.iter().take(order_count) {
+                ix_data.extend_from_slice(&refund.to_le_bytes());
+            }
+
+            let mut accounts = Vec::with_capacity(5 + order_count);
+            accounts.push(AccountMeta::new_readonly(expected_mxe_authority, true)); // MXE authority (signer)
+            accounts.push(AccountMeta::new(*user_base_balance.key, false));
+            accounts.push(AccountMeta::new(*user_quote_balance.key, false));
+            accounts.push(AccountMeta::new(*pair.key, false));
+            accounts.push(AccountMeta::new_readonly(*exchange.key, false));
+            for order in order_infos.iter() {
+                accounts.push(AccountMeta::new(*order.key, false));
+            }
+
+            let ix = Instruction {
+                program_id: DEX_PROGRAM_ID,
+                accounts,
+                data: ix_data,
+            };
+
+            let mut account_infos = Vec::with_capacity(6 + order_count);
+            account_infos.push(mxe_authority_info.clone());
+            account_infos.push(user_base_balance.clone());
+            account_infos.push(user_quote_balance.clone());
+            account_infos.push(pair.clone());
+            account_infos.push(exchange.clone());
+            for order in order_infos.iter() {
+                account_infos.push(order.clone());
+            }
+            account_infos.push(dex_program_info.clone());
+
+            let seeds: &[&[u8]] = &[MXE_AUTHORITY_SEED, &[bump]];
+            let signer_seeds = &[seeds];
+
+            invoke_signed(&ix, &account_infos, signer_seeds)?;
+
+            msg!("CPI to DEX cancel_orders_batch_callback complete: {} orders", order_count);
+        } else {
+            msg!("Warning: Not enough remaining accounts for batch cancel CPI");
+        }
+
+        Ok(())
+    }
+
+    // =============================================================
+    // PERPETUALS OPERATIONS
+    // =============================================================
+
+    /// Queue position parameter verification
+    pub fn verify_position_params(
+        ctx: Context<VerifyPositionParams>,
+        computation_offset: u64,
+        entry_price_ciphertext: [u8; 32],
+        leverage: u8,
+        mm_bps: u16,
+        is_long: bool,
+        pub_key: [u8; 32],
+        nonce: u128,
+    ) -> Result<()> {
+        let args = ArgBuilder::new()
+            .x25519_pubkey(pub_key)
+            .plaintext_u128(nonce)
+            .encrypted_u64(entry_price_ciphertext)
+            .plaintext_u8(leverage)
+            .plaintext_u16(mm_bps)
+            .plaintext_bool(is_long)
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![VerifyPositionParamsCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &[],
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    /// Callback for position parameter verification
+    #[arcium_callback(encrypted_ix = "verify_position_params")]
+    pub fn verify_position_params_callback(
+        ctx: Context<VerifyPositionParamsCallback>,
+        output: SignedComputationOutputs<VerifyPositionParamsOutput>,
+    ) -> Result<()> {
+        let result = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(VerifyPositionParamsOutput { field_0 }) => field_0,
+            Err(e) => {
+                msg!("Computation verification failed: {}", e);
+                return Err(ErrorCode::AbortedComputation.into());
+            }
+        };
+
+        emit!(PositionParamsVerified {
+            computation_offset: ctx.accounts.computation_account.key(),
+            liq_threshold_ciphertext: result.ciphertexts[0],
+            nonce: result.nonce.to_le_bytes(),
+        });
+
+        Ok(())
+    }
+
+    /// Queue single liquidation check
+    pub fn check_liquidation(
+        ctx: Context<CheckLiquidation>,
+        computation_offset: u64,
+        liq_threshold_ciphertext: [u8; 32],
+        mark_price: u64,
+        is_long: bool,
+        pub_key: [u8; 32],
+        nonce: u128,
+    ) -> Result<()> {
+        let args = ArgBuilder::new()
+            .x25519_pubkey(pub_key)
+            .plaintext_u128(nonce)
+            .encrypted_u64(liq_threshold_ciphertext)
+            .plaintext_u64(mark_price)
+            .plaintext_bool(is_long)
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![CheckLiquidationCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
                 &[],
             )?],
             1,
@@ -1505,15 +2258,19 @@ pub mod confidex_mxe {
     }
 
     /// Queue batch liquidation check (up to 10 positions)
+    ///
+    /// Each position carries its own market's mark price, so one chunk may
+    /// span several markets. Results are written back to the DEX batch_request.
     pub fn batch_liquidation_check(
         ctx: Context<BatchLiquidationCheck>,
         computation_offset: u64,
         thresholds: [[u8; 32]; 10],
         is_long: [bool; 10],
         count: u8,
-        mark_price: u64,
+        mark_prices: [u64; 10],
         pub_key: [u8; 32],
         nonce: u128,
+        batch_request: Pubkey,
     ) -> Result<()> {
         let mut builder = ArgBuilder::new()
             .x25519_pubkey(pub_key)
@@ -1529,12 +2286,30 @@ pub mod confidex_mxe {
             builder = builder.plaintext_bool(*flag);
         }
 
-        builder = builder.plaintext_u8(count).plaintext_u64(mark_price);
+        builder = builder.plaintext_u8(count);
+
+        // Add all 10 mark prices
+        for price in mark_prices.iter() {
+            builder = builder.plaintext_u64(*price);
+        }
 
         let args = builder.build();
 
         ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
+        // Build callback accounts for DEX CPI
+        let (mxe_authority, _) = Pubkey::find_program_address(
+            &[MXE_AUTHORITY_SEED],
+            ctx.program_id,
+        );
+
+        let callback_accounts = vec![
+            CallbackAccount { pubkey: mxe_authority, is_writable: false },
+            CallbackAccount { pubkey: batch_request, is_writable: true },
+            // Include DEX program ID so callback can CPI to it
+            CallbackAccount { pubkey: DEX_PROGRAM_ID, is_writable: false },
+        ];
+
         queue_computation(
             ctx.accounts,
             computation_offset,
@@ -1543,7 +2318,7 @@ pub mod confidex_mxe {
             vec![BatchLiquidationCheckCallback::callback_ix(
                 computation_offset,
                 &ctx.accounts.mxe_account,
-                &[],
+                &callback_accounts,
             )?],
             1,
             0,
@@ -1584,6 +2359,66 @@ pub mod confidex_mxe {
             r9: results.field_9,
         });
 
+        // CPI to DEX liquidation_batch_callback
+        // remaining_accounts[0] = MXE authority
+        // remaining_accounts[1] = batch_request
+        // remaining_accounts[2] = DEX program
+        if ctx.remaining_accounts.len() >= 3 {
+            let mxe_authority_info = &ctx.remaining_accounts[0];
+            let dex_program_info = &ctx.remaining_accounts[2];
+
+            require!(
+                *dex_program_info.key == DEX_PROGRAM_ID,
+                ErrorCode::AbortedComputation
+            );
+
+            // Derive MXE authority PDA
+            let (expected_mxe_authority, bump) = Pubkey::find_program_address(
+                &[MXE_AUTHORITY_SEED],
+                ctx.program_id,
+            );
+
+            require!(
+                *mxe_authority_info.key == expected_mxe_authority,
+                ErrorCode::AbortedComputation
+            );
+
+            // Request ID = computation account (recorded per chunk by the DEX)
+            let request_id = ctx.accounts.computation_account.key().to_bytes();
+            let flags = [
+                results.field_0, results.field_1, results.field_2, results.field_3,
+                results.field_4, results.field_5, results.field_6, results.field_7,
+                results.field_8, results.field_9,
+            ];
+
+            // Build CPI data: [discriminator(8) | request_id(32) | results: Vec<bool>]
+            let mut ix_data = Vec::with_capacity(8 + 32 + 4 + flags.len());
+            ix_data.extend_from_slice(&DEX_LIQUIDATION_BATCH_CALLBACK_DISCRIMINATOR);
+            ix_data.extend_from_slice(&request_id);
+            ix_data.extend_from_slice(&(flags.len() as u32).to_le_bytes());
+            for flag in flags.iter() {
+                ix_data.push(if *flag { 1 } else { 0 });
+            }
+
+            let ix = Instruction {
+                program_id: DEX_PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new_readonly(expected_mxe_authority, true), // MXE authority (signer)
+                    AccountMeta::new(*ctx.remaining_accounts[1].key, false),
+                ],
+                data: ix_data,
+            };
+
+            let seeds: &[&[u8]] = &[MXE_AUTHORITY_SEED, &[bump]];
+            let signer_seeds = &[seeds];
+
+            invoke_signed(&ix, ctx.remaining_accounts, signer_seeds)?;
+
+            msg!("CPI to DEX liquidation_batch_callback complete");
+        } else {
+            msg!("Warning: Not enough remaining accounts for batch liquidation CPI");
+        }
+
         Ok(())
     }
 
@@ -1718,70 +2553,838 @@ pub mod confidex_mxe {
 
         Ok(())
     }
-}
+    /// Queue auto-deleverage settlement
+    ///
+    /// Closes min(bankrupt_size, target_size) of both positions at the
+    /// bankrupt position's bankruptcy price. The callback CPIs to the DEX
+    /// adl_pnl_callback, which updates both positions and pays the target
+    /// out of the collateral vault.
+    pub fn calculate_adl(
+        ctx: Context<CalculateAdl>,
+        computation_offset: u64,
+        bankrupt_size_ciphertext: [u8; 32],
+        bankrupt_entry_price_ciphertext: [u8; 32],
+        bankrupt_collateral_ciphertext: [u8; 32],
+        target_size_ciphertext: [u8; 32],
+        target_entry_price_ciphertext: [u8; 32],
+        target_collateral_ciphertext: [u8; 32],
+        bankrupt_is_long: bool,
+        pub_key: [u8; 32],
+        nonce: u128,
+        // Required: ADL settlement accounts for CPI callback
+        bankrupt_position: Pubkey,
+        target_position: Pubkey,
+        perp_market: Pubkey,
+        target_collateral_account: Pubkey,
+        collateral_vault: Pubkey,
+        vault_authority: Pubkey,
+        token_program: Pubkey,
+    ) -> Result<()> {
+        let args = ArgBuilder::new()
+            .x25519_pubkey(pub_key)
+            .plaintext_u128(nonce)
+            .encrypted_u64(bankrupt_size_ciphertext)
+            .encrypted_u64(bankrupt_entry_price_ciphertext)
+            .encrypted_u64(bankrupt_collateral_ciphertext)
+            .encrypted_u64(target_size_ciphertext)
+            .encrypted_u64(target_entry_price_ciphertext)
+            .encrypted_u64(target_collateral_ciphertext)
+            .plaintext_bool(bankrupt_is_long)
+            .build();
 
-// =============================================================
-// EVENTS
-// =============================================================
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
 
-#[event]
-pub struct PriceCompareResult {
-    pub computation_offset: Pubkey,
-    pub prices_match: bool,
-    pub nonce: [u8; 16],
-}
+        // Build callback accounts for ADL settlement CPI
+        let (mxe_authority, _) = Pubkey::find_program_address(
+            &[MXE_AUTHORITY_SEED],
+            ctx.program_id,
+        );
 
-#[event]
-pub struct FillCalculationResult {
-    pub computation_offset: Pubkey,
-    pub fill_amount_ciphertext: [u8; 32],
-    pub buy_fully_filled: bool,
-    pub sell_fully_filled: bool,
-    pub nonce: [u8; 16],
-}
+        let callback_accounts = vec![
+            CallbackAccount { pubkey: mxe_authority, is_writable: false },
+            CallbackAccount { pubkey: bankrupt_position, is_writable: true },
+            CallbackAccount { pubkey: target_position, is_writable: true },
+            CallbackAccount { pubkey: perp_market, is_writable: true },
+            CallbackAccount { pubkey: target_collateral_account, is_writable: true },
+            CallbackAccount { pubkey: collateral_vault, is_writable: true },
+            CallbackAccount { pubkey: vault_authority, is_writable: false },
+            CallbackAccount { pubkey: token_program, is_writable: false },
+            // Include DEX program ID so callback can CPI to it
+            CallbackAccount { pubkey: DEX_PROGRAM_ID, is_writable: false },
+        ];
 
-#[event]
-pub struct PositionParamsVerified {
-    pub computation_offset: Pubkey,
-    pub liq_threshold_ciphertext: [u8; 32],
-    pub nonce: [u8; 16],
-}
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![CalculateAdlCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &callback_accounts,
+            )?],
+            1,
+            0,
+        )?;
 
-#[event]
-pub struct LiquidationCheckResult {
-    pub computation_offset: Pubkey,
-    pub should_liquidate: bool,
-}
+        Ok(())
+    }
 
-#[event]
-pub struct BatchLiquidationResult {
-    pub computation_offset: Pubkey,
-    pub r0: bool,
-    pub r1: bool,
-    pub r2: bool,
-    pub r3: bool,
-    pub r4: bool,
-    pub r5: bool,
-    pub r6: bool,
-    pub r7: bool,
+    /// Callback for auto-deleverage settlement
+    ///
+    /// Receives the encrypted remaining sizes/collaterals and the revealed
+    /// payout, ADL size and close flags, then CPIs to DEX adl_pnl_callback.
+    #[arcium_callback(encrypted_ix = "calculate_adl")]
+    pub fn calculate_adl_callback(
+        ctx: Context<CalculateAdlCallback>,
+        output: SignedComputationOutputs<CalculateAdlOutput>,
+    ) -> Result<()> {
+        let result = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CalculateAdlOutput { field_0 }) => field_0,
+            Err(e) => {
+                msg!("ADL calculation verification failed: {}", e);
+                return Err(ErrorCode::AbortedComputation.into());
+            }
+        };
+
+        // field_0 = encrypted (bankrupt_size, bankrupt_collateral, target_size, target_collateral)
+        // field_1..4 = revealed target_payout / adl_size / bankrupt_fully_closed / target_fully_closed
+        let encrypted = result.field_0;
+        let target_payout = result.field_1;
+        let adl_size = result.field_2;
+        let bankrupt_fully_closed = result.field_3;
+        let target_fully_closed = result.field_4;
+
+        // Emit minimal event (NO amounts for privacy)
+        emit!(AdlCalculationResult {
+            computation_offset: ctx.accounts.computation_account.key(),
+            bankrupt_fully_closed,
+            target_fully_closed,
+        });
+
+        // CPI to DEX adl_pnl_callback
+        // remaining_accounts[0] = MXE authority
+        // remaining_accounts[1] = bankrupt_position
+        // remaining_accounts[2] = target_position
+        // remaining_accounts[3] = perp_market
+        // remaining_accounts[4] = target_collateral_account
+        // remaining_accounts[5] = collateral_vault
+        // remaining_accounts[6] = vault_authority
+        // remaining_accounts[7] = token_program
+        // remaining_accounts[8] = DEX program
+        if ctx.remaining_accounts.len() >= 9 {
+            let mxe_authority_info = &ctx.remaining_accounts[0];
+            let dex_program_info = &ctx.remaining_accounts[8];
+
+            require!(
+                *dex_program_info.key == DEX_PROGRAM_ID,
+                ErrorCode::AbortedComputation
+            );
+
+            // Derive MXE authority PDA
+            let (expected_mxe_authority, bump) = Pubkey::find_program_address(
+                &[MXE_AUTHORITY_SEED],
+                ctx.program_id,
+            );
+
+            require!(
+                *mxe_authority_info.key == expected_mxe_authority,
+                ErrorCode::AbortedComputation
+            );
+
+            // Request ID = computation account (the DEX records it on both
+            // positions when queuing, so out-of-band results are rejected)
+            let request_id = ctx.accounts.computation_account.key().to_bytes();
+            let nonce = encrypted.nonce.to_le_bytes();
+
+            // Build CPI data: [discriminator(8) | request_id(32) |
+            //                  4x encrypted value(64) | target_payout(8) |
+            //                  adl_size(8) | bankrupt_fully_closed(1) | target_fully_closed(1)]
+            // Each value uses the DEX format: [nonce (16) | ciphertext (32) | pubkey (16)]
+            let mut ix_data = Vec::with_capacity(8 + 32 + 64 * 4 + 8 + 8 + 2);
+            ix_data.extend_from_slice(&DEX_ADL_PNL_CALLBACK_DISCRIMINATOR);
+            ix_data.extend_from_slice(&request_id);
+            for ciphertext in encrypted.ciphertexts.iter() {
+                ix_data.extend_from_slice(&nonce);
+                ix_data.extend_from_slice(ciphertext);
+                ix_data.extend_from_slice(&encrypted.encryption_key[0..16]);
+            }
+            ix_data.extend_from_slice(&target_payout.to_le_bytes());
+            ix_data.extend_from_slice(&adl_size.to_le_bytes());
+            ix_data.push(if bankrupt_fully_closed { 1 } else { 0 });
+            ix_data.push(if target_fully_closed { 1 } else { 0 });
+
+            let ix = Instruction {
+                program_id: DEX_PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new_readonly(expected_mxe_authority, true), // MXE authority (signer)
+                    AccountMeta::new(*ctx.remaining_accounts[1].key, false),
+                    AccountMeta::new(*ctx.remaining_accounts[2].key, false),
+                    AccountMeta::new(*ctx.remaining_accounts[3].key, false),
+                    AccountMeta::new(*ctx.remaining_accounts[4].key, false),
+                    AccountMeta::new(*ctx.remaining_accounts[5].key, false),
+                    AccountMeta::new_readonly(*ctx.remaining_accounts[6].key, false),
+                    AccountMeta::new_readonly(*ctx.remaining_accounts[7].key, false),
+                ],
+                data: ix_data,
+            };
+
+            let seeds: &[&[u8]] = &[MXE_AUTHORITY_SEED, &[bump]];
+            let signer_seeds = &[seeds];
+
+            invoke_signed(&ix, ctx.remaining_accounts, signer_seeds)?;
+
+            msg!("CPI to DEX adl_pnl_callback complete: bankrupt_closed={}, target_closed={}",
+                 bankrupt_fully_closed, target_fully_closed);
+        } else {
+            msg!("Warning: Not enough remaining accounts for ADL settlement CPI");
+        }
+
+        Ok(())
+    }
+
+    /// Queue ADL candidate ranking (up to 10 positions of one market)
+    ///
+    /// Scores each position by leverage * unrealized profit ratio at the
+    /// mark price. The callback CPIs the revealed ranks to the DEX
+    /// adl_ranking_callback, which rebuilds the market's ADL queues.
+    pub fn rank_adl_candidates(
+        ctx: Context<RankAdlCandidates>,
+        computation_offset: u64,
+        size_ciphertexts: [[u8; 32]; 10],
+        entry_price_ciphertexts: [[u8; 32]; 10],
+        collateral_ciphertexts: [[u8; 32]; 10],
+        is_long: [bool; 10],
+        count: u8,
+        mark_price: u64,
+        pub_key: [u8; 32],
+        nonce: u128,
+        // Required: ranking account for CPI callback
+        adl_ranking: Pubkey,
+    ) -> Result<()> {
+        require!(count > 0 && count <= 10, ErrorCode::AbortedComputation);
+
+        let mut args = ArgBuilder::new()
+            .x25519_pubkey(pub_key)
+            .plaintext_u128(nonce);
+
+        for size in size_ciphertexts.iter() {
+            args = args.encrypted_u64(*size);
+        }
+        for entry_price in entry_price_ciphertexts.iter() {
+            args = args.encrypted_u64(*entry_price);
+        }
+        for collateral in collateral_ciphertexts.iter() {
+            args = args.encrypted_u64(*collateral);
+        }
+        for flag in is_long.iter() {
+            args = args.plaintext_bool(*flag);
+        }
+        args = args.plaintext_u8(count).plaintext_u64(mark_price);
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        // Build callback accounts for the ranking CPI
+        let (mxe_authority, _) = Pubkey::find_program_address(
+            &[MXE_AUTHORITY_SEED],
+            ctx.program_id,
+        );
+
+        let callback_accounts = vec![
+            CallbackAccount { pubkey: mxe_authority, is_writable: false },
+            CallbackAccount { pubkey: adl_ranking, is_writable: true },
+            // Include DEX program ID so callback can CPI to it
+            CallbackAccount { pubkey: DEX_PROGRAM_ID, is_writable: false },
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args.build(),
+            None,
+            vec![RankAdlCandidatesCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &callback_accounts,
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    /// Callback for ADL candidate ranking
+    ///
+    /// Receives the revealed rank of each slot (0 = not eligible), then CPIs
+    /// to DEX adl_ranking_callback.
+    #[arcium_callback(encrypted_ix = "rank_adl_candidates")]
+    pub fn rank_adl_candidates_callback(
+        ctx: Context<RankAdlCandidatesCallback>,
+        output: SignedComputationOutputs<RankAdlCandidatesOutput>,
+    ) -> Result<()> {
+        let result = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(RankAdlCandidatesOutput { field_0 }) => field_0,
+            Err(e) => {
+                msg!("ADL ranking verification failed: {}", e);
+                return Err(ErrorCode::AbortedComputation.into());
+            }
+        };
+
+        let ranks = [
+            result.field_0,
+            result.field_1,
+            result.field_2,
+            result.field_3,
+            result.field_4,
+            result.field_5,
+            result.field_6,
+            result.field_7,
+            result.field_8,
+            result.field_9,
+        ];
+
+        emit!(AdlRankingResult {
+            computation_offset: ctx.accounts.computation_account.key(),
+        });
+
+        // CPI to DEX adl_ranking_callback
+        // remaining_accounts[0] = MXE authority
+        // remaining_accounts[1] = adl_ranking
+        // remaining_accounts[2] = DEX program
+        if ctx.remaining_accounts.len() >= 3 {
+            let mxe_authority_info = &ctx.remaining_accounts[0];
+            let dex_program_info = &ctx.remaining_accounts[2];
+
+            require!(
+                *dex_program_info.key == DEX_PROGRAM_ID,
+                ErrorCode::AbortedComputation
+            );
+
+            // Derive MXE authority PDA
+            let (expected_mxe_authority, bump) = Pubkey::find_program_address(
+                &[MXE_AUTHORITY_SEED],
+                ctx.program_id,
+            );
+
+            require!(
+                *mxe_authority_info.key == expected_mxe_authority,
+                ErrorCode::AbortedComputation
+            );
+
+            let request_id = ctx.accounts.computation_account.key().to_bytes();
+
+            // Build CPI data: [discriminator(8) | request_id(32) | ranks(10)]
+            let mut ix_data = Vec::with_capacity(8 + 32 + 10);
+            ix_data.extend_from_slice(&DEX_ADL_RANKING_CALLBACK_DISCRIMINATOR);
+            ix_data.extend_from_slice(&request_id);
+            ix_data.extend_from_slice(&ranks);
+
+            let ix = Instruction {
+                program_id: DEX_PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new_readonly(expected_mxe_authority, true), // MXE authority (signer)
+                    AccountMeta::new(*ctx.remaining_accounts[1].key, false),
+                ],
+                data: ix_data,
+            };
+
+            let seeds: &[&[u8]] = &[MXE_AUTHORITY_SEED, &[bump]];
+            let signer_seeds = &[seeds];
+
+            invoke_signed(&ix, ctx.remaining_accounts, signer_seeds)?;
+
+            msg!("CPI to DEX adl_ranking_callback complete");
+        } else {
+            msg!("Warning: Not enough remaining accounts for ADL ranking CPI");
+        }
+
+        Ok(())
+    }
+
+    /// Queue partial liquidation of a position
+    ///
+    /// Computes equity at the mark price including unrealized PnL and accrued
+    /// funding, then closes the minimum size that restores maintenance margin
+    /// (capped at max_close_notional). The callback CPIs to the DEX
+    /// partial_liquidation_callback, which updates the position, pays the
+    /// liquidator, insurance fund and trader, and covers bad debt from the
+    /// insurance fund.
+    pub fn calculate_partial_liquidation(
+        ctx: Context<CalculatePartialLiquidation>,
+        computation_offset: u64,
+        size_ciphertext: [u8; 32],
+        entry_price_ciphertext: [u8; 32],
+        collateral_ciphertext: [u8; 32],
+        is_long: bool,
+        mark_price: u64,
+        mm_bps: u16,
+        bonus_bps: u16,
+        max_close_notional: u64,
+        min_remaining_notional: u64,
+        funding_e8: u64,
+        funding_is_paying: bool,
+        pub_key: [u8; 32],
+        nonce: u128,
+        // Required: liquidation accounts for CPI callback
+        position: Pubkey,
+        perp_market: Pubkey,
+        liquidation_config: Pubkey,
+        collateral_vault: Pubkey,
+        insurance_fund: Pubkey,
+        vault_authority: Pubkey,
+        token_program: Pubkey,
+        liquidator_balance: Pubkey,
+        trader_balance: Pubkey,
+    ) -> Result<()> {
+        let args = ArgBuilder::new()
+            .x25519_pubkey(pub_key)
+            .plaintext_u128(nonce)
+            .encrypted_u64(size_ciphertext)
+            .encrypted_u64(entry_price_ciphertext)
+            .encrypted_u64(collateral_ciphertext)
+            .plaintext_bool(is_long)
+            .plaintext_u64(mark_price)
+            .plaintext_u16(mm_bps)
+            .plaintext_u16(bonus_bps)
+            .plaintext_u64(max_close_notional)
+            .plaintext_u64(min_remaining_notional)
+            .plaintext_u64(funding_e8)
+            .plaintext_bool(funding_is_paying)
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        // Build callback accounts for liquidation CPI
+        let (mxe_authority, _) = Pubkey::find_program_address(
+            &[MXE_AUTHORITY_SEED],
+            ctx.program_id,
+        );
+
+        let callback_accounts = vec![
+            CallbackAccount { pubkey: mxe_authority, is_writable: false },
+            CallbackAccount { pubkey: position, is_writable: true },
+            CallbackAccount { pubkey: perp_market, is_writable: true },
+            CallbackAccount { pubkey: liquidation_config, is_writable: true },
+            CallbackAccount { pubkey: collateral_vault, is_writable: true },
+            CallbackAccount { pubkey: insurance_fund, is_writable: true },
+            CallbackAccount { pubkey: vault_authority, is_writable: false },
+            CallbackAccount { pubkey: token_program, is_writable: false },
+            CallbackAccount { pubkey: liquidator_balance, is_writable: true },
+            CallbackAccount { pubkey: trader_balance, is_writable: true },
+            // Include DEX program ID so callback can CPI to it
+            CallbackAccount { pubkey: DEX_PROGRAM_ID, is_writable: false },
+        ];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            None,
+            vec![CalculatePartialLiquidationCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &callback_accounts,
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    /// Callback for partial liquidation
+    ///
+    /// Receives the encrypted remaining size/collateral/threshold and the
+    /// revealed close size, liquidator bonus, released collateral, bad debt
+    /// and close flag, then CPIs to DEX partial_liquidation_callback.
+    #[arcium_callback(encrypted_ix = "calculate_partial_liquidation")]
+    pub fn calculate_partial_liquidation_callback(
+        ctx: Context<CalculatePartialLiquidationCallback>,
+        output: SignedComputationOutputs<CalculatePartialLiquidationOutput>,
+    ) -> Result<()> {
+        let result = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(CalculatePartialLiquidationOutput { field_0 }) => field_0,
+            Err(e) => {
+                msg!("Partial liquidation verification failed: {}", e);
+                return Err(ErrorCode::AbortedComputation.into());
+            }
+        };
+
+        // field_0 = encrypted (size, collateral, liq_threshold)
+        // field_1..5 = revealed close_size / liquidator_bonus / released_collateral /
+        //              bad_debt / fully_closed
+        let encrypted = result.field_0;
+        let close_size = result.field_1;
+        let liquidator_bonus = result.field_2;
+        let released_collateral = result.field_3;
+        let bad_debt = result.field_4;
+        let fully_closed = result.field_5;
+
+        // Emit minimal event (NO amounts for privacy)
+        emit!(PartialLiquidationResult {
+            computation_offset: ctx.accounts.computation_account.key(),
+            fully_closed,
+        });
+
+        // CPI to DEX partial_liquidation_callback
+        // remaining_accounts[0] = MXE authority
+        // remaining_accounts[1] = position
+        // remaining_accounts[2] = perp_market
+        // remaining_accounts[3] = liquidation_config
+        // remaining_accounts[4] = collateral_vault
+        // remaining_accounts[5] = insurance_fund
+        // remaining_accounts[6] = vault_authority
+        // remaining_accounts[7] = token_program
+        // remaining_accounts[8] = liquidator_balance
+        // remaining_accounts[9] = trader_balance
+        // remaining_accounts[10] = DEX program
+        if ctx.remaining_accounts.len() >= 11 {
+            let mxe_authority_info = &ctx.remaining_accounts[0];
+            let dex_program_info = &ctx.remaining_accounts[10];
+
+            require!(
+                *dex_program_info.key == DEX_PROGRAM_ID,
+                ErrorCode::AbortedComputation
+            );
+
+            // Derive MXE authority PDA
+            let (expected_mxe_authority, bump) = Pubkey::find_program_address(
+                &[MXE_AUTHORITY_SEED],
+                ctx.program_id,
+            );
+
+            require!(
+                *mxe_authority_info.key == expected_mxe_authority,
+                ErrorCode::AbortedComputation
+            );
+
+            // Request ID = computation account (recorded on the position by the DEX)
+            let request_id = ctx.accounts.computation_account.key().to_bytes();
+            let nonce = encrypted.nonce.to_le_bytes();
+
+            // Build CPI data: [discriminator(8) | request_id(32) |
+            //                  3x encrypted value(64) | close_size(8) |
+            //                  liquidator_bonus(8) | released_collateral(8) |
+            //                  bad_debt(8) | fully_closed(1)]
+            // Each value uses the DEX format: [nonce (16) | ciphertext (32) | pubkey (16)]
+            let mut ix_data = Vec::with_capacity(8 + 32 + 64 * 3 + 8 * 4 + 1);
+            ix_data.extend_from_slice(&DEX_PARTIAL_LIQUIDATION_CALLBACK_DISCRIMINATOR);
+            ix_data.extend_from_slice(&request_id);
+            for ciphertext in encrypted.ciphertexts.iter() {
+                ix_data.extend_from_slice(&nonce);
+                ix_data.extend_from_slice(ciphertext);
+                ix_data.extend_from_slice(&encrypted.encryption_key[0..16]);
+            }
+            ix_data.extend_from_slice(&close_size.to_le_bytes());
+            ix_data.extend_from_slice(&liquidator_bonus.to_le_bytes());
+            ix_data.extend_from_slice(&released_collateral.to_le_bytes());
+            ix_data.extend_from_slice(&bad_debt.to_le_bytes());
+            ix_data.push(if fully_closed { 1 } else { 0 });
+
+            let ix = Instruction {
+                program_id: DEX_PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new_readonly(expected_mxe_authority, true), // MXE authority (signer)
+                    AccountMeta::new(*ctx.remaining_accounts[1].key, false),
+                    AccountMeta::new(*ctx.remaining_accounts[2].key, false),
+                    AccountMeta::new(*ctx.remaining_accounts[3].key, false),
+                    AccountMeta::new(*ctx.remaining_accounts[4].key, false),
+                    AccountMeta::new(*ctx.remaining_accounts[5].key, false),
+                    AccountMeta::new_readonly(*ctx.remaining_accounts[6].key, false),
+                    AccountMeta::new_readonly(*ctx.remaining_accounts[7].key, false),
+                    AccountMeta::new(*ctx.remaining_accounts[8].key, false),
+                    AccountMeta::new(*ctx.remaining_accounts[9].key, false),
+                ],
+                data: ix_data,
+            };
+
+            let seeds: &[&[u8]] = &[MXE_AUTHORITY_SEED, &[bump]];
+            let signer_seeds = &[seeds];
+
+            invoke_signed(&ix, ctx.remaining_accounts, signer_seeds)?;
+
+            msg!("CPI to DEX partial_liquidation_callback complete: fully_closed={}", fully_closed);
+        } else {
+            msg!("Warning: Not enough remaining accounts for partial liquidation CPI");
+        }
+
+        Ok(())
+    }
+
+    /// Queue batch stop-loss / take-profit check (up to 10 positions, one market)
+    ///
+    /// Only whether each position's trigger fired is revealed. The callback
+    /// marks fired positions on the DEX so any keeper can close them.
+    pub fn batch_exit_trigger_check(
+        ctx: Context<BatchExitTriggerCheck>,
+        computation_offset: u64,
+        stop_losses: [[u8; 32]; 10],
+        take_profits: [[u8; 32]; 10],
+        has_stop_loss: [bool; 10],
+        has_take_profit: [bool; 10],
+        is_long: [bool; 10],
+        count: u8,
+        mark_price: u64,
+        pub_key: [u8; 32],
+        nonce: u128,
+        // Required: Position accounts for CPI callback
+        positions: Vec<Pubkey>,
+    ) -> Result<()> {
+        require!(count > 0 && count <= 10, ErrorCode::AbortedComputation);
+        require!(positions.len() == count as usize, ErrorCode::AbortedComputation);
+
+        let mut args = ArgBuilder::new()
+            .x25519_pubkey(pub_key)
+            .plaintext_u128(nonce);
+
+        for stop_loss in stop_losses.iter() {
+            args = args.encrypted_u64(*stop_loss);
+        }
+        for take_profit in take_profits.iter() {
+            args = args.encrypted_u64(*take_profit);
+        }
+        for flag in has_stop_loss.iter() {
+            args = args.plaintext_bool(*flag);
+        }
+        for flag in has_take_profit.iter() {
+            args = args.plaintext_bool(*flag);
+        }
+        for flag in is_long.iter() {
+            args = args.plaintext_bool(*flag);
+        }
+        args = args.plaintext_u8(count).plaintext_u64(mark_price);
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        // Build callback accounts for the exit trigger CPI
+        let (mxe_authority, _) = Pubkey::find_program_address(
+            &[MXE_AUTHORITY_SEED],
+            ctx.program_id,
+        );
+
+        let mut callback_accounts = vec![
+            CallbackAccount { pubkey: mxe_authority, is_writable: false },
+            // Include DEX program ID so callback can CPI to it
+            CallbackAccount { pubkey: DEX_PROGRAM_ID, is_writable: false },
+        ];
+        for position in positions.iter() {
+            callback_accounts.push(CallbackAccount {
+                pubkey: *position,
+                is_writable: true,
+            });
+        }
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args.build(),
+            None,
+            vec![BatchExitTriggerCheckCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &callback_accounts,
+            )?],
+            1,
+            0,
+        )?;
+
+        Ok(())
+    }
+
+    /// Callback for batch exit trigger check
+    ///
+    /// Receives the revealed trigger flags from MPC, then CPIs to DEX
+    /// exit_trigger_callback to mark fired positions.
+    #[arcium_callback(encrypted_ix = "batch_exit_trigger_check")]
+    pub fn batch_exit_trigger_check_callback(
+        ctx: Context<BatchExitTriggerCheckCallback>,
+        output: SignedComputationOutputs<BatchExitTriggerCheckOutput>,
+    ) -> Result<()> {
+        let results = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(BatchExitTriggerCheckOutput { field_0 }) => field_0,
+            Err(e) => {
+                msg!("Exit trigger check verification failed: {}", e);
+                return Err(ErrorCode::AbortedComputation.into());
+            }
+        };
+
+        let fired = [
+            results.field_0, results.field_1, results.field_2, results.field_3,
+            results.field_4, results.field_5, results.field_6, results.field_7,
+            results.field_8, results.field_9,
+        ];
+
+        emit!(BatchExitTriggerResult {
+            computation_offset: ctx.accounts.computation_account.key(),
+            fired_count: fired.iter().filter(|&&f| f).count() as u8,
+        });
+
+        // CPI to DEX exit_trigger_callback
+        // remaining_accounts[0] = MXE authority
+        // remaining_accounts[1] = DEX program
+        // remaining_accounts[2..] = positions
+        if ctx.remaining_accounts.len() >= 3 {
+            let mxe_authority_info = &ctx.remaining_accounts[0];
+            let dex_program_info = &ctx.remaining_accounts[1];
+            let position_infos = &ctx.remaining_accounts[2..];
+
+            require!(position_infos.len() <= 10, ErrorCode::AbortedComputation);
+
+            require!(
+                *dex_program_info.key == DEX_PROGRAM_ID,
+                ErrorCode::AbortedComputation
+            );
+
+            // Derive MXE authority PDA
+            let (expected_mxe_authority, bump) = Pubkey::find_program_address(
+                &[MXE_AUTHORITY_SEED],
+                ctx.program_id,
+            );
+
+            require!(
+                *mxe_authority_info.key == expected_mxe_authority,
+                ErrorCode::AbortedComputation
+            );
+
+            // Request ID = computation account (recorded on each position by the DEX)
+            let request_id = ctx.accounts.computation_account.key().to_bytes();
+            let position_count = position_infos.len();
+
+            // Build CPI data: [discriminator(8) | request_id(32) | fired: Vec<bool>]
+            let mut ix_data = Vec::with_capacity(8 + 32 + 4 + position_count);
+            ix_data.extend_from_slice(&DEX_EXIT_TRIGGER_CALLBACK_DISCRIMINATOR);
+            ix_data.extend_from_slice(&request_id);
+            ix_data.extend_from_slice(&(position_count as u32).to_le_bytes());
+            for flag in fired.iter().take(position_count) {
+                ix_data.push(if *flag { 1 } else { 0 });
+            }
+
+            let mut accounts = Vec::with_capacity(1 + position_count);
+            accounts.push(AccountMeta::new_readonly(expected_mxe_authority, true)); // MXE authority (signer)
+            for position in position_infos.iter() {
+                accounts.push(AccountMeta::new(*position.key, false));
+            }
+
+            let ix = Instruction {
+                program_id: DEX_PROGRAM_ID,
+                accounts,
+                data: ix_data,
+            };
+
+            let seeds: &[&[u8]] = &[MXE_AUTHORITY_SEED, &[bump]];
+            let signer_seeds = &[seeds];
+
+            invoke_signed(&ix, ctx.remaining_accounts, signer_seeds)?;
+
+            msg!("CPI to DEX exit_trigger_callback complete: {} positions", position_count);
+        } else {
+            msg!("Warning: Not enough remaining accounts for exit trigger CPI");
+        }
+
+        Ok(())
+    }
+}
+
+// =============================================================
+// EVENTS
+// =============================================================
+
+#[event]
+pub struct PriceCompareResult {
+    pub computation_offset: Pubkey,
+    pub prices_match: bool,
+    pub nonce: [u8; 16],
+}
+
+#[event]
+pub struct FillCalculationResult {
+    pub computation_offset: Pubkey,
+    pub fill_amount_ciphertext: [u8; 32],
+    pub matched: bool,
+    pub buy_fully_filled: bool,
+    pub sell_fully_filled: bool,
+    pub nonce: [u8; 16],
+}
+
+#[event]
+pub struct PositionParamsVerified {
+    pub computation_offset: Pubkey,
+    pub liq_threshold_ciphertext: [u8; 32],
+    pub nonce: [u8; 16],
+}
+
+#[event]
+pub struct LiquidationCheckResult {
+    pub computation_offset: Pubkey,
+    pub should_liquidate: bool,
+}
+
+#[event]
+pub struct BatchLiquidationResult {
+    pub computation_offset: Pubkey,
+    pub r0: bool,
+    pub r1: bool,
+    pub r2: bool,
+    pub r3: bool,
+    pub r4: bool,
+    pub r5: bool,
+    pub r6: bool,
+    pub r7: bool,
     pub r8: bool,
     pub r9: bool,
 }
 
 #[event]
-pub struct PnlCalculationResult {
+pub struct PnlCalculationResult {
+    pub computation_offset: Pubkey,
+    pub pnl_ciphertext: [u8; 32],
+    pub is_loss: bool,
+    pub nonce: [u8; 16],
+}
+
+#[event]
+pub struct FundingCalculationResult {
+    pub computation_offset: Pubkey,
+    pub funding_amount_ciphertext: [u8; 32],
+    pub is_paying: bool,
+    pub nonce: [u8; 16],
+}
+
+#[event]
+pub struct AdlCalculationResult {
+    pub computation_offset: Pubkey,
+    pub bankrupt_fully_closed: bool,
+    pub target_fully_closed: bool,
+}
+
+#[event]
+pub struct AdlRankingResult {
+    pub computation_offset: Pubkey,
+}
+
+#[event]
+pub struct PartialLiquidationResult {
     pub computation_offset: Pubkey,
-    pub pnl_ciphertext: [u8; 32],
-    pub is_loss: bool,
-    pub nonce: [u8; 16],
+    pub fully_closed: bool,
 }
 
 #[event]
-pub struct FundingCalculationResult {
+pub struct BatchExitTriggerResult {
     pub computation_offset: Pubkey,
-    pub funding_amount_ciphertext: [u8; 32],
-    pub is_paying: bool,
-    pub nonce: [u8; 16],
+    pub fired_count: u8,
 }
 
 #[event]
@@ -1796,6 +3399,12 @@ pub struct OrderBalanceCheckResult {
     pub sufficient: bool,
 }
 
+#[event]
+pub struct OrderAmendCheckResult {
+    pub computation_offset: Pubkey,
+    pub sufficient: bool,
+}
+
 #[event]
 pub struct SettlementDecryptionResult {
     /// Computation account key (no amounts for privacy)
@@ -1810,6 +3419,12 @@ pub struct RefundCalculationResult {
     pub had_fills: bool,
 }
 
+#[event]
+pub struct BatchRefundCalculationResult {
+    /// Computation account key (no amounts for privacy)
+    pub computation_offset: Pubkey,
+}
+
 #[event]
 pub struct BatchPriceCompareResult {
     /// Computation account key
@@ -1947,6 +3562,62 @@ pub struct InitCalculateFundingCompDef<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[init_computation_definition_accounts("calculate_adl", payer)]
+#[derive(Accounts)]
+pub struct InitCalculateAdlCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    /// CHECK: comp_def_account initialized via CPI
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("rank_adl_candidates", payer)]
+#[derive(Accounts)]
+pub struct InitRankAdlCandidatesCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    /// CHECK: comp_def_account initialized via CPI
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("calculate_partial_liquidation", payer)]
+#[derive(Accounts)]
+pub struct InitCalculatePartialLiquidationCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    /// CHECK: comp_def_account initialized via CPI
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("batch_exit_trigger_check", payer)]
+#[derive(Accounts)]
+pub struct InitBatchExitTriggerCheckCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    /// CHECK: comp_def_account initialized via CPI
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
 #[init_computation_definition_accounts("add_encrypted", payer)]
 #[derive(Accounts)]
 pub struct InitAddEncryptedCompDef<'info> {
@@ -2017,6 +3688,20 @@ pub struct InitCheckOrderBalanceCompDef<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[init_computation_definition_accounts("check_order_amend", payer)]
+#[derive(Accounts)]
+pub struct InitCheckOrderAmendCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    /// CHECK: comp_def_account initialized via CPI
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
 #[init_computation_definition_accounts("decrypt_for_settlement", payer)]
 #[derive(Accounts)]
 pub struct InitDecryptForSettlementCompDef<'info> {
@@ -2057,27 +3742,193 @@ pub struct InitBatchComparePricesCompDef<'info> {
     pub comp_def_account: UncheckedAccount<'info>,
     pub arcium_program: Program<'info, Arcium>,
     pub system_program: Program<'info, System>,
-}
-
-#[init_computation_definition_accounts("batch_calculate_fill", payer)]
-#[derive(Accounts)]
-pub struct InitBatchCalculateFillCompDef<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    #[account(mut, address = derive_mxe_pda!())]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    /// CHECK: comp_def_account initialized via CPI
-    #[account(mut)]
-    pub comp_def_account: UncheckedAccount<'info>,
+}
+
+#[init_computation_definition_accounts("batch_calculate_fill", payer)]
+#[derive(Accounts)]
+pub struct InitBatchCalculateFillCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    /// CHECK: comp_def_account initialized via CPI
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("batch_calculate_refund", payer)]
+#[derive(Accounts)]
+pub struct InitBatchCalculateRefundCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    /// CHECK: comp_def_account initialized via CPI
+    #[account(mut)]
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+// Queue computation accounts
+#[queue_computation_accounts("compare_prices", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct ComparePrices<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: mempool_account checked by arcium program
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: executing_pool checked by arcium program
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub executing_pool: UncheckedAccount<'info>,
+    /// CHECK: computation_account checked by arcium program
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::AbortedComputation))]
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMPARE_PRICES))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[queue_computation_accounts("calculate_fill", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CalculateFill<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: mempool_account checked by arcium program
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: executing_pool checked by arcium program
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub executing_pool: UncheckedAccount<'info>,
+    /// CHECK: computation_account checked by arcium program
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::AbortedComputation))]
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CALCULATE_FILL))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[queue_computation_accounts("verify_position_params", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct VerifyPositionParams<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: mempool_account checked by arcium program
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: executing_pool checked by arcium program
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub executing_pool: UncheckedAccount<'info>,
+    /// CHECK: computation_account checked by arcium program
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::AbortedComputation))]
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_VERIFY_POSITION_PARAMS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[queue_computation_accounts("check_liquidation", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CheckLiquidation<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: mempool_account checked by arcium program
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: executing_pool checked by arcium program
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub executing_pool: UncheckedAccount<'info>,
+    /// CHECK: computation_account checked by arcium program
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::AbortedComputation))]
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CHECK_LIQUIDATION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
     pub arcium_program: Program<'info, Arcium>,
-    pub system_program: Program<'info, System>,
 }
 
-// Queue computation accounts
-#[queue_computation_accounts("compare_prices", payer)]
+#[queue_computation_accounts("batch_liquidation_check", payer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64)]
-pub struct ComparePrices<'info> {
+pub struct BatchLiquidationCheck<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
@@ -2100,7 +3951,7 @@ pub struct ComparePrices<'info> {
     /// CHECK: computation_account checked by arcium program
     #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::AbortedComputation))]
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMPARE_PRICES))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_BATCH_LIQUIDATION_CHECK))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
     pub cluster_account: Account<'info, Cluster>,
@@ -2112,10 +3963,10 @@ pub struct ComparePrices<'info> {
     pub arcium_program: Program<'info, Arcium>,
 }
 
-#[queue_computation_accounts("calculate_fill", payer)]
+#[queue_computation_accounts("calculate_pnl", payer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64)]
-pub struct CalculateFill<'info> {
+pub struct CalculatePnl<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
@@ -2138,7 +3989,7 @@ pub struct CalculateFill<'info> {
     /// CHECK: computation_account checked by arcium program
     #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::AbortedComputation))]
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CALCULATE_FILL))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CALCULATE_PNL))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
     pub cluster_account: Account<'info, Cluster>,
@@ -2150,10 +4001,10 @@ pub struct CalculateFill<'info> {
     pub arcium_program: Program<'info, Arcium>,
 }
 
-#[queue_computation_accounts("verify_position_params", payer)]
+#[queue_computation_accounts("calculate_funding", payer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64)]
-pub struct VerifyPositionParams<'info> {
+pub struct CalculateFunding<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
@@ -2176,7 +4027,7 @@ pub struct VerifyPositionParams<'info> {
     /// CHECK: computation_account checked by arcium program
     #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::AbortedComputation))]
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_VERIFY_POSITION_PARAMS))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CALCULATE_FUNDING))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
     pub cluster_account: Account<'info, Cluster>,
@@ -2188,10 +4039,10 @@ pub struct VerifyPositionParams<'info> {
     pub arcium_program: Program<'info, Arcium>,
 }
 
-#[queue_computation_accounts("check_liquidation", payer)]
+#[queue_computation_accounts("calculate_adl", payer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64)]
-pub struct CheckLiquidation<'info> {
+pub struct CalculateAdl<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
@@ -2214,7 +4065,7 @@ pub struct CheckLiquidation<'info> {
     /// CHECK: computation_account checked by arcium program
     #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::AbortedComputation))]
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CHECK_LIQUIDATION))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CALCULATE_ADL))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
     pub cluster_account: Account<'info, Cluster>,
@@ -2226,10 +4077,10 @@ pub struct CheckLiquidation<'info> {
     pub arcium_program: Program<'info, Arcium>,
 }
 
-#[queue_computation_accounts("batch_liquidation_check", payer)]
+#[queue_computation_accounts("rank_adl_candidates", payer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64)]
-pub struct BatchLiquidationCheck<'info> {
+pub struct RankAdlCandidates<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
@@ -2252,7 +4103,7 @@ pub struct BatchLiquidationCheck<'info> {
     /// CHECK: computation_account checked by arcium program
     #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::AbortedComputation))]
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_BATCH_LIQUIDATION_CHECK))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_RANK_ADL_CANDIDATES))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
     pub cluster_account: Account<'info, Cluster>,
@@ -2264,10 +4115,10 @@ pub struct BatchLiquidationCheck<'info> {
     pub arcium_program: Program<'info, Arcium>,
 }
 
-#[queue_computation_accounts("calculate_pnl", payer)]
+#[queue_computation_accounts("calculate_partial_liquidation", payer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64)]
-pub struct CalculatePnl<'info> {
+pub struct CalculatePartialLiquidation<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
@@ -2290,7 +4141,7 @@ pub struct CalculatePnl<'info> {
     /// CHECK: computation_account checked by arcium program
     #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::AbortedComputation))]
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CALCULATE_PNL))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CALCULATE_PARTIAL_LIQUIDATION))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
     pub cluster_account: Account<'info, Cluster>,
@@ -2302,10 +4153,10 @@ pub struct CalculatePnl<'info> {
     pub arcium_program: Program<'info, Arcium>,
 }
 
-#[queue_computation_accounts("calculate_funding", payer)]
+#[queue_computation_accounts("batch_exit_trigger_check", payer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64)]
-pub struct CalculateFunding<'info> {
+pub struct BatchExitTriggerCheck<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
@@ -2328,7 +4179,7 @@ pub struct CalculateFunding<'info> {
     /// CHECK: computation_account checked by arcium program
     #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::AbortedComputation))]
     pub computation_account: UncheckedAccount<'info>,
-    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CALCULATE_FUNDING))]
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_BATCH_EXIT_TRIGGER_CHECK))]
     pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
     #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
     pub cluster_account: Account<'info, Cluster>,
@@ -2416,6 +4267,44 @@ pub struct CheckOrderBalance<'info> {
     pub arcium_program: Program<'info, Arcium>,
 }
 
+#[queue_computation_accounts("check_order_amend", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct CheckOrderAmend<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: mempool_account checked by arcium program
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: executing_pool checked by arcium program
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub executing_pool: UncheckedAccount<'info>,
+    /// CHECK: computation_account checked by arcium program
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::AbortedComputation))]
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CHECK_ORDER_AMEND))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
 #[queue_computation_accounts("decrypt_for_settlement", payer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64)]
@@ -2492,6 +4381,44 @@ pub struct CalculateRefund<'info> {
     pub arcium_program: Program<'info, Arcium>,
 }
 
+#[queue_computation_accounts("batch_calculate_refund", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct BatchCalculateRefund<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: mempool_account checked by arcium program
+    #[account(mut, address = derive_mempool_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub mempool_account: UncheckedAccount<'info>,
+    /// CHECK: executing_pool checked by arcium program
+    #[account(mut, address = derive_execpool_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub executing_pool: UncheckedAccount<'info>,
+    /// CHECK: computation_account checked by arcium program
+    #[account(mut, address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::AbortedComputation))]
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_BATCH_CALCULATE_REFUND))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(mut, address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
 #[queue_computation_accounts("batch_compare_prices", payer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64)]
@@ -2688,6 +4615,74 @@ pub struct CalculateFundingCallback<'info> {
     pub instructions_sysvar: AccountInfo<'info>,
 }
 
+#[callback_accounts("calculate_adl")]
+#[derive(Accounts)]
+pub struct CalculateAdlCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CALCULATE_ADL))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account checked by arcium program via constraints
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub cluster_account: Account<'info, Cluster>,
+    /// CHECK: instructions_sysvar checked by account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+#[callback_accounts("rank_adl_candidates")]
+#[derive(Accounts)]
+pub struct RankAdlCandidatesCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_RANK_ADL_CANDIDATES))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account checked by arcium program via constraints
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub cluster_account: Account<'info, Cluster>,
+    /// CHECK: instructions_sysvar checked by account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+#[callback_accounts("calculate_partial_liquidation")]
+#[derive(Accounts)]
+pub struct CalculatePartialLiquidationCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CALCULATE_PARTIAL_LIQUIDATION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account checked by arcium program via constraints
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub cluster_account: Account<'info, Cluster>,
+    /// CHECK: instructions_sysvar checked by account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+#[callback_accounts("batch_exit_trigger_check")]
+#[derive(Accounts)]
+pub struct BatchExitTriggerCheckCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_BATCH_EXIT_TRIGGER_CHECK))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account checked by arcium program via constraints
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub cluster_account: Account<'info, Cluster>,
+    /// CHECK: instructions_sysvar checked by account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
 #[callback_accounts("check_balance")]
 #[derive(Accounts)]
 pub struct CheckBalanceCallback<'info> {
@@ -2722,6 +4717,23 @@ pub struct CheckOrderBalanceCallback<'info> {
     pub instructions_sysvar: AccountInfo<'info>,
 }
 
+#[callback_accounts("check_order_amend")]
+#[derive(Accounts)]
+pub struct CheckOrderAmendCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_CHECK_ORDER_AMEND))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account checked by arcium program via constraints
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub cluster_account: Account<'info, Cluster>,
+    /// CHECK: instructions_sysvar checked by account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
 #[callback_accounts("decrypt_for_settlement")]
 #[derive(Accounts)]
 pub struct DecryptForSettlementCallback<'info> {
@@ -2756,6 +4768,23 @@ pub struct CalculateRefundCallback<'info> {
     pub instructions_sysvar: AccountInfo<'info>,
 }
 
+#[callback_accounts("batch_calculate_refund")]
+#[derive(Accounts)]
+pub struct BatchCalculateRefundCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_BATCH_CALCULATE_REFUND))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: computation_account checked by arcium program via constraints
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::AbortedComputation))]
+    pub cluster_account: Account<'info, Cluster>,
+    /// CHECK: instructions_sysvar checked by account constraint
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
 #[callback_accounts("batch_compare_prices")]
 #[derive(Accounts)]
 pub struct BatchComparePricesCallback<'info> {
@@ -2824,4 +4853,136 @@ mod tests {
             "DEX_FINALIZE_MATCH_DISCRIMINATOR doesn't match sha256('global:finalize_match')[0..8]"
         );
     }
+
+    /// Verify DEX_PLACE_ORDER_CALLBACK_DISCRIMINATOR is sha256("global:place_order_callback")[0..8]
+    #[test]
+    fn verify_place_order_callback_discriminator() {
+        // Verified manually via: echo -n "global:place_order_callback" | sha256sum
+        // Result: f6be9a922c0b692c... (first 8 bytes)
+        let expected: [u8; 8] = [0xf6, 0xbe, 0x9a, 0x92, 0x2c, 0x0b, 0x69, 0x2c];
+        assert_eq!(
+            DEX_PLACE_ORDER_CALLBACK_DISCRIMINATOR, expected,
+            "DEX_PLACE_ORDER_CALLBACK_DISCRIMINATOR doesn't match sha256('global:place_order_callback')[0..8]"
+        );
+    }
+
+    /// Verify DEX_FINALIZE_MATCH_BATCH_DISCRIMINATOR is sha256("global:finalize_match_batch")[0..8]
+    #[test]
+    fn verify_finalize_match_batch_discriminator() {
+        // Verified manually via: echo -n "global:finalize_match_batch" | sha256sum
+        // Result: 6ca70bc980d45bec... (first 8 bytes)
+        let expected: [u8; 8] = [0x6c, 0xa7, 0x0b, 0xc9, 0x80, 0xd4, 0x5b, 0xec];
+        assert_eq!(
+            DEX_FINALIZE_MATCH_BATCH_DISCRIMINATOR, expected,
+            "DEX_FINALIZE_MATCH_BATCH_DISCRIMINATOR doesn't match sha256('global:finalize_match_batch')[0..8]"
+        );
+    }
+
+    /// Verify DEX_SETTLE_ORDER_CALLBACK_DISCRIMINATOR is sha256("global:settle_order_callback")[0..8]
+    #[test]
+    fn verify_settle_order_callback_discriminator() {
+        // Verified manually via: echo -n "global:settle_order_callback" | sha256sum
+        // Result: 23211dae257a63f1... (first 8 bytes)
+        let expected: [u8; 8] = [0x23, 0x21, 0x1d, 0xae, 0x25, 0x7a, 0x63, 0xf1];
+        assert_eq!(
+            DEX_SETTLE_ORDER_CALLBACK_DISCRIMINATOR, expected,
+            "DEX_SETTLE_ORDER_CALLBACK_DISCRIMINATOR doesn't match sha256('global:settle_order_callback')[0..8]"
+        );
+    }
+
+    /// Verify DEX_CANCEL_ORDER_CALLBACK_DISCRIMINATOR is sha256("global:cancel_order_callback")[0..8]
+    #[test]
+    fn verify_cancel_order_callback_discriminator() {
+        // Verified manually via: echo -n "global:cancel_order_callback" | sha256sum
+        // Result: 8a221de9f008d32a... (first 8 bytes)
+        let expected: [u8; 8] = [0x8a, 0x22, 0x1d, 0xe9, 0xf0, 0x08, 0xd3, 0x2a];
+        assert_eq!(
+            DEX_CANCEL_ORDER_CALLBACK_DISCRIMINATOR, expected,
+            "DEX_CANCEL_ORDER_CALLBACK_DISCRIMINATOR doesn't match sha256('global:cancel_order_callback')[0..8]"
+        );
+    }
+
+    /// Verify DEX_CANCEL_ORDERS_BATCH_CALLBACK_DISCRIMINATOR is sha256("global:cancel_orders_batch_callback")[0..8]
+    #[test]
+    fn verify_cancel_orders_batch_callback_discriminator() {
+        // Verified manually via: echo -n "global:cancel_orders_batch_callback" | sha256sum
+        // Result: a84a4a78127ef96c... (first 8 bytes)
+        let expected: [u8; 8] = [0xa8, 0x4a, 0x4a, 0x78, 0x12, 0x7e, 0xf9, 0x6c];
+        assert_eq!(
+            DEX_CANCEL_ORDERS_BATCH_CALLBACK_DISCRIMINATOR, expected,
+            "DEX_CANCEL_ORDERS_BATCH_CALLBACK_DISCRIMINATOR doesn't match sha256('global:cancel_orders_batch_callback')[0..8]"
+        );
+    }
+
+    /// Verify DEX_AMEND_ORDER_CALLBACK_DISCRIMINATOR is sha256("global:amend_order_callback")[0..8]
+    #[test]
+    fn verify_amend_order_callback_discriminator() {
+        // Verified manually via: echo -n "global:amend_order_callback" | sha256sum
+        // Result: e85dbf1b7136d6b2... (first 8 bytes)
+        let expected: [u8; 8] = [0xe8, 0x5d, 0xbf, 0x1b, 0x71, 0x36, 0xd6, 0xb2];
+        assert_eq!(
+            DEX_AMEND_ORDER_CALLBACK_DISCRIMINATOR, expected,
+            "DEX_AMEND_ORDER_CALLBACK_DISCRIMINATOR doesn't match sha256('global:amend_order_callback')[0..8]"
+        );
+    }
+
+    /// Verify DEX_ADL_PNL_CALLBACK_DISCRIMINATOR is sha256("global:adl_pnl_callback")[0..8]
+    #[test]
+    fn verify_adl_pnl_callback_discriminator() {
+        // Verified manually via: echo -n "global:adl_pnl_callback" | sha256sum
+        // Result: f310ccc0ee1740c8... (first 8 bytes)
+        let expected: [u8; 8] = [0xf3, 0x10, 0xcc, 0xc0, 0xee, 0x17, 0x40, 0xc8];
+        assert_eq!(
+            DEX_ADL_PNL_CALLBACK_DISCRIMINATOR, expected,
+            "DEX_ADL_PNL_CALLBACK_DISCRIMINATOR doesn't match sha256('global:adl_pnl_callback')[0..8]"
+        );
+    }
+
+    /// Verify DEX_ADL_RANKING_CALLBACK_DISCRIMINATOR is sha256("global:adl_ranking_callback")[0..8]
+    #[test]
+    fn verify_adl_ranking_callback_discriminator() {
+        // Verified manually via: echo -n "global:adl_ranking_callback" | sha256sum
+        // Result: 2f95f5e26b139abd... (first 8 bytes)
+        let expected: [u8; 8] = [0x2f, 0x95, 0xf5, 0xe2, 0x6b, 0x13, 0x9a, 0xbd];
+        assert_eq!(
+            DEX_ADL_RANKING_CALLBACK_DISCRIMINATOR, expected,
+            "DEX_ADL_RANKING_CALLBACK_DISCRIMINATOR doesn't match sha256('global:adl_ranking_callback')[0..8]"
+        );
+    }
+
+    /// Verify DEX_PARTIAL_LIQUIDATION_CALLBACK_DISCRIMINATOR is sha256("global:partial_liquidation_callback")[0..8]
+    #[test]
+    fn verify_partial_liquidation_callback_discriminator() {
+        // Verified manually via: echo -n "global:partial_liquidation_callback" | sha256sum
+        // Result: 7e6d1302e09555f0... (first 8 bytes)
+        let expected: [u8; 8] = [0x7e, 0x6d, 0x13, 0x02, 0xe0, 0x95, 0x55, 0xf0];
+        assert_eq!(
+            DEX_PARTIAL_LIQUIDATION_CALLBACK_DISCRIMINATOR, expected,
+            "DEX_PARTIAL_LIQUIDATION_CALLBACK_DISCRIMINATOR doesn't match sha256('global:partial_liquidation_callback')[0..8]"
+        );
+    }
+
+    /// Verify DEX_LIQUIDATION_BATCH_CALLBACK_DISCRIMINATOR is sha256("global:liquidation_batch_callback")[0..8]
+    #[test]
+    fn verify_liquidation_batch_callback_discriminator() {
+        // Verified manually via: echo -n "global:liquidation_batch_callback" | sha256sum
+        // Result: b05d6bddb387853e... (first 8 bytes)
+        let expected: [u8; 8] = [0xb0, 0x5d, 0x6b, 0xdd, 0xb3, 0x87, 0x85, 0x3e];
+        assert_eq!(
+            DEX_LIQUIDATION_BATCH_CALLBACK_DISCRIMINATOR, expected,
+            "DEX_LIQUIDATION_BATCH_CALLBACK_DISCRIMINATOR doesn't match sha256('global:liquidation_batch_callback')[0..8]"
+        );
+    }
+
+    /// Verify DEX_EXIT_TRIGGER_CALLBACK_DISCRIMINATOR is sha256("global:exit_trigger_callback")[0..8]
+    #[test]
+    fn verify_exit_trigger_callback_discriminator() {
+        // Verified manually via: echo -n "global:exit_trigger_callback" | sha256sum
+        // Result: 8555bcff5f452ddd... (first 8 bytes)
+        let expected: [u8; 8] = [0x85, 0x55, 0xbc, 0xff, 0x5f, 0x45, 0x2d, 0xdd];
+        assert_eq!(
+            DEX_EXIT_TRIGGER_CALLBACK_DISCRIMINATOR, expected,
+            "DEX_EXIT_TRIGGER_CALLBACK_DISCRIMINATOR doesn't match sha256('global:exit_trigger_callback')[0..8]"
+        );
+    }
 }
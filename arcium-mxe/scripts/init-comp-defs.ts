@@ -58,6 +58,12 @@ const CIRCUITS = [
   'calculate_refund',
   'batch_compare_prices',
   'batch_calculate_fill',
+  'batch_calculate_refund',
+  'check_order_amend',
+  'calculate_adl',
+  'rank_adl_candidates',
+  'calculate_partial_liquidation',
+  'batch_exit_trigger_check',
 ];
 
 // Anchor discriminator for each init function
@@ -607,6 +607,12 @@ export async function syncToOnChain(adminKeypair: Keypair): Promise<string> {
     PROGRAM_ID
   );
 
+  // Root history keeps the previous root valid for the on-chain grace window
+  const [blacklistHistoryPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('blacklist_history')],
+    PROGRAM_ID
+  );
+
   // Build update_blacklist instruction
   // Anchor instruction discriminator = sha256("global:update_blacklist")[0..8]
   // sha256("global:update_blacklist") = c6b8f938c73e5d26...
@@ -615,6 +621,7 @@ export async function syncToOnChain(adminKeypair: Keypair): Promise<string> {
   const instruction = new TransactionInstruction({
     keys: [
       { pubkey: exchangeStatePda, isSigner: false, isWritable: true },
+      { pubkey: blacklistHistoryPda, isSigner: false, isWritable: true },
       { pubkey: adminKeypair.publicKey, isSigner: true, isWritable: false },
     ],
     programId: PROGRAM_ID,
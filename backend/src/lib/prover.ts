@@ -5,6 +5,7 @@ import { join, dirname } from 'path';
 import { randomBytes, createHash } from 'crypto';
 import { existsSync } from 'fs';
 import { fileURLToPath } from 'url';
+import bs58 from 'bs58';
 import { logger } from './logger.js';
 
 const execAsync = promisify(exec);
@@ -40,6 +41,17 @@ const SUNSPOT_BIN = process.env.SUNSPOT_BINARY_PATH ||
                     process.env.SUNSPOT_BIN ||
                     join(process.env.HOME || '~', 'sunspot', 'go', 'sunspot');
 
+// Eligibility epoch length - must match ELIGIBILITY_EPOCH_SECS in programs/confidex_dex/src/cpi/verifier.rs
+// Proofs are bound to the epoch and rejected on-chain once it ends
+export const ELIGIBILITY_EPOCH_SECS = 86_400;
+
+/**
+ * Current eligibility epoch (unix seconds / epoch length)
+ */
+export function currentEligibilityEpoch(nowMs: number = Date.now()): number {
+  return Math.floor(nowMs / 1000 / ELIGIBILITY_EPOCH_SECS);
+}
+
 // Circuit directory (backend/src/lib -> backend -> project root -> circuits/eligibility)
 const CIRCUIT_DIR = process.env.CIRCUIT_DIR ||
                     join(dirname(dirname(dirname(__dirname))), 'circuits', 'eligibility');
@@ -66,17 +78,17 @@ class ProofLRUCache {
   }
 
   /**
-   * Generate cache key from address and blacklist root
+   * Generate cache key from address, blacklist root and epoch
    */
-  private makeKey(address: string, blacklistRoot: string): string {
-    return `${address}:${blacklistRoot}`;
+  private makeKey(address: string, blacklistRoot: string, epoch: number): string {
+    return `${address}:${blacklistRoot}:${epoch}`;
   }
 
   /**
    * Get cached proof if valid
    */
-  get(address: string, blacklistRoot: string): Buffer | null {
-    const key = this.makeKey(address, blacklistRoot);
+  get(address: string, blacklistRoot: string, epoch: number = currentEligibilityEpoch()): Buffer | null {
+    const key = this.makeKey(address, blacklistRoot, epoch);
     const entry = this.cache.get(key);
 
     if (!entry) return null;
@@ -97,8 +109,8 @@ class ProofLRUCache {
   /**
    * Store proof in cache
    */
-  set(address: string, blacklistRoot: string, proof: Buffer): void {
-    const key = this.makeKey(address, blacklistRoot);
+  set(address: string, blacklistRoot: string, proof: Buffer, epoch: number = currentEligibilityEpoch()): void {
+    const key = this.makeKey(address, blacklistRoot, epoch);
 
     // Evict oldest if at capacity
     if (this.cache.size >= this.maxSize) {
@@ -189,8 +201,11 @@ interface ProofResult {
 export async function generateEligibilityProof(inputs: ProofInputs): Promise<Buffer> {
   const startTime = Date.now();
 
+  // Proofs are bound to the signer and epoch, so the epoch is part of the cache key
+  const epoch = currentEligibilityEpoch();
+
   // Check cache first
-  const cached = proofCache.get(inputs.address, inputs.blacklistRoot);
+  const cached = proofCache.get(inputs.address, inputs.blacklistRoot, epoch);
   if (cached) {
     log.debug({ address: inputs.address.slice(0, 8), durationMs: Date.now() - startTime }, 'Cache hit');
     return cached;
@@ -210,6 +225,8 @@ export async function generateEligibilityProof(inputs: ProofInputs): Promise<Buf
     // Write Prover.toml with the inputs
     const proverToml = generateProverToml({
       blacklistRoot: inputs.blacklistRoot,
+      address: inputs.address,
+      epoch,
       merklePath: inputs.merklePath,
       pathIndices: inputs.pathIndices,
     });
@@ -327,7 +344,7 @@ export async function generateEligibilityProof(inputs: ProofInputs): Promise<Buf
     }
 
     // Cache the proof
-    proofCache.set(inputs.address, inputs.blacklistRoot, rawProof);
+    proofCache.set(inputs.address, inputs.blacklistRoot, rawProof, epoch);
 
     const totalDuration = Date.now() - startTime;
     log.info({ proofSize: rawProof.length, durationMs: totalDuration }, 'Generated real Groth16 proof');
@@ -352,13 +369,22 @@ export async function generateEligibilityProof(inputs: ProofInputs): Promise<Buf
 
 /**
  * Generate Prover.toml content
- * Note: We no longer include 'address' as the simplified circuit doesn't use it
+ *
+ * The signer's pubkey is split into two 16-byte halves (address_hi / address_lo)
+ * so each fits in a field element. The program rebuilds these from the signer
+ * on-chain, so the proof only verifies for this address and epoch.
  */
 function generateProverToml(inputs: {
   blacklistRoot: string;
+  address: string;
+  epoch: number;
   merklePath: string[];
   pathIndices: number[];
 }): string {
+  const addressBytes = Buffer.from(bs58.decode(inputs.address));
+  const addressHi = `0x${addressBytes.subarray(0, 16).toString('hex')}`;
+  const addressLo = `0x${addressBytes.subarray(16, 32).toString('hex')}`;
+
   // Format merkle path
   const pathArray = inputs.merklePath
     .map((p) => `    "${p}"`)
@@ -371,6 +397,9 @@ function generateProverToml(inputs: {
 
   return `# Auto-generated prover inputs
 blacklist_root = "${inputs.blacklistRoot}"
+address_hi = "${addressHi}"
+address_lo = "${addressLo}"
+epoch = "${inputs.epoch}"
 merkle_path = [
 ${pathArray}
 ]
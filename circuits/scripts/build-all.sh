@@ -18,6 +18,7 @@ NC='\033[0m' # No Color
 CIRCUITS=(
     "shared"
     "eligibility"
+    "allowlist"
     "range_proof"
     "solvency"
 )
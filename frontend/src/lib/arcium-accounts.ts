@@ -87,6 +87,7 @@ export const CIRCUIT_OFFSETS = {
   add_encrypted: () => getCircuitCompDefOffset('add_encrypted'),
   sub_encrypted: () => getCircuitCompDefOffset('sub_encrypted'),
   check_liquidation: () => getCircuitCompDefOffset('check_liquidation'),
+  check_order_balance: () => getCircuitCompDefOffset('check_order_balance'),
 } as const;
 
 /**
@@ -58,6 +58,7 @@ const PAIR_SEED = Buffer.from('pair');
 const ORDER_SEED = Buffer.from('order');
 const USER_BALANCE_SEED = Buffer.from('user_balance');
 const TRADER_ELIGIBILITY_SEED = Buffer.from('trader_eligibility');
+const BLACKLIST_HISTORY_SEED = Buffer.from('blacklist_history');
 const MPC_REQUEST_SEED = Buffer.from('mpc_request');
 const COMPUTATION_SEED = Buffer.from('computation');
 const MXE_CONFIG_SEED_BUF = Buffer.from('mxe_config');
@@ -105,6 +106,22 @@ export enum OrderType {
   Market = 1,
 }
 
+// TimeInForce enum (matching Anchor)
+export enum TimeInForce {
+  GoodTillCancel = 0,
+  ImmediateOrCancel = 1,
+  FillOrKill = 2,
+  PostOnly = 3,
+}
+
+// SelfTradePrevention enum (matching Anchor)
+export enum SelfTradePrevention {
+  CancelNewest = 0,
+  CancelOldest = 1,
+  CancelBoth = 2,
+  Decrement = 3,
+}
+
 /**
  * Derive Exchange PDA
  */
@@ -112,6 +129,14 @@ export function deriveExchangePda(): [PublicKey, number] {
   return PublicKey.findProgramAddressSync([EXCHANGE_SEED], CONFIDEX_PROGRAM_ID);
 }
 
+/**
+ * Derive Blacklist Root History PDA
+ * Seeds: ["blacklist_history"]
+ */
+export function deriveBlacklistHistoryPda(): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([BLACKLIST_HISTORY_SEED], CONFIDEX_PROGRAM_ID);
+}
+
 /**
  * Derive Trader Eligibility PDA
  * Seeds: ["trader_eligibility", trader_pubkey]
@@ -183,19 +208,27 @@ function serializeOrderType(orderType: OrderType): Buffer {
 
 /**
  * Build place_order instruction data in Anchor format
- * V5 format: No plaintext fields - pure privacy
+ * V5 format: No plaintext amounts/prices - pure privacy
  */
 export function buildPlaceOrderData(
   side: Side,
   orderType: OrderType,
   encryptedAmount: Uint8Array,
   encryptedPrice: Uint8Array,
-  eligibilityProof: Uint8Array,
-  ephemeralPubkey: Uint8Array
+  eligibilityProof: Uint8Array | null,
+  ephemeralPubkey: Uint8Array,
+  computationOffset: BN,
+  nonce: bigint,
+  timeInForce: TimeInForce,
+  expiresAtHour: bigint,
+  selfTradePrevention: SelfTradePrevention
 ): Buffer {
-  // V5 Anchor format: [discriminator(8), side(1), order_type(1), encrypted_amount(64), encrypted_price(64), eligibility_proof(324), ephemeral_pubkey(32)]
-  // Total instruction data: 8 + 1 + 1 + 64 + 64 + 324 + 32 = 494 bytes
-  const data = Buffer.alloc(8 + 1 + 1 + 64 + 64 + GROTH16_PROOF_SIZE + 32);
+  // Anchor format: [discriminator(8), side(1), order_type(1), encrypted_amount(64),
+  //   encrypted_price(64), eligibility_proof(Option: 1 + 324), ephemeral_pubkey(32),
+  //   computation_offset(8), nonce(16), time_in_force(1), expires_at_hour(8),
+  //   self_trade_prevention(1)]
+  const proofSize = eligibilityProof ? 1 + GROTH16_PROOF_SIZE : 1;
+  const data = Buffer.alloc(8 + 1 + 1 + 64 + 64 + proofSize + 32 + 8 + 16 + 1 + 8 + 1);
   let offset = 0;
 
   // Discriminator (8 bytes)
@@ -216,13 +249,38 @@ export function buildPlaceOrderData(
   Buffer.from(encryptedPrice).copy(data, offset);
   offset += 64;
 
-  // Eligibility proof (388 bytes)
-  const proofSlice = eligibilityProof.slice(0, GROTH16_PROOF_SIZE);
-  Buffer.from(proofSlice).copy(data, offset);
-  offset += GROTH16_PROOF_SIZE;
+  // Eligibility proof (Option<[u8; 324]>) - only needed without a valid
+  // TraderEligibility account
+  if (eligibilityProof) {
+    data[offset++] = 1;
+    Buffer.from(eligibilityProof.slice(0, GROTH16_PROOF_SIZE)).copy(data, offset);
+    offset += GROTH16_PROOF_SIZE;
+  } else {
+    data[offset++] = 0;
+  }
 
   // Ephemeral X25519 public key (32 bytes) for production MPC decryption
   Buffer.from(ephemeralPubkey).copy(data, offset);
+  offset += 32;
+
+  // computation_offset (u64) - selects the check_order_balance computation account
+  data.writeBigUInt64LE(BigInt(computationOffset.toString()), offset);
+  offset += 8;
+
+  // nonce (u128) - written as two u64s (low, high)
+  data.writeBigUInt64LE(nonce & BigInt('0xFFFFFFFFFFFFFFFF'), offset);
+  data.writeBigUInt64LE(nonce >> BigInt(64), offset + 8);
+  offset += 16;
+
+  // TimeInForce (1 byte enum)
+  data[offset++] = timeInForce;
+
+  // expires_at_hour (i64) - 0 = no expiry
+  data.writeBigInt64LE(expiresAtHour, offset);
+  offset += 8;
+
+  // SelfTradePrevention (1 byte enum)
+  data[offset] = selfTradePrevention;
 
   return data;
 }
@@ -236,9 +294,16 @@ export interface PlaceOrderParams {
   orderType: OrderType;
   encryptedAmount: Uint8Array;
   encryptedPrice: Uint8Array;
-  eligibilityProof: Uint8Array;
+  // Per-order proof, only needed without a valid TraderEligibility account
+  eligibilityProof: Uint8Array | null;
   // Production MPC: Full 32-byte ephemeral X25519 public key for Arcium decryption
   ephemeralPubkey: Uint8Array;
+  // Defaults to GoodTillCancel
+  timeInForce?: TimeInForce;
+  // Unix timestamp the order expires at (floored to the hour), 0 = no expiry
+  expiresAtHour?: bigint;
+  // Defaults to CancelNewest
+  selfTradePrevention?: SelfTradePrevention;
 }
 
 /**
@@ -315,11 +380,15 @@ export interface PlaceOrderResult {
 }
 
 /**
- * Build place_order transaction
+ * Build the place_order instruction for the given order nonce
+ *
+ * Queues the MPC balance check (check_order_balance), so the order starts as
+ * PendingBalanceCheck and is activated or rejected by place_order_callback.
  */
-export async function buildPlaceOrderTransaction(
-  params: PlaceOrderParams
-): Promise<PlaceOrderResult> {
+async function buildPlaceOrderInstruction(
+  params: PlaceOrderParams,
+  orderCount: bigint
+): Promise<TransactionInstruction> {
   const {
     connection,
     maker,
@@ -331,72 +400,122 @@ export async function buildPlaceOrderTransaction(
     encryptedPrice,
     eligibilityProof,
     ephemeralPubkey,
+    timeInForce = TimeInForce.GoodTillCancel,
+    expiresAtHour = BigInt(0),
+    selfTradePrevention = SelfTradePrevention.CancelNewest,
   } = params;
 
-  log.debug('Building place_order transaction...');
-
   // Derive PDAs
   const [exchangePda] = deriveExchangePda();
   const [pairPda] = derivePairPda(baseMint, quoteMint);
+  const [orderPda] = deriveOrderPda(maker, orderCount);
 
-  log.debug('[ConfidexClient] Exchange PDA:', { toString: exchangePda.toString() });
-  log.debug('[ConfidexClient] Pair PDA:', { toString: pairPda.toString() });
+  // Determine which token mint the user is spending
+  // Buy orders spend quote (USDC), sell orders spend base (SOL)
+  const spendMint = side === Side.Buy ? quoteMint : baseMint;
+  const [userBalancePda] = deriveUserBalancePda(maker, spendMint);
 
-  // Fetch current order count to derive order PDA
-  const orderCount = await fetchOrderCount(connection);
-  const [orderPda] = deriveOrderPda(maker, orderCount);
+  // Use the maker's TraderEligibility record when present (None = program ID)
+  const [eligibilityPda] = deriveTraderEligibilityPda(maker);
+  const eligibilityAccount = await connection.getAccountInfo(eligibilityPda);
 
   log.debug('[ConfidexClient] Order PDA:', { toString: orderPda.toString() });
   log.debug('[ConfidexClient] Order count:', { toString: orderCount.toString() });
+  log.debug('[ConfidexClient] User balance PDA:', { toString: userBalancePda.toString() });
+  log.debug('[ConfidexClient] Spending mint:', { toString: spendMint.toString() });
+
+  // The MPC balance check decrypts the amount with the nonce it was encrypted
+  // under: V2 ciphertexts are [nonce (16) | ciphertext (32) | ephemeral_pubkey (16)]
+  const nonceBytes = Buffer.from(encryptedAmount.slice(0, 16));
+  const nonce = nonceBytes.readBigUInt64LE(0) + (nonceBytes.readBigUInt64LE(8) << BigInt(64));
+  const computationOffset = generateComputationOffset();
+  const mxeAccounts = deriveArciumAccounts(
+    'check_order_balance',
+    computationOffset,
+    MXE_PROGRAM_ID
+  );
+
+  log.debug('MXE accounts derived for check_order_balance', {
+    computationAccount: mxeAccounts.computationAccount.toBase58(),
+    compDefAccount: mxeAccounts.compDefAccount.toBase58(),
+  });
 
-  // Build instruction data (V5 format - no plaintext fields)
   const instructionData = buildPlaceOrderData(
     side,
     orderType,
     encryptedAmount,
     encryptedPrice,
     eligibilityProof,
-    ephemeralPubkey
+    ephemeralPubkey,
+    computationOffset,
+    nonce,
+    timeInForce,
+    expiresAtHour,
+    selfTradePrevention
   );
 
   log.debug('[ConfidexClient] Instruction data length:', { length: instructionData.length });
 
-  // Determine which token mint the user is spending
-  // Buy orders spend quote (USDC), sell orders spend base (SOL)
-  const spendMint = side === Side.Buy ? quoteMint : baseMint;
-  const [userBalancePda] = deriveUserBalancePda(maker, spendMint);
-
-  log.debug('[ConfidexClient] User balance PDA:', { toString: userBalancePda.toString() });
-  log.debug('[ConfidexClient] Spending mint:', { toString: spendMint.toString() });
-
-  // Build instruction with required accounts
   // PlaceOrder accounts (from place_order.rs):
   // 1. exchange (mut) - ExchangeState PDA
   // 2. pair (mut) - TradingPair PDA
   // 3. order (init, mut) - ConfidentialOrder PDA
   // 4. user_balance (mut) - User's confidential balance for the token being spent
-  // 5. verifier_program - Sunspot ZK verifier
-  // 6. maker (signer, mut)
-  // 7. system_program
-  const instruction = new TransactionInstruction({
+  // 5. blacklist_history (read) - recent blacklist roots for eligibility validation
+  // 6. eligibility (optional, read) - program ID placeholder when absent
+  // 7. allowlist_credential (optional, read) - program ID placeholder for non-allowlisted pairs
+  // 8. verifier_program - Sunspot ZK verifier
+  // 9. maker (signer, mut)
+  // 10. system_program
+  // 11-21. Arcium MXE accounts (remaining_accounts)
+  return new TransactionInstruction({
     keys: [
       { pubkey: exchangePda, isSigner: false, isWritable: true },
       { pubkey: pairPda, isSigner: false, isWritable: true },
       { pubkey: orderPda, isSigner: false, isWritable: true },
       { pubkey: userBalancePda, isSigner: false, isWritable: true },
+      { pubkey: deriveBlacklistHistoryPda()[0], isSigner: false, isWritable: false },
+      {
+        pubkey: eligibilityAccount ? eligibilityPda : CONFIDEX_PROGRAM_ID,
+        isSigner: false,
+        isWritable: false,
+      },
+      { pubkey: CONFIDEX_PROGRAM_ID, isSigner: false, isWritable: false },
       { pubkey: VERIFIER_PROGRAM_ID, isSigner: false, isWritable: false },
       { pubkey: maker, isSigner: true, isWritable: true },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      ...arciumAccountsToAccountMetas(mxeAccounts),
     ],
     programId: CONFIDEX_PROGRAM_ID,
     data: instructionData,
   });
+}
+
+/**
+ * Build place_order transaction
+ */
+export async function buildPlaceOrderTransaction(
+  params: PlaceOrderParams
+): Promise<PlaceOrderResult> {
+  const { connection, maker, baseMint, quoteMint } = params;
+
+  log.debug('Building place_order transaction...');
+
+  const [exchangePda] = deriveExchangePda();
+  const [pairPda] = derivePairPda(baseMint, quoteMint);
+
+  log.debug('[ConfidexClient] Exchange PDA:', { toString: exchangePda.toString() });
+  log.debug('[ConfidexClient] Pair PDA:', { toString: pairPda.toString() });
+
+  // Fetch current order count to derive order PDA
+  const orderCount = await fetchOrderCount(connection);
+  const instruction = await buildPlaceOrderInstruction(params, orderCount);
 
   // Build transaction
   const transaction = new Transaction().add(instruction);
 
   // Get recent blockhash
-  const { blockhash, lastValidBlockHeight } = await connection.getLatestBlockhash();
+  const { blockhash } = await connection.getLatestBlockhash();
   transaction.recentBlockhash = blockhash;
   transaction.feePayer = maker;
 
@@ -1105,10 +1224,11 @@ export interface VerifyEligibilityParams {
  *
  * Account order (from verify_eligibility.rs):
  * 1. exchange (read) - contains blacklist root
- * 2. eligibility (init_if_needed, mut) - stores verification result
- * 3. verifier_program (read) - Sunspot ZK verifier
- * 4. trader (signer, mut) - pays for account creation
- * 5. system_program (read)
+ * 2. blacklist_history (read) - attestation lifetime
+ * 3. eligibility (init_if_needed, mut) - stores verification result
+ * 4. verifier_program (read) - Sunspot ZK verifier
+ * 5. trader (signer, mut) - pays for account creation
+ * 6. system_program (read)
  */
 export async function buildVerifyEligibilityTransaction(
   params: VerifyEligibilityParams
@@ -1121,6 +1241,7 @@ export async function buildVerifyEligibilityTransaction(
 
   // Derive PDAs
   const [exchangePda] = deriveExchangePda();
+  const [blacklistHistoryPda] = deriveBlacklistHistoryPda();
   const [eligibilityPda] = deriveTraderEligibilityPda(trader);
 
   log.debug('PDAs derived:', {
@@ -1146,6 +1267,7 @@ export async function buildVerifyEligibilityTransaction(
   const instruction = new TransactionInstruction({
     keys: [
       { pubkey: exchangePda, isSigner: false, isWritable: false },
+      { pubkey: blacklistHistoryPda, isSigner: false, isWritable: false },
       { pubkey: eligibilityPda, isSigner: false, isWritable: true },
       { pubkey: VERIFIER_PROGRAM_ID, isSigner: false, isWritable: false },
       { pubkey: trader, isSigner: true, isWritable: true },
@@ -1158,7 +1280,7 @@ export async function buildVerifyEligibilityTransaction(
   const transaction = new Transaction();
   transaction.add(instruction);
 
-  log.debug('Verify eligibility transaction built', { accounts: 5 });
+  log.debug('Verify eligibility transaction built', { accounts: 6 });
 
   return transaction;
 }
@@ -1189,8 +1311,16 @@ export async function checkTraderEligibility(
   }
 
   // Parse TraderEligibility account
-  // Layout: discriminator(8) + trader(32) + is_verified(1) + verified_blacklist_root(32) + ...
-  const isVerified = accountInfo.data[8 + 32] === 1;
+  // Layout: discriminator(8) + trader(32) + is_verified(1) + verified_blacklist_root(32) +
+  //         verified_at(8) + verification_count(4) + bump(1) + valid_until(8)
+  // The on-chain check also accepts the previous blacklist root during its grace window,
+  // so only the flag and expiry are checked here.
+  const VALID_UNTIL_OFFSET = 8 + 32 + 1 + 32 + 8 + 4 + 1;
+  const data = accountInfo.data;
+  const validUntil = data.length >= VALID_UNTIL_OFFSET + 8
+    ? Number(data.readBigInt64LE(VALID_UNTIL_OFFSET))
+    : 0;
+  const isVerified = data[8 + 32] === 1 && validUntil > Math.floor(Date.now() / 1000);
 
   return { isVerified, eligibilityPda };
 }
@@ -1277,18 +1407,20 @@ export async function fetchPerpMarketData(
  * Build open_position transaction for perpetuals
  *
  * V3 Account order (from perp_open_position.rs - two-instruction pattern):
- * 1. exchange (read) - for blacklist root validation
- * 2. eligibility (read) - trader's ZK eligibility (must be verified via verify_eligibility first)
- * 3. perp_market (mut)
- * 4. funding_state (read)
- * 5. position (init, mut)
- * 6. oracle (read)
- * 7. trader_collateral_account (mut) - trader's USDC ATA
- * 8. collateral_vault (mut) - market's collateral vault
- * 9. trader (signer, mut)
- * 10. arcium_program (read)
- * 11. token_program (read) - SPL Token program for collateral transfer
- * 12. system_program (read)
+ * 1. exchange (read)
+ * 2. blacklist_history (read) - recent blacklist roots for eligibility validation
+ * 3. eligibility (read) - trader's ZK eligibility (must be verified via verify_eligibility first)
+ * 4. perp_market (mut)
+ * 5. funding_state (read)
+ * 6. allowlist_credential (optional, read) - program ID placeholder for non-allowlisted markets
+ * 7. position (init, mut)
+ * 8. oracle (read)
+ * 9. trader_collateral_account (mut) - trader's USDC ATA
+ * 10. collateral_vault (mut) - market's collateral vault
+ * 11. trader (signer, mut)
+ * 12. arcium_program (read)
+ * 13. token_program (read) - SPL Token program for collateral transfer
+ * 14. system_program (read)
  *
  * NOTE: ZK eligibility proof is verified separately via verify_eligibility instruction.
  * The trader must have a valid TraderEligibility account before calling open_position.
@@ -1442,9 +1574,12 @@ export async function buildOpenPositionTransaction(
   const instruction = new TransactionInstruction({
     keys: [
       { pubkey: exchangePda, isSigner: false, isWritable: false },
+      { pubkey: deriveBlacklistHistoryPda()[0], isSigner: false, isWritable: false },
       { pubkey: eligibilityPda, isSigner: false, isWritable: false },
       { pubkey: perpMarketPda, isSigner: false, isWritable: true },
       { pubkey: fundingStatePda, isSigner: false, isWritable: false },
+      // Optional allowlist credential (None = program ID); allowlisted markets need verify_allowlist first
+      { pubkey: CONFIDEX_PROGRAM_ID, isSigner: false, isWritable: false },
       { pubkey: positionPda, isSigner: false, isWritable: true },
       { pubkey: marketData.oraclePriceFeed, isSigner: false, isWritable: false },
       { pubkey: traderCollateralAta, isSigner: false, isWritable: true },
@@ -1466,18 +1601,7 @@ export async function buildOpenPositionTransaction(
   return { transaction, positionPda };
 }
 
-export interface AutoWrapAndPlaceOrderParams {
-  connection: Connection;
-  maker: PublicKey;
-  baseMint: PublicKey;
-  quoteMint: PublicKey;
-  side: Side;
-  orderType: OrderType;
-  encryptedAmount: Uint8Array;
-  encryptedPrice: Uint8Array;
-  eligibilityProof: Uint8Array;
-  // Production MPC: Full 32-byte ephemeral X25519 public key for Arcium decryption
-  ephemeralPubkey: Uint8Array;
+export interface AutoWrapAndPlaceOrderParams extends PlaceOrderParams {
   // Auto-wrap parameters
   wrapTokenMint: PublicKey;  // Which token to wrap (SOL or USDC)
   wrapAmount: bigint;        // How much to wrap (difference needed)
@@ -1503,11 +1627,6 @@ export async function buildAutoWrapAndPlaceOrderTransaction(
     baseMint,
     quoteMint,
     side,
-    orderType,
-    encryptedAmount,
-    encryptedPrice,
-    eligibilityProof,
-    ephemeralPubkey,
     wrapTokenMint,
     wrapAmount,
   } = params;
@@ -1536,55 +1655,9 @@ export async function buildAutoWrapAndPlaceOrderTransaction(
   console.log('[ConfidexClient] Added', wrapInstructions.length, 'wrap instructions');
 
   // Step 2: Build place_order instruction
-  // Derive PDAs
-  const [exchangePda] = deriveExchangePda();
-  const [pairPda] = derivePairPda(baseMint, quoteMint);
-
   // Fetch current order count to derive order PDA
   const orderCount = await fetchOrderCount(connection);
-  const [orderPda] = deriveOrderPda(maker, orderCount);
-
-  // Determine which token mint the user is spending
-  // Buy orders spend quote (USDC), sell orders spend base (SOL)
-  const spendMint = side === Side.Buy ? quoteMint : baseMint;
-  const [userBalancePda] = deriveUserBalancePda(maker, spendMint);
-
-  log.debug('[ConfidexClient] Order PDA:', { toString: orderPda.toString() });
-  log.debug('[ConfidexClient] Order count:', { toString: orderCount.toString() });
-  log.debug('[ConfidexClient] User balance PDA:', { toString: userBalancePda.toString() });
-  log.debug('[ConfidexClient] Spending mint:', { toString: spendMint.toString() });
-
-  // Build instruction data (V5 format - no plaintext fields)
-  const instructionData = buildPlaceOrderData(
-    side,
-    orderType,
-    encryptedAmount,
-    encryptedPrice,
-    eligibilityProof,
-    ephemeralPubkey
-  );
-
-  // Build place_order instruction with accounts (matching place_order.rs):
-  // 1. exchange (mut) - ExchangeState PDA
-  // 2. pair (mut) - TradingPair PDA
-  // 3. order (init, mut) - ConfidentialOrder PDA
-  // 4. user_balance (mut) - User's confidential balance for the token being spent
-  // 5. verifier_program - Sunspot ZK verifier
-  // 6. maker (signer, mut)
-  // 7. system_program
-  const placeOrderInstruction = new TransactionInstruction({
-    keys: [
-      { pubkey: exchangePda, isSigner: false, isWritable: true },
-      { pubkey: pairPda, isSigner: false, isWritable: true },
-      { pubkey: orderPda, isSigner: false, isWritable: true },
-      { pubkey: userBalancePda, isSigner: false, isWritable: true },
-      { pubkey: VERIFIER_PROGRAM_ID, isSigner: false, isWritable: false },
-      { pubkey: maker, isSigner: true, isWritable: true },
-      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
-    ],
-    programId: CONFIDEX_PROGRAM_ID,
-    data: instructionData,
-  });
+  const placeOrderInstruction = await buildPlaceOrderInstruction(params, orderCount);
 
   transaction.add(placeOrderInstruction);
 
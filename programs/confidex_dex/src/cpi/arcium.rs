@@ -62,6 +62,16 @@ pub mod mxe_discriminators {
     pub const COMPARE_PRICES: [u8; 8] = [0x0f, 0xe0, 0x51, 0x76, 0xbb, 0x73, 0xde, 0xa6];
     /// calculate_fill: sha256("global:calculate_fill")[0..8]
     pub const CALCULATE_FILL: [u8; 8] = [0xe2, 0xd3, 0xaf, 0xc6, 0x8b, 0xce, 0xa4, 0xd0];
+    /// batch_calculate_fill: sha256("global:batch_calculate_fill")[0..8]
+    pub const BATCH_CALCULATE_FILL: [u8; 8] = [0xa7, 0x53, 0x0e, 0xc6, 0xa5, 0x67, 0xe2, 0xe6];
+    /// check_order_balance: sha256("global:check_order_balance")[0..8]
+    pub const CHECK_ORDER_BALANCE: [u8; 8] = [0x8e, 0x07, 0xc5, 0xfa, 0x0f, 0x02, 0x6e, 0x25];
+    /// check_order_amend: sha256("global:check_order_amend")[0..8]
+    pub const CHECK_ORDER_AMEND: [u8; 8] = [0x2c, 0x90, 0x7a, 0x5c, 0x77, 0x88, 0xf8, 0x99];
+    /// calculate_refund: sha256("global:calculate_refund")[0..8]
+    pub const CALCULATE_REFUND: [u8; 8] = [0x1d, 0xbc, 0x15, 0xfc, 0x14, 0x52, 0x4f, 0x78];
+    /// batch_calculate_refund: sha256("global:batch_calculate_refund")[0..8]
+    pub const BATCH_CALCULATE_REFUND: [u8; 8] = [0x2f, 0xf8, 0x0f, 0xdb, 0x4a, 0x11, 0x41, 0x46];
 
     // === Perpetuals Operations ===
     /// verify_position_params: sha256("global:verify_position_params")[0..8]
@@ -74,6 +84,14 @@ pub mod mxe_discriminators {
     pub const CALCULATE_PNL: [u8; 8] = [0x59, 0xdf, 0x00, 0x06, 0xae, 0x49, 0x22, 0xb0];
     /// calculate_funding: sha256("global:calculate_funding")[0..8]
     pub const CALCULATE_FUNDING: [u8; 8] = [0x6d, 0x7e, 0x85, 0xc8, 0xe7, 0x30, 0xe3, 0x80];
+    /// calculate_adl: sha256("global:calculate_adl")[0..8]
+    pub const CALCULATE_ADL: [u8; 8] = [0xbd, 0xde, 0x90, 0x23, 0x21, 0x9e, 0x7f, 0x24];
+    /// rank_adl_candidates: sha256("global:rank_adl_candidates")[0..8]
+    pub const RANK_ADL_CANDIDATES: [u8; 8] = [0x77, 0xe8, 0x76, 0x09, 0xc8, 0x00, 0x4a, 0x97];
+    /// calculate_partial_liquidation: sha256("global:calculate_partial_liquidation")[0..8]
+    pub const CALCULATE_PARTIAL_LIQUIDATION: [u8; 8] = [0x38, 0xd7, 0x94, 0x4a, 0x24, 0xc8, 0x70, 0xfc];
+    /// batch_exit_trigger_check: sha256("global:batch_exit_trigger_check")[0..8]
+    pub const BATCH_EXIT_TRIGGER_CHECK: [u8; 8] = [0x79, 0x2f, 0x66, 0xa6, 0x06, 0xb4, 0xd8, 0x0f];
 }
 
 /// Supported Arcium operations for confidential DEX
@@ -303,6 +321,7 @@ pub fn compare_encrypted_prices(
 ///
 /// CPIs to arcium_mxe program using the full 12-account structure.
 /// Calculates: min(buy_remaining, sell_remaining) where remaining = amount - filled
+/// (zero if prices don't overlap), plus the updated cumulative filled amounts.
 pub fn queue_calculate_fill<'info>(
     accounts: MxeCpiAccounts<'_, 'info>,
     computation_offset: u64,
@@ -310,6 +329,12 @@ pub fn queue_calculate_fill<'info>(
     buy_filled: &EncryptedU64,
     sell_amount: &EncryptedU64,
     sell_filled: &EncryptedU64,
+    buy_price: &EncryptedU64,
+    sell_price: &EncryptedU64,
+    buy_has_fills: bool,
+    sell_has_fills: bool,
+    buy_fill_or_kill: bool,
+    sell_fill_or_kill: bool,
     pub_key: &[u8; 32],
     nonce: u128,
     buy_order: Option<&Pubkey>,
@@ -318,11 +343,13 @@ pub fn queue_calculate_fill<'info>(
     msg!("Arcium CPI: calculate_fill (MPC) via MXE");
 
     // Build CPI instruction data
-    // Format: discriminator (8) + computation_offset (8) + 4x ciphertext (32 each) +
-    //         pub_key (32) + nonce (16) + buy_order (Option) + sell_order (Option)
+    // Format: discriminator (8) + computation_offset (8) + 6x ciphertext (32 each) +
+    //         buy_has_fills (1) + sell_has_fills (1) + buy_fill_or_kill (1) +
+    //         sell_fill_or_kill (1) + pub_key (32) + nonce (16) +
+    //         buy_order (Option) + sell_order (Option)
     let buy_order_size = if buy_order.is_some() { 33 } else { 1 };
     let sell_order_size = if sell_order.is_some() { 33 } else { 1 };
-    let total_size = 8 + 8 + 32 * 4 + 32 + 16 + buy_order_size + sell_order_size;
+    let total_size = 8 + 8 + 32 * 6 + 4 + 32 + 16 + buy_order_size + sell_order_size;
 
     let mut ix_data = Vec::with_capacity(total_size);
     ix_data.extend_from_slice(&mxe_discriminators::CALCULATE_FILL);
@@ -332,6 +359,12 @@ pub fn queue_calculate_fill<'info>(
     ix_data.extend_from_slice(&buy_filled[16..48]);
     ix_data.extend_from_slice(&sell_amount[16..48]);
     ix_data.extend_from_slice(&sell_filled[16..48]);
+    ix_data.extend_from_slice(&buy_price[16..48]);
+    ix_data.extend_from_slice(&sell_price[16..48]);
+    ix_data.push(buy_has_fills as u8);
+    ix_data.push(sell_has_fills as u8);
+    ix_data.push(buy_fill_or_kill as u8);
+    ix_data.push(sell_fill_or_kill as u8);
     ix_data.extend_from_slice(pub_key);
     ix_data.extend_from_slice(&nonce.to_le_bytes());
 
@@ -404,100 +437,656 @@ pub fn queue_calculate_fill<'info>(
     Ok(QueuedComputation { request_id })
 }
 
-/// REMOVED IN MIGRATION: Sync fill calculation extracted plaintext from ciphertext
-///
-/// This function has been removed because it:
-/// 1. Extracted plaintext from ciphertext position 16-23 (CRITICAL SECURITY RISK)
-/// 2. Bypassed MPC entirely, defeating the purpose of encryption
+/// Queue an order balance check via MPC
 ///
-/// Use the async MPC flow via queue_calculate_fill() or CPI to MXE.calculate_fill() instead.
+/// CPIs to arcium_mxe program using the full 12-account structure.
+/// Checks: balance >= amount * price / 1e9 (buys) or balance >= amount (sells)
 ///
-/// PANICS: This function always panics. Migration to proper MXE CPI required.
-#[deprecated(since = "0.2.0", note = "Use queue_calculate_fill() or CPI to MXE instead")]
-#[allow(unused_variables)]
-pub fn calculate_encrypted_fill(
-    _arcium_program: &AccountInfo,
-    _cluster: &Pubkey,
-    _buy_amount: &EncryptedU64,
-    _buy_filled: &EncryptedU64,
-    _sell_amount: &EncryptedU64,
-    _sell_filled: &EncryptedU64,
-) -> Result<(EncryptedU64, bool, bool)> {
-    panic!(
-        "FATAL: calculate_encrypted_fill() sync fallback removed in migration. \
-        This function extracted plaintext from ciphertext, defeating encryption. \
-        Use queue_calculate_fill() for async MPC or CPI to MXE.calculate_fill()."
-    );
+/// The MXE callback CPIs to the DEX's place_order_callback with the revealed
+/// result and escrow amount, activating or rejecting the pending order.
+pub fn queue_check_order_balance<'info>(
+    accounts: MxeCpiAccounts<'_, 'info>,
+    computation_offset: u64,
+    balance: u64,
+    order_amount: &EncryptedU64,
+    order_price: &EncryptedU64,
+    is_buy: bool,
+    pub_key: &[u8; 32],
+    nonce: u128,
+    order: &Pubkey,
+    user_balance: &Pubkey,
+    pair: &Pubkey,
+) -> Result<QueuedComputation> {
+    msg!("Arcium CPI: check_order_balance (MPC) via MXE");
+
+    // Build CPI instruction data
+    // Format: discriminator (8) + computation_offset (8) + balance (8) + 2x ciphertext (32 each) +
+    //         is_buy (1) + pub_key (32) + nonce (16) + order (32) + user_balance (32) + pair (32)
+    let mut ix_data = Vec::with_capacity(8 + 8 + 8 + 32 * 2 + 1 + 32 + 16 + 32 * 3);
+    ix_data.extend_from_slice(&mxe_discriminators::CHECK_ORDER_BALANCE);
+    ix_data.extend_from_slice(&computation_offset.to_le_bytes());
+    ix_data.extend_from_slice(&balance.to_le_bytes());
+    // Extract 32-byte ciphertext portions from 64-byte encrypted values
+    ix_data.extend_from_slice(&order_amount[16..48]);
+    ix_data.extend_from_slice(&order_price[16..48]);
+    ix_data.push(if is_buy { 1 } else { 0 });
+    ix_data.extend_from_slice(pub_key);
+    ix_data.extend_from_slice(&nonce.to_le_bytes());
+    ix_data.extend_from_slice(order.as_ref());
+    ix_data.extend_from_slice(user_balance.as_ref());
+    ix_data.extend_from_slice(pair.as_ref());
+
+    // Build the 12-account structure required by Arcium's queue_computation
+    let ix = Instruction {
+        program_id: ARCIUM_MXE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*accounts.payer.key, true),
+            AccountMeta::new(*accounts.sign_pda_account.key, false),
+            AccountMeta::new(*accounts.mxe_account.key, false),
+            AccountMeta::new(*accounts.mempool_account.key, false),
+            AccountMeta::new(*accounts.executing_pool.key, false),
+            AccountMeta::new(*accounts.computation_account.key, false),
+            AccountMeta::new_readonly(*accounts.comp_def_account.key, false),
+            AccountMeta::new(*accounts.cluster_account.key, false),
+            AccountMeta::new(*accounts.pool_account.key, false),
+            AccountMeta::new(*accounts.clock_account.key, false),
+            AccountMeta::new_readonly(*accounts.system_program.key, false),
+            AccountMeta::new_readonly(*accounts.arcium_program.key, false),
+        ],
+        data: ix_data,
+    };
+
+    invoke(
+        &ix,
+        &[
+            accounts.payer.clone(),
+            accounts.sign_pda_account.clone(),
+            accounts.mxe_account.clone(),
+            accounts.mempool_account.clone(),
+            accounts.executing_pool.clone(),
+            accounts.computation_account.clone(),
+            accounts.comp_def_account.clone(),
+            accounts.cluster_account.clone(),
+            accounts.pool_account.clone(),
+            accounts.clock_account.clone(),
+            accounts.system_program.clone(),
+            accounts.arcium_program.clone(),
+        ],
+    )?;
+
+    // Use computation account's public key as request_id (matches MXE callback)
+    let request_id = accounts.computation_account.key.to_bytes();
+
+    msg!("MXE CPI complete (check_order_balance), computation_offset={}, request_id={:?}",
+        computation_offset, &request_id[0..8]);
+
+    Ok(QueuedComputation { request_id })
 }
 
-/// REMOVED IN MIGRATION: On-chain "encryption" stored plaintext at known position
-///
-/// This function has been removed because it:
-/// 1. Stored plaintext at offset 16-23, NOT actual encryption
-/// 2. Created fake "ciphertext" that MPC couldn't decrypt
-/// 3. Provided false sense of security
+/// Queue an order amend (cancel-replace) balance check via MPC
 ///
-/// For public constants in MPC operations, use ArgBuilder.plaintext_u64() in the MXE.
-/// User values must be encrypted client-side using RescueCipher.
+/// CPIs to arcium_mxe program using the full 12-account structure.
+/// Computes the escrow required by the replacement and checks it against
+/// `available` (balance + the order's current escrow_remaining). The current
+/// escrow is plaintext on-chain, so it is never re-derived from ciphertexts.
 ///
-/// PANICS: This function always panics. Migration to proper encryption required.
-#[deprecated(since = "0.2.0", note = "Use client-side RescueCipher or ArgBuilder.plaintext_*()")]
-#[allow(unused_variables)]
-pub fn encrypt_value(
-    _arcium_program: &AccountInfo,
-    _mxe_pubkey: &[u8; 32],
-    _value: u64,
-) -> Result<EncryptedU64> {
-    panic!(
-        "FATAL: encrypt_value() removed in migration. \
-        This function stored plaintext at known position, NOT actual encryption. \
-        Use client-side RescueCipher for user values or ArgBuilder.plaintext_u64() for constants."
-    );
+/// The MXE callback CPIs to the DEX's amend_order_callback with the revealed
+/// result and the new escrow amount.
+pub fn queue_check_order_amend<'info>(
+    accounts: MxeCpiAccounts<'_, 'info>,
+    computation_offset: u64,
+    new_amount: &EncryptedU64,
+    new_price: &EncryptedU64,
+    available: u64,
+    is_buy: bool,
+    pub_key: &[u8; 32],
+    nonce: u128,
+    order: &Pubkey,
+    user_balance: &Pubkey,
+    pair: &Pubkey,
+) -> Result<QueuedComputation> {
+    msg!("Arcium CPI: check_order_amend (MPC) via MXE");
+
+    // Build CPI instruction data
+    // Format: discriminator (8) + computation_offset (8) + 2x ciphertext (32 each) +
+    //         available (8) + is_buy (1) + pub_key (32) + nonce (16) +
+    //         order (32) + user_balance (32) + pair (32)
+    let mut ix_data = Vec::with_capacity(8 + 8 + 32 * 2 + 8 + 1 + 32 + 16 + 32 * 3);
+    ix_data.extend_from_slice(&mxe_discriminators::CHECK_ORDER_AMEND);
+    ix_data.extend_from_slice(&computation_offset.to_le_bytes());
+    // Extract 32-byte ciphertext portions from 64-byte encrypted values
+    ix_data.extend_from_slice(&new_amount[16..48]);
+    ix_data.extend_from_slice(&new_price[16..48]);
+    ix_data.extend_from_slice(&available.to_le_bytes());
+    ix_data.push(if is_buy { 1 } else { 0 });
+    ix_data.extend_from_slice(pub_key);
+    ix_data.extend_from_slice(&nonce.to_le_bytes());
+    ix_data.extend_from_slice(order.as_ref());
+    ix_data.extend_from_slice(user_balance.as_ref());
+    ix_data.extend_from_slice(pair.as_ref());
+
+    // Build the 12-account structure required by Arcium's queue_computation
+    let ix = Instruction {
+        program_id: ARCIUM_MXE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*accounts.payer.key, true),
+            AccountMeta::new(*accounts.sign_pda_account.key, false),
+            AccountMeta::new(*accounts.mxe_account.key, false),
+            AccountMeta::new(*accounts.mempool_account.key, false),
+            AccountMeta::new(*accounts.executing_pool.key, false),
+            AccountMeta::new(*accounts.computation_account.key, false),
+            AccountMeta::new_readonly(*accounts.comp_def_account.key, false),
+            AccountMeta::new(*accounts.cluster_account.key, false),
+            AccountMeta::new(*accounts.pool_account.key, false),
+            AccountMeta::new(*accounts.clock_account.key, false),
+            AccountMeta::new_readonly(*accounts.system_program.key, false),
+            AccountMeta::new_readonly(*accounts.arcium_program.key, false),
+        ],
+        data: ix_data,
+    };
+
+    invoke(
+        &ix,
+        &[
+            accounts.payer.clone(),
+            accounts.sign_pda_account.clone(),
+            accounts.mxe_account.clone(),
+            accounts.mempool_account.clone(),
+            accounts.executing_pool.clone(),
+            accounts.computation_account.clone(),
+            accounts.comp_def_account.clone(),
+            accounts.cluster_account.clone(),
+            accounts.pool_account.clone(),
+            accounts.clock_account.clone(),
+            accounts.system_program.clone(),
+            accounts.arcium_program.clone(),
+        ],
+    )?;
+
+    // Use computation account's public key as request_id (matches MXE callback)
+    let request_id = accounts.computation_account.key.to_bytes();
+
+    msg!("MXE CPI complete (check_order_amend), computation_offset={}, request_id={:?}",
+        computation_offset, &request_id[0..8]);
+
+    Ok(QueuedComputation { request_id })
 }
 
-/// REMOVED IN MIGRATION: Addition on encrypted data requires MPC
+/// Queue an order refund calculation via MPC
 ///
-/// This function has been removed because it:
-/// 1. Returned first operand unchanged (NOT actual addition)
-/// 2. Created incorrect results silently
-/// 3. Cannot perform arithmetic on ciphertext without MPC
-///
-/// Use MXE.add_encrypted() instruction via CPI, or redesign to use
-/// async MPC computation queue.
+/// CPIs to arcium_mxe program using the full 12-account structure.
+/// Calculates the unfilled escrow: (amount - filled) for sells, or
+/// (amount - filled) * price / 1e9 for buys.
 ///
-/// PANICS: This function always panics. Migration to MXE CPI required.
-#[deprecated(since = "0.2.0", note = "Use MXE CPI for encrypted arithmetic")]
-#[allow(unused_variables)]
-pub fn add_encrypted(
-    _arcium_program: &AccountInfo,
-    _a: &EncryptedU64,
-    _b: &EncryptedU64,
-) -> Result<EncryptedU64> {
-    panic!(
-        "FATAL: add_encrypted() cannot perform arithmetic on ciphertext without MPC. \
-        This function returned first operand unchanged (incorrect). \
-        Use MXE.add_encrypted() via CPI or redesign with async MPC queue."
-    );
+/// The MXE callback CPIs to the DEX's cancel_order_callback with the revealed
+/// refund amount, releasing the escrow and deactivating the order.
+pub fn queue_calculate_refund<'info>(
+    accounts: MxeCpiAccounts<'_, 'info>,
+    computation_offset: u64,
+    amount: &EncryptedU64,
+    filled: &EncryptedU64,
+    price: &EncryptedU64,
+    is_buy: bool,
+    has_fills: bool,
+    pub_key: &[u8; 32],
+    nonce: u128,
+    order: &Pubkey,
+    user_base_balance: &Pubkey,
+    user_quote_balance: &Pubkey,
+    pair: &Pubkey,
+    exchange: &Pubkey,
+) -> Result<QueuedComputation> {
+    msg!("Arcium CPI: calculate_refund (MPC) via MXE");
+
+    // Build CPI instruction data
+    // Format: discriminator (8) + computation_offset (8) + 3x ciphertext (32 each) +
+    //         is_buy (1) + has_fills (1) + pub_key (32) + nonce (16) +
+    //         order (32) + user_base_balance (32) + user_quote_balance (32) +
+    //         pair (32) + exchange (32)
+    let mut ix_data = Vec::with_capacity(8 + 8 + 32 * 3 + 2 + 32 + 16 + 32 * 5);
+    ix_data.extend_from_slice(&mxe_discriminators::CALCULATE_REFUND);
+    ix_data.extend_from_slice(&computation_offset.to_le_bytes());
+    // Extract 32-byte ciphertext portions from 64-byte encrypted values
+    ix_data.extend_from_slice(&amount[16..48]);
+    ix_data.extend_from_slice(&filled[16..48]);
+    ix_data.extend_from_slice(&price[16..48]);
+    ix_data.push(is_buy as u8);
+    ix_data.push(has_fills as u8);
+    ix_data.extend_from_slice(pub_key);
+    ix_data.extend_from_slice(&nonce.to_le_bytes());
+    ix_data.extend_from_slice(order.as_ref());
+    ix_data.extend_from_slice(user_base_balance.as_ref());
+    ix_data.extend_from_slice(user_quote_balance.as_ref());
+    ix_data.extend_from_slice(pair.as_ref());
+    ix_data.extend_from_slice(exchange.as_ref());
+
+    // Build the 12-account structure required by Arcium's queue_computation
+    let ix = Instruction {
+        program_id: ARCIUM_MXE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*accounts.payer.key, true),
+            AccountMeta::new(*accounts.sign_pda_account.key, false),
+            AccountMeta::new(*accounts.mxe_account.key, false),
+            AccountMeta::new(*accounts.mempool_account.key, false),
+            AccountMeta::new(*accounts.executing_pool.key, false),
+            AccountMeta::new(*accounts.computation_account.key, false),
+            AccountMeta::new_readonly(*accounts.comp_def_account.key, false),
+            AccountMeta::new(*accounts.cluster_account.key, false),
+            AccountMeta::new(*accounts.pool_account.key, false),
+            AccountMeta::new(*accounts.clock_account.key, false),
+            AccountMeta::new_readonly(*accounts.system_program.key, false),
+            AccountMeta::new_readonly(*accounts.arcium_program.key, false),
+        ],
+        data: ix_data,
+    };
+
+    invoke(
+        &ix,
+        &[
+            accounts.payer.clone(),
+            accounts.sign_pda_account.clone(),
+            accounts.mxe_account.clone(),
+            accounts.mempool_account.clone(),
+            accounts.executing_pool.clone(),
+            accounts.computation_account.clone(),
+            accounts.comp_def_account.clone(),
+            accounts.cluster_account.clone(),
+            accounts.pool_account.clone(),
+            accounts.clock_account.clone(),
+            accounts.system_program.clone(),
+            accounts.arcium_program.clone(),
+        ],
+    )?;
+
+    // Use computation account's public key as request_id (matches MXE callback)
+    let request_id = accounts.computation_account.key.to_bytes();
+
+    msg!("MXE CPI complete (calculate_refund), computation_offset={}, request_id={:?}",
+        computation_offset, &request_id[0..8]);
+
+    Ok(QueuedComputation { request_id })
 }
 
-/// REMOVED IN MIGRATION: Subtraction on encrypted data requires MPC
-///
-/// This function has been removed because it:
-/// 1. Returned first operand unchanged (NOT actual subtraction)
-/// 2. Created incorrect results silently
-/// 3. Cannot perform arithmetic on ciphertext without MPC
+/// Maximum orders per batch_calculate_refund computation (fixed by the circuit)
+pub const MAX_BATCH_CANCEL_ORDERS: usize = 5;
+
+/// Order data for batch refund calculation
+pub struct BatchRefundOrderData {
+    /// Order account (refunded by the callback)
+    pub order: Pubkey,
+    pub encrypted_amount: EncryptedU64,
+    pub encrypted_filled: EncryptedU64,
+    pub encrypted_price: EncryptedU64,
+    pub is_buy: bool,
+    /// False while the order's filled ciphertext is unset
+    pub has_fills: bool,
+}
+
+/// Queue a batch refund calculation for up to 5 orders via MPC
 ///
-/// Use MXE.sub_encrypted() instruction via CPI, or redesign to use
-/// async MPC computation queue.
+/// CPIs to arcium_mxe program using the full 12-account structure.
+/// All orders must belong to one maker on one pair, since the callback
+/// refunds into a single base/quote balance pair.
 ///
-/// PANICS: This function always panics. Migration to MXE CPI required.
-#[deprecated(since = "0.2.0", note = "Use MXE CPI for encrypted arithmetic")]
-#[allow(unused_variables)]
-pub fn sub_encrypted(
-    _arcium_program: &AccountInfo,
-    _a: &EncryptedU64,
-    _b: &EncryptedU64,
-) -> Result<EncryptedU64> {
+/// The MXE callback CPIs to the DEX's cancel_orders_batch_callback with the
+/// revealed refund amounts, releasing every escrow in one instruction.
+pub fn queue_batch_calculate_refund<'info>(
+    accounts: MxeCpiAccounts<'_, 'info>,
+    computation_offset: u64,
+    orders: &[BatchRefundOrderData],
+    pub_key: &[u8; 32],
+    nonce: u128,
+    user_base_balance: &Pubkey,
+    user_quote_balance: &Pubkey,
+    pair: &Pubkey,
+    exchange: &Pubkey,
+) -> Result<QueuedComputation> {
+    let count = orders.len();
+    if count == 0 || count > MAX_BATCH_CANCEL_ORDERS {
+        return Err(error!(ArciumError::InvalidResult));
+    }
+
+    msg!("Arcium CPI: batch_calculate_refund (MPC) via MXE - {} orders", count);
+
+    // Build CPI instruction data
+    // Format: discriminator (8) + computation_offset (8) + amounts (32 * 5) +
+    //         filled (32 * 5) + prices (32 * 5) + is_buy (5) + has_fills (5) +
+    //         count (1) + pub_key (32) + nonce (16) +
+    //         orders (Vec<Pubkey> = 4 + 32 * count) +
+    //         user_base_balance (32) + user_quote_balance (32) + pair (32) + exchange (32)
+    let mut ix_data = Vec::with_capacity(
+        8 + 8 + 32 * MAX_BATCH_CANCEL_ORDERS * 3 + MAX_BATCH_CANCEL_ORDERS * 2 + 1
+            + 32 + 16 + 4 + 32 * count + 32 * 4,
+    );
+    ix_data.extend_from_slice(&mxe_discriminators::BATCH_CALCULATE_REFUND);
+    ix_data.extend_from_slice(&computation_offset.to_le_bytes());
+
+    // Extract 32-byte ciphertext portions, padding unused slots with zeros
+    // (the circuit refunds 0 for slots >= count)
+    for i in 0..MAX_BATCH_CANCEL_ORDERS {
+        match orders.get(i) {
+            Some(o) => ix_data.extend_from_slice(&o.encrypted_amount[16..48]),
+            None => ix_data.extend_from_slice(&[0u8; 32]),
+        }
+    }
+    for i in 0..MAX_BATCH_CANCEL_ORDERS {
+        match orders.get(i) {
+            Some(o) => ix_data.extend_from_slice(&o.encrypted_filled[16..48]),
+            None => ix_data.extend_from_slice(&[0u8; 32]),
+        }
+    }
+    for i in 0..MAX_BATCH_CANCEL_ORDERS {
+        match orders.get(i) {
+            Some(o) => ix_data.extend_from_slice(&o.encrypted_price[16..48]),
+            None => ix_data.extend_from_slice(&[0u8; 32]),
+        }
+    }
+    for i in 0..MAX_BATCH_CANCEL_ORDERS {
+        ix_data.push(orders.get(i).map_or(0, |o| o.is_buy as u8));
+    }
+    for i in 0..MAX_BATCH_CANCEL_ORDERS {
+        ix_data.push(orders.get(i).map_or(0, |o| o.has_fills as u8));
+    }
+    ix_data.push(count as u8);
+    ix_data.extend_from_slice(pub_key);
+    ix_data.extend_from_slice(&nonce.to_le_bytes());
+
+    // Serialize Vec<Pubkey> for the order accounts
+    ix_data.extend_from_slice(&(count as u32).to_le_bytes());
+    for o in orders {
+        ix_data.extend_from_slice(o.order.as_ref());
+    }
+    ix_data.extend_from_slice(user_base_balance.as_ref());
+    ix_data.extend_from_slice(user_quote_balance.as_ref());
+    ix_data.extend_from_slice(pair.as_ref());
+    ix_data.extend_from_slice(exchange.as_ref());
+
+    // Build the 12-account structure required by Arcium's queue_computation
+    let ix = Instruction {
+        program_id: ARCIUM_MXE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*accounts.payer.key, true),
+            AccountMeta::new(*accounts.sign_pda_account.key, false),
+            AccountMeta::new(*accounts.mxe_account.key, false),
+            AccountMeta::new(*accounts.mempool_account.key, false),
+            AccountMeta::new(*accounts.executing_pool.key, false),
+            AccountMeta::new(*accounts.computation_account.key, false),
+            AccountMeta::new_readonly(*accounts.comp_def_account.key, false),
+            AccountMeta::new(*accounts.cluster_account.key, false),
+            AccountMeta::new(*accounts.pool_account.key, false),
+            AccountMeta::new(*accounts.clock_account.key, false),
+            AccountMeta::new_readonly(*accounts.system_program.key, false),
+            AccountMeta::new_readonly(*accounts.arcium_program.key, false),
+        ],
+        data: ix_data,
+    };
+
+    invoke(
+        &ix,
+        &[
+            accounts.payer.clone(),
+            accounts.sign_pda_account.clone(),
+            accounts.mxe_account.clone(),
+            accounts.mempool_account.clone(),
+            accounts.executing_pool.clone(),
+            accounts.computation_account.clone(),
+            accounts.comp_def_account.clone(),
+            accounts.cluster_account.clone(),
+            accounts.pool_account.clone(),
+            accounts.clock_account.clone(),
+            accounts.system_program.clone(),
+            accounts.arcium_program.clone(),
+        ],
+    )?;
+
+    // Use computation account's public key as request_id (matches MXE callback)
+    let request_id = accounts.computation_account.key.to_bytes();
+
+    msg!("MXE CPI complete (batch_calculate_refund), computation_offset={}, request_id={:?}",
+        computation_offset, &request_id[0..8]);
+
+    Ok(QueuedComputation { request_id })
+}
+
+/// REMOVED IN MIGRATION: Sync fill calculation extracted plaintext from ciphertext
+///
+/// This function has been removed because it:
+/// 1. Extracted plaintext from ciphertext position 16-23 (CRITICAL SECURITY RISK)
+/// 2. Bypassed MPC entirely, defeating the purpose of encryption
+///
+/// Use the async MPC flow via queue_calculate_fill() or CPI to MXE.calculate_fill() instead.
+///
+/// PANICS: This function always panics. Migration to proper MXE CPI required.
+#[deprecated(since = "0.2.0", note = "Use queue_calculate_fill() or CPI to MXE instead")]
+#[allow(unused_variables)]
+pub fn calculate_encrypted_fill(
+    _arcium_program: &AccountInfo,
+    _cluster: &Pubkey,
+    _buy_amount: &EncryptedU64,
+    _buy_filled: &EncryptedU64,
+    _sell_amount: &EncryptedU64,
+    _sell_filled: &EncryptedU64,
+) -> Result<(EncryptedU64, bool, bool)> {
+    panic!(
+        "FATAL: calculate_encrypted_fill() sync fallback removed in migration. \
+        This function extracted plaintext from ciphertext, defeating encryption. \
+        Use queue_calculate_fill() for async MPC or CPI to MXE.calculate_fill()."
+    );
+}
+
+/// Maximum order pairs per batch_calculate_fill computation (fixed by the circuit)
+pub const MAX_BATCH_MATCH_PAIRS: usize = 5;
+
+/// Order pair data for batch fill calculation
+pub struct BatchFillPairData {
+    pub buy_order: Pubkey,
+    pub sell_order: Pubkey,
+    pub buy_amount: EncryptedU64,
+    pub buy_filled: EncryptedU64,
+    pub sell_amount: EncryptedU64,
+    pub sell_filled: EncryptedU64,
+    pub buy_price: EncryptedU64,
+    pub sell_price: EncryptedU64,
+    /// False while the order's filled ciphertext is unset
+    pub buy_has_fills: bool,
+    pub sell_has_fills: bool,
+    /// True if the order's time-in-force is fill-or-kill
+    pub buy_fill_or_kill: bool,
+    pub sell_fill_or_kill: bool,
+}
+
+/// Queue a batch fill calculation for up to 5 order pairs via MPC
+///
+/// CPIs to arcium_mxe program using the full 12-account structure.
+/// One MPC computation (and one fee) covers every pair in the batch; each
+/// slot applies the same remaining-size and fill-or-kill rules as
+/// queue_calculate_fill.
+///
+/// The MXE callback CPIs to the DEX's finalize_match_batch with one
+/// calculate_fill-format result per pair.
+pub fn queue_batch_calculate_fill<'info>(
+    accounts: MxeCpiAccounts<'_, 'info>,
+    computation_offset: u64,
+    pairs: &[BatchFillPairData],
+    pub_key: &[u8; 32],
+    nonce: u128,
+) -> Result<QueuedComputation> {
+    let count = pairs.len();
+    if count == 0 || count > MAX_BATCH_MATCH_PAIRS {
+        return Err(error!(ArciumError::InvalidResult));
+    }
+
+    msg!("Arcium CPI: batch_calculate_fill (MPC) via MXE - {} pairs", count);
+
+    // Build CPI instruction data
+    // Format: discriminator (8) + computation_offset (8) + buy_amounts (32 * 5) +
+    //         buy_filled (32 * 5) + sell_amounts (32 * 5) + sell_filled (32 * 5) +
+    //         buy_prices (32 * 5) + sell_prices (32 * 5) + buy_has_fills (5) +
+    //         sell_has_fills (5) + buy_fill_or_kill (5) + sell_fill_or_kill (5) +
+    //         count (1) + pub_key (32) + nonce (16) +
+    //         order_pairs (Vec<Pubkey> = 4 + 32 * 2 * count)
+    let mut ix_data = Vec::with_capacity(
+        8 + 8 + 32 * MAX_BATCH_MATCH_PAIRS * 6 + MAX_BATCH_MATCH_PAIRS * 4 + 1
+            + 32 + 16 + 4 + 64 * count,
+    );
+    ix_data.extend_from_slice(&mxe_discriminators::BATCH_CALCULATE_FILL);
+    ix_data.extend_from_slice(&computation_offset.to_le_bytes());
+
+    // Extract 32-byte ciphertext portions, padding unused slots with zeros
+    // (the circuit never matches slots >= count)
+    let ciphertext_fields: [fn(&BatchFillPairData) -> &EncryptedU64; 6] = [
+        |p| &p.buy_amount,
+        |p| &p.buy_filled,
+        |p| &p.sell_amount,
+        |p| &p.sell_filled,
+        |p| &p.buy_price,
+        |p| &p.sell_price,
+    ];
+    for field in ciphertext_fields {
+        for i in 0..MAX_BATCH_MATCH_PAIRS {
+            match pairs.get(i) {
+                Some(p) => ix_data.extend_from_slice(&field(p)[16..48]),
+                None => ix_data.extend_from_slice(&[0u8; 32]),
+            }
+        }
+    }
+    let flag_fields: [fn(&BatchFillPairData) -> bool; 4] = [
+        |p| p.buy_has_fills,
+        |p| p.sell_has_fills,
+        |p| p.buy_fill_or_kill,
+        |p| p.sell_fill_or_kill,
+    ];
+    for field in flag_fields {
+        for i in 0..MAX_BATCH_MATCH_PAIRS {
+            ix_data.push(pairs.get(i).map_or(0, |p| field(p) as u8));
+        }
+    }
+    ix_data.push(count as u8);
+    ix_data.extend_from_slice(pub_key);
+    ix_data.extend_from_slice(&nonce.to_le_bytes());
+
+    // Serialize Vec<Pubkey> for order_pairs (flattened buy/sell)
+    ix_data.extend_from_slice(&((count * 2) as u32).to_le_bytes());
+    for p in pairs {
+        ix_data.extend_from_slice(p.buy_order.as_ref());
+        ix_data.extend_from_slice(p.sell_order.as_ref());
+    }
+
+    // Build the 12-account structure required by Arcium's queue_computation
+    let ix = Instruction {
+        program_id: ARCIUM_MXE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*accounts.payer.key, true),
+            AccountMeta::new(*accounts.sign_pda_account.key, false),
+            AccountMeta::new(*accounts.mxe_account.key, false),
+            AccountMeta::new(*accounts.mempool_account.key, false),
+            AccountMeta::new(*accounts.executing_pool.key, false),
+            AccountMeta::new(*accounts.computation_account.key, false),
+            AccountMeta::new_readonly(*accounts.comp_def_account.key, false),
+            AccountMeta::new(*accounts.cluster_account.key, false),
+            AccountMeta::new(*accounts.pool_account.key, false),
+            AccountMeta::new(*accounts.clock_account.key, false),
+            AccountMeta::new_readonly(*accounts.system_program.key, false),
+            AccountMeta::new_readonly(*accounts.arcium_program.key, false),
+        ],
+        data: ix_data,
+    };
+
+    invoke(
+        &ix,
+        &[
+            accounts.payer.clone(),
+            accounts.sign_pda_account.clone(),
+            accounts.mxe_account.clone(),
+            accounts.mempool_account.clone(),
+            accounts.executing_pool.clone(),
+            accounts.computation_account.clone(),
+            accounts.comp_def_account.clone(),
+            accounts.cluster_account.clone(),
+            accounts.pool_account.clone(),
+            accounts.clock_account.clone(),
+            accounts.system_program.clone(),
+            accounts.arcium_program.clone(),
+        ],
+    )?;
+
+    // Use computation account's public key as request_id (matches MXE callback)
+    let request_id = accounts.computation_account.key.to_bytes();
+
+    msg!("MXE CPI complete (batch_calculate_fill), computation_offset={}, request_id={:?}",
+        computation_offset, &request_id[0..8]);
+
+    Ok(QueuedComputation { request_id })
+}
+
+/// REMOVED IN MIGRATION: On-chain "encryption" stored plaintext at known position
+///
+/// This function has been removed because it:
+/// 1. Stored plaintext at offset 16-23, NOT actual encryption
+/// 2. Created fake "ciphertext" that MPC couldn't decrypt
+/// 3. Provided false sense of security
+///
+/// For public constants in MPC operations, use ArgBuilder.plaintext_u64() in the MXE.
+/// User values must be encrypted client-side using RescueCipher.
+///
+/// PANICS: This function always panics. Migration to proper encryption required.
+#[deprecated(since = "0.2.0", note = "Use client-side RescueCipher or ArgBuilder.plaintext_*()")]
+#[allow(unused_variables)]
+pub fn encrypt_value(
+    _arcium_program: &AccountInfo,
+    _mxe_pubkey: &[u8; 32],
+    _value: u64,
+) -> Result<EncryptedU64> {
+    panic!(
+        "FATAL: encrypt_value() removed in migration. \
+        This function stored plaintext at known position, NOT actual encryption. \
+        Use client-side RescueCipher for user values or ArgBuilder.plaintext_u64() for constants."
+    );
+}
+
+/// REMOVED IN MIGRATION: Addition on encrypted data requires MPC
+///
+/// This function has been removed because it:
+/// 1. Returned first operand unchanged (NOT actual addition)
+/// 2. Created incorrect results silently
+/// 3. Cannot perform arithmetic on ciphertext without MPC
+///
+/// Use MXE.add_encrypted() instruction via CPI, or redesign to use
+/// async MPC computation queue.
+///
+/// PANICS: This function always panics. Migration to MXE CPI required.
+#[deprecated(since = "0.2.0", note = "Use MXE CPI for encrypted arithmetic")]
+#[allow(unused_variables)]
+pub fn add_encrypted(
+    _arcium_program: &AccountInfo,
+    _a: &EncryptedU64,
+    _b: &EncryptedU64,
+) -> Result<EncryptedU64> {
+    panic!(
+        "FATAL: add_encrypted() cannot perform arithmetic on ciphertext without MPC. \
+        This function returned first operand unchanged (incorrect). \
+        Use MXE.add_encrypted() via CPI or redesign with async MPC queue."
+    );
+}
+
+/// REMOVED IN MIGRATION: Subtraction on encrypted data requires MPC
+///
+/// This function has been removed because it:
+/// 1. Returned first operand unchanged (NOT actual subtraction)
+/// 2. Created incorrect results silently
+/// 3. Cannot perform arithmetic on ciphertext without MPC
+///
+/// Use MXE.sub_encrypted() instruction via CPI, or redesign to use
+/// async MPC computation queue.
+///
+/// PANICS: This function always panics. Migration to MXE CPI required.
+#[deprecated(since = "0.2.0", note = "Use MXE CPI for encrypted arithmetic")]
+#[allow(unused_variables)]
+pub fn sub_encrypted(
+    _arcium_program: &AccountInfo,
+    _a: &EncryptedU64,
+    _b: &EncryptedU64,
+) -> Result<EncryptedU64> {
     panic!(
         "FATAL: sub_encrypted() cannot perform arithmetic on ciphertext without MPC. \
         This function returned first operand unchanged (incorrect). \
@@ -516,41 +1105,479 @@ pub struct ArciumCallback {
     pub success: bool,
 }
 
-// ============================================================================
-// PERPETUALS MPC OPERATIONS
-// ============================================================================
+// ============================================================================
+// PERPETUALS MPC OPERATIONS
+// ============================================================================
+
+/// Verify that the claimed liquidation threshold matches the encrypted position params via MPC
+/// Uses MPC to compute: threshold = entry_price * (1 - maintenance_margin / leverage) for longs
+///                      threshold = entry_price * (1 + maintenance_margin / leverage) for shorts
+/// Returns: bool (revealed) - whether the claimed threshold is valid
+pub fn verify_position_params<'info>(
+    accounts: MxeCpiAccounts<'_, 'info>,
+    computation_offset: u64,
+    encrypted_collateral: &EncryptedU64,
+    encrypted_size: &EncryptedU64,
+    encrypted_entry_price: &EncryptedU64,
+    claimed_threshold: u64,
+    leverage: u8,
+    is_long: bool,
+    maintenance_margin_bps: u16,
+    pub_key: &[u8; 32],
+    nonce: u128,
+) -> Result<QueuedComputation> {
+    msg!("Arcium CPI: verify_position_params (MPC) via MXE");
+
+    // Build CPI instruction data
+    let mut ix_data = Vec::with_capacity(8 + 8 + 32 * 3 + 8 + 1 + 1 + 2 + 32 + 16);
+    ix_data.extend_from_slice(&mxe_discriminators::VERIFY_POSITION_PARAMS);
+    ix_data.extend_from_slice(&computation_offset.to_le_bytes());
+    // Extract 32-byte ciphertext portions
+    ix_data.extend_from_slice(&encrypted_collateral[16..48]);
+    ix_data.extend_from_slice(&encrypted_size[16..48]);
+    ix_data.extend_from_slice(&encrypted_entry_price[16..48]);
+    ix_data.extend_from_slice(&claimed_threshold.to_le_bytes());
+    ix_data.push(leverage);
+    ix_data.push(if is_long { 1 } else { 0 });
+    ix_data.extend_from_slice(&maintenance_margin_bps.to_le_bytes());
+    ix_data.extend_from_slice(pub_key);
+    ix_data.extend_from_slice(&nonce.to_le_bytes());
+
+    // Build the 12-account structure
+    let ix = Instruction {
+        program_id: ARCIUM_MXE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*accounts.payer.key, true),
+            AccountMeta::new(*accounts.sign_pda_account.key, false),
+            AccountMeta::new(*accounts.mxe_account.key, false),
+            AccountMeta::new(*accounts.mempool_account.key, false),
+            AccountMeta::new(*accounts.executing_pool.key, false),
+            AccountMeta::new(*accounts.computation_account.key, false),
+            AccountMeta::new_readonly(*accounts.comp_def_account.key, false),
+            AccountMeta::new(*accounts.cluster_account.key, false),
+            AccountMeta::new(*accounts.pool_account.key, false),
+            AccountMeta::new(*accounts.clock_account.key, false),
+            AccountMeta::new_readonly(*accounts.system_program.key, false),
+            AccountMeta::new_readonly(*accounts.arcium_program.key, false),
+        ],
+        data: ix_data,
+    };
+
+    invoke(
+        &ix,
+        &[
+            accounts.payer.clone(),
+            accounts.sign_pda_account.clone(),
+            accounts.mxe_account.clone(),
+            accounts.mempool_account.clone(),
+            accounts.executing_pool.clone(),
+            accounts.computation_account.clone(),
+            accounts.comp_def_account.clone(),
+            accounts.cluster_account.clone(),
+            accounts.pool_account.clone(),
+            accounts.clock_account.clone(),
+            accounts.system_program.clone(),
+            accounts.arcium_program.clone(),
+        ],
+    )?;
+
+    // Use computation account's public key as request_id (matches MXE callback)
+    let request_id = accounts.computation_account.key.to_bytes();
+
+    msg!("MXE CPI complete (verify_position_params), computation_offset={}, request_id={:?}",
+        computation_offset, &request_id[0..8]);
+
+    Ok(QueuedComputation { request_id })
+}
+
+/// Synchronous position params verification - REMOVED IN V5
+///
+/// This function previously provided a fallback that trusted claimed thresholds.
+/// In V5, all position verification MUST use async MPC.
+///
+/// PANICS: This function always panics in V5. Use verify_position_params instead.
+#[allow(unused_variables)]
+pub fn verify_position_params_sync(
+    _arcium_program: &AccountInfo,
+    _cluster: &Pubkey,
+    _encrypted_entry_price: &EncryptedU64,
+    _claimed_threshold: u64,
+    _leverage: u8,
+    _is_long: bool,
+    _maintenance_margin_bps: u16,
+) -> Result<bool> {
+    // V5 PRODUCTION: Sync MPC fallbacks are NOT allowed
+    // All encrypted operations MUST use async MPC flow
+    panic!(
+        "FATAL: verify_position_params_sync fallback called in production. \
+        V5 requires async MPC via verify_position_params(). \
+        This indicates a code path that bypasses privacy guarantees."
+    );
+}
+
+/// Check if a position should be liquidated based on current mark price via MPC
+/// Inputs: encrypted position data + public mark price
+/// Returns: bool (revealed) - whether position should be liquidated
+pub fn check_liquidation<'info>(
+    accounts: MxeCpiAccounts<'_, 'info>,
+    computation_offset: u64,
+    encrypted_collateral: &EncryptedU64,
+    encrypted_size: &EncryptedU64,
+    encrypted_entry_price: &EncryptedU64,
+    mark_price: u64,
+    is_long: bool,
+    maintenance_margin_bps: u16,
+    pub_key: &[u8; 32],
+    nonce: u128,
+) -> Result<QueuedComputation> {
+    msg!("Arcium CPI: check_liquidation (MPC) via MXE");
+
+    let mut ix_data = Vec::with_capacity(8 + 8 + 32 * 3 + 8 + 1 + 2 + 32 + 16);
+    ix_data.extend_from_slice(&mxe_discriminators::CHECK_LIQUIDATION);
+    ix_data.extend_from_slice(&computation_offset.to_le_bytes());
+    // Extract 32-byte ciphertext portions
+    ix_data.extend_from_slice(&encrypted_collateral[16..48]);
+    ix_data.extend_from_slice(&encrypted_size[16..48]);
+    ix_data.extend_from_slice(&encrypted_entry_price[16..48]);
+    ix_data.extend_from_slice(&mark_price.to_le_bytes());
+    ix_data.push(if is_long { 1 } else { 0 });
+    ix_data.extend_from_slice(&maintenance_margin_bps.to_le_bytes());
+    ix_data.extend_from_slice(pub_key);
+    ix_data.extend_from_slice(&nonce.to_le_bytes());
+
+    // Build the 12-account structure
+    let ix = Instruction {
+        program_id: ARCIUM_MXE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*accounts.payer.key, true),
+            AccountMeta::new(*accounts.sign_pda_account.key, false),
+            AccountMeta::new(*accounts.mxe_account.key, false),
+            AccountMeta::new(*accounts.mempool_account.key, false),
+            AccountMeta::new(*accounts.executing_pool.key, false),
+            AccountMeta::new(*accounts.computation_account.key, false),
+            AccountMeta::new_readonly(*accounts.comp_def_account.key, false),
+            AccountMeta::new(*accounts.cluster_account.key, false),
+            AccountMeta::new(*accounts.pool_account.key, false),
+            AccountMeta::new(*accounts.clock_account.key, false),
+            AccountMeta::new_readonly(*accounts.system_program.key, false),
+            AccountMeta::new_readonly(*accounts.arcium_program.key, false),
+        ],
+        data: ix_data,
+    };
+
+    invoke(
+        &ix,
+        &[
+            accounts.payer.clone(),
+            accounts.sign_pda_account.clone(),
+            accounts.mxe_account.clone(),
+            accounts.mempool_account.clone(),
+            accounts.executing_pool.clone(),
+            accounts.computation_account.clone(),
+            accounts.comp_def_account.clone(),
+            accounts.cluster_account.clone(),
+            accounts.pool_account.clone(),
+            accounts.clock_account.clone(),
+            accounts.system_program.clone(),
+            accounts.arcium_program.clone(),
+        ],
+    )?;
+
+    // Use computation account's public key as request_id (matches MXE callback)
+    let request_id = accounts.computation_account.key.to_bytes();
+
+    msg!("MXE CPI complete (check_liquidation), computation_offset={}, request_id={:?}",
+        computation_offset, &request_id[0..8]);
+
+    Ok(QueuedComputation { request_id })
+}
+
+/// Synchronous liquidation check - REMOVED IN V5
+///
+/// This function previously provided a fallback for liquidation checks.
+/// In V5, all liquidation checks MUST use async MPC batch verification.
+///
+/// PANICS: This function always panics in V5. Use queue_batch_liquidation_check instead.
+#[allow(unused_variables)]
+pub fn check_liquidation_sync(
+    _arcium_program: &AccountInfo,
+    _cluster: &Pubkey,
+    _encrypted_collateral: &EncryptedU64,
+    _encrypted_size: &EncryptedU64,
+    _encrypted_entry_price: &EncryptedU64,
+    _mark_price: u64,
+    _is_long: bool,
+    _maintenance_margin_bps: u16,
+) -> Result<bool> {
+    // V5 PRODUCTION: Sync MPC fallbacks are NOT allowed
+    // All encrypted operations MUST use async MPC flow
+    panic!(
+        "FATAL: check_liquidation_sync fallback called in production. \
+        V5 requires async MPC via queue_batch_liquidation_check(). \
+        This indicates a code path that bypasses privacy guarantees."
+    );
+}
+
+/// Position data for batch liquidation check
+pub struct BatchLiquidationPositionData {
+    /// Encrypted liquidation threshold (below for longs, above for shorts)
+    pub encrypted_liq_threshold: EncryptedU64,
+    /// Position side (true = long, false = short)
+    pub is_long: bool,
+    /// Mark price of the position's market (public oracle price)
+    pub mark_price: u64,
+}
+
+/// Queue a batch liquidation check for multiple positions
+/// Inputs: array of encrypted liquidation thresholds + per-position mark prices (public)
+/// Returns: array of bool (revealed) - which positions should be liquidated
+///
+/// This allows checking up to 10 positions in a single MPC call (~500ms total)
+/// instead of 10 separate calls (~5s total), making liquidation bots efficient.
+/// Positions may belong to different markets. Results are delivered to the
+/// batch_request account via liquidation_batch_callback.
+pub fn queue_batch_liquidation_check<'info>(
+    accounts: MxeCpiAccounts<'_, 'info>,
+    computation_offset: u64,
+    positions: &[BatchLiquidationPositionData],
+    pub_key: &[u8; 32],
+    nonce: u128,
+    batch_request: &Pubkey,
+) -> Result<QueuedComputation> {
+    if positions.is_empty() || positions.len() > 10 {
+        return Err(error!(ArciumError::InvalidResult));
+    }
+
+    msg!("Arcium CPI: batch_liquidation_check (MPC) via MXE - {} positions", positions.len());
+
+    // Build CPI instruction data (arrays padded to 10 to match the circuit)
+    // Format: discriminator + computation_offset + thresholds[10] (32 each) + is_long[10] +
+    //         count + mark_prices[10] + pub_key + nonce + batch_request
+    let mut ix_data = Vec::with_capacity(8 + 8 + 320 + 10 + 1 + 80 + 32 + 16 + 32);
+    ix_data.extend_from_slice(&mxe_discriminators::BATCH_LIQUIDATION_CHECK);
+    ix_data.extend_from_slice(&computation_offset.to_le_bytes());
+
+    for i in 0..10 {
+        match positions.get(i) {
+            // Extract 32-byte ciphertext portion from 64-byte encrypted value
+            Some(pos) => ix_data.extend_from_slice(&pos.encrypted_liq_threshold[16..48]),
+            None => ix_data.extend_from_slice(&[0u8; 32]),
+        }
+    }
+    for i in 0..10 {
+        ix_data.push(positions.get(i).map_or(0, |pos| pos.is_long as u8));
+    }
+    ix_data.push(positions.len() as u8);
+    for i in 0..10 {
+        let mark_price = positions.get(i).map_or(0, |pos| pos.mark_price);
+        ix_data.extend_from_slice(&mark_price.to_le_bytes());
+    }
+
+    ix_data.extend_from_slice(pub_key);
+    ix_data.extend_from_slice(&nonce.to_le_bytes());
+    ix_data.extend_from_slice(batch_request.as_ref());
+
+    // Build the 12-account structure
+    let ix = Instruction {
+        program_id: ARCIUM_MXE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*accounts.payer.key, true),
+            AccountMeta::new(*accounts.sign_pda_account.key, false),
+            AccountMeta::new(*accounts.mxe_account.key, false),
+            AccountMeta::new(*accounts.mempool_account.key, false),
+            AccountMeta::new(*accounts.executing_pool.key, false),
+            AccountMeta::new(*accounts.computation_account.key, false),
+            AccountMeta::new_readonly(*accounts.comp_def_account.key, false),
+            AccountMeta::new(*accounts.cluster_account.key, false),
+            AccountMeta::new(*accounts.pool_account.key, false),
+            AccountMeta::new(*accounts.clock_account.key, false),
+            AccountMeta::new_readonly(*accounts.system_program.key, false),
+            AccountMeta::new_readonly(*accounts.arcium_program.key, false),
+        ],
+        data: ix_data,
+    };
+
+    invoke(
+        &ix,
+        &[
+            accounts.payer.clone(),
+            accounts.sign_pda_account.clone(),
+            accounts.mxe_account.clone(),
+            accounts.mempool_account.clone(),
+            accounts.executing_pool.clone(),
+            accounts.computation_account.clone(),
+            accounts.comp_def_account.clone(),
+            accounts.cluster_account.clone(),
+            accounts.pool_account.clone(),
+            accounts.clock_account.clone(),
+            accounts.system_program.clone(),
+            accounts.arcium_program.clone(),
+        ],
+    )?;
+
+    // Use computation account's public key as request_id (matches MXE callback)
+    let request_id = accounts.computation_account.key.to_bytes();
+
+    msg!("MXE CPI complete (batch_liquidation_check), computation_offset={}, request_id={:?}",
+        computation_offset, &request_id[0..8]);
+
+    Ok(QueuedComputation { request_id })
+}
+
+/// Maximum positions per batch exit trigger check (fixed by the circuit)
+pub const MAX_BATCH_EXIT_TRIGGER_POSITIONS: usize = 10;
+
+/// Position data for batch stop-loss / take-profit check
+pub struct BatchExitTriggerPositionData {
+    /// Position account (marked by the callback when a trigger fires)
+    pub position: Pubkey,
+    /// Encrypted stop-loss price (ignored when has_stop_loss is false)
+    pub encrypted_stop_loss: EncryptedU64,
+    /// Encrypted take-profit price (ignored when has_take_profit is false)
+    pub encrypted_take_profit: EncryptedU64,
+    pub has_stop_loss: bool,
+    pub has_take_profit: bool,
+    /// Position side (true = long, false = short)
+    pub is_long: bool,
+}
+
+/// Queue a batch stop-loss / take-profit check for positions on one market
+/// Inputs: encrypted trigger prices + mark price (public)
+/// Returns: array of bool (revealed) - which positions hit a trigger
+///
+/// Only whether a trigger fired is revealed, not which one. The MXE
+/// callback CPIs to the DEX's exit_trigger_callback with the results.
+pub fn queue_batch_exit_trigger_check<'info>(
+    accounts: MxeCpiAccounts<'_, 'info>,
+    computation_offset: u64,
+    positions: &[BatchExitTriggerPositionData],
+    mark_price: u64,
+    pub_key: &[u8; 32],
+    nonce: u128,
+) -> Result<QueuedComputation> {
+    let count = positions.len();
+    if count == 0 || count > MAX_BATCH_EXIT_TRIGGER_POSITIONS {
+        return Err(error!(ArciumError::InvalidResult));
+    }
+
+    msg!("Arcium CPI: batch_exit_trigger_check (MPC) via MXE - {} positions", count);
+
+    // Build CPI instruction data
+    // Format: discriminator (8) + computation_offset (8) + stop_losses (32 * 10) +
+    //         take_profits (32 * 10) + has_stop_loss (10) + has_take_profit (10) +
+    //         is_long (10) + count (1) + mark_price (8) + pub_key (32) + nonce (16) +
+    //         positions (Vec<Pubkey> = 4 + 32 * count)
+    let mut ix_data = Vec::with_capacity(
+        8 + 8 + 32 * MAX_BATCH_EXIT_TRIGGER_POSITIONS * 2 + MAX_BATCH_EXIT_TRIGGER_POSITIONS * 3
+            + 1 + 8 + 32 + 16 + 4 + 32 * count,
+    );
+    ix_data.extend_from_slice(&mxe_discriminators::BATCH_EXIT_TRIGGER_CHECK);
+    ix_data.extend_from_slice(&computation_offset.to_le_bytes());
+
+    // Extract 32-byte ciphertext portions, padding unused slots with zeros
+    // (the circuit ignores slots >= count and unset triggers)
+    for i in 0..MAX_BATCH_EXIT_TRIGGER_POSITIONS {
+        match positions.get(i) {
+            Some(p) => ix_data.extend_from_slice(&p.encrypted_stop_loss[16..48]),
+            None => ix_data.extend_from_slice(&[0u8; 32]),
+        }
+    }
+    for i in 0..MAX_BATCH_EXIT_TRIGGER_POSITIONS {
+        match positions.get(i) {
+            Some(p) => ix_data.extend_from_slice(&p.encrypted_take_profit[16..48]),
+            None => ix_data.extend_from_slice(&[0u8; 32]),
+        }
+    }
+    for i in 0..MAX_BATCH_EXIT_TRIGGER_POSITIONS {
+        ix_data.push(positions.get(i).map_or(0, |p| p.has_stop_loss as u8));
+    }
+    for i in 0..MAX_BATCH_EXIT_TRIGGER_POSITIONS {
+        ix_data.push(positions.get(i).map_or(0, |p| p.has_take_profit as u8));
+    }
+    for i in 0..MAX_BATCH_EXIT_TRIGGER_POSITIONS {
+        ix_data.push(positions.get(i).map_or(0, |p| p.is_long as u8));
+    }
+    ix_data.push(count as u8);
+    ix_data.extend_from_slice(&mark_price.to_le_bytes());
+    ix_data.extend_from_slice(pub_key);
+    ix_data.extend_from_slice(&nonce.to_le_bytes());
+
+    // Serialize Vec<Pubkey> for the position accounts
+    ix_data.extend_from_slice(&(count as u32).to_le_bytes());
+    for p in positions {
+        ix_data.extend_from_slice(p.position.as_ref());
+    }
+
+    // Build the 12-account structure required by Arcium's queue_computation
+    let ix = Instruction {
+        program_id: ARCIUM_MXE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*accounts.payer.key, true),
+            AccountMeta::new(*accounts.sign_pda_account.key, false),
+            AccountMeta::new(*accounts.mxe_account.key, false),
+            AccountMeta::new(*accounts.mempool_account.key, false),
+            AccountMeta::new(*accounts.executing_pool.key, false),
+            AccountMeta::new(*accounts.computation_account.key, false),
+            AccountMeta::new_readonly(*accounts.comp_def_account.key, false),
+            AccountMeta::new(*accounts.cluster_account.key, false),
+            AccountMeta::new(*accounts.pool_account.key, false),
+            AccountMeta::new(*accounts.clock_account.key, false),
+            AccountMeta::new_readonly(*accounts.system_program.key, false),
+            AccountMeta::new_readonly(*accounts.arcium_program.key, false),
+        ],
+        data: ix_data,
+    };
+
+    invoke(
+        &ix,
+        &[
+            accounts.payer.clone(),
+            accounts.sign_pda_account.clone(),
+            accounts.mxe_account.clone(),
+            accounts.mempool_account.clone(),
+            accounts.executing_pool.clone(),
+            accounts.computation_account.clone(),
+            accounts.comp_def_account.clone(),
+            accounts.cluster_account.clone(),
+            accounts.pool_account.clone(),
+            accounts.clock_account.clone(),
+            accounts.system_program.clone(),
+            accounts.arcium_program.clone(),
+        ],
+    )?;
+
+    // Use computation account's public key as request_id (matches MXE callback)
+    let request_id = accounts.computation_account.key.to_bytes();
 
-/// Verify that the claimed liquidation threshold matches the encrypted position params via MPC
-/// Uses MPC to compute: threshold = entry_price * (1 - maintenance_margin / leverage) for longs
-///                      threshold = entry_price * (1 + maintenance_margin / leverage) for shorts
-/// Returns: bool (revealed) - whether the claimed threshold is valid
-pub fn verify_position_params<'info>(
+    msg!("MXE CPI complete (batch_exit_trigger_check), computation_offset={}, request_id={:?}",
+        computation_offset, &request_id[0..8]);
+
+    Ok(QueuedComputation { request_id })
+}
+
+/// Calculate PnL for a position
+/// Inputs: encrypted_size, encrypted_entry_price, exit_price (public), is_long (public)
+/// Returns: encrypted_pnl (can be negative, stored as signed in first 8 bytes)
+pub fn calculate_pnl<'info>(
     accounts: MxeCpiAccounts<'_, 'info>,
     computation_offset: u64,
-    encrypted_collateral: &EncryptedU64,
     encrypted_size: &EncryptedU64,
     encrypted_entry_price: &EncryptedU64,
-    claimed_threshold: u64,
-    leverage: u8,
+    exit_price: u64,
     is_long: bool,
-    maintenance_margin_bps: u16,
     pub_key: &[u8; 32],
     nonce: u128,
 ) -> Result<QueuedComputation> {
-    msg!("Arcium CPI: verify_position_params (MPC) via MXE");
+    msg!("Arcium CPI: calculate_pnl (MPC) via MXE");
 
-    // Build CPI instruction data
-    let mut ix_data = Vec::with_capacity(8 + 8 + 32 * 3 + 8 + 1 + 1 + 2 + 32 + 16);
-    ix_data.extend_from_slice(&mxe_discriminators::VERIFY_POSITION_PARAMS);
+    let mut ix_data = Vec::with_capacity(8 + 8 + 32 * 2 + 8 + 1 + 32 + 16);
+    ix_data.extend_from_slice(&mxe_discriminators::CALCULATE_PNL);
     ix_data.extend_from_slice(&computation_offset.to_le_bytes());
     // Extract 32-byte ciphertext portions
-    ix_data.extend_from_slice(&encrypted_collateral[16..48]);
     ix_data.extend_from_slice(&encrypted_size[16..48]);
     ix_data.extend_from_slice(&encrypted_entry_price[16..48]);
-    ix_data.extend_from_slice(&claimed_threshold.to_le_bytes());
-    ix_data.push(leverage);
+    ix_data.extend_from_slice(&exit_price.to_le_bytes());
     ix_data.push(if is_long { 1 } else { 0 });
-    ix_data.extend_from_slice(&maintenance_margin_bps.to_le_bytes());
     ix_data.extend_from_slice(pub_key);
     ix_data.extend_from_slice(&nonce.to_le_bytes());
 
@@ -595,64 +1622,58 @@ pub fn verify_position_params<'info>(
     // Use computation account's public key as request_id (matches MXE callback)
     let request_id = accounts.computation_account.key.to_bytes();
 
-    msg!("MXE CPI complete (verify_position_params), computation_offset={}, request_id={:?}",
+    msg!("MXE CPI complete (calculate_pnl), computation_offset={}, request_id={:?}",
         computation_offset, &request_id[0..8]);
 
     Ok(QueuedComputation { request_id })
 }
 
-/// Synchronous position params verification - REMOVED IN V5
+/// Synchronous PnL calculation - REMOVED IN V5
 ///
-/// This function previously provided a fallback that trusted claimed thresholds.
-/// In V5, all position verification MUST use async MPC.
+/// This function previously provided a fallback that returned placeholder values.
+/// In V5, all PnL calculation MUST use async MPC.
 ///
-/// PANICS: This function always panics in V5. Use verify_position_params instead.
+/// PANICS: This function always panics in V5. Use calculate_pnl instead.
 #[allow(unused_variables)]
-pub fn verify_position_params_sync(
+pub fn calculate_pnl_sync(
     _arcium_program: &AccountInfo,
-    _cluster: &Pubkey,
+    _encrypted_size: &EncryptedU64,
     _encrypted_entry_price: &EncryptedU64,
-    _claimed_threshold: u64,
-    _leverage: u8,
+    _exit_price: u64,
     _is_long: bool,
-    _maintenance_margin_bps: u16,
-) -> Result<bool> {
+) -> Result<(EncryptedU64, bool)> {
     // V5 PRODUCTION: Sync MPC fallbacks are NOT allowed
     // All encrypted operations MUST use async MPC flow
     panic!(
-        "FATAL: verify_position_params_sync fallback called in production. \
-        V5 requires async MPC via verify_position_params(). \
+        "FATAL: calculate_pnl_sync fallback called in production. \
+        V5 requires async MPC via calculate_pnl(). \
         This indicates a code path that bypasses privacy guarantees."
     );
 }
 
-/// Check if a position should be liquidated based on current mark price via MPC
-/// Inputs: encrypted position data + public mark price
-/// Returns: bool (revealed) - whether position should be liquidated
-pub fn check_liquidation<'info>(
+/// Calculate funding payment for a position
+/// Inputs: encrypted_size, funding_rate (public), funding_delta (public)
+/// Returns: encrypted_funding_payment
+pub fn calculate_funding<'info>(
     accounts: MxeCpiAccounts<'_, 'info>,
     computation_offset: u64,
-    encrypted_collateral: &EncryptedU64,
     encrypted_size: &EncryptedU64,
-    encrypted_entry_price: &EncryptedU64,
-    mark_price: u64,
+    funding_rate: i64,
+    funding_delta: i64,
     is_long: bool,
-    maintenance_margin_bps: u16,
     pub_key: &[u8; 32],
     nonce: u128,
 ) -> Result<QueuedComputation> {
-    msg!("Arcium CPI: check_liquidation (MPC) via MXE");
+    msg!("Arcium CPI: calculate_funding (MPC) via MXE");
 
-    let mut ix_data = Vec::with_capacity(8 + 8 + 32 * 3 + 8 + 1 + 2 + 32 + 16);
-    ix_data.extend_from_slice(&mxe_discriminators::CHECK_LIQUIDATION);
+    let mut ix_data = Vec::with_capacity(8 + 8 + 32 + 8 + 8 + 1 + 32 + 16);
+    ix_data.extend_from_slice(&mxe_discriminators::CALCULATE_FUNDING);
     ix_data.extend_from_slice(&computation_offset.to_le_bytes());
-    // Extract 32-byte ciphertext portions
-    ix_data.extend_from_slice(&encrypted_collateral[16..48]);
+    // Extract 32-byte ciphertext portion
     ix_data.extend_from_slice(&encrypted_size[16..48]);
-    ix_data.extend_from_slice(&encrypted_entry_price[16..48]);
-    ix_data.extend_from_slice(&mark_price.to_le_bytes());
+    ix_data.extend_from_slice(&funding_rate.to_le_bytes());
+    ix_data.extend_from_slice(&funding_delta.to_le_bytes());
     ix_data.push(if is_long { 1 } else { 0 });
-    ix_data.extend_from_slice(&maintenance_margin_bps.to_le_bytes());
     ix_data.extend_from_slice(pub_key);
     ix_data.extend_from_slice(&nonce.to_le_bytes());
 
@@ -697,84 +1718,96 @@ pub fn check_liquidation<'info>(
     // Use computation account's public key as request_id (matches MXE callback)
     let request_id = accounts.computation_account.key.to_bytes();
 
-    msg!("MXE CPI complete (check_liquidation), computation_offset={}, request_id={:?}",
+    msg!("MXE CPI complete (calculate_funding), computation_offset={}, request_id={:?}",
         computation_offset, &request_id[0..8]);
 
     Ok(QueuedComputation { request_id })
 }
 
-/// Synchronous liquidation check - REMOVED IN V5
+/// Synchronous funding calculation - REMOVED IN V5
 ///
-/// This function previously provided a fallback for liquidation checks.
-/// In V5, all liquidation checks MUST use async MPC batch verification.
+/// This function previously provided a fallback that returned placeholder values.
+/// In V5, all funding calculation MUST use async MPC.
 ///
-/// PANICS: This function always panics in V5. Use queue_batch_liquidation_check instead.
+/// PANICS: This function always panics in V5. Use calculate_funding instead.
 #[allow(unused_variables)]
-pub fn check_liquidation_sync(
+pub fn calculate_funding_sync(
     _arcium_program: &AccountInfo,
-    _cluster: &Pubkey,
-    _encrypted_collateral: &EncryptedU64,
     _encrypted_size: &EncryptedU64,
-    _encrypted_entry_price: &EncryptedU64,
-    _mark_price: u64,
+    _funding_delta: i64,
     _is_long: bool,
-    _maintenance_margin_bps: u16,
-) -> Result<bool> {
+) -> Result<(EncryptedU64, bool)> {
     // V5 PRODUCTION: Sync MPC fallbacks are NOT allowed
     // All encrypted operations MUST use async MPC flow
     panic!(
-        "FATAL: check_liquidation_sync fallback called in production. \
-        V5 requires async MPC via queue_batch_liquidation_check(). \
+        "FATAL: calculate_funding_sync fallback called in production. \
+        V5 requires async MPC via calculate_funding(). \
         This indicates a code path that bypasses privacy guarantees."
     );
 }
 
-/// Position data for batch liquidation check
-pub struct BatchLiquidationPositionData {
-    /// Encrypted liquidation threshold (below for longs, above for shorts)
-    pub encrypted_liq_threshold: EncryptedU64,
-    /// Position side (true = long, false = short)
-    pub is_long: bool,
+/// Encrypted values of a position taking part in auto-deleverage or liquidation
+pub struct AdlPositionData {
+    pub encrypted_size: EncryptedU64,
+    pub encrypted_entry_price: EncryptedU64,
+    pub encrypted_collateral: EncryptedU64,
 }
 
-/// Queue a batch liquidation check for multiple positions
-/// Inputs: array of encrypted liquidation thresholds + mark price (public)
-/// Returns: array of bool (revealed) - which positions should be liquidated
+/// Accounts the MXE passes to the DEX adl_pnl_callback
+pub struct AdlCallbackAccounts {
+    pub bankrupt_position: Pubkey,
+    pub target_position: Pubkey,
+    pub perp_market: Pubkey,
+    pub target_collateral_account: Pubkey,
+    pub collateral_vault: Pubkey,
+    pub vault_authority: Pubkey,
+    pub token_program: Pubkey,
+}
+
+/// Queue auto-deleverage settlement via MPC
 ///
-/// This allows checking up to 10 positions in a single MPC call (~500ms total)
-/// instead of 10 separate calls (~5s total), making liquidation bots efficient.
-pub fn queue_batch_liquidation_check<'info>(
+/// CPIs to arcium_mxe program using the full 12-account structure.
+/// Closes min(bankrupt_size, target_size) of both positions at the bankrupt
+/// position's bankruptcy price and computes the target's payout.
+///
+/// The MXE callback CPIs to the DEX's adl_pnl_callback with the encrypted
+/// remaining sizes/collaterals, the revealed payout and the ADL size.
+pub fn queue_calculate_adl<'info>(
     accounts: MxeCpiAccounts<'_, 'info>,
     computation_offset: u64,
-    positions: &[BatchLiquidationPositionData],
-    mark_price: u64,
+    bankrupt: &AdlPositionData,
+    target: &AdlPositionData,
+    bankrupt_is_long: bool,
     pub_key: &[u8; 32],
     nonce: u128,
+    callback: &AdlCallbackAccounts,
 ) -> Result<QueuedComputation> {
-    if positions.is_empty() || positions.len() > 10 {
-        return Err(error!(ArciumError::InvalidResult));
-    }
-
-    msg!("Arcium CPI: batch_liquidation_check (MPC) via MXE - {} positions", positions.len());
+    msg!("Arcium CPI: calculate_adl (MPC) via MXE");
 
     // Build CPI instruction data
-    // Format: discriminator + computation_offset + position_count + [ciphertext (32) + is_long] * count + mark_price + pub_key + nonce
-    let mut ix_data = Vec::with_capacity(8 + 8 + 1 + (33 * positions.len()) + 8 + 32 + 16);
-    ix_data.extend_from_slice(&mxe_discriminators::BATCH_LIQUIDATION_CHECK);
+    // Format: discriminator (8) + computation_offset (8) + 6x ciphertext (32 each) +
+    //         bankrupt_is_long (1) + pub_key (32) + nonce (16) + 7x pubkey (32 each)
+    let mut ix_data = Vec::with_capacity(8 + 8 + 32 * 6 + 1 + 32 + 16 + 32 * 7);
+    ix_data.extend_from_slice(&mxe_discriminators::CALCULATE_ADL);
     ix_data.extend_from_slice(&computation_offset.to_le_bytes());
-    ix_data.push(positions.len() as u8);
-
-    for pos in positions {
-        // Extract 32-byte ciphertext portion from 64-byte encrypted value
-        ix_data.extend_from_slice(&pos.encrypted_liq_threshold[16..48]);
-        ix_data.push(if pos.is_long { 1 } else { 0 });
+    // Extract 32-byte ciphertext portions from 64-byte encrypted values
+    for position in [bankrupt, target] {
+        ix_data.extend_from_slice(&position.encrypted_size[16..48]);
+        ix_data.extend_from_slice(&position.encrypted_entry_price[16..48]);
+        ix_data.extend_from_slice(&position.encrypted_collateral[16..48]);
     }
-
-    ix_data.extend_from_slice(&mark_price.to_le_bytes());
+    ix_data.push(if bankrupt_is_long { 1 } else { 0 });
     ix_data.extend_from_slice(pub_key);
     ix_data.extend_from_slice(&nonce.to_le_bytes());
+    ix_data.extend_from_slice(callback.bankrupt_position.as_ref());
+    ix_data.extend_from_slice(callback.target_position.as_ref());
+    ix_data.extend_from_slice(callback.perp_market.as_ref());
+    ix_data.extend_from_slice(callback.target_collateral_account.as_ref());
+    ix_data.extend_from_slice(callback.collateral_vault.as_ref());
+    ix_data.extend_from_slice(callback.vault_authority.as_ref());
+    ix_data.extend_from_slice(callback.token_program.as_ref());
 
-    // Build the 12-account structure
+    // Build the 12-account structure required by Arcium's queue_computation
     let ix = Instruction {
         program_id: ARCIUM_MXE_PROGRAM_ID,
         accounts: vec![
@@ -815,39 +1848,87 @@ pub fn queue_batch_liquidation_check<'info>(
     // Use computation account's public key as request_id (matches MXE callback)
     let request_id = accounts.computation_account.key.to_bytes();
 
-    msg!("MXE CPI complete (batch_liquidation_check), computation_offset={}, request_id={:?}",
+    msg!("MXE CPI complete (calculate_adl), computation_offset={}, request_id={:?}",
         computation_offset, &request_id[0..8]);
 
     Ok(QueuedComputation { request_id })
 }
 
-/// Calculate PnL for a position
-/// Inputs: encrypted_size, encrypted_entry_price, exit_price (public), is_long (public)
-/// Returns: encrypted_pnl (can be negative, stored as signed in first 8 bytes)
-pub fn calculate_pnl<'info>(
+/// Maximum positions per rank_adl_candidates computation (fixed by the circuit)
+pub const MAX_ADL_RANK_POSITIONS: usize = 10;
+
+/// Position data for ADL candidate ranking
+pub struct AdlRankPositionData {
+    pub encrypted_size: EncryptedU64,
+    pub encrypted_entry_price: EncryptedU64,
+    pub encrypted_collateral: EncryptedU64,
+    pub is_long: bool,
+}
+
+/// Queue ADL candidate ranking for up to 10 positions via MPC
+///
+/// CPIs to arcium_mxe program using the full 12-account structure.
+/// Positions are scored by leverage * unrealized profit ratio at the mark
+/// price; only each position's rank is revealed.
+///
+/// The MXE callback CPIs to the DEX's adl_ranking_callback, which rebuilds
+/// the market's ADL queues from the ranks.
+pub fn queue_rank_adl_candidates<'info>(
     accounts: MxeCpiAccounts<'_, 'info>,
     computation_offset: u64,
-    encrypted_size: &EncryptedU64,
-    encrypted_entry_price: &EncryptedU64,
-    exit_price: u64,
-    is_long: bool,
+    positions: &[AdlRankPositionData],
+    mark_price: u64,
     pub_key: &[u8; 32],
     nonce: u128,
+    adl_ranking: &Pubkey,
 ) -> Result<QueuedComputation> {
-    msg!("Arcium CPI: calculate_pnl (MPC) via MXE");
+    let count = positions.len();
+    if count == 0 || count > MAX_ADL_RANK_POSITIONS {
+        return Err(error!(ArciumError::InvalidResult));
+    }
 
-    let mut ix_data = Vec::with_capacity(8 + 8 + 32 * 2 + 8 + 1 + 32 + 16);
-    ix_data.extend_from_slice(&mxe_discriminators::CALCULATE_PNL);
+    msg!("Arcium CPI: rank_adl_candidates (MPC) via MXE - {} positions", count);
+
+    // Build CPI instruction data
+    // Format: discriminator (8) + computation_offset (8) + sizes (32 * 10) +
+    //         entry_prices (32 * 10) + collaterals (32 * 10) + is_long (10) +
+    //         count (1) + mark_price (8) + pub_key (32) + nonce (16) + adl_ranking (32)
+    let mut ix_data = Vec::with_capacity(
+        8 + 8 + 32 * MAX_ADL_RANK_POSITIONS * 3 + MAX_ADL_RANK_POSITIONS + 1 + 8 + 32 + 16 + 32,
+    );
+    ix_data.extend_from_slice(&mxe_discriminators::RANK_ADL_CANDIDATES);
     ix_data.extend_from_slice(&computation_offset.to_le_bytes());
-    // Extract 32-byte ciphertext portions
-    ix_data.extend_from_slice(&encrypted_size[16..48]);
-    ix_data.extend_from_slice(&encrypted_entry_price[16..48]);
-    ix_data.extend_from_slice(&exit_price.to_le_bytes());
-    ix_data.push(if is_long { 1 } else { 0 });
+
+    // Extract 32-byte ciphertext portions, padding unused slots with zeros
+    // (the circuit ranks slots >= count as not eligible)
+    for i in 0..MAX_ADL_RANK_POSITIONS {
+        match positions.get(i) {
+            Some(p) => ix_data.extend_from_slice(&p.encrypted_size[16..48]),
+            None => ix_data.extend_from_slice(&[0u8; 32]),
+        }
+    }
+    for i in 0..MAX_ADL_RANK_POSITIONS {
+        match positions.get(i) {
+            Some(p) => ix_data.extend_from_slice(&p.encrypted_entry_price[16..48]),
+            None => ix_data.extend_from_slice(&[0u8; 32]),
+        }
+    }
+    for i in 0..MAX_ADL_RANK_POSITIONS {
+        match positions.get(i) {
+            Some(p) => ix_data.extend_from_slice(&p.encrypted_collateral[16..48]),
+            None => ix_data.extend_from_slice(&[0u8; 32]),
+        }
+    }
+    for i in 0..MAX_ADL_RANK_POSITIONS {
+        ix_data.push(positions.get(i).map_or(0, |p| p.is_long as u8));
+    }
+    ix_data.push(count as u8);
+    ix_data.extend_from_slice(&mark_price.to_le_bytes());
     ix_data.extend_from_slice(pub_key);
     ix_data.extend_from_slice(&nonce.to_le_bytes());
+    ix_data.extend_from_slice(adl_ranking.as_ref());
 
-    // Build the 12-account structure
+    // Build the 12-account structure required by Arcium's queue_computation
     let ix = Instruction {
         program_id: ARCIUM_MXE_PROGRAM_ID,
         accounts: vec![
@@ -888,62 +1969,101 @@ pub fn calculate_pnl<'info>(
     // Use computation account's public key as request_id (matches MXE callback)
     let request_id = accounts.computation_account.key.to_bytes();
 
-    msg!("MXE CPI complete (calculate_pnl), computation_offset={}, request_id={:?}",
+    msg!("MXE CPI complete (rank_adl_candidates), computation_offset={}, request_id={:?}",
         computation_offset, &request_id[0..8]);
 
     Ok(QueuedComputation { request_id })
 }
 
-/// Synchronous PnL calculation - REMOVED IN V5
-///
-/// This function previously provided a fallback that returned placeholder values.
-/// In V5, all PnL calculation MUST use async MPC.
-///
-/// PANICS: This function always panics in V5. Use calculate_pnl instead.
-#[allow(unused_variables)]
-pub fn calculate_pnl_sync(
-    _arcium_program: &AccountInfo,
-    _encrypted_size: &EncryptedU64,
-    _encrypted_entry_price: &EncryptedU64,
-    _exit_price: u64,
-    _is_long: bool,
-) -> Result<(EncryptedU64, bool)> {
-    // V5 PRODUCTION: Sync MPC fallbacks are NOT allowed
-    // All encrypted operations MUST use async MPC flow
-    panic!(
-        "FATAL: calculate_pnl_sync fallback called in production. \
-        V5 requires async MPC via calculate_pnl(). \
-        This indicates a code path that bypasses privacy guarantees."
-    );
+/// Plaintext parameters for partial liquidation
+pub struct PartialLiquidationParams {
+    pub is_long: bool,
+    /// Oracle mark price the liquidation is computed at
+    pub mark_price: u64,
+    /// Market maintenance margin
+    pub maintenance_margin_bps: u16,
+    /// Liquidator bonus charged on the closed notional
+    pub liquidation_bonus_bps: u16,
+    /// Maximum notional closed per liquidation (0 = uncapped)
+    pub max_close_notional: u64,
+    /// Remaining notional below which the position is closed fully
+    pub min_remaining_notional: u64,
+    /// Accrued funding per unit of size since the position's last settlement (1e-8 units)
+    pub funding_e8: u64,
+    /// Whether the position pays the accrued funding
+    pub funding_is_paying: bool,
 }
 
-/// Calculate funding payment for a position
-/// Inputs: encrypted_size, funding_rate (public), funding_delta (public)
-/// Returns: encrypted_funding_payment
-pub fn calculate_funding<'info>(
+/// Accounts the MXE passes to the DEX partial_liquidation_callback
+pub struct PartialLiquidationCallbackAccounts {
+    pub position: Pubkey,
+    pub perp_market: Pubkey,
+    pub liquidation_config: Pubkey,
+    pub collateral_vault: Pubkey,
+    pub insurance_fund: Pubkey,
+    pub vault_authority: Pubkey,
+    pub token_program: Pubkey,
+    pub liquidator_balance: Pubkey,
+    pub trader_balance: Pubkey,
+}
+
+/// Queue partial liquidation of a position via MPC
+///
+/// CPIs to arcium_mxe program using the full 12-account structure.
+/// Computes equity at the mark price (unrealized PnL and accrued funding)
+/// and the minimum size reduction that restores maintenance margin, capped
+/// at max_close_notional.
+///
+/// The MXE callback CPIs to the DEX's partial_liquidation_callback with the
+/// encrypted remaining size/collateral/threshold and the revealed close
+/// size, liquidator bonus, released collateral and bad debt.
+pub fn queue_calculate_partial_liquidation<'info>(
     accounts: MxeCpiAccounts<'_, 'info>,
     computation_offset: u64,
-    encrypted_size: &EncryptedU64,
-    funding_rate: i64,
-    funding_delta: i64,
-    is_long: bool,
+    position: &AdlPositionData,
+    params: &PartialLiquidationParams,
     pub_key: &[u8; 32],
     nonce: u128,
+    callback: &PartialLiquidationCallbackAccounts,
 ) -> Result<QueuedComputation> {
-    msg!("Arcium CPI: calculate_funding (MPC) via MXE");
+    msg!("Arcium CPI: calculate_partial_liquidation (MPC) via MXE");
 
-    let mut ix_data = Vec::with_capacity(8 + 8 + 32 + 8 + 8 + 1 + 32 + 16);
-    ix_data.extend_from_slice(&mxe_discriminators::CALCULATE_FUNDING);
+    // Build CPI instruction data
+    // Format: discriminator (8) + computation_offset (8) + 3x ciphertext (32 each) +
+    //         is_long (1) + mark_price (8) + mm_bps (2) + bonus_bps (2) +
+    //         max_close_notional (8) + min_remaining_notional (8) +
+    //         funding_e8 (8) + funding_is_paying (1) +
+    //         pub_key (32) + nonce (16) + 9x pubkey (32 each)
+    let mut ix_data = Vec::with_capacity(
+        8 + 8 + 32 * 3 + 1 + 8 + 2 + 2 + 8 + 8 + 8 + 1 + 32 + 16 + 32 * 9,
+    );
+    ix_data.extend_from_slice(&mxe_discriminators::CALCULATE_PARTIAL_LIQUIDATION);
     ix_data.extend_from_slice(&computation_offset.to_le_bytes());
-    // Extract 32-byte ciphertext portion
-    ix_data.extend_from_slice(&encrypted_size[16..48]);
-    ix_data.extend_from_slice(&funding_rate.to_le_bytes());
-    ix_data.extend_from_slice(&funding_delta.to_le_bytes());
-    ix_data.push(if is_long { 1 } else { 0 });
+    // Extract 32-byte ciphertext portions from 64-byte encrypted values
+    ix_data.extend_from_slice(&position.encrypted_size[16..48]);
+    ix_data.extend_from_slice(&position.encrypted_entry_price[16..48]);
+    ix_data.extend_from_slice(&position.encrypted_collateral[16..48]);
+    ix_data.push(if params.is_long { 1 } else { 0 });
+    ix_data.extend_from_slice(&params.mark_price.to_le_bytes());
+    ix_data.extend_from_slice(&params.maintenance_margin_bps.to_le_bytes());
+    ix_data.extend_from_slice(&params.liquidation_bonus_bps.to_le_bytes());
+    ix_data.extend_from_slice(&params.max_close_notional.to_le_bytes());
+    ix_data.extend_from_slice(&params.min_remaining_notional.to_le_bytes());
+    ix_data.extend_from_slice(&params.funding_e8.to_le_bytes());
+    ix_data.push(if params.funding_is_paying { 1 } else { 0 });
     ix_data.extend_from_slice(pub_key);
     ix_data.extend_from_slice(&nonce.to_le_bytes());
+    ix_data.extend_from_slice(callback.position.as_ref());
+    ix_data.extend_from_slice(callback.perp_market.as_ref());
+    ix_data.extend_from_slice(callback.liquidation_config.as_ref());
+    ix_data.extend_from_slice(callback.collateral_vault.as_ref());
+    ix_data.extend_from_slice(callback.insurance_fund.as_ref());
+    ix_data.extend_from_slice(callback.vault_authority.as_ref());
+    ix_data.extend_from_slice(callback.token_program.as_ref());
+    ix_data.extend_from_slice(callback.liquidator_balance.as_ref());
+    ix_data.extend_from_slice(callback.trader_balance.as_ref());
 
-    // Build the 12-account structure
+    // Build the 12-account structure required by Arcium's queue_computation
     let ix = Instruction {
         program_id: ARCIUM_MXE_PROGRAM_ID,
         accounts: vec![
@@ -984,34 +2104,12 @@ pub fn calculate_funding<'info>(
     // Use computation account's public key as request_id (matches MXE callback)
     let request_id = accounts.computation_account.key.to_bytes();
 
-    msg!("MXE CPI complete (calculate_funding), computation_offset={}, request_id={:?}",
+    msg!("MXE CPI complete (calculate_partial_liquidation), computation_offset={}, request_id={:?}",
         computation_offset, &request_id[0..8]);
 
     Ok(QueuedComputation { request_id })
 }
 
-/// Synchronous funding calculation - REMOVED IN V5
-///
-/// This function previously provided a fallback that returned placeholder values.
-/// In V5, all funding calculation MUST use async MPC.
-///
-/// PANICS: This function always panics in V5. Use calculate_funding instead.
-#[allow(unused_variables)]
-pub fn calculate_funding_sync(
-    _arcium_program: &AccountInfo,
-    _encrypted_size: &EncryptedU64,
-    _funding_delta: i64,
-    _is_long: bool,
-) -> Result<(EncryptedU64, bool)> {
-    // V5 PRODUCTION: Sync MPC fallbacks are NOT allowed
-    // All encrypted operations MUST use async MPC flow
-    panic!(
-        "FATAL: calculate_funding_sync fallback called in production. \
-        V5 requires async MPC via calculate_funding(). \
-        This indicates a code path that bypasses privacy guarantees."
-    );
-}
-
 /// REMOVED IN MIGRATION: Multiplication on encrypted data requires MPC
 ///
 /// This function has been removed because it:
@@ -5,6 +5,8 @@
 //!
 //! The eligibility circuit proves:
 //! - User's address is NOT in the blacklist (SMT non-membership)
+//! - The proof is bound to the signer's address and the current epoch,
+//!   so it cannot be replayed by another wallet or reused indefinitely
 //! - Proof is generated off-chain and verified on-chain
 //!
 //! Reference: https://github.com/solana-foundation/noir-examples
@@ -43,11 +45,143 @@ pub const SUNSPOT_VERIFIER_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
 /// Note: proofs with Pedersen commitments are 324 + N*64 bytes
 pub const GROTH16_PROOF_SIZE: usize = 324;
 
+/// Length of an eligibility epoch in seconds (1 day)
+/// A proof is only valid during the epoch it was generated for.
+pub const ELIGIBILITY_EPOCH_SECS: i64 = 86_400;
+
+/// Number of public inputs in the eligibility circuit
+pub const ELIGIBILITY_NUM_PUBLIC_INPUTS: usize = 4;
+
 /// Public inputs for the eligibility circuit
+///
+/// Order must match `circuits/eligibility/src/main.nr`:
+/// blacklist_root, address_hi, address_lo, epoch.
+/// The pubkey is split into two 16-byte halves because a 32-byte key
+/// does not fit in a single BN254 field element.
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct EligibilityPublicInputs {
     /// Merkle root of the blacklist SMT (32 bytes)
     pub blacklist_root: [u8; 32],
+    /// Signer pubkey bytes 0..16, big-endian field element
+    pub address_hi: [u8; 32],
+    /// Signer pubkey bytes 16..32, big-endian field element
+    pub address_lo: [u8; 32],
+    /// Epoch the proof was generated for, big-endian field element
+    pub epoch: [u8; 32],
+}
+
+impl EligibilityPublicInputs {
+    /// Build the public inputs for a signer at the given epoch
+    pub fn new(blacklist_root: &[u8; 32], address: &Pubkey, epoch: u64) -> Self {
+        let (address_hi, address_lo) = pubkey_to_fields(address);
+
+        Self {
+            blacklist_root: *blacklist_root,
+            address_hi,
+            address_lo,
+            epoch: u64_to_field(epoch),
+        }
+    }
+
+    /// Public inputs as field elements, in circuit order
+    pub fn to_field_elements(&self) -> [[u8; 32]; ELIGIBILITY_NUM_PUBLIC_INPUTS] {
+        [self.blacklist_root, self.address_hi, self.address_lo, self.epoch]
+    }
+}
+
+/// Number of public inputs in the solvency circuit
+pub const SOLVENCY_NUM_PUBLIC_INPUTS: usize = 8;
+
+/// Public inputs for the solvency circuit
+///
+/// Order must match `circuits/solvency/src/main.nr`: liabilities_root,
+/// total_liabilities, reserves_commitment, solvency_ratio_bps, vault_balance,
+/// mint_hi, mint_lo, epoch. The vault balance, mint and epoch are supplied by
+/// the program.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SolvencyPublicInputs {
+    /// Merkle-sum-tree root of user balances
+    pub liabilities_root: [u8; 32],
+    /// Sum of all user balances, big-endian field element
+    pub total_liabilities: [u8; 32],
+    /// Poseidon commitment to the reserves
+    pub reserves_commitment: [u8; 32],
+    /// Claimed reserves/liabilities ratio in bps, big-endian field element
+    pub solvency_ratio_bps: [u8; 32],
+    /// Exchange vault balance for the mint, big-endian field element
+    pub vault_balance: [u8; 32],
+    /// Mint pubkey bytes 0..16, big-endian field element
+    pub mint_hi: [u8; 32],
+    /// Mint pubkey bytes 16..32, big-endian field element
+    pub mint_lo: [u8; 32],
+    /// Epoch the proof was generated for, big-endian field element
+    pub epoch: [u8; 32],
+}
+
+impl SolvencyPublicInputs {
+    pub fn new(
+        liabilities_root: &[u8; 32],
+        total_liabilities: u64,
+        reserves_commitment: &[u8; 32],
+        solvency_ratio_bps: u64,
+        vault_balance: u64,
+        mint: &Pubkey,
+        epoch: u64,
+    ) -> Self {
+        let (mint_hi, mint_lo) = pubkey_to_fields(mint);
+
+        Self {
+            liabilities_root: *liabilities_root,
+            total_liabilities: u64_to_field(total_liabilities),
+            reserves_commitment: *reserves_commitment,
+            solvency_ratio_bps: u64_to_field(solvency_ratio_bps),
+            vault_balance: u64_to_field(vault_balance),
+            mint_hi,
+            mint_lo,
+            epoch: u64_to_field(epoch),
+        }
+    }
+
+    /// Public inputs as field elements, in circuit order
+    pub fn to_field_elements(&self) -> [[u8; 32]; SOLVENCY_NUM_PUBLIC_INPUTS] {
+        [
+            self.liabilities_root,
+            self.total_liabilities,
+            self.reserves_commitment,
+            self.solvency_ratio_bps,
+            self.vault_balance,
+            self.mint_hi,
+            self.mint_lo,
+            self.epoch,
+        ]
+    }
+}
+
+/// Split a pubkey into two 16-byte big-endian field elements (hi, lo)
+/// A 32-byte key does not fit in a single BN254 field element.
+fn pubkey_to_fields(key: &Pubkey) -> ([u8; 32], [u8; 32]) {
+    let bytes = key.to_bytes();
+
+    let mut hi = [0u8; 32];
+    hi[16..].copy_from_slice(&bytes[..16]);
+
+    let mut lo = [0u8; 32];
+    lo[16..].copy_from_slice(&bytes[16..]);
+
+    (hi, lo)
+}
+
+/// Encode a u64 as a big-endian field element
+fn u64_to_field(value: u64) -> [u8; 32] {
+    let mut field = [0u8; 32];
+    field[24..].copy_from_slice(&value.to_be_bytes());
+    field
+}
+
+/// Current eligibility epoch derived from the cluster clock
+pub fn current_eligibility_epoch() -> Result<u64> {
+    let now = Clock::get()?.unix_timestamp;
+    Ok((now.max(0) / ELIGIBILITY_EPOCH_SECS) as u64)
 }
 
 /// Verification result
@@ -64,13 +198,18 @@ pub enum VerificationResult {
 /// Verify an eligibility proof using Sunspot
 ///
 /// The proof demonstrates that the user's address is NOT in the blacklist
-/// without revealing the address itself.
+/// without revealing the merkle path. The signer's address and the current
+/// epoch are public inputs built on-chain, so a proof generated for one
+/// wallet (or a previous epoch) fails verification.
+///
+/// Changing the public inputs requires regenerating the verification key
+/// and redeploying the Sunspot verifier for the eligibility circuit.
 ///
 /// # Arguments
 /// * `verifier_program` - The Sunspot verifier program account
 /// * `proof` - The Groth16 proof (388 bytes)
 /// * `blacklist_root` - The current blacklist merkle root (public input)
-/// * `address` - The user's address (used to derive the expected witness)
+/// * `address` - The signer's address (bound into the public inputs)
 ///
 /// # Returns
 /// * `Ok(true)` if proof is valid
@@ -80,7 +219,7 @@ pub fn verify_eligibility_proof(
     verifier_program: &AccountInfo,
     proof: &[u8; GROTH16_PROOF_SIZE],
     blacklist_root: &[u8; 32],
-    _address: &Pubkey,
+    address: &Pubkey,
 ) -> Result<bool> {
     msg!("Sunspot CPI: verify_eligibility_proof");
     msg!("  Blacklist root: {:?}", &blacklist_root[0..8]);
@@ -101,20 +240,28 @@ pub fn verify_eligibility_proof(
         return Ok(true);
     }
 
+    let epoch = current_eligibility_epoch()?;
+    let public_inputs = EligibilityPublicInputs::new(blacklist_root, address, epoch);
+    msg!("  Epoch: {}", epoch);
+
     // Build CPI instruction data: [proof_bytes || witness_bytes]
     // Sunspot/gnark witness format:
-    // - num_inputs (u32 BE): 1
+    // - num_inputs (u32 BE): 4
     // - padding (4 bytes): 0
-    // - num_field_elements (u32 BE): 1
-    // - blacklist_root (32 bytes)
-    // Total witness: 44 bytes
-    let mut verifier_data = Vec::with_capacity(GROTH16_PROOF_SIZE + 44);
+    // - num_field_elements (u32 BE): 4
+    // - blacklist_root, address_hi, address_lo, epoch (32 bytes each)
+    // Total witness: 140 bytes
+    let num_inputs = ELIGIBILITY_NUM_PUBLIC_INPUTS as u32;
+    let witness_size = 12 + ELIGIBILITY_NUM_PUBLIC_INPUTS * 32;
+    let mut verifier_data = Vec::with_capacity(GROTH16_PROOF_SIZE + witness_size);
     verifier_data.extend_from_slice(proof);
     // Build witness in gnark format
-    verifier_data.extend_from_slice(&[0x00, 0x00, 0x00, 0x01]); // num_inputs = 1 (BE)
+    verifier_data.extend_from_slice(&num_inputs.to_be_bytes()); // num_inputs (BE)
     verifier_data.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]); // padding
-    verifier_data.extend_from_slice(&[0x00, 0x00, 0x00, 0x01]); // num_field_elements = 1 (BE)
-    verifier_data.extend_from_slice(blacklist_root);
+    verifier_data.extend_from_slice(&num_inputs.to_be_bytes()); // num_field_elements (BE)
+    for input in public_inputs.to_field_elements().iter() {
+        verifier_data.extend_from_slice(input);
+    }
 
     // Build the CPI instruction
     // Sunspot verifiers take zero accounts - all data is in instruction
@@ -140,6 +287,61 @@ pub fn verify_eligibility_proof(
     }
 }
 
+/// Verify an allowlist membership proof using Sunspot
+///
+/// The allowlist circuit uses the same public input layout as the eligibility
+/// circuit (root, address_hi, address_lo, epoch), so the inputs are built from
+/// the signer and clock the same way. Each market names its own verifier
+/// program; the caller must check `verifier_program` against it.
+///
+/// # Returns
+/// * `Ok(true)` if proof is valid
+/// * `Ok(false)` if proof is invalid
+pub fn verify_allowlist_proof(
+    verifier_program: &AccountInfo,
+    proof: &[u8; GROTH16_PROOF_SIZE],
+    allowlist_root: &[u8; 32],
+    address: &Pubkey,
+) -> Result<bool> {
+    msg!("Sunspot CPI: verify_allowlist_proof");
+    msg!("  Allowlist root: {:?}", &allowlist_root[0..8]);
+
+    let epoch = current_eligibility_epoch()?;
+    let public_inputs = EligibilityPublicInputs::new(allowlist_root, address, epoch);
+
+    let result = verify_groth16_proof(
+        verifier_program,
+        verifier_program,
+        proof,
+        &public_inputs.to_field_elements(),
+    )?;
+
+    Ok(result == VerificationResult::Valid)
+}
+
+/// Verify a proof-of-solvency proof using Sunspot
+///
+/// The caller builds the public inputs with the attested mint and the
+/// current epoch and must check `verifier_program` against the configured
+/// solvency verifier.
+pub fn verify_solvency_proof(
+    verifier_program: &AccountInfo,
+    proof: &[u8; GROTH16_PROOF_SIZE],
+    public_inputs: &SolvencyPublicInputs,
+) -> Result<bool> {
+    msg!("Sunspot CPI: verify_solvency_proof");
+    msg!("  Liabilities root: {:?}", &public_inputs.liabilities_root[0..8]);
+
+    let result = verify_groth16_proof(
+        verifier_program,
+        verifier_program,
+        proof,
+        &public_inputs.to_field_elements(),
+    )?;
+
+    Ok(result == VerificationResult::Valid)
+}
+
 /// Verify a generic Groth16 proof with custom public inputs
 ///
 /// This is a more flexible version that can be used for other circuits
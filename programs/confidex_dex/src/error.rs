@@ -217,4 +217,143 @@ pub enum ConfidexError {
 
     #[msg("Order is not in matching state")]
     OrderNotMatching,
+
+    // === Order Escrow Errors ===
+
+    #[msg("Order is not awaiting balance verification")]
+    OrderNotPendingBalanceCheck,
+
+    // === Time-In-Force Errors ===
+
+    #[msg("Order expiry must be a future time")]
+    InvalidOrderExpiry,
+
+    #[msg("Time-in-force is not valid for this order type")]
+    InvalidTimeInForce,
+
+    #[msg("Order has expired")]
+    OrderExpired,
+
+    #[msg("Order has not expired")]
+    OrderNotExpired,
+
+    #[msg("Match would violate an order's time-in-force (post-only/IOC/FOK)")]
+    TimeInForceViolation,
+
+    // === Self-Trade Prevention Errors ===
+
+    #[msg("Orders belong to the same maker (self-trade prevention)")]
+    SelfTradePrevented,
+
+    // === Order Amend Errors ===
+
+    #[msg("Order is not awaiting an amend check")]
+    OrderNotPendingAmend,
+
+    #[msg("Orders with fills cannot be amended")]
+    OrderHasFills,
+
+    // === ADL Ranking Errors ===
+
+    #[msg("ADL ranking is stale or has never been refreshed")]
+    AdlRankingStale,
+
+    #[msg("ADL target is not at the head of the market's ADL queue")]
+    AdlTargetNotRanked,
+
+    #[msg("ADL queue head is still open")]
+    AdlRankingHeadStillOpen,
+
+    #[msg("No ADL ranking round in progress, or the market changed since it started")]
+    AdlRankingRoundInvalid,
+
+    #[msg("ADL ranking chunk must carry the round leaders and continue with new positions in ascending order")]
+    AdlRankingCoverageInvalid,
+
+    // === Liquidation Batch Errors ===
+
+    #[msg("Invalid liquidation batch capacity")]
+    InvalidBatchCapacity,
+
+    #[msg("Liquidation batch is full")]
+    LiquidationBatchFull,
+
+    #[msg("Position's market is not included in the liquidation batch")]
+    BatchMarketNotIncluded,
+
+    #[msg("Liquidation batch chunk already completed")]
+    BatchChunkAlreadyCompleted,
+
+    // === Exit Trigger Errors ===
+
+    #[msg("Position has no stop-loss or take-profit set")]
+    NoExitTriggers,
+
+    #[msg("Position exit trigger has already fired")]
+    ExitTriggerAlreadyFired,
+
+    #[msg("Position exit trigger has not fired")]
+    ExitTriggerNotFired,
+
+    // === Blacklist Root History Errors ===
+
+    #[msg("Invalid blacklist grace window or attestation lifetime")]
+    InvalidBlacklistHistoryConfig,
+
+    // === Allowlist Errors ===
+
+    #[msg("Invalid allowlist configuration")]
+    InvalidAllowlistConfig,
+
+    #[msg("Market does not have an allowlist enabled")]
+    AllowlistNotEnabled,
+
+    #[msg("Verifier program does not match the market's allowlist verifier")]
+    InvalidAllowlistVerifier,
+
+    #[msg("Allowlist proof verification failed")]
+    AllowlistProofFailed,
+
+    #[msg("Trader has no valid allowlist credential for this market")]
+    AllowlistCredentialInvalid,
+
+    // === Solvency Errors ===
+
+    #[msg("Invalid solvency configuration")]
+    InvalidSolvencyConfig,
+
+    #[msg("Verifier program does not match the configured solvency verifier")]
+    InvalidSolvencyVerifier,
+
+    #[msg("Solvency proof verification failed")]
+    SolvencyProofFailed,
+
+    #[msg("Solvency ratio below 100%")]
+    InvalidSolvencyRatio,
+
+    #[msg("Solvency attestation is still fresh")]
+    SolvencyAttestationFresh,
+
+    #[msg("Solvency auto-pause is disabled")]
+    SolvencyAutoPauseDisabled,
+
+    #[msg("Mint is not traded on the given pair")]
+    InvalidSolvencyMint,
+
+    #[msg("Solvency vaults must be unique pairs in ascending key order")]
+    InvalidSolvencyVaults,
+
+    // === Pending Order Recovery Errors ===
+
+    #[msg("Order's pending MPC request has not timed out yet")]
+    PendingOrderNotTimedOut,
+
+    #[msg("ADL ranking round or chunk is still in flight")]
+    AdlRankingInFlight,
+
+    #[msg("Total liabilities cannot be zero while the vaults hold funds")]
+    InvalidSolvencyLiabilities,
+
+    #[msg("Batch liquidation result is older than the allowed age")]
+    LiquidationResultExpired,
 }
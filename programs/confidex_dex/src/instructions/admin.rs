@@ -3,7 +3,7 @@ use anchor_lang::prelude::*;
 use crate::cpi::arcium::{ARCIUM_PROGRAM_ID, ARCIUM_MXE_PROGRAM_ID};
 use crate::cpi::verifier::SUNSPOT_VERIFIER_PROGRAM_ID;
 use crate::error::ConfidexError;
-use crate::state::ExchangeState;
+use crate::state::{BlacklistRootHistory, ExchangeState};
 
 // ============================================================================
 // Pause Trading
@@ -72,7 +72,7 @@ pub struct UpdateFees<'info> {
 
 pub fn update_fees_handler(
     ctx: Context<UpdateFees>,
-    maker_fee_bps: u16,
+    maker_fee_bps: i16,
     taker_fee_bps: u16,
 ) -> Result<()> {
     require!(
@@ -102,6 +102,14 @@ pub struct UpdateBlacklist<'info> {
     )]
     pub exchange: Account<'info, ExchangeState>,
 
+    /// Recent roots, so attestations against the old root survive the grace window
+    #[account(
+        mut,
+        seeds = [BlacklistRootHistory::SEED],
+        bump = blacklist_history.bump
+    )]
+    pub blacklist_history: Account<'info, BlacklistRootHistory>,
+
     pub authority: Signer<'info>,
 }
 
@@ -109,8 +117,118 @@ pub fn update_blacklist_handler(
     ctx: Context<UpdateBlacklist>,
     new_root: [u8; 32],
 ) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
     ctx.accounts.exchange.blacklist_root = new_root;
-    msg!("Blacklist merkle root updated");
+    ctx.accounts.blacklist_history.push(new_root, now);
+
+    msg!(
+        "Blacklist merkle root updated (previous root accepted for {}s)",
+        ctx.accounts.blacklist_history.grace_period_secs
+    );
+    Ok(())
+}
+
+// ============================================================================
+// Initialize Blacklist Root History
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct InitializeBlacklistHistory<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+        has_one = authority @ ConfidexError::Unauthorized
+    )]
+    pub exchange: Account<'info, ExchangeState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = BlacklistRootHistory::SIZE,
+        seeds = [BlacklistRootHistory::SEED],
+        bump
+    )]
+    pub blacklist_history: Account<'info, BlacklistRootHistory>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the root history, seeded with the exchange's current blacklist root
+pub fn initialize_blacklist_history_handler(
+    ctx: Context<InitializeBlacklistHistory>,
+    grace_period_secs: i64,
+    attestation_ttl_secs: i64,
+) -> Result<()> {
+    require!(
+        BlacklistRootHistory::validate_config(grace_period_secs, attestation_ttl_secs),
+        ConfidexError::InvalidBlacklistHistoryConfig
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let history = &mut ctx.accounts.blacklist_history;
+    history.grace_period_secs = grace_period_secs;
+    history.attestation_ttl_secs = attestation_ttl_secs;
+    history.bump = ctx.bumps.blacklist_history;
+    history.push(ctx.accounts.exchange.blacklist_root, now);
+
+    msg!(
+        "Blacklist root history initialized: grace {}s, attestation ttl {}s",
+        grace_period_secs,
+        attestation_ttl_secs
+    );
+    Ok(())
+}
+
+// ============================================================================
+// Update Blacklist Grace Window
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct UpdateBlacklistHistoryConfig<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+        has_one = authority @ ConfidexError::Unauthorized
+    )]
+    pub exchange: Account<'info, ExchangeState>,
+
+    #[account(
+        mut,
+        seeds = [BlacklistRootHistory::SEED],
+        bump = blacklist_history.bump
+    )]
+    pub blacklist_history: Account<'info, BlacklistRootHistory>,
+
+    pub authority: Signer<'info>,
+}
+
+/// Update the grace window and attestation lifetime
+///
+/// A shorter grace window applies to already superseded roots immediately.
+/// A new attestation lifetime only applies to attestations verified afterwards.
+pub fn update_blacklist_history_config_handler(
+    ctx: Context<UpdateBlacklistHistoryConfig>,
+    grace_period_secs: i64,
+    attestation_ttl_secs: i64,
+) -> Result<()> {
+    require!(
+        BlacklistRootHistory::validate_config(grace_period_secs, attestation_ttl_secs),
+        ConfidexError::InvalidBlacklistHistoryConfig
+    );
+
+    let history = &mut ctx.accounts.blacklist_history;
+    history.grace_period_secs = grace_period_secs;
+    history.attestation_ttl_secs = attestation_ttl_secs;
+
+    msg!(
+        "Blacklist history config updated: grace {}s, attestation ttl {}s",
+        grace_period_secs,
+        attestation_ttl_secs
+    );
     Ok(())
 }
 
@@ -177,7 +295,7 @@ pub fn set_pair_vaults_handler(ctx: Context<SetPairVaults>) -> Result<()> {
 // Close Perpetual Market (admin only - for migration)
 // ============================================================================
 
-use crate::state::PerpetualMarket;
+use crate::state::{OracleSource, PerpetualMarket};
 use anchor_lang::system_program;
 
 #[derive(Accounts)]
@@ -276,6 +394,195 @@ pub fn close_funding_state_handler(ctx: Context<CloseFundingState>) -> Result<()
     Ok(())
 }
 
+// ============================================================================
+// Update Funding Config (admin only)
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct UpdateFundingConfig<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+        has_one = authority @ ConfidexError::Unauthorized
+    )]
+    pub exchange: Account<'info, ExchangeState>,
+
+    #[account(
+        seeds = [PerpetualMarket::SEED, perp_market.underlying_mint.as_ref()],
+        bump = perp_market.bump
+    )]
+    pub perp_market: Account<'info, PerpetualMarket>,
+
+    #[account(
+        mut,
+        seeds = [FundingRateState::SEED, perp_market.key().as_ref()],
+        bump = funding_state.bump,
+        constraint = funding_state.market == perp_market.key() @ ConfidexError::InvalidFundingState
+    )]
+    pub funding_state: Account<'info, FundingRateState>,
+
+    pub authority: Signer<'info>,
+}
+
+/// Parameters for updating the premium funding model
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UpdateFundingConfigParams {
+    /// New interest component per interval (None = keep current)
+    pub interest_rate_bps: Option<i16>,
+    /// New dampening band (None = keep current)
+    pub premium_dampening_bps: Option<u16>,
+    /// New mark price feed format (None = keep current)
+    pub mark_oracle_source: Option<OracleSource>,
+    /// New mark price feed (None = keep current, Pubkey::default() = no mark feed)
+    pub mark_oracle_feed: Option<Pubkey>,
+    /// New mark EMA window in seconds (None = keep current)
+    pub mark_ema_window_seconds: Option<u32>,
+    /// New max funding rate cap (None = keep current)
+    pub max_funding_rate_bps: Option<u16>,
+}
+
+pub fn update_funding_config_handler(
+    ctx: Context<UpdateFundingConfig>,
+    params: UpdateFundingConfigParams,
+) -> Result<()> {
+    let funding_state = &mut ctx.accounts.funding_state;
+
+    if let Some(interest_rate_bps) = params.interest_rate_bps {
+        funding_state.interest_rate_bps = interest_rate_bps;
+    }
+
+    if let Some(premium_dampening_bps) = params.premium_dampening_bps {
+        require!(premium_dampening_bps <= 10000, ConfidexError::InvalidFeeBps);
+        funding_state.premium_dampening_bps = premium_dampening_bps;
+    }
+
+    // A new mark source restarts both averages (reseeded together on the
+    // next update) so the old feed's history doesn't leak into the premium
+    if params.mark_oracle_source.is_some() || params.mark_oracle_feed.is_some() {
+        if let Some(mark_oracle_source) = params.mark_oracle_source {
+            funding_state.mark_oracle_source = mark_oracle_source;
+        }
+        if let Some(mark_oracle_feed) = params.mark_oracle_feed {
+            funding_state.mark_oracle_feed = mark_oracle_feed;
+        }
+        funding_state.mark_price_ema = 0;
+        funding_state.index_price_ema = 0;
+        funding_state.mark_ema_remainder = 0;
+        funding_state.index_ema_remainder = 0;
+    }
+
+    if let Some(mark_ema_window_seconds) = params.mark_ema_window_seconds {
+        require!(mark_ema_window_seconds > 0, ConfidexError::InvalidFundingInterval);
+        funding_state.mark_ema_window_seconds = mark_ema_window_seconds;
+    }
+
+    if let Some(max_funding_rate_bps) = params.max_funding_rate_bps {
+        require!(max_funding_rate_bps <= 10000, ConfidexError::InvalidFeeBps);
+        funding_state.max_funding_rate_bps = max_funding_rate_bps;
+    }
+
+    msg!("Funding config updated");
+
+    Ok(())
+}
+
+// ============================================================================
+// Migrate Funding State (V1 → current)
+// ============================================================================
+// Appends the premium funding model fields (V2). No mark feed is configured;
+// set one with update_funding_config.
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct MigrateFundingState<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+        has_one = authority @ ConfidexError::Unauthorized
+    )]
+    pub exchange: Account<'info, ExchangeState>,
+
+    #[account(
+        seeds = [PerpetualMarket::SEED, perp_market.underlying_mint.as_ref()],
+        bump = perp_market.bump
+    )]
+    pub perp_market: Account<'info, PerpetualMarket>,
+
+    /// CHECK: We use AccountInfo to handle both old and new sizes
+    #[account(
+        mut,
+        seeds = [FundingRateState::SEED, perp_market.key().as_ref()],
+        bump,
+    )]
+    pub funding_state: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_funding_state_handler(ctx: Context<MigrateFundingState>) -> Result<()> {
+    let funding_info = &ctx.accounts.funding_state;
+    let authority = &ctx.accounts.authority;
+    let system_program = &ctx.accounts.system_program;
+
+    require!(funding_info.owner == &crate::ID, ConfidexError::InvalidAccountData);
+
+    let current_size = funding_info.data_len();
+    msg!("Funding state current size: {} bytes", current_size);
+
+    if current_size == FundingRateState::SIZE {
+        msg!("Funding state already at current size ({}), no migration needed", FundingRateState::SIZE);
+        return Ok(());
+    }
+
+    require!(
+        current_size == FundingRateState::V1_SIZE,
+        ConfidexError::InvalidAccountSize
+    );
+    require!(
+        &funding_info.try_borrow_data()?[..8] == FundingRateState::DISCRIMINATOR,
+        ConfidexError::InvalidAccountData
+    );
+
+    // Transfer additional rent from authority to the funding state
+    let rent = Rent::get()?;
+    let additional_rent = rent
+        .minimum_balance(FundingRateState::SIZE)
+        .saturating_sub(funding_info.lamports());
+
+    if additional_rent > 0 {
+        let cpi_context = CpiContext::new(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: authority.to_account_info(),
+                to: funding_info.clone(),
+            },
+        );
+        anchor_lang::system_program::transfer(cpi_context, additional_rent)?;
+        msg!("Transferred {} lamports for additional rent", additional_rent);
+    }
+
+    // Grow the account; appended bytes are zero-initialized
+    funding_info.resize(FundingRateState::SIZE)?;
+
+    // Write non-zero V2 defaults (interest_rate_bps, the mark feed and the
+    // mark/index average state stay zero; the first update seeds both averages)
+    let offset = FundingRateState::V1_SIZE;
+    let mut data = funding_info.try_borrow_mut_data()?;
+    // premium_dampening_bps
+    data[offset + 2..offset + 4]
+        .copy_from_slice(&FundingRateState::DEFAULT_PREMIUM_DAMPENING_BPS.to_le_bytes());
+    // mark_ema_window_seconds (after mark_oracle_source and mark_oracle_feed)
+    data[offset + 37..offset + 41]
+        .copy_from_slice(&FundingRateState::DEFAULT_MARK_EMA_WINDOW_SECS.to_le_bytes());
+
+    msg!("Funding state migrated to {} bytes", FundingRateState::SIZE);
+
+    Ok(())
+}
+
 // ============================================================================
 // Set Perpetual Market Vaults (admin only)
 // ============================================================================
@@ -302,8 +609,10 @@ pub struct SetPerpMarketVaults<'info> {
     )]
     pub collateral_vault: Account<'info, TokenAccount>,
 
-    /// Insurance fund token account (USDC)
+    /// Insurance fund token account (USDC) - the market's insurance fund PDA
     #[account(
+        seeds = [PerpetualMarket::INSURANCE_FUND_SEED, perp_market.key().as_ref()],
+        bump,
         constraint = insurance_fund.mint == perp_market.quote_mint @ ConfidexError::InvalidTokenMint
     )]
     pub insurance_fund: Account<'info, TokenAccount>,
@@ -322,6 +631,7 @@ pub fn set_perp_market_vaults_handler(ctx: Context<SetPerpMarketVaults>) -> Resu
 
     perp_market.collateral_vault = ctx.accounts.collateral_vault.key();
     perp_market.insurance_fund = ctx.accounts.insurance_fund.key();
+    perp_market.insurance_fund_balance = ctx.accounts.insurance_fund.amount;
     perp_market.fee_recipient = ctx.accounts.fee_recipient.key();
 
     msg!(
@@ -376,6 +686,19 @@ pub struct UpdatePerpMarketParams {
     pub active: Option<bool>,
     /// New Arcium cluster (None = keep current)
     pub arcium_cluster: Option<Pubkey>,
+    /// New primary oracle feed (None = keep current)
+    /// Account address, or Pyth feed ID for PythPull
+    pub oracle_price_feed: Option<Pubkey>,
+    /// New primary oracle source (None = keep current)
+    pub oracle_source: Option<OracleSource>,
+    /// New secondary oracle feed (None = keep current, Pubkey::default() = remove)
+    pub secondary_oracle_feed: Option<Pubkey>,
+    /// New secondary oracle source (None = keep current)
+    pub secondary_oracle_source: Option<OracleSource>,
+    /// New max primary/secondary divergence bps (None = keep current, 0 = unchecked)
+    pub max_oracle_divergence_bps: Option<u16>,
+    /// New price precision (None = keep current, requires no open positions)
+    pub price_decimals: Option<u8>,
 }
 
 pub fn update_perp_market_config_handler(
@@ -419,6 +742,39 @@ pub fn update_perp_market_config_handler(
         perp_market.arcium_cluster = arcium_cluster;
     }
 
+    // Oracle sources may be switched with open positions (e.g. legacy Pyth
+    // deprecation); prices are normalized to price_decimals either way
+    if let Some(oracle_price_feed) = params.oracle_price_feed {
+        perp_market.oracle_price_feed = oracle_price_feed;
+    }
+
+    if let Some(oracle_source) = params.oracle_source {
+        perp_market.oracle_source = oracle_source;
+    }
+
+    if let Some(secondary_oracle_feed) = params.secondary_oracle_feed {
+        perp_market.secondary_oracle_feed = secondary_oracle_feed;
+    }
+
+    if let Some(secondary_oracle_source) = params.secondary_oracle_source {
+        perp_market.secondary_oracle_source = secondary_oracle_source;
+    }
+
+    if let Some(max_oracle_divergence_bps) = params.max_oracle_divergence_bps {
+        require!(max_oracle_divergence_bps <= 10000, ConfidexError::InvalidOraclePrice);
+        perp_market.max_oracle_divergence_bps = max_oracle_divergence_bps;
+    }
+
+    // Entry prices of open positions are in the current precision
+    if let Some(price_decimals) = params.price_decimals {
+        require!(perp_market.position_count == 0, ConfidexError::InvalidOraclePrice);
+        require!(
+            price_decimals <= PerpetualMarket::MAX_PRICE_DECIMALS,
+            ConfidexError::InvalidOraclePrice
+        );
+        perp_market.price_decimals = price_decimals;
+    }
+
     msg!("Perp market config updated");
 
     Ok(())
@@ -641,6 +997,470 @@ pub fn update_program_ids_handler(
     Ok(())
 }
 
+// ============================================================================
+// Migrate Order Account (V5 → current)
+// ============================================================================
+// Orders from older layouts (366, 374, 439, 449 or 457 bytes) can't be
+// deserialized, so they can be neither cancelled nor expired. Appended fields
+// are zero-filled (no escrow, no unsettled fill, GTC, no expiry, CancelNewest,
+// no pending request timestamp - a stuck PendingCancel is recoverable at once,
+// no staged amend).
+// Before V10 an amend replaced the ciphertexts in place, so a PendingAmend
+// order has its current ciphertexts staged (a passing check keeps them) and is
+// expired: if the check fails they stay live against the old escrow, so the
+// order must not trade again.
+// Orders from before escrow tracking never locked funds (escrow_remaining = 0),
+// so they are expired on migration: they can't be matched into a settlement
+// the escrow can't cover, and expire_order / cancel_order can close them out.
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct MigrateOrder<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+        has_one = authority @ ConfidexError::Unauthorized
+    )]
+    pub exchange: Account<'info, ExchangeState>,
+
+    /// CHECK: We use AccountInfo to handle both old and new sizes
+    /// Owner, discriminator and PDA derivation are verified in the handler
+    #[account(mut)]
+    pub order: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_order_handler(ctx: Context<MigrateOrder>) -> Result<()> {
+    let order_info = &ctx.accounts.order;
+    let authority = &ctx.accounts.authority;
+    let system_program = &ctx.accounts.system_program;
+
+    require!(order_info.owner == &crate::ID, ConfidexError::InvalidAccountData);
+
+    let current_size = order_info.data_len();
+    msg!("Order current size: {} bytes", current_size);
+
+    if current_size == ConfidentialOrder::SIZE {
+        msg!("Order already at current size ({}), no migration needed", ConfidentialOrder::SIZE);
+        return Ok(());
+    }
+
+    require!(
+        current_size >= ConfidentialOrder::V5_SIZE && current_size < ConfidentialOrder::SIZE,
+        ConfidexError::InvalidAccountSize
+    );
+
+    {
+        let data = order_info.try_borrow_data()?;
+        require!(
+            &data[..8] == ConfidentialOrder::DISCRIMINATOR,
+            ConfidexError::InvalidAccountData
+        );
+    }
+
+    // Transfer additional rent from authority to the order
+    let rent = Rent::get()?;
+    let additional_rent = rent
+        .minimum_balance(ConfidentialOrder::SIZE)
+        .saturating_sub(order_info.lamports());
+
+    if additional_rent > 0 {
+        let cpi_context = CpiContext::new(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: authority.to_account_info(),
+                to: order_info.clone(),
+            },
+        );
+        anchor_lang::system_program::transfer(cpi_context, additional_rent)?;
+        msg!("Transferred {} lamports for additional rent", additional_rent);
+    }
+
+    // Grow the account; appended bytes are zero-initialized
+    order_info.resize(ConfidentialOrder::SIZE)?;
+
+    // The zero-filled layout now deserializes; verify the PDA
+    // (seeds = [order, maker, order_nonce]) before writing anything back
+    let mut order = {
+        let data = order_info.try_borrow_data()?;
+        ConfidentialOrder::try_deserialize(&mut &data[..])?
+    };
+    let expected = Pubkey::create_program_address(
+        &[
+            ConfidentialOrder::SEED,
+            order.maker.as_ref(),
+            &order.order_nonce,
+            &[order.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| ConfidexError::InvalidAccountData)?;
+    require!(expected == order_info.key(), ConfidexError::InvalidAccountData);
+
+    let mut expired = false;
+    if current_size < ConfidentialOrder::V6_SIZE && order.status == OrderStatus::Active {
+        order.mark_expired(Clock::get()?.unix_timestamp);
+        expired = true;
+        msg!("Pre-escrow order expired on migration");
+    }
+    if current_size <= ConfidentialOrder::V9_SIZE && order.is_pending_amend() {
+        order.stage_amend(order.encrypted_amount, order.encrypted_price, order.ephemeral_pubkey);
+        order.mark_expired(Clock::get()?.unix_timestamp);
+        expired = true;
+        msg!("In-place amend staged and order expired on migration");
+    }
+    if expired {
+        let mut data = order_info.try_borrow_mut_data()?;
+        let mut writer = &mut data[8..]; // Skip discriminator
+        order.serialize(&mut writer)?;
+    }
+
+    msg!("Order migrated to {} bytes", ConfidentialOrder::SIZE);
+
+    Ok(())
+}
+
+// ============================================================================
+// Migrate Perpetual Market Account (V1 → current)
+// ============================================================================
+// New PerpetualMarket fields are appended after `bump`, so migration only
+// grows the account (zero-filled) and writes non-zero defaults for the
+// appended fields. Existing fields keep their offsets.
+//
+// The V6 open position count can't be derived on-chain, so the authority
+// supplies it once when a V5 (or older) market is migrated.
+// ============================================================================
+
+use crate::oracle::INTERNAL_PRICE_DECIMALS;
+
+/// Byte offset of PerpetualMarket::position_count (present since V1)
+const PERP_MARKET_POSITION_COUNT_OFFSET: usize = 8 + 32 + 32 + 1 + 2 * 5 + 8 * 5;
+
+#[derive(Accounts)]
+pub struct MigratePerpMarket<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+        has_one = authority @ ConfidexError::Unauthorized
+    )]
+    pub exchange: Account<'info, ExchangeState>,
+
+    /// CHECK: We use AccountInfo to handle both old and new sizes
+    /// Owner, discriminator and PDA derivation are verified in the handler
+    #[account(mut)]
+    pub perp_market: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_perp_market_handler(
+    ctx: Context<MigratePerpMarket>,
+    open_position_count: u64,
+) -> Result<()> {
+    let market_info = &ctx.accounts.perp_market;
+    let authority = &ctx.accounts.authority;
+    let system_program = &ctx.accounts.system_program;
+
+    require!(market_info.owner == &crate::ID, ConfidexError::InvalidAccountData);
+
+    let current_size = market_info.data_len();
+    msg!("Perp market current size: {} bytes", current_size);
+
+    if current_size == PerpetualMarket::SIZE {
+        msg!("Perp market already at current size ({}), no migration needed", PerpetualMarket::SIZE);
+        return Ok(());
+    }
+
+    require!(
+        current_size >= PerpetualMarket::V1_SIZE && current_size < PerpetualMarket::SIZE,
+        ConfidexError::InvalidAccountSize
+    );
+
+    // Verify discriminator and PDA (seeds = [perp_market, underlying_mint])
+    {
+        let data = market_info.try_borrow_data()?;
+        require!(
+            &data[..8] == PerpetualMarket::DISCRIMINATOR,
+            ConfidexError::InvalidAccountData
+        );
+        let underlying_mint: [u8; 32] = data[8..40]
+            .try_into()
+            .map_err(|_| ConfidexError::InvalidAccountData)?;
+        // bump is the last V1 field
+        let bump = data[PerpetualMarket::V1_SIZE - 1];
+        let expected = Pubkey::create_program_address(
+            &[PerpetualMarket::SEED, &underlying_mint, &[bump]],
+            &crate::ID,
+        )
+        .map_err(|_| ConfidexError::InvalidAccountData)?;
+        require!(expected == market_info.key(), ConfidexError::InvalidAccountData);
+    }
+
+    // Transfer additional rent from authority to the market
+    let rent = Rent::get()?;
+    let additional_rent = rent
+        .minimum_balance(PerpetualMarket::SIZE)
+        .saturating_sub(market_info.lamports());
+
+    if additional_rent > 0 {
+        let cpi_context = CpiContext::new(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: authority.to_account_info(),
+                to: market_info.clone(),
+            },
+        );
+        anchor_lang::system_program::transfer(cpi_context, additional_rent)?;
+        msg!("Transferred {} lamports for additional rent", additional_rent);
+    }
+
+    // Grow the account; appended bytes are zero-initialized
+    market_info.resize(PerpetualMarket::SIZE)?;
+
+    // Write non-zero defaults for fields the old layout didn't have
+    let mut data = market_info.try_borrow_mut_data()?;
+    if current_size <= PerpetualMarket::V1_SIZE {
+        // price_decimals (V2)
+        data[PerpetualMarket::V1_SIZE] = INTERNAL_PRICE_DECIMALS as u8;
+    }
+    if current_size <= PerpetualMarket::V2_SIZE {
+        // oracle_source / secondary_oracle_source (PythLegacy = 0) and
+        // secondary_oracle_feed (none = zero) are already zeroed
+        // max_oracle_divergence_bps (V3)
+        let offset = PerpetualMarket::V2_SIZE + 1 + 1 + 32;
+        data[offset..offset + 2].copy_from_slice(
+            &PerpetualMarket::DEFAULT_MAX_ORACLE_DIVERGENCE_BPS.to_le_bytes(),
+        );
+    }
+    // insurance_fund_balance (V4) starts at zero and is refreshed from the
+    // fund's token balance on the next deposit, withdrawal or ADL
+    if current_size <= PerpetualMarket::V4_SIZE {
+        // open_position_count (V5) - can't exceed the positions ever opened
+        let offset = PERP_MARKET_POSITION_COUNT_OFFSET;
+        let position_count = u64::from_le_bytes(
+            data[offset..offset + 8]
+                .try_into()
+                .map_err(|_| ConfidexError::InvalidAccountData)?,
+        );
+        require!(
+            open_position_count <= position_count,
+            ConfidexError::InvalidAccountData
+        );
+        let offset = PerpetualMarket::V4_SIZE;
+        data[offset..offset + 8].copy_from_slice(&open_position_count.to_le_bytes());
+    }
+    // allowlist (V6) zeroed = allowlist disabled
+
+    msg!("Perp market migrated to {} bytes", PerpetualMarket::SIZE);
+
+    Ok(())
+}
+
+// ============================================================================
+// Migrate Trading Pair Account (V1 → current)
+// ============================================================================
+// Same approach as perp markets: appended fields are zero-filled, which for
+// the V2 allowlist means "disabled".
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct MigrateTradingPair<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+        has_one = authority @ ConfidexError::Unauthorized
+    )]
+    pub exchange: Account<'info, ExchangeState>,
+
+    /// CHECK: We use AccountInfo to handle both old and new sizes
+    /// Owner, discriminator and PDA derivation are verified in the handler
+    #[account(mut)]
+    pub pair: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_trading_pair_handler(ctx: Context<MigrateTradingPair>) -> Result<()> {
+    let pair_info = &ctx.accounts.pair;
+    let authority = &ctx.accounts.authority;
+    let system_program = &ctx.accounts.system_program;
+
+    require!(pair_info.owner == &crate::ID, ConfidexError::InvalidAccountData);
+
+    let current_size = pair_info.data_len();
+    msg!("Trading pair current size: {} bytes", current_size);
+
+    if current_size == TradingPair::SIZE {
+        msg!("Trading pair already at current size ({}), no migration needed", TradingPair::SIZE);
+        return Ok(());
+    }
+
+    require!(
+        current_size >= TradingPair::V1_SIZE && current_size < TradingPair::SIZE,
+        ConfidexError::InvalidAccountSize
+    );
+
+    // Verify discriminator and PDA (seeds = [pair, base_mint, quote_mint])
+    {
+        let data = pair_info.try_borrow_data()?;
+        require!(
+            &data[..8] == TradingPair::DISCRIMINATOR,
+            ConfidexError::InvalidAccountData
+        );
+        let base_mint: [u8; 32] = data[8..40]
+            .try_into()
+            .map_err(|_| ConfidexError::InvalidAccountData)?;
+        let quote_mint: [u8; 32] = data[40..72]
+            .try_into()
+            .map_err(|_| ConfidexError::InvalidAccountData)?;
+        // bump is the last V1 field
+        let bump = data[TradingPair::V1_SIZE - 1];
+        let expected = Pubkey::create_program_address(
+            &[TradingPair::SEED, &base_mint, &quote_mint, &[bump]],
+            &crate::ID,
+        )
+        .map_err(|_| ConfidexError::InvalidAccountData)?;
+        require!(expected == pair_info.key(), ConfidexError::InvalidAccountData);
+    }
+
+    // Transfer additional rent from authority to the pair
+    let rent = Rent::get()?;
+    let additional_rent = rent
+        .minimum_balance(TradingPair::SIZE)
+        .saturating_sub(pair_info.lamports());
+
+    if additional_rent > 0 {
+        let cpi_context = CpiContext::new(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: authority.to_account_info(),
+                to: pair_info.clone(),
+            },
+        );
+        anchor_lang::system_program::transfer(cpi_context, additional_rent)?;
+        msg!("Transferred {} lamports for additional rent", additional_rent);
+    }
+
+    // Grow the account; allowlist (V2) is zero-initialized = disabled
+    pair_info.resize(TradingPair::SIZE)?;
+
+    msg!("Trading pair migrated to {} bytes", TradingPair::SIZE);
+
+    Ok(())
+}
+
+// ============================================================================
+// Migrate Position Account (V8 → current)
+// ============================================================================
+// Positions opened before exit triggers are 724 bytes and can't be
+// deserialized, so they can't be closed, liquidated or topped up. Appended
+// fields are zero-filled: no stop-loss / take-profit and no trigger check
+// pending.
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct MigratePosition<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+        has_one = authority @ ConfidexError::Unauthorized
+    )]
+    pub exchange: Account<'info, ExchangeState>,
+
+    /// CHECK: We use AccountInfo to handle both old and new sizes
+    /// Owner, discriminator and PDA derivation are verified in the handler
+    #[account(mut)]
+    pub position: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_position_handler(ctx: Context<MigratePosition>) -> Result<()> {
+    let position_info = &ctx.accounts.position;
+    let authority = &ctx.accounts.authority;
+    let system_program = &ctx.accounts.system_program;
+
+    require!(position_info.owner == &crate::ID, ConfidexError::InvalidAccountData);
+
+    let current_size = position_info.data_len();
+    msg!("Position current size: {} bytes", current_size);
+
+    if current_size == ConfidentialPosition::SIZE {
+        msg!("Position already at current size ({}), no migration needed", ConfidentialPosition::SIZE);
+        return Ok(());
+    }
+
+    require!(
+        current_size >= ConfidentialPosition::V8_SIZE && current_size < ConfidentialPosition::SIZE,
+        ConfidexError::InvalidAccountSize
+    );
+
+    {
+        let data = position_info.try_borrow_data()?;
+        require!(
+            &data[..8] == ConfidentialPosition::DISCRIMINATOR,
+            ConfidexError::InvalidAccountData
+        );
+    }
+
+    // Transfer additional rent from authority to the position
+    let rent = Rent::get()?;
+    let additional_rent = rent
+        .minimum_balance(ConfidentialPosition::SIZE)
+        .saturating_sub(position_info.lamports());
+
+    if additional_rent > 0 {
+        let cpi_context = CpiContext::new(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: authority.to_account_info(),
+                to: position_info.clone(),
+            },
+        );
+        anchor_lang::system_program::transfer(cpi_context, additional_rent)?;
+        msg!("Transferred {} lamports for additional rent", additional_rent);
+    }
+
+    // Grow the account; appended bytes are zero-initialized
+    position_info.resize(ConfidentialPosition::SIZE)?;
+
+    // The zero-filled layout now deserializes; verify the PDA
+    // (seeds = [position, trader, market, position_seed])
+    let position = {
+        let data = position_info.try_borrow_data()?;
+        ConfidentialPosition::try_deserialize(&mut &data[..])?
+    };
+    let expected = Pubkey::create_program_address(
+        &[
+            ConfidentialPosition::SEED,
+            position.trader.as_ref(),
+            position.market.as_ref(),
+            &position.position_seed.to_le_bytes(),
+            &[position.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| ConfidexError::InvalidAccountData)?;
+    require!(expected == position_info.key(), ConfidexError::InvalidAccountData);
+
+    msg!("Position migrated to {} bytes", ConfidentialPosition::SIZE);
+
+    Ok(())
+}
+
 // ============================================================================
 // Admin Force Close Position (for broken V2 / legacy positions)
 // ============================================================================
@@ -815,6 +1635,7 @@ pub fn admin_force_close_handler(
 
     // Mark position as closed
     position.status = PositionStatus::Closed;
+    ctx.accounts.perp_market.record_position_closed();
     position.last_updated_hour = ConfidentialPosition::coarse_timestamp(Clock::get()?.unix_timestamp);
 
     // Emit event for tracking
@@ -1190,8 +2011,14 @@ pub struct AdminForceCancelOrder<'info> {
             &order.order_nonce
         ],
         bump = order.bump,
-        // Order must be active (status=Active and not matching)
-        constraint = order.is_active() @ ConfidexError::OrderNotOpen
+        // Order must be active (status=Active and not matching), or stuck
+        // waiting for the MPC balance check (nothing escrowed - refund 0)
+        // or for the MPC refund callback (initiate_cancel_order / expire_order)
+        // or for the MPC amend check (old escrow still locked)
+        constraint = order.is_active()
+            || order.is_pending_balance_check()
+            || order.is_pending_cancel()
+            || order.is_pending_amend() @ ConfidexError::OrderNotOpen
     )]
     pub order: Account<'info, ConfidentialOrder>,
 
@@ -1283,9 +2110,14 @@ pub fn admin_force_cancel_order_handler(
     // Mark order as Inactive (cancelled - privacy preserving, no separate Cancelled variant)
     order.status = OrderStatus::Inactive;
 
+    // The admin refund replaces whatever escrow was recorded on the order
+    order.escrow_remaining = 0;
+
     // Clear any matching state
     order.is_matching = false;
     order.pending_match_request = [0u8; 32];
+    order.pending_queued_at = 0;
+    order.discard_staged_amend();
 
     // Decrement open order count
     pair.open_order_count = pair.open_order_count.checked_sub(1)
@@ -0,0 +1,260 @@
+use anchor_lang::prelude::*;
+
+use crate::cpi::verifier::{verify_allowlist_proof, GROTH16_PROOF_SIZE};
+use crate::error::ConfidexError;
+use crate::state::{
+    AllowlistConfig, AllowlistCredential, BlacklistRootHistory, ExchangeState, PerpetualMarket,
+    TradingPair,
+};
+
+// ============================================================================
+// PER-MARKET ALLOWLIST (credential tree membership)
+// ============================================================================
+//
+// A TradingPair or PerpetualMarket can be restricted to traders holding a
+// credential in an issuer's tree (KYC'd / jurisdiction-permitted). Same
+// two-instruction pattern as blacklist eligibility:
+//   1. verify_allowlist: Sunspot CPI proves membership, stores an
+//      AllowlistCredential for (market, trader)
+//   2. place_order / open_position: check the credential against the
+//      market's current allowlist root
+//
+// Both market types are passed as an unchecked account and loaded by
+// discriminator, so one pair of instructions serves spot and perps.
+
+/// A market that can carry an allowlist
+enum AllowlistMarket {
+    Pair(Box<TradingPair>),
+    Perp(Box<PerpetualMarket>),
+}
+
+impl AllowlistMarket {
+    fn load(info: &AccountInfo) -> Result<Self> {
+        require!(info.owner == &crate::ID, ConfidexError::InvalidAccountData);
+
+        let data = info.try_borrow_data()?;
+        require!(data.len() >= 8, ConfidexError::InvalidAccountData);
+
+        if &data[..8] == TradingPair::DISCRIMINATOR {
+            Ok(Self::Pair(Box::new(TradingPair::try_deserialize(&mut &data[..])?)))
+        } else {
+            Ok(Self::Perp(Box::new(PerpetualMarket::try_deserialize(&mut &data[..])?)))
+        }
+    }
+
+    fn allowlist(&self) -> &AllowlistConfig {
+        match self {
+            Self::Pair(pair) => &pair.allowlist,
+            Self::Perp(market) => &market.allowlist,
+        }
+    }
+
+    fn allowlist_mut(&mut self) -> &mut AllowlistConfig {
+        match self {
+            Self::Pair(pair) => &mut pair.allowlist,
+            Self::Perp(market) => &mut market.allowlist,
+        }
+    }
+
+    fn save(&self, info: &AccountInfo) -> Result<()> {
+        let mut data = info.try_borrow_mut_data()?;
+        let mut writer = &mut data[8..]; // Skip discriminator
+        match self {
+            Self::Pair(pair) => pair.serialize(&mut writer)?,
+            Self::Perp(market) => market.serialize(&mut writer)?,
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Set Allowlist
+// ============================================================================
+
+/// Accounts for configuring a market's allowlist
+#[derive(Accounts)]
+pub struct SetAllowlist<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+    )]
+    pub exchange: Account<'info, ExchangeState>,
+
+    /// CHECK: TradingPair or PerpetualMarket, loaded by discriminator in the handler
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+
+    /// Exchange authority, or the market's allowlist issuer (root rotation only)
+    pub signer: Signer<'info>,
+}
+
+/// Parameters for configuring a market's allowlist
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetAllowlistParams {
+    /// New credential tree root ([0; 32] disables the allowlist, exchange authority only)
+    pub allowlist_root: [u8; 32],
+    /// New issuer (exchange authority only)
+    pub issuer: Option<Pubkey>,
+    /// New Sunspot verifier program (exchange authority only)
+    pub verifier: Option<Pubkey>,
+}
+
+pub fn set_allowlist_handler(ctx: Context<SetAllowlist>, params: SetAllowlistParams) -> Result<()> {
+    let signer = ctx.accounts.signer.key();
+    let market_info = ctx.accounts.market.to_account_info();
+    let mut market = AllowlistMarket::load(&market_info)?;
+
+    let is_authority = signer == ctx.accounts.exchange.authority;
+    let current_issuer = market.allowlist().issuer;
+    let is_issuer = current_issuer != Pubkey::default() && signer == current_issuer;
+
+    require!(is_authority || is_issuer, ConfidexError::Unauthorized);
+
+    // The issuer can only rotate the root; disabling the allowlist is
+    // reserved for the exchange authority
+    if !is_authority {
+        require!(
+            params.issuer.is_none() && params.verifier.is_none(),
+            ConfidexError::Unauthorized
+        );
+        require!(
+            params.allowlist_root != [0u8; 32],
+            ConfidexError::Unauthorized
+        );
+    }
+
+    let allowlist = market.allowlist_mut();
+    if let Some(issuer) = params.issuer {
+        allowlist.issuer = issuer;
+    }
+    if let Some(verifier) = params.verifier {
+        allowlist.verifier = verifier;
+    }
+    allowlist.root = params.allowlist_root;
+
+    // An enabled allowlist needs a verifier to prove against
+    require!(
+        !allowlist.is_enabled() || allowlist.verifier != Pubkey::default(),
+        ConfidexError::InvalidAllowlistConfig
+    );
+
+    let enabled = allowlist.is_enabled();
+    market.save(&market_info)?;
+
+    msg!(
+        "Allowlist for market {} {} (root: {:?})",
+        ctx.accounts.market.key(),
+        if enabled { "updated" } else { "disabled" },
+        &params.allowlist_root[0..8]
+    );
+
+    Ok(())
+}
+
+// ============================================================================
+// Verify Allowlist Credential
+// ============================================================================
+
+/// Accounts for proving allowlist membership for a market
+#[derive(Accounts)]
+pub struct VerifyAllowlist<'info> {
+    /// Blacklist root history (attestation lifetime)
+    #[account(
+        seeds = [BlacklistRootHistory::SEED],
+        bump = blacklist_history.bump
+    )]
+    pub blacklist_history: Account<'info, BlacklistRootHistory>,
+
+    /// CHECK: TradingPair or PerpetualMarket, loaded by discriminator in the handler
+    pub market: UncheckedAccount<'info>,
+
+    /// Trader's credential for this market (created if doesn't exist)
+    #[account(
+        init_if_needed,
+        payer = trader,
+        space = AllowlistCredential::SIZE,
+        seeds = [AllowlistCredential::SEED, market.key().as_ref(), trader.key().as_ref()],
+        bump
+    )]
+    pub credential: Account<'info, AllowlistCredential>,
+
+    /// CHECK: Sunspot verifier for the allowlist circuit (must match the market's)
+    pub verifier_program: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub trader: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Parameters for proving allowlist membership
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct VerifyAllowlistParams {
+    /// ZK proof of membership in the market's credential tree
+    /// 324 bytes - Groth16 proof verified via Sunspot on-chain
+    pub allowlist_proof: [u8; GROTH16_PROOF_SIZE],
+}
+
+pub fn verify_allowlist_handler(
+    ctx: Context<VerifyAllowlist>,
+    params: VerifyAllowlistParams,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let market = AllowlistMarket::load(&ctx.accounts.market.to_account_info())?;
+    let allowlist = *market.allowlist();
+
+    require!(allowlist.is_enabled(), ConfidexError::AllowlistNotEnabled);
+    require!(
+        ctx.accounts.verifier_program.key() == allowlist.verifier,
+        ConfidexError::InvalidAllowlistVerifier
+    );
+
+    let proof_valid = verify_allowlist_proof(
+        &ctx.accounts.verifier_program.to_account_info(),
+        &params.allowlist_proof,
+        &allowlist.root,
+        &ctx.accounts.trader.key(),
+    )?;
+
+    require!(proof_valid, ConfidexError::AllowlistProofFailed);
+
+    let credential = &mut ctx.accounts.credential;
+    credential.trader = ctx.accounts.trader.key();
+    credential.market = ctx.accounts.market.key();
+    credential.verified_allowlist_root = allowlist.root;
+    credential.verified_at = clock.unix_timestamp;
+    credential.valid_until = clock
+        .unix_timestamp
+        .saturating_add(ctx.accounts.blacklist_history.attestation_ttl_secs);
+    credential.bump = ctx.bumps.credential;
+
+    msg!(
+        "Allowlist credential verified: {} on market {} (valid until {})",
+        credential.trader,
+        credential.market,
+        credential.valid_until
+    );
+
+    Ok(())
+}
+
+/// Check a trader's allowlist credential for a market
+///
+/// Markets without an allowlist accept any trader. Otherwise the credential
+/// must exist and be valid for the market's current root.
+pub(crate) fn require_allowlist_credential(
+    allowlist: &AllowlistConfig,
+    credential: Option<&AllowlistCredential>,
+    now: i64,
+) -> Result<()> {
+    if !allowlist.is_enabled() {
+        return Ok(());
+    }
+
+    require!(
+        credential.is_some_and(|credential| credential.is_valid(allowlist, now)),
+        ConfidexError::AllowlistCredentialInvalid
+    );
+
+    Ok(())
+}
@@ -0,0 +1,194 @@
+use anchor_lang::prelude::*;
+
+use crate::cpi::arcium::{queue_check_order_amend, MxeCpiAccounts};
+use crate::error::ConfidexError;
+use crate::state::{ConfidentialOrder, ExchangeState, OrderStatus, Side, TradingPair, UserConfidentialBalance};
+use super::place_order::get_order_token_mint;
+
+/// Accounts for amending a resting order in place (maker only)
+///
+/// The new amount/price ciphertexts are staged on the same order account and
+/// only replace the old ones once amend_order_callback accepts them, so the
+/// order keeps its PDA, order_id and eligibility proof. The escrow is
+/// re-sized via MPC check_order_amend.
+#[derive(Accounts)]
+pub struct AmendOrder<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+        constraint = !exchange.paused @ ConfidexError::ExchangePaused
+    )]
+    pub exchange: Box<Account<'info, ExchangeState>>,
+
+    #[account(
+        seeds = [
+            TradingPair::SEED,
+            pair.base_mint.as_ref(),
+            pair.quote_mint.as_ref()
+        ],
+        bump = pair.bump,
+        constraint = pair.active @ ConfidexError::PairNotActive
+    )]
+    pub pair: Box<Account<'info, TradingPair>>,
+
+    /// Order to amend - set to PendingAmend until the MPC callback
+    #[account(
+        mut,
+        seeds = [
+            ConfidentialOrder::SEED,
+            order.maker.as_ref(),
+            &order.order_nonce
+        ],
+        bump = order.bump,
+        constraint = order.pair == pair.key() @ ConfidexError::InvalidOrder,
+        constraint = order.maker == maker.key() @ ConfidexError::OrderOwnerMismatch,
+        constraint = order.is_active() @ ConfidexError::OrderNotOpen,
+        constraint = !order.is_matching @ ConfidexError::OrderAlreadyMatching
+    )]
+    pub order: Box<Account<'info, ConfidentialOrder>>,
+
+    /// Maker's balance for the token escrowed by the order
+    /// For buy orders: quote token (USDC) balance
+    /// For sell orders: base token (SOL) balance
+    #[account(
+        seeds = [
+            UserConfidentialBalance::SEED,
+            maker.key().as_ref(),
+            get_order_token_mint(&pair, order.side).as_ref()
+        ],
+        bump = user_balance.bump,
+    )]
+    pub user_balance: Box<Account<'info, UserConfidentialBalance>>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Order maker (also pays the MPC fees)
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    // =========================================================================
+    // ARCIUM MXE ACCOUNTS (11 accounts via remaining_accounts)
+    // Same layout as match_orders (5: comp_def_account for check_order_amend)
+    // =========================================================================
+}
+
+/// Input parameters for amend_order instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AmendOrderParams {
+    /// Replacement order amount (encrypted)
+    pub encrypted_amount: [u8; 64],
+    /// Replacement limit price (encrypted)
+    pub encrypted_price: [u8; 64],
+    /// Ephemeral X25519 public key used to encrypt the replacement values
+    pub ephemeral_pubkey: [u8; 32],
+    /// Random seed for computation account derivation
+    pub computation_offset: u64,
+    /// Encryption nonce
+    pub nonce: u128,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AmendOrder<'info>>,
+    params: AmendOrderParams,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let order = &ctx.accounts.order;
+
+    require!(
+        !order.is_expired(clock.unix_timestamp),
+        ConfidexError::OrderExpired
+    );
+    // IOC/FOK orders never rest on the book, so there is nothing to amend
+    require!(!order.is_immediate(), ConfidexError::InvalidTimeInForce);
+    // Earlier fills settle against the order's encrypted price, so the price
+    // can't be swapped once the order has been (partially) filled
+    require!(!order.has_fills(), ConfidexError::OrderHasFills);
+
+    require!(
+        ctx.remaining_accounts.len() >= 11,
+        ConfidexError::InvalidAccountCount
+    );
+
+    let order_key = order.key();
+    let pair_key = ctx.accounts.pair.key();
+    let user_balance_key = ctx.accounts.user_balance.key();
+    // The current escrow is released before the new one is taken
+    let available = ctx.accounts.user_balance.get_balance()
+        .checked_add(order.escrow_remaining)
+        .ok_or(ConfidexError::ArithmeticOverflow)?;
+
+    // Store AccountInfo in local variables to avoid lifetime issues
+    let payer_info = ctx.accounts.maker.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+
+    let mxe_accounts = MxeCpiAccounts {
+        payer: &payer_info,
+        sign_pda_account: &ctx.remaining_accounts[0],
+        mxe_account: &ctx.remaining_accounts[1],
+        mempool_account: &ctx.remaining_accounts[2],
+        executing_pool: &ctx.remaining_accounts[3],
+        computation_account: &ctx.remaining_accounts[4],
+        comp_def_account: &ctx.remaining_accounts[5],
+        cluster_account: &ctx.remaining_accounts[6],
+        pool_account: &ctx.remaining_accounts[7],
+        clock_account: &ctx.remaining_accounts[8],
+        system_program: &system_program_info,
+        arcium_program: &ctx.remaining_accounts[9],
+        mxe_program: &ctx.remaining_accounts[10],
+    };
+
+    // Queue escrow re-check - result comes back via amend_order_callback
+    let queued = queue_check_order_amend(
+        mxe_accounts,
+        params.computation_offset,
+        &params.encrypted_amount,
+        &params.encrypted_price,
+        available,
+        order.side == Side::Buy,
+        &params.ephemeral_pubkey,
+        params.nonce,
+        &order_key,
+        &user_balance_key,
+        &pair_key,
+    )?;
+
+    let order = &mut ctx.accounts.order;
+
+    // Stage the new ciphertexts; the order can't be matched, cancelled or
+    // expired until the callback applies (or rejects) them, or the maker
+    // recovers it with recover_pending_order after the timeout
+    order.stage_amend(
+        params.encrypted_amount,
+        params.encrypted_price,
+        params.ephemeral_pubkey,
+    );
+    order.status = OrderStatus::PendingAmend;
+    order.pending_match_request = queued.request_id;
+    order.pending_queued_at = clock.unix_timestamp;
+
+    let coarse_time = ConfidentialOrder::coarse_timestamp(clock.unix_timestamp);
+
+    emit!(OrderAmendQueued {
+        order_id: order.order_id,
+        maker: order.maker,
+        pair: order.pair,
+        request_id: queued.request_id,
+        timestamp: coarse_time,
+    });
+
+    msg!("Order amend queued via MPC: {:?}", order.order_id);
+
+    Ok(())
+}
+
+#[event]
+pub struct OrderAmendQueued {
+    /// Hash-based order ID (unchanged by the amend)
+    pub order_id: [u8; 16],
+    pub maker: Pubkey,
+    pub pair: Pubkey,
+    /// Request ID for tracking MPC computation
+    pub request_id: [u8; 32],
+    /// Coarse timestamp (hour precision)
+    pub timestamp: i64,
+}
@@ -0,0 +1,157 @@
+//! Amend order callback from MXE
+//!
+//! This instruction receives the revealed amend check result and new escrow
+//! amount from the MXE's check_order_amend callback. It either swaps in the
+//! staged ciphertexts and re-sizes the order's escrow, or discards them and
+//! reactivates the original order with its old escrow.
+//!
+//! SECURITY: Only the MXE authority PDA can call this instruction.
+//! The escrow amount is computed via MPC and passed securely - it is
+//! NOT emitted in events to preserve privacy.
+
+use anchor_lang::prelude::*;
+
+use crate::error::ConfidexError;
+use crate::state::{ConfidentialOrder, OrderStatus, TradingPair, UserConfidentialBalance};
+use crate::cpi::arcium::ARCIUM_MXE_PROGRAM_ID;
+use super::place_order::get_order_token_mint;
+
+/// MXE authority PDA seed (must match MXE program)
+const MXE_AUTHORITY_SEED: &[u8] = b"mxe_authority";
+
+/// Accounts for MPC-based amend order callback
+#[derive(Accounts)]
+pub struct AmendOrderCallback<'info> {
+    /// MXE authority PDA - must be the signer
+    /// This ensures only the MXE callback can apply amends
+    #[account(
+        signer,
+        seeds = [MXE_AUTHORITY_SEED],
+        bump,
+        seeds::program = ARCIUM_MXE_PROGRAM_ID,
+    )]
+    pub mxe_authority: AccountInfo<'info>,
+
+    /// Amended order - reactivated with the new or the original values
+    #[account(
+        mut,
+        constraint = order.is_pending_amend() @ ConfidexError::OrderNotPendingAmend,
+        constraint = order.pair == pair.key() @ ConfidexError::InvalidOrder,
+    )]
+    pub order: Box<Account<'info, ConfidentialOrder>>,
+
+    /// Maker's balance for the token escrowed by the order
+    #[account(
+        mut,
+        seeds = [
+            UserConfidentialBalance::SEED,
+            order.maker.as_ref(),
+            get_order_token_mint(&pair, order.side).as_ref()
+        ],
+        bump = user_balance.bump,
+    )]
+    pub user_balance: Box<Account<'info, UserConfidentialBalance>>,
+
+    /// Trading pair account
+    #[account(
+        seeds = [
+            TradingPair::SEED,
+            pair.base_mint.as_ref(),
+            pair.quote_mint.as_ref()
+        ],
+        bump = pair.bump,
+    )]
+    pub pair: Box<Account<'info, TradingPair>>,
+}
+
+/// Apply or reject an order amend using the MPC escrow check result
+///
+/// Called by the MXE's check_order_amend_callback. escrow_amount is the
+/// escrow for the replacement; the released amount is the order's on-chain
+/// escrow_remaining, never an MPC output. The balance may have changed since
+/// queueing, so it is re-checked before escrowing. A rejected amend leaves
+/// the balance, escrow and ciphertexts exactly as they were.
+///
+/// The request_id (MXE computation account) must match the amend request
+/// recorded on the order.
+///
+/// IMPORTANT: This function does NOT emit escrow amounts in events
+/// to preserve privacy. Only order ID and timestamp are emitted.
+pub fn handler(
+    ctx: Context<AmendOrderCallback>,
+    request_id: [u8; 32],
+    sufficient: bool,
+    escrow_amount: u64,
+) -> Result<()> {
+    let order = &mut ctx.accounts.order;
+    let user_balance = &mut ctx.accounts.user_balance;
+    let clock = Clock::get()?;
+
+    require!(
+        order.pending_match_request == request_id,
+        ConfidexError::InvalidMpcRequest
+    );
+
+    // The old escrow is only released if the replacement is taken
+    let available = user_balance.get_balance()
+        .checked_add(order.escrow_remaining)
+        .ok_or(ConfidexError::ArithmeticOverflow)?;
+    let amended = sufficient && available >= escrow_amount;
+
+    order.status = OrderStatus::Active;
+    order.pending_match_request = [0u8; 32];
+    order.pending_queued_at = 0;
+
+    let coarse_time = ConfidentialOrder::coarse_timestamp(clock.unix_timestamp);
+
+    if amended {
+        user_balance.set_balance(available - escrow_amount);
+        order.escrow_remaining = escrow_amount;
+        order.apply_staged_amend();
+
+        msg!("Order amended after MPC escrow check");
+
+        emit!(OrderAmended {
+            order_id: order.order_id,
+            maker: order.maker,
+            pair: order.pair,
+            timestamp: coarse_time,
+        });
+    } else {
+        // The original ciphertexts and escrow were never touched
+        order.discard_staged_amend();
+
+        msg!("Order amend rejected: insufficient balance, original order kept");
+
+        emit!(OrderAmendRejected {
+            order_id: order.order_id,
+            maker: order.maker,
+            pair: order.pair,
+            timestamp: coarse_time,
+        });
+    }
+
+    Ok(())
+}
+
+/// Emitted when an amended order passes the MPC escrow check
+#[event]
+pub struct OrderAmended {
+    /// Hash-based order ID (unchanged by the amend)
+    pub order_id: [u8; 16],
+    pub maker: Pubkey,
+    pub pair: Pubkey,
+    /// Coarse timestamp (hour precision for privacy)
+    pub timestamp: i64,
+}
+
+/// Emitted when an amend fails the MPC escrow check (original order kept)
+#[event]
+pub struct OrderAmendRejected {
+    /// Hash-based order ID (no sequential correlation)
+    pub order_id: [u8; 16],
+    pub maker: Pubkey,
+    pub pair: Pubkey,
+    /// Coarse timestamp (hour precision for privacy)
+    pub timestamp: i64,
+}
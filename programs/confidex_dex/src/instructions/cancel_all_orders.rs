@@ -0,0 +1,157 @@
+use anchor_lang::prelude::*;
+
+use crate::cpi::arcium::MAX_BATCH_CANCEL_ORDERS;
+use crate::error::ConfidexError;
+use crate::instructions::cancel_orders_batch::{
+    queue_pair_cancels, require_unique_orders, CancelTarget,
+};
+use crate::state::{ExchangeState, TradingPair, UserConfidentialBalance};
+
+/// Number of Arcium MXE accounts at the front of remaining_accounts
+const MXE_ACCOUNT_COUNT: usize = 11;
+
+/// Accounts for cancelling a maker's orders across several pairs at once
+///
+/// Each pair is passed via remaining_accounts followed by its orders. Every
+/// pair's orders are split into chunks of MAX_BATCH_CANCEL_ORDERS and queued
+/// exactly like cancel_orders_batch, so each chunk is refunded into that
+/// pair's balances by cancel_orders_batch_callback.
+#[derive(Accounts)]
+pub struct CancelAllOrders<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump
+    )]
+    pub exchange: Box<Account<'info, ExchangeState>>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Order maker (also pays the MPC fees - one fee per chunk)
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    // =========================================================================
+    // REMAINING ACCOUNTS
+    //   0..10: Arcium MXE accounts (same order as cancel_orders_batch,
+    //          4: computation_account for the first chunk)
+    //   next chunk_count - 1: computation accounts for the remaining chunks
+    //   then, for each entry in pair_order_counts:
+    //          the TradingPair, followed by that many of its orders (writable)
+    // =========================================================================
+}
+
+/// Input parameters for cancel_all_orders instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CancelAllOrdersParams {
+    /// Number of orders passed after each pair, in remaining_accounts order
+    pub pair_order_counts: Vec<u8>,
+    /// Random seed for each chunk's computation account, pairs in order
+    /// (one per MAX_BATCH_CANCEL_ORDERS orders of each pair)
+    pub computation_offsets: Vec<u64>,
+    /// X25519 public key for output encryption (from ephemeral keypair)
+    pub pub_key: [u8; 32],
+    /// Encryption nonce (incremented per chunk)
+    pub nonce: u128,
+}
+
+/// Load a TradingPair passed via remaining_accounts
+fn load_pair(info: &AccountInfo) -> Result<TradingPair> {
+    require!(info.owner == &crate::ID, ConfidexError::InvalidAccountData);
+    let data = info.try_borrow_data()?;
+    TradingPair::try_deserialize(&mut &data[..])
+}
+
+/// Maker's balance PDA for a mint (refund target checked again by the callback)
+fn balance_address(maker: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[UserConfidentialBalance::SEED, maker.as_ref(), mint.as_ref()],
+        &crate::ID,
+    )
+    .0
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CancelAllOrders<'info>>,
+    params: CancelAllOrdersParams,
+) -> Result<()> {
+    let chunk_count = params.computation_offsets.len();
+    require!(
+        chunk_count > 0 && !params.pair_order_counts.is_empty(),
+        ConfidexError::InvalidAccountCount
+    );
+
+    let groups_start = MXE_ACCOUNT_COUNT + chunk_count - 1;
+    let group_accounts: usize = params
+        .pair_order_counts
+        .iter()
+        .map(|&count| 1 + count as usize)
+        .sum();
+    require!(
+        ctx.remaining_accounts.len() == groups_start + group_accounts,
+        ConfidexError::InvalidAccountCount
+    );
+
+    // Orders must be unique across every pair, not just within one
+    let mut all_orders = Vec::with_capacity(group_accounts);
+    let mut cursor = groups_start;
+    for &count in params.pair_order_counts.iter() {
+        require!(count > 0, ConfidexError::InvalidAccountCount);
+        all_orders.extend(&ctx.remaining_accounts[cursor + 1..cursor + 1 + count as usize]);
+        cursor += 1 + count as usize;
+    }
+    require_unique_orders(&all_orders)?;
+
+    let maker_key = ctx.accounts.maker.key();
+    let exchange_key = ctx.accounts.exchange.key();
+
+    // Store AccountInfo in local variables to avoid lifetime issues
+    let payer_info = ctx.accounts.maker.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+
+    let mut chunk = 0usize;
+    let mut cursor = groups_start;
+    for &count in params.pair_order_counts.iter() {
+        let count = count as usize;
+        let pair_info = &ctx.remaining_accounts[cursor];
+        let pair = load_pair(pair_info)?;
+
+        let pair_chunks = count.div_ceil(MAX_BATCH_CANCEL_ORDERS);
+        require!(
+            chunk + pair_chunks <= chunk_count,
+            ConfidexError::InvalidAccountCount
+        );
+
+        let target = CancelTarget {
+            pair: *pair_info.key,
+            user_base_balance: balance_address(&maker_key, &pair.base_mint),
+            user_quote_balance: balance_address(&maker_key, &pair.quote_mint),
+        };
+
+        chunk += queue_pair_cancels(
+            ctx.remaining_accounts,
+            &payer_info,
+            &system_program_info,
+            &ctx.remaining_accounts[cursor + 1..cursor + 1 + count],
+            &target,
+            &maker_key,
+            &exchange_key,
+            chunk,
+            &params.computation_offsets[chunk..chunk + pair_chunks],
+            &params.pub_key,
+            params.nonce,
+        )?;
+        cursor += 1 + count;
+    }
+
+    // Every computation offset must have been used
+    require!(chunk == chunk_count, ConfidexError::InvalidAccountCount);
+
+    msg!(
+        "Cancel-all queued via MPC: {} orders on {} pairs in {} chunks",
+        all_orders.len(),
+        params.pair_order_counts.len(),
+        chunk_count
+    );
+
+    Ok(())
+}
@@ -69,9 +69,9 @@ pub struct CancelOrder<'info> {
 ///
 /// # Migration
 ///
-/// Use `cancel_order_callback` instead, which receives MPC-calculated refund
-/// amount securely from the Arcium MXE. The callback flow:
-/// 1. User initiates cancel (triggers MPC calculate_refund)
+/// Use `initiate_cancel_order` instead, which queues the MPC refund
+/// calculation; the refund arrives securely via `cancel_order_callback`:
+/// 1. Maker calls initiate_cancel_order (order -> PendingCancel, queues MPC calculate_refund)
 /// 2. MPC computes refund_amount = encrypted_amount - encrypted_filled
 /// 3. MXE calls `cancel_order_callback` with decrypted refund_amount
 /// 4. Cancellation executes without reading plaintext from on-chain data
@@ -93,10 +93,10 @@ pub fn handler(_ctx: Context<CancelOrder>) -> Result<()> {
     //
     // Both broke privacy guarantees and have been removed.
     //
-    // Use cancel_order_callback instead, which receives MPC-calculated refund.
+    // Use initiate_cancel_order instead; cancel_order_callback receives the MPC-calculated refund.
     // ==========================================================================
 
-    msg!("ERROR: cancel_order is deprecated. Use cancel_order_callback with MPC-calculated refund.");
+    msg!("ERROR: cancel_order is deprecated. Use initiate_cancel_order (MPC-calculated refund).");
     Err(ConfidexError::FeatureDisabled.into())
 }
 
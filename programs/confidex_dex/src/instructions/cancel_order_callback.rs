@@ -31,9 +31,12 @@ pub struct CancelOrderCallback<'info> {
     pub mxe_authority: AccountInfo<'info>,
 
     /// Order to cancel - will be marked Inactive
+    /// Only PendingCancel orders (from initiate_cancel_order / expire_order)
+    /// can be refunded, so a refund queued out-of-band can't cancel an
+    /// active order
     #[account(
         mut,
-        constraint = order.is_active() @ ConfidexError::OrderNotOpen,
+        constraint = order.is_pending_cancel() @ ConfidexError::OrderNotOpen,
     )]
     pub order: Box<Account<'info, ConfidentialOrder>>,
 
@@ -85,13 +88,24 @@ pub struct CancelOrderCallback<'info> {
 /// Cancel order using decrypted refund amount from MPC
 ///
 /// This is called by the MXE's calculate_refund_callback with the
-/// revealed refund_amount. This value was computed via MPC as:
-/// refund_amount = encrypted_amount - encrypted_filled
+/// revealed refund_amount. This value was computed via MPC as the
+/// unfilled escrow: (amount - filled) for sells, or
+/// (amount - filled) * price / 1e9 for buys
+///
+/// The request_id (MXE computation account) must match the refund request
+/// recorded on the order, so only a refund computed from the order's own
+/// ciphertexts is accepted.
+///
+/// The MPC refund is debited from the order's plaintext escrow, so a refund
+/// the escrow can't cover is rejected and the order stays PendingCancel
+/// until the maker recovers it. Escrow left after the refund is price
+/// improvement from earlier fills and is released along with it.
 ///
 /// IMPORTANT: This function does NOT emit the refund_amount in events
 /// to preserve privacy. Only order ID and timestamp are emitted.
 pub fn handler(
     ctx: Context<CancelOrderCallback>,
+    request_id: [u8; 32],
     refund_amount: u64,
 ) -> Result<()> {
     let order = &mut ctx.accounts.order;
@@ -100,26 +114,40 @@ pub fn handler(
     let user_quote_balance = &mut ctx.accounts.user_quote_balance;
     let clock = Clock::get()?;
 
+    require!(
+        order.pending_match_request == request_id,
+        ConfidexError::InvalidMpcRequest
+    );
+
     // Perform the refund based on order side
-    if refund_amount > 0 {
+    let released = order.take_cancel_refund(refund_amount)?;
+    if released > 0 {
         match order.side {
             Side::Buy => {
                 // Buy orders escrow quote tokens (USDC)
                 // Refund quote tokens back to user
                 let current_balance = user_quote_balance.get_balance();
-                user_quote_balance.set_balance(current_balance + refund_amount);
+                user_quote_balance.set_balance(
+                    current_balance.checked_add(released)
+                        .ok_or(ConfidexError::ArithmeticOverflow)?
+                );
             }
             Side::Sell => {
                 // Sell orders escrow base tokens (SOL)
                 // Refund base tokens back to user
                 let current_balance = user_base_balance.get_balance();
-                user_base_balance.set_balance(current_balance + refund_amount);
+                user_base_balance.set_balance(
+                    current_balance.checked_add(released)
+                        .ok_or(ConfidexError::ArithmeticOverflow)?
+                );
             }
         }
     }
 
     // Mark order as Inactive
     order.status = OrderStatus::Inactive;
+    order.pending_match_request = [0u8; 32];
+    order.pending_queued_at = 0;
 
     // Decrement open order count
     pair.open_order_count = pair.open_order_count.checked_sub(1)
@@ -0,0 +1,311 @@
+use anchor_lang::prelude::*;
+
+use crate::cpi::arcium::{
+    queue_batch_calculate_refund, BatchRefundOrderData, MxeCpiAccounts, MAX_BATCH_CANCEL_ORDERS,
+};
+use crate::error::ConfidexError;
+use crate::state::{ConfidentialOrder, ExchangeState, OrderStatus, Side, TradingPair, UserConfidentialBalance};
+
+/// Number of Arcium MXE accounts at the front of remaining_accounts
+const MXE_ACCOUNT_COUNT: usize = 11;
+
+/// Accounts for cancelling any number of a maker's orders on one pair at once
+///
+/// Orders are variable-count, so they are passed via remaining_accounts
+/// after the MXE accounts. Orders are split into chunks of
+/// MAX_BATCH_CANCEL_ORDERS; each chunk is one MPC batch_calculate_refund and
+/// its escrows are released together by cancel_orders_batch_callback.
+#[derive(Accounts)]
+pub struct CancelOrdersBatch<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump
+    )]
+    pub exchange: Box<Account<'info, ExchangeState>>,
+
+    #[account(
+        seeds = [
+            TradingPair::SEED,
+            pair.base_mint.as_ref(),
+            pair.quote_mint.as_ref()
+        ],
+        bump = pair.bump
+    )]
+    pub pair: Box<Account<'info, TradingPair>>,
+
+    /// Maker's base token balance - refund target for sell orders
+    #[account(
+        seeds = [
+            UserConfidentialBalance::SEED,
+            maker.key().as_ref(),
+            pair.base_mint.as_ref()
+        ],
+        bump = user_base_balance.bump,
+    )]
+    pub user_base_balance: Box<Account<'info, UserConfidentialBalance>>,
+
+    /// Maker's quote token balance - refund target for buy orders
+    #[account(
+        seeds = [
+            UserConfidentialBalance::SEED,
+            maker.key().as_ref(),
+            pair.quote_mint.as_ref()
+        ],
+        bump = user_quote_balance.bump,
+    )]
+    pub user_quote_balance: Box<Account<'info, UserConfidentialBalance>>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Order maker (also pays the MPC fees - one fee per chunk)
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    // =========================================================================
+    // REMAINING ACCOUNTS
+    //   0..10: Arcium MXE accounts (same order as match_orders,
+    //          4: computation_account for the first chunk,
+    //          5: comp_def_account for batch_calculate_refund)
+    //   next chunk_count - 1: computation accounts for the remaining chunks
+    //   rest:  orders to cancel, writable
+    // =========================================================================
+}
+
+/// Input parameters for cancel_orders_batch instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CancelOrdersBatchParams {
+    /// Random seed for each chunk's computation account
+    /// (one per MAX_BATCH_CANCEL_ORDERS orders)
+    pub computation_offsets: Vec<u64>,
+    /// X25519 public key for output encryption (from ephemeral keypair)
+    pub pub_key: [u8; 32],
+    /// Encryption nonce (incremented per chunk)
+    pub nonce: u128,
+}
+
+/// Load and validate an order passed via remaining_accounts
+///
+/// Applies the same checks as the InitiateCancelOrder account constraints.
+fn load_cancellable_order(
+    info: &AccountInfo,
+    pair: &Pubkey,
+    maker: &Pubkey,
+) -> Result<ConfidentialOrder> {
+    require!(info.owner == &crate::ID, ConfidexError::InvalidOrder);
+    require!(info.is_writable, ConfidexError::InvalidOrder);
+
+    let order = {
+        let data = info.try_borrow_data()?;
+        ConfidentialOrder::try_deserialize(&mut &data[..])?
+    };
+
+    // Verify PDA derivation (seeds = [order, maker, order_nonce])
+    let expected = Pubkey::create_program_address(
+        &[
+            ConfidentialOrder::SEED,
+            order.maker.as_ref(),
+            &order.order_nonce,
+            &[order.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| error!(ConfidexError::InvalidOrder))?;
+    require!(expected == *info.key, ConfidexError::InvalidOrder);
+
+    require!(order.pair == *pair, ConfidexError::InvalidOrder);
+    require!(order.maker == *maker, ConfidexError::OrderOwnerMismatch);
+    require!(order.is_active(), ConfidexError::OrderNotOpen);
+    require!(!order.is_matching, ConfidexError::OrderAlreadyMatching);
+
+    Ok(order)
+}
+
+/// Refund targets for one pair's orders (keys passed through to the callback)
+pub(crate) struct CancelTarget {
+    pub pair: Pubkey,
+    pub user_base_balance: Pubkey,
+    pub user_quote_balance: Pubkey,
+}
+
+/// Reject batches that list the same order twice
+pub(crate) fn require_unique_orders(order_infos: &[&AccountInfo]) -> Result<()> {
+    for (i, a) in order_infos.iter().enumerate() {
+        for b in order_infos.iter().skip(i + 1) {
+            require!(a.key != b.key, ConfidexError::InvalidOrder);
+        }
+    }
+    Ok(())
+}
+
+/// Queue batch_calculate_refund for one pair's orders, one computation per
+/// MAX_BATCH_CANCEL_ORDERS orders
+///
+/// `first_chunk` is the index of this pair's first chunk in the instruction;
+/// chunk 0 uses the computation account at remaining_accounts[4], chunk c > 0
+/// the one at remaining_accounts[10 + c]. Returns the number of chunks queued.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn queue_pair_cancels<'info>(
+    remaining_accounts: &[AccountInfo<'info>],
+    payer_info: &AccountInfo<'info>,
+    system_program_info: &AccountInfo<'info>,
+    order_infos: &[AccountInfo<'info>],
+    target: &CancelTarget,
+    maker: &Pubkey,
+    exchange: &Pubkey,
+    first_chunk: usize,
+    computation_offsets: &[u64],
+    pub_key: &[u8; 32],
+    nonce: u128,
+) -> Result<usize> {
+    let clock = Clock::get()?;
+    let coarse_time = ConfidentialOrder::coarse_timestamp(clock.unix_timestamp);
+
+    let chunk_count = order_infos.len().div_ceil(MAX_BATCH_CANCEL_ORDERS);
+    require!(
+        chunk_count > 0 && computation_offsets.len() == chunk_count,
+        ConfidexError::InvalidAccountCount
+    );
+
+    for (c, chunk_infos) in order_infos.chunks(MAX_BATCH_CANCEL_ORDERS).enumerate() {
+        let chunk_index = first_chunk + c;
+
+        let mut orders = Vec::with_capacity(chunk_infos.len());
+        let mut refund_inputs = Vec::with_capacity(chunk_infos.len());
+
+        for info in chunk_infos.iter() {
+            let order = load_cancellable_order(info, &target.pair, maker)?;
+
+            refund_inputs.push(BatchRefundOrderData {
+                order: *info.key,
+                encrypted_amount: order.encrypted_amount,
+                encrypted_filled: order.encrypted_filled,
+                encrypted_price: order.encrypted_price,
+                is_buy: order.side == Side::Buy,
+                has_fills: order.has_fills(),
+            });
+            orders.push(order);
+        }
+
+        let computation_account = if chunk_index == 0 {
+            &remaining_accounts[4]
+        } else {
+            &remaining_accounts[MXE_ACCOUNT_COUNT - 1 + chunk_index]
+        };
+
+        let mxe_accounts = MxeCpiAccounts {
+            payer: payer_info,
+            sign_pda_account: &remaining_accounts[0],
+            mxe_account: &remaining_accounts[1],
+            mempool_account: &remaining_accounts[2],
+            executing_pool: &remaining_accounts[3],
+            computation_account,
+            comp_def_account: &remaining_accounts[5],
+            cluster_account: &remaining_accounts[6],
+            pool_account: &remaining_accounts[7],
+            clock_account: &remaining_accounts[8],
+            system_program: system_program_info,
+            arcium_program: &remaining_accounts[9],
+            mxe_program: &remaining_accounts[10],
+        };
+
+        // Result comes back via cancel_orders_batch_callback from MXE
+        let queued = queue_batch_calculate_refund(
+            mxe_accounts,
+            computation_offsets[c],
+            &refund_inputs,
+            pub_key,
+            nonce.wrapping_add(chunk_index as u128),
+            &target.user_base_balance,
+            &target.user_quote_balance,
+            &target.pair,
+            exchange,
+        )?;
+
+        // Block matching and duplicate cancels while the refund is in flight;
+        // the callback only accepts the result of this chunk's request, and
+        // recover_pending_order frees the orders if it never arrives
+        for (info, order) in chunk_infos.iter().zip(orders.iter_mut()) {
+            order.status = OrderStatus::PendingCancel;
+            order.pending_match_request = queued.request_id;
+            order.pending_queued_at = clock.unix_timestamp;
+
+            let mut data = info.try_borrow_mut_data()?;
+            let mut writer = &mut data[8..]; // Skip discriminator
+            order.serialize(&mut writer)?;
+        }
+
+        emit!(OrderCancelBatchQueued {
+            maker: *maker,
+            pair: target.pair,
+            order_count: orders.len() as u8,
+            request_id: queued.request_id,
+            timestamp: coarse_time,
+        });
+    }
+
+    Ok(chunk_count)
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CancelOrdersBatch<'info>>,
+    params: CancelOrdersBatchParams,
+) -> Result<()> {
+    let chunk_count = params.computation_offsets.len();
+    require!(chunk_count > 0, ConfidexError::InvalidAccountCount);
+
+    let orders_start = MXE_ACCOUNT_COUNT + chunk_count - 1;
+    require!(
+        ctx.remaining_accounts.len() > orders_start,
+        ConfidexError::InvalidAccountCount
+    );
+
+    let order_infos = &ctx.remaining_accounts[orders_start..];
+    require_unique_orders(&order_infos.iter().collect::<Vec<_>>())?;
+
+    let target = CancelTarget {
+        pair: ctx.accounts.pair.key(),
+        user_base_balance: ctx.accounts.user_base_balance.key(),
+        user_quote_balance: ctx.accounts.user_quote_balance.key(),
+    };
+    let maker_key = ctx.accounts.maker.key();
+    let exchange_key = ctx.accounts.exchange.key();
+
+    // Store AccountInfo in local variables to avoid lifetime issues
+    let payer_info = ctx.accounts.maker.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+
+    queue_pair_cancels(
+        ctx.remaining_accounts,
+        &payer_info,
+        &system_program_info,
+        order_infos,
+        &target,
+        &maker_key,
+        &exchange_key,
+        0,
+        &params.computation_offsets,
+        &params.pub_key,
+        params.nonce,
+    )?;
+
+    msg!(
+        "Batch cancel queued via MPC: {} orders in {} chunks",
+        order_infos.len(),
+        chunk_count
+    );
+
+    Ok(())
+}
+
+/// Emitted once per queued chunk
+#[event]
+pub struct OrderCancelBatchQueued {
+    pub maker: Pubkey,
+    pub pair: Pubkey,
+    /// Number of orders in the chunk
+    pub order_count: u8,
+    /// Request ID for tracking MPC computation
+    pub request_id: [u8; 32],
+    /// Coarse timestamp (hour precision)
+    pub timestamp: i64,
+}
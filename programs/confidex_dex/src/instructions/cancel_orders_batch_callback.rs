@@ -0,0 +1,175 @@
+//! Batch cancel callback from MXE
+//!
+//! This instruction receives the decrypted refund amounts from the MXE's
+//! batch_calculate_refund callback and cancels every order queued by
+//! cancel_orders_batch in one instruction, so the batch either applies
+//! fully or not at all.
+//!
+//! SECURITY: Only the MXE authority PDA can call this instruction.
+//! The refund amounts are computed via MPC and passed securely - they are
+//! NOT emitted in events to preserve privacy.
+
+use anchor_lang::prelude::*;
+
+use crate::error::ConfidexError;
+use crate::state::{ConfidentialOrder, ExchangeState, OrderStatus, Side, TradingPair, UserConfidentialBalance};
+use crate::cpi::arcium::{ARCIUM_MXE_PROGRAM_ID, MAX_BATCH_CANCEL_ORDERS};
+use super::cancel_order_callback::OrderCancelledPrivate;
+
+/// MXE authority PDA seed (must match MXE program)
+const MXE_AUTHORITY_SEED: &[u8] = b"mxe_authority";
+
+/// Accounts for MPC-based batch cancel callback
+/// MXE passes the orders via remaining_accounts in batch order
+#[derive(Accounts)]
+pub struct CancelOrdersBatchCallback<'info> {
+    /// MXE authority PDA - must be the signer
+    /// This ensures only the MXE callback can invoke cancellation
+    #[account(
+        signer,
+        seeds = [MXE_AUTHORITY_SEED],
+        bump,
+        seeds::program = ARCIUM_MXE_PROGRAM_ID,
+    )]
+    pub mxe_authority: AccountInfo<'info>,
+
+    /// Maker's base token balance - for sell order refunds
+    #[account(
+        mut,
+        seeds = [
+            UserConfidentialBalance::SEED,
+            user_base_balance.owner.as_ref(),
+            pair.base_mint.as_ref()
+        ],
+        bump = user_base_balance.bump,
+    )]
+    pub user_base_balance: Box<Account<'info, UserConfidentialBalance>>,
+
+    /// Maker's quote token balance - for buy order refunds
+    #[account(
+        mut,
+        seeds = [
+            UserConfidentialBalance::SEED,
+            user_base_balance.owner.as_ref(),
+            pair.quote_mint.as_ref()
+        ],
+        bump = user_quote_balance.bump,
+    )]
+    pub user_quote_balance: Box<Account<'info, UserConfidentialBalance>>,
+
+    /// Trading pair account
+    #[account(
+        mut,
+        seeds = [
+            TradingPair::SEED,
+            pair.base_mint.as_ref(),
+            pair.quote_mint.as_ref()
+        ],
+        bump = pair.bump,
+    )]
+    pub pair: Box<Account<'info, TradingPair>>,
+
+    /// Exchange state
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+    )]
+    pub exchange: Box<Account<'info, ExchangeState>>,
+    // Orders are passed via remaining_accounts to allow variable count
+}
+
+/// Cancel a batch of orders using decrypted refund amounts from MPC
+///
+/// Each order must be PendingCancel for this request_id (set by
+/// cancel_orders_batch), so only refunds computed from the orders' own
+/// ciphertexts are accepted. refund_amounts[i] belongs to the i-th order
+/// in remaining_accounts.
+///
+/// As in cancel_order_callback, each MPC refund is debited from its order's
+/// plaintext escrow (with any leftover price improvement), and a refund the
+/// escrow can't cover rejects the whole batch.
+///
+/// IMPORTANT: This function does NOT emit refund amounts in events
+/// to preserve privacy. Only order IDs and timestamps are emitted.
+pub fn handler(
+    ctx: Context<CancelOrdersBatchCallback>,
+    request_id: [u8; 32],
+    refund_amounts: Vec<u64>,
+) -> Result<()> {
+    let pair = &mut ctx.accounts.pair;
+    let user_base_balance = &mut ctx.accounts.user_base_balance;
+    let user_quote_balance = &mut ctx.accounts.user_quote_balance;
+    let clock = Clock::get()?;
+
+    require!(
+        !refund_amounts.is_empty() && refund_amounts.len() <= MAX_BATCH_CANCEL_ORDERS,
+        ConfidexError::InvalidMpcRequest
+    );
+    require!(
+        ctx.remaining_accounts.len() == refund_amounts.len(),
+        ConfidexError::InvalidAccountCount
+    );
+
+    let pair_key = pair.key();
+    let maker = user_base_balance.owner;
+    let coarse_time = ConfidentialOrder::coarse_timestamp(clock.unix_timestamp);
+
+    for (info, &refund_amount) in ctx.remaining_accounts.iter().zip(refund_amounts.iter()) {
+        require!(info.owner == &crate::ID, ConfidexError::InvalidOrder);
+
+        let mut data = info.try_borrow_mut_data()?;
+        let mut order = ConfidentialOrder::try_deserialize(&mut &data[..])?;
+
+        // Same checks as the CancelOrderCallback account constraints
+        require!(order.is_pending_cancel(), ConfidexError::OrderNotOpen);
+        require!(order.pair == pair_key, ConfidexError::InvalidOrder);
+        require!(order.maker == maker, ConfidexError::OrderOwnerMismatch);
+        require!(
+            order.pending_match_request == request_id,
+            ConfidexError::InvalidMpcRequest
+        );
+
+        // Refund the escrowed token for the order side
+        let released = order.take_cancel_refund(refund_amount)?;
+        if released > 0 {
+            match order.side {
+                Side::Buy => {
+                    let current_balance = user_quote_balance.get_balance();
+                    user_quote_balance.set_balance(
+                        current_balance.checked_add(released)
+                            .ok_or(ConfidexError::ArithmeticOverflow)?
+                    );
+                }
+                Side::Sell => {
+                    let current_balance = user_base_balance.get_balance();
+                    user_base_balance.set_balance(
+                        current_balance.checked_add(released)
+                            .ok_or(ConfidexError::ArithmeticOverflow)?
+                    );
+                }
+            }
+        }
+
+        order.status = OrderStatus::Inactive;
+        order.pending_match_request = [0u8; 32];
+        order.pending_queued_at = 0;
+
+        let mut writer = &mut data[8..]; // Skip discriminator
+        order.serialize(&mut writer)?;
+
+        pair.open_order_count = pair.open_order_count.checked_sub(1)
+            .ok_or(ConfidexError::ArithmeticOverflow)?;
+
+        // Emit minimal cancellation event - NO AMOUNTS for privacy
+        emit!(OrderCancelledPrivate {
+            order_id: order.order_id,
+            maker: order.maker,
+            pair: order.pair,
+            timestamp: coarse_time,
+        });
+    }
+
+    msg!("MPC batch cancel complete: {} orders", refund_amounts.len());
+
+    Ok(())
+}
@@ -2,44 +2,99 @@ use anchor_lang::prelude::*;
 
 use crate::cpi::arcium::{
     queue_batch_liquidation_check, BatchLiquidationPositionData, MxeCpiAccounts,
-    ARCIUM_MXE_PROGRAM_ID, ARCIUM_PROGRAM_ID,
+    ARCIUM_MXE_PROGRAM_ID,
 };
 use crate::error::ConfidexError;
-use crate::oracle::get_sol_usd_price_for_liquidation;
+use crate::oracle::get_market_price;
 use crate::state::{
-    ConfidentialPosition, LiquidationBatchRequest, PerpetualMarket,
+    ConfidentialPosition, LiquidationBatchEntry, LiquidationBatchRequest, PerpetualMarket,
 };
 
-/// Maximum positions per batch check
-pub const MAX_BATCH_SIZE: usize = 10;
+/// MXE authority PDA seed (must match MXE program)
+const MXE_AUTHORITY_SEED: &[u8] = b"mxe_authority";
+
+// ============================================================================
+// BATCH LIQUIDATION CHECK (async MPC)
+// ============================================================================
+//
+// 1. create_liquidation_batch allocates a batch of up to `capacity` positions
+// 2. check_liquidation_batch appends positions (from any number of markets)
+//    and queues one MPC batch_liquidation_check per CHUNK_SIZE positions,
+//    each position compared against its own market's mark price
+// 3. liquidation_batch_callback records each chunk's results on the batch;
+//    liquidate_position reads the per-position entry
+
+/// Accounts for allocating a liquidation batch
+#[derive(Accounts)]
+#[instruction(batch_id: u64, capacity: u8)]
+pub struct CreateLiquidationBatch<'info> {
+    #[account(
+        init,
+        payer = requester,
+        space = LiquidationBatchRequest::space(capacity as usize),
+        seeds = [
+            LiquidationBatchRequest::SEED,
+            requester.key().as_ref(),
+            &batch_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub batch_request: Box<Account<'info, LiquidationBatchRequest>>,
+
+    #[account(mut)]
+    pub requester: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_handler(
+    ctx: Context<CreateLiquidationBatch>,
+    batch_id: u64,
+    capacity: u8,
+) -> Result<()> {
+    require!(
+        capacity > 0 && capacity as usize <= LiquidationBatchRequest::MAX_POSITIONS,
+        ConfidexError::InvalidBatchCapacity
+    );
+
+    let batch_request = &mut ctx.accounts.batch_request;
+    batch_request.requester = ctx.accounts.requester.key();
+    batch_request.batch_id = batch_id;
+    batch_request.capacity = capacity;
+    batch_request.entries = Vec::new();
+    batch_request.chunk_requests = Vec::new();
+    batch_request.chunks_completed = 0;
+    batch_request.completed = false;
+    batch_request.created_at = Clock::get()?.unix_timestamp;
+    batch_request.bump = ctx.bumps.batch_request;
+
+    msg!("Liquidation batch {} created (capacity {})", batch_id, capacity);
+
+    Ok(())
+}
 
 /// Uses Box<Account<>> for large account types to reduce stack usage.
 /// MXE accounts are passed via remaining_accounts to keep stack under 4KB.
 ///
 /// remaining_accounts layout:
-///   0..position_count: Position accounts to check
-///   position_count..position_count+11: MXE accounts (same order as MatchOrders)
+///   0..3*market_count: (perp_market, oracle, secondary_oracle) per market;
+///                      pass the program ID as secondary_oracle when unused
+///   next position_count: Position accounts to check
+///   next 11: MXE accounts (same order as MatchOrders), computation_account
+///            for the first chunk
+///   next chunk_count - 1: computation accounts for the remaining chunks
 #[derive(Accounts)]
 #[instruction(params: CheckLiquidationBatchParams)]
 pub struct CheckLiquidationBatch<'info> {
     #[account(
-        seeds = [PerpetualMarket::SEED, perp_market.underlying_mint.as_ref()],
-        bump = perp_market.bump,
-    )]
-    pub perp_market: Box<Account<'info, PerpetualMarket>>,
-
-    /// CHECK: Pyth oracle for current mark price
-    #[account(
-        constraint = oracle.key() == perp_market.oracle_price_feed @ ConfidexError::InvalidOraclePrice
-    )]
-    pub oracle: AccountInfo<'info>,
-
-    #[account(
-        init,
-        payer = requester,
-        space = LiquidationBatchRequest::SIZE,
-        seeds = [LiquidationBatchRequest::SEED, &Clock::get()?.slot.to_le_bytes()],
-        bump
+        mut,
+        seeds = [
+            LiquidationBatchRequest::SEED,
+            requester.key().as_ref(),
+            &batch_request.batch_id.to_le_bytes()
+        ],
+        bump = batch_request.bump,
+        constraint = batch_request.requester == requester.key() @ ConfidexError::Unauthorized
     )]
     pub batch_request: Box<Account<'info, LiquidationBatchRequest>>,
 
@@ -50,7 +105,7 @@ pub struct CheckLiquidationBatch<'info> {
 
     // =========================================================================
     // ARCIUM MXE ACCOUNTS (11 accounts via remaining_accounts to reduce stack)
-    // Order in remaining_accounts[position_count..position_count+11]:
+    // Order in remaining_accounts[mxe_start..mxe_start+11]:
     //   0: sign_pda_account (mut)
     //   1: mxe_account (mut)
     //   2: mempool_account (mut)
@@ -68,13 +123,15 @@ pub struct CheckLiquidationBatch<'info> {
 /// Parameters for batch liquidation check
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct CheckLiquidationBatchParams {
-    /// Position pubkeys to check (up to MAX_BATCH_SIZE)
+    /// Position pubkeys to check (up to the batch's remaining capacity)
     pub position_keys: Vec<Pubkey>,
-    /// Random seed for computation account derivation
-    pub computation_offset: u64,
+    /// Number of market account triples at the start of remaining_accounts
+    pub market_count: u8,
+    /// Random seed for each chunk's computation account (one per CHUNK_SIZE positions)
+    pub computation_offsets: Vec<u64>,
     /// X25519 public key for output encryption
     pub pub_key: [u8; 32],
-    /// Encryption nonce
+    /// Encryption nonce (incremented per chunk)
     pub nonce: u128,
 }
 
@@ -83,39 +140,78 @@ pub fn handler<'info>(
     params: CheckLiquidationBatchParams,
 ) -> Result<()> {
     let clock = Clock::get()?;
+    let position_count = params.position_keys.len();
+    let market_count = params.market_count as usize;
+    let chunk_count = position_count.div_ceil(LiquidationBatchRequest::CHUNK_SIZE);
 
-    // Validate batch size
+    require!(position_count > 0, ConfidexError::InvalidAmount);
     require!(
-        !params.position_keys.is_empty() && params.position_keys.len() <= MAX_BATCH_SIZE,
-        ConfidexError::InvalidAmount
+        market_count > 0 && market_count <= position_count,
+        ConfidexError::InvalidAccountCount
+    );
+    require!(
+        params.computation_offsets.len() == chunk_count,
+        ConfidexError::InvalidAccountCount
     );
 
-    // Fetch current mark price from oracle with strict validation for liquidations
-    // Enforces: price < 60s old on mainnet, confidence < 1%
-    let mark_price = get_sol_usd_price_for_liquidation(&ctx.accounts.oracle)?;
+    let batch_request = &ctx.accounts.batch_request;
+    require!(
+        batch_request.entries.len() + position_count <= batch_request.capacity as usize,
+        ConfidexError::LiquidationBatchFull
+    );
+    require!(
+        batch_request.chunk_requests.len() + chunk_count <= LiquidationBatchRequest::MAX_CHUNKS,
+        ConfidexError::LiquidationBatchFull
+    );
+    let first_chunk = batch_request.chunk_requests.len();
 
-    // Remaining accounts layout:
-    // [0..position_count]: Position accounts to check
-    // [position_count..position_count+11]: MXE accounts
-    let remaining_accounts = &ctx.remaining_accounts;
-    let position_count = params.position_keys.len();
+    let remaining_accounts = ctx.remaining_accounts;
+    let positions_start = market_count * 3;
+    let mxe_start = positions_start + position_count;
     require!(
-        remaining_accounts.len() >= position_count + 11,
+        remaining_accounts.len() >= mxe_start + 11 + chunk_count - 1,
         ConfidexError::InvalidAccountCount
     );
 
+    // Fetch each market's mark price from its oracle with strict validation for liquidations
+    // Enforces: price < 60s old on mainnet, confidence < 1%
+    let mut market_prices: Vec<(Pubkey, u64)> = Vec::with_capacity(market_count);
+    for i in 0..market_count {
+        let market_info = &remaining_accounts[i * 3];
+        let perp_market: Account<PerpetualMarket> = Account::try_from(market_info)?;
+
+        let secondary_info = &remaining_accounts[i * 3 + 2];
+        let secondary_oracle = if secondary_info.key() == crate::ID {
+            None
+        } else {
+            Some(secondary_info)
+        };
+
+        let mark_price = get_market_price(
+            &perp_market,
+            &remaining_accounts[i * 3 + 1],
+            secondary_oracle,
+        )?;
+        market_prices.push((market_info.key(), mark_price));
+    }
+
     let mut position_data: Vec<BatchLiquidationPositionData> = Vec::with_capacity(position_count);
-    let mut positions_array = [[0u8; 32]; 10];
+    let mut entries: Vec<LiquidationBatchEntry> = Vec::with_capacity(position_count);
 
     for (i, position_key) in params.position_keys.iter().enumerate() {
+        let position_account = &remaining_accounts[positions_start + i];
+
         // Verify the account matches the expected key
         require!(
-            remaining_accounts[i].key() == *position_key,
+            position_account.key() == *position_key,
             ConfidexError::OrderOwnerMismatch
         );
+        require!(
+            position_account.owner == &crate::ID,
+            ConfidexError::InvalidOwner
+        );
 
         // Deserialize position account
-        let position_account = &remaining_accounts[i];
         let position_data_ref = position_account.try_borrow_data()?;
 
         // Skip discriminator (8 bytes)
@@ -130,6 +226,18 @@ pub fn handler<'info>(
         // Then: 64 encrypted_size + 64 encrypted_entry_price + 64 encrypted_collateral + 64 encrypted_realized_pnl
         // = 256 bytes of encrypted core data
         // Then: 64 encrypted_liq_below + 64 encrypted_liq_above
+        let market_offset = 8 + 32;
+        let mut market_bytes = [0u8; 32];
+        market_bytes.copy_from_slice(&position_data_ref[market_offset..market_offset + 32]);
+        let market = Pubkey::new_from_array(market_bytes);
+
+        // Each position is checked against its own market's mark price
+        let mark_price = market_prices
+            .iter()
+            .find(|(key, _)| *key == market)
+            .map(|(_, price)| *price)
+            .ok_or(ConfidexError::BatchMarketNotIncluded)?;
+
         let side_offset = 8 + 32 + 32 + 16 + 8 + 8;
         let side_byte = position_data_ref[side_offset];
         let is_long = side_byte == 0; // PositionSide::Long is default (0)
@@ -153,62 +261,73 @@ pub fn handler<'info>(
         position_data.push(BatchLiquidationPositionData {
             encrypted_liq_threshold: encrypted_threshold,
             is_long,
+            mark_price,
         });
 
-        positions_array[i] = position_key.to_bytes();
+        entries.push(LiquidationBatchEntry {
+            position: *position_key,
+            market,
+            mark_price,
+            liquidatable: false,
+            chunk: (first_chunk + i / LiquidationBatchRequest::CHUNK_SIZE) as u8,
+            queued_at: clock.unix_timestamp,
+        });
     }
 
-    // Initialize batch request account
-    let batch_request = &mut ctx.accounts.batch_request;
-    batch_request.market = ctx.accounts.perp_market.key();
-    batch_request.mark_price = mark_price;
-    batch_request.position_count = position_count as u8;
-    batch_request.positions = positions_array;
-    batch_request.results = [false; 10];
-    batch_request.completed = false;
-    batch_request.created_at = clock.unix_timestamp;
-    batch_request.bump = ctx.bumps.batch_request;
-
-    // Extract MXE accounts from remaining_accounts (after position accounts)
     // Store AccountInfo in local variables to avoid lifetime issues
-    let mxe_start = position_count;
+    let batch_key = ctx.accounts.batch_request.key();
     let payer_info = ctx.accounts.requester.to_account_info();
     let system_program_info = ctx.accounts.system_program.to_account_info();
 
-    let mxe_accounts = MxeCpiAccounts {
-        payer: &payer_info,
-        sign_pda_account: &remaining_accounts[mxe_start],
-        mxe_account: &remaining_accounts[mxe_start + 1],
-        mempool_account: &remaining_accounts[mxe_start + 2],
-        executing_pool: &remaining_accounts[mxe_start + 3],
-        computation_account: &remaining_accounts[mxe_start + 4],
-        comp_def_account: &remaining_accounts[mxe_start + 5],
-        cluster_account: &remaining_accounts[mxe_start + 6],
-        pool_account: &remaining_accounts[mxe_start + 7],
-        clock_account: &remaining_accounts[mxe_start + 8],
-        system_program: &system_program_info,
-        arcium_program: &remaining_accounts[mxe_start + 9],
-        mxe_program: &remaining_accounts[mxe_start + 10],
-    };
-
-    // Queue MPC batch liquidation check
-    let computation = queue_batch_liquidation_check(
-        mxe_accounts,
-        params.computation_offset,
-        &position_data,
-        mark_price,
-        &params.pub_key,
-        params.nonce,
-    )?;
-
-    // Store request ID
-    batch_request.request_id = computation.request_id;
+    // Queue one MPC batch liquidation check per chunk
+    let mut chunk_requests: Vec<[u8; 32]> = Vec::with_capacity(chunk_count);
+    for (c, chunk) in position_data.chunks(LiquidationBatchRequest::CHUNK_SIZE).enumerate() {
+        let computation_account = if c == 0 {
+            &remaining_accounts[mxe_start + 4]
+        } else {
+            &remaining_accounts[mxe_start + 10 + c]
+        };
+
+        let mxe_accounts = MxeCpiAccounts {
+            payer: &payer_info,
+            sign_pda_account: &remaining_accounts[mxe_start],
+            mxe_account: &remaining_accounts[mxe_start + 1],
+            mempool_account: &remaining_accounts[mxe_start + 2],
+            executing_pool: &remaining_accounts[mxe_start + 3],
+            computation_account,
+            comp_def_account: &remaining_accounts[mxe_start + 5],
+            cluster_account: &remaining_accounts[mxe_start + 6],
+            pool_account: &remaining_accounts[mxe_start + 7],
+            clock_account: &remaining_accounts[mxe_start + 8],
+            system_program: &system_program_info,
+            arcium_program: &remaining_accounts[mxe_start + 9],
+            mxe_program: &remaining_accounts[mxe_start + 10],
+        };
+
+        let computation = queue_batch_liquidation_check(
+            mxe_accounts,
+            params.computation_offsets[c],
+            chunk,
+            &params.pub_key,
+            params.nonce.wrapping_add(c as u128),
+            &batch_key,
+        )?;
+
+        chunk_requests.push(computation.request_id);
+    }
+
+    let batch_request = &mut ctx.accounts.batch_request;
+    batch_request.entries.extend(entries);
+    batch_request.chunk_requests.extend(chunk_requests);
+    batch_request.completed = false;
 
     msg!(
-        "Queued batch liquidation check for {} positions at mark price {}, computation_offset={}",
+        "Queued batch liquidation check for {} positions across {} markets in {} chunks ({}/{} used)",
         position_count,
-        mark_price,
-        params.computation_offset
+        market_count,
+        chunk_count,
+        batch_request.entries.len(),
+        batch_request.capacity
     );
 
     Ok(())
@@ -217,26 +336,34 @@ pub fn handler<'info>(
 /// Callback handler for batch liquidation results from MPC
 #[derive(Accounts)]
 pub struct LiquidationBatchCallback<'info> {
+    /// MXE authority PDA - must be the signer
+    /// This ensures only the MXE callback can record results
     #[account(
-        mut,
-        seeds = [LiquidationBatchRequest::SEED, batch_request.request_id.as_ref()],
-        bump = batch_request.bump,
+        signer,
+        seeds = [MXE_AUTHORITY_SEED],
+        bump,
+        seeds::program = ARCIUM_MXE_PROGRAM_ID,
     )]
-    pub batch_request: Account<'info, LiquidationBatchRequest>,
+    pub mxe_authority: AccountInfo<'info>,
 
-    /// CHECK: Must be the Arcium MXE program
     #[account(
-        constraint = mxe_program.key() == crate::cpi::arcium::ARCIUM_MXE_PROGRAM_ID @ ConfidexError::Unauthorized
+        mut,
+        seeds = [
+            LiquidationBatchRequest::SEED,
+            batch_request.requester.as_ref(),
+            &batch_request.batch_id.to_le_bytes()
+        ],
+        bump = batch_request.bump,
     )]
-    pub mxe_program: Signer<'info>,
+    pub batch_request: Box<Account<'info, LiquidationBatchRequest>>,
 }
 
 /// Callback params from MPC
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct LiquidationBatchCallbackParams {
-    /// Request ID matching the batch
+    /// Request ID of the chunk (MXE computation account)
     pub request_id: [u8; 32],
-    /// Results: true = position is liquidatable
+    /// Results for the chunk's positions, in order: true = position is liquidatable
     pub results: Vec<bool>,
 }
 
@@ -246,46 +373,63 @@ pub fn callback_handler(
 ) -> Result<()> {
     let batch_request = &mut ctx.accounts.batch_request;
 
-    // Verify request ID matches
+    // Find the chunk this computation was queued for
+    let chunk = batch_request
+        .chunk_index(&params.request_id)
+        .ok_or(ConfidexError::InvalidMpcRequest)?;
+
+    // Verify we haven't already processed this chunk
+    let chunk_bit = 1u8 << chunk;
     require!(
-        batch_request.request_id == params.request_id,
-        ConfidexError::Unauthorized
+        batch_request.chunks_completed & chunk_bit == 0,
+        ConfidexError::BatchChunkAlreadyCompleted
     );
 
-    // Verify we haven't already processed this callback
+    // Results that arrive too long after queueing were computed against a
+    // mark price that no longer applies
+    let now = Clock::get()?.unix_timestamp;
     require!(
-        !batch_request.completed,
-        ConfidexError::InvalidAmount
+        batch_request
+            .entries
+            .iter()
+            .filter(|e| e.chunk as usize == chunk)
+            .all(|e| e.is_fresh(now)),
+        ConfidexError::LiquidationResultExpired
     );
 
-    // Copy results
-    for (i, result) in params.results.iter().enumerate() {
-        if i < 10 {
-            batch_request.results[i] = *result;
+    let mut checked = 0usize;
+    let mut liquidatable_count = 0usize;
+    for (i, entry) in batch_request.entries.iter_mut().enumerate() {
+        if entry.chunk as usize != chunk {
+            continue;
         }
-    }
-
-    batch_request.completed = true;
 
-    let liquidatable_count = params.results.iter().filter(|&&r| r).count();
-    msg!(
-        "Batch liquidation check complete: {}/{} positions liquidatable",
-        liquidatable_count,
-        params.results.len()
-    );
+        entry.liquidatable = params.results.get(checked).copied().unwrap_or(false);
+        checked += 1;
 
-    // Emit events for liquidatable positions (for crank to pick up)
-    for (i, &is_liquidatable) in params.results.iter().enumerate() {
-        if is_liquidatable && i < batch_request.position_count as usize {
+        // Emit events for liquidatable positions (for crank to pick up)
+        if entry.liquidatable {
+            liquidatable_count += 1;
             emit!(PositionLiquidatable {
-                position_pubkey: Pubkey::new_from_array(batch_request.positions[i]),
-                market: batch_request.market,
-                mark_price: batch_request.mark_price,
+                position_pubkey: entry.position,
+                market: entry.market,
+                mark_price: entry.mark_price,
                 batch_index: i as u8,
             });
         }
     }
 
+    batch_request.chunks_completed |= chunk_bit;
+    batch_request.completed = batch_request.all_chunks_completed();
+
+    msg!(
+        "Batch liquidation chunk {} complete: {}/{} positions liquidatable (batch complete: {})",
+        chunk,
+        liquidatable_count,
+        checked,
+        batch_request.completed
+    );
+
     Ok(())
 }
 
@@ -0,0 +1,183 @@
+use anchor_lang::prelude::*;
+
+use crate::cpi::arcium::{queue_calculate_refund, MxeCpiAccounts};
+use crate::error::ConfidexError;
+use crate::state::{ConfidentialOrder, ExchangeState, OrderStatus, Side, TradingPair, UserConfidentialBalance};
+
+/// Accounts for expiring a stale order (permissionless)
+///
+/// Anyone can expire an order once its good-till-time has passed (or an
+/// IOC/FOK order after its single match attempt). The refund is computed via
+/// MPC calculate_refund and released by cancel_order_callback.
+#[derive(Accounts)]
+pub struct ExpireOrder<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump
+    )]
+    pub exchange: Box<Account<'info, ExchangeState>>,
+
+    #[account(
+        seeds = [
+            TradingPair::SEED,
+            pair.base_mint.as_ref(),
+            pair.quote_mint.as_ref()
+        ],
+        bump = pair.bump
+    )]
+    pub pair: Box<Account<'info, TradingPair>>,
+
+    /// Expired order - set to PendingCancel until the refund callback
+    #[account(
+        mut,
+        seeds = [
+            ConfidentialOrder::SEED,
+            order.maker.as_ref(),
+            &order.order_nonce
+        ],
+        bump = order.bump,
+        constraint = order.pair == pair.key() @ ConfidexError::InvalidOrder,
+        constraint = order.is_active() @ ConfidexError::OrderNotOpen,
+        constraint = !order.is_matching @ ConfidexError::OrderAlreadyMatching
+    )]
+    pub order: Box<Account<'info, ConfidentialOrder>>,
+
+    /// Maker's base token balance - refund target for sell orders
+    #[account(
+        seeds = [
+            UserConfidentialBalance::SEED,
+            order.maker.as_ref(),
+            pair.base_mint.as_ref()
+        ],
+        bump = user_base_balance.bump,
+    )]
+    pub user_base_balance: Box<Account<'info, UserConfidentialBalance>>,
+
+    /// Maker's quote token balance - refund target for buy orders
+    #[account(
+        seeds = [
+            UserConfidentialBalance::SEED,
+            order.maker.as_ref(),
+            pair.quote_mint.as_ref()
+        ],
+        bump = user_quote_balance.bump,
+    )]
+    pub user_quote_balance: Box<Account<'info, UserConfidentialBalance>>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Crank operator (payer for MPC fees)
+    #[account(mut)]
+    pub crank: Signer<'info>,
+
+    // =========================================================================
+    // ARCIUM MXE ACCOUNTS (11 accounts via remaining_accounts)
+    // Same layout as match_orders (5: comp_def_account for calculate_refund)
+    // =========================================================================
+}
+
+/// Input parameters for expire_order instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ExpireOrderParams {
+    /// Random seed for computation account derivation
+    pub computation_offset: u64,
+    /// X25519 public key for output encryption (from ephemeral keypair)
+    pub pub_key: [u8; 32],
+    /// Encryption nonce
+    pub nonce: u128,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExpireOrder<'info>>,
+    params: ExpireOrderParams,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(
+        ctx.accounts.order.is_expired(clock.unix_timestamp),
+        ConfidexError::OrderNotExpired
+    );
+
+    require!(
+        ctx.remaining_accounts.len() >= 11,
+        ConfidexError::InvalidAccountCount
+    );
+
+    let order_key = ctx.accounts.order.key();
+    let exchange_key = ctx.accounts.exchange.key();
+    let pair_key = ctx.accounts.pair.key();
+    let user_base_balance_key = ctx.accounts.user_base_balance.key();
+    let user_quote_balance_key = ctx.accounts.user_quote_balance.key();
+
+    // Store AccountInfo in local variables to avoid lifetime issues
+    let payer_info = ctx.accounts.crank.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+
+    let mxe_accounts = MxeCpiAccounts {
+        payer: &payer_info,
+        sign_pda_account: &ctx.remaining_accounts[0],
+        mxe_account: &ctx.remaining_accounts[1],
+        mempool_account: &ctx.remaining_accounts[2],
+        executing_pool: &ctx.remaining_accounts[3],
+        computation_account: &ctx.remaining_accounts[4],
+        comp_def_account: &ctx.remaining_accounts[5],
+        cluster_account: &ctx.remaining_accounts[6],
+        pool_account: &ctx.remaining_accounts[7],
+        clock_account: &ctx.remaining_accounts[8],
+        system_program: &system_program_info,
+        arcium_program: &ctx.remaining_accounts[9],
+        mxe_program: &ctx.remaining_accounts[10],
+    };
+
+    let order = &mut ctx.accounts.order;
+
+    // Queue refund of the unfilled escrow - result comes back via cancel_order_callback
+    let queued = queue_calculate_refund(
+        mxe_accounts,
+        params.computation_offset,
+        &order.encrypted_amount,
+        &order.encrypted_filled,
+        &order.encrypted_price,
+        order.side == Side::Buy,
+        order.has_fills(),
+        &params.pub_key,
+        params.nonce,
+        &order_key,
+        &user_base_balance_key,
+        &user_quote_balance_key,
+        &pair_key,
+        &exchange_key,
+    )?;
+
+    // Block matching and duplicate expiry while the refund is in flight;
+    // the callback only accepts the result of this request
+    order.status = OrderStatus::PendingCancel;
+    order.pending_match_request = queued.request_id;
+    order.pending_queued_at = clock.unix_timestamp;
+
+    let coarse_time = ConfidentialOrder::coarse_timestamp(clock.unix_timestamp);
+
+    emit!(OrderExpiryQueued {
+        order_id: order.order_id,
+        maker: order.maker,
+        pair: order.pair,
+        request_id: queued.request_id,
+        timestamp: coarse_time,
+    });
+
+    msg!("Order expiry queued via MPC: {:?}", order.order_id);
+
+    Ok(())
+}
+
+#[event]
+pub struct OrderExpiryQueued {
+    /// Hash-based order ID (no sequential correlation)
+    pub order_id: [u8; 16],
+    pub maker: Pubkey,
+    pub pair: Pubkey,
+    /// Request ID for tracking MPC computation
+    pub request_id: [u8; 32],
+    /// Coarse timestamp (hour precision)
+    pub timestamp: i64,
+}
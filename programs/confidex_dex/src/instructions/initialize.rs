@@ -22,7 +22,7 @@ pub struct Initialize<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<Initialize>, maker_fee_bps: u16, taker_fee_bps: u16) -> Result<()> {
+pub fn handler(ctx: Context<Initialize>, maker_fee_bps: i16, taker_fee_bps: u16) -> Result<()> {
     require!(
         ExchangeState::validate_fees(maker_fee_bps, taker_fee_bps),
         ConfidexError::InvalidFeeBps
@@ -29,7 +29,7 @@ pub struct InitiateSettlement<'info> {
     )]
     pub pair: Box<Account<'info, TradingPair>>,
 
-    /// Buy order - must be matched (have non-zero encrypted_filled)
+    /// Buy order - must carry an unsettled fill (moved into the request)
     #[account(
         mut,
         constraint = buy_order.pair == pair.key() @ ConfidexError::InvalidOrder,
@@ -37,7 +37,7 @@ pub struct InitiateSettlement<'info> {
     )]
     pub buy_order: Box<Account<'info, ConfidentialOrder>>,
 
-    /// Sell order - must be matched (have non-zero encrypted_filled)
+    /// Sell order - must carry the same unsettled fill as the buy order
     #[account(
         mut,
         constraint = sell_order.pair == pair.key() @ ConfidexError::InvalidOrder,
@@ -85,22 +85,18 @@ pub struct InitiateSettlementParams {
 /// * `params` - Settlement parameters including method selection
 ///
 /// # Errors
-/// * `OrderNotFilled` - Orders don't have non-zero encrypted_filled
+/// * `OrderNotFilled` - Orders don't share an unsettled fill from one match
 /// * `InvalidOrder` - Orders are not from the same pair
 pub fn handler(ctx: Context<InitiateSettlement>, params: InitiateSettlementParams) -> Result<()> {
-    let buy_order = &ctx.accounts.buy_order;
-    let sell_order = &ctx.accounts.sell_order;
+    let buy_order = &mut ctx.accounts.buy_order;
+    let sell_order = &mut ctx.accounts.sell_order;
     let pair = &ctx.accounts.pair;
     let settlement = &mut ctx.accounts.settlement_request;
 
-    // Verify orders have been matched (encrypted_filled[0] != 0 indicates MPC set fill)
+    // Verify both orders carry the unsettled fill of the same match
     // This is the privacy-preserving check - we don't read the actual amount
     require!(
-        buy_order.encrypted_filled[0] != 0,
-        ConfidexError::OrderNotFilled
-    );
-    require!(
-        sell_order.encrypted_filled[0] != 0,
+        buy_order.shares_unsettled_fill(sell_order),
         ConfidexError::OrderNotFilled
     );
 
@@ -128,9 +124,11 @@ pub fn handler(ctx: Context<InitiateSettlement>, params: InitiateSettlementParam
     settlement.base_mint = pair.base_mint;
     settlement.quote_mint = pair.quote_mint;
 
-    // Copy encrypted values - NO PLAINTEXT READS
-    // Backend will obtain decrypted values via MPC callback
-    settlement.encrypted_fill_amount = buy_order.encrypted_filled;
+    // Move the match's fill into the request - NO PLAINTEXT READS
+    // Clearing it on the orders means the same fill can't be settled again
+    // (here or via decrypt_for_settlement). encrypted_filled stays cumulative.
+    settlement.encrypted_fill_amount = buy_order.take_unsettled_fill();
+    sell_order.take_unsettled_fill();
 
     // For fill_value, we'd ideally compute amount * price via MPC
     // For now, copy the encrypted price - backend will compute fill_value
@@ -1,10 +1,10 @@
 use anchor_lang::prelude::*;
 
 use crate::cpi::arcium::{
-    queue_compare_prices, MxeCpiAccounts, ARCIUM_MXE_PROGRAM_ID, ARCIUM_PROGRAM_ID,
+    queue_calculate_fill, MxeCpiAccounts, ARCIUM_MXE_PROGRAM_ID, ARCIUM_PROGRAM_ID,
 };
 use crate::error::ConfidexError;
-use crate::state::{ConfidentialOrder, ExchangeState, Side, TradingPair};
+use crate::state::{ConfidentialOrder, ExchangeState, SelfTradePrevention, Side, TradingPair};
 
 /// Accounts required for order matching with full Arcium MPC support.
 ///
@@ -113,15 +113,70 @@ pub fn handler<'info>(
         ConfidexError::OrdersNotMatchable
     );
 
+    // Enforce GTT expiry and time-in-force (maker = order placed first)
+    let now = clock.unix_timestamp;
+    let buy_is_maker = ctx.accounts.buy_order.is_maker_against(&ctx.accounts.sell_order);
+    require!(
+        !ctx.accounts.buy_order.is_expired(now) && !ctx.accounts.sell_order.is_expired(now),
+        ConfidexError::OrderExpired
+    );
+    require!(
+        ctx.accounts.buy_order.can_match_at(now, buy_is_maker)
+            && ctx.accounts.sell_order.can_match_at(now, !buy_is_maker),
+        ConfidexError::TimeInForceViolation
+    );
+
+    // Self-trade prevention: the taker's mode decides. Cancel modes expire the
+    // affected order(s) in place (refunded via expire_order) without queuing MPC.
+    // Decrement proceeds to the fill calculation and is resolved at settlement.
+    if ctx.accounts.buy_order.maker == ctx.accounts.sell_order.maker {
+        let mode = if buy_is_maker {
+            ctx.accounts.sell_order.self_trade_prevention
+        } else {
+            ctx.accounts.buy_order.self_trade_prevention
+        };
+
+        if mode != SelfTradePrevention::Decrement {
+            let (cancel_buy, cancel_sell) = mode.cancels(buy_is_maker);
+            if cancel_buy {
+                ctx.accounts.buy_order.mark_expired(now);
+            }
+            if cancel_sell {
+                ctx.accounts.sell_order.mark_expired(now);
+            }
+
+            emit!(SelfTradePrevented {
+                buy_order_id: ctx.accounts.buy_order.order_id,
+                sell_order_id: ctx.accounts.sell_order.order_id,
+                maker: ctx.accounts.buy_order.maker,
+                mode,
+                buy_cancelled: cancel_buy,
+                sell_cancelled: cancel_sell,
+                timestamp: ConfidentialOrder::coarse_timestamp(now),
+            });
+
+            msg!("Self-trade prevented: {:?}", mode);
+            return Ok(());
+        }
+    }
+
     // Extract MXE accounts from remaining_accounts (11 accounts)
     require!(
         ctx.remaining_accounts.len() >= 11,
         ConfidexError::InvalidAccountCount
     );
 
-    // Copy encrypted prices to avoid borrowing issues
+    // Copy encrypted values to avoid borrowing issues
+    let buy_amount = ctx.accounts.buy_order.encrypted_amount;
+    let buy_filled = ctx.accounts.buy_order.encrypted_filled;
     let buy_price = ctx.accounts.buy_order.encrypted_price;
+    let buy_has_fills = ctx.accounts.buy_order.has_fills();
+    let sell_amount = ctx.accounts.sell_order.encrypted_amount;
+    let sell_filled = ctx.accounts.sell_order.encrypted_filled;
     let sell_price = ctx.accounts.sell_order.encrypted_price;
+    let sell_has_fills = ctx.accounts.sell_order.has_fills();
+    let buy_fill_or_kill = ctx.accounts.buy_order.is_fill_or_kill();
+    let sell_fill_or_kill = ctx.accounts.sell_order.is_fill_or_kill();
     let buy_order_id = ctx.accounts.buy_order.order_id;
     let sell_order_id = ctx.accounts.sell_order.order_id;
 
@@ -146,17 +201,25 @@ pub fn handler<'info>(
         mxe_program: &ctx.remaining_accounts[10],
     };
 
-    // Queue price comparison via MPC
+    // Queue fill calculation via MPC (price check + remaining size = amount - filled)
     // Result will come back via finalize_match callback from MXE
     // Pass order pubkeys so MXE callback can CPI back to DEX with them
     let buy_order_key = ctx.accounts.buy_order.key();
     let sell_order_key = ctx.accounts.sell_order.key();
 
-    let queued = queue_compare_prices(
+    let queued = queue_calculate_fill(
         mxe_accounts,
         params.computation_offset,
+        &buy_amount,
+        &buy_filled,
+        &sell_amount,
+        &sell_filled,
         &buy_price,
         &sell_price,
+        buy_has_fills,
+        sell_has_fills,
+        buy_fill_or_kill,
+        sell_fill_or_kill,
         &params.pub_key,
         params.nonce,
         Some(&buy_order_key),
@@ -204,6 +267,24 @@ pub struct MatchQueued {
     pub timestamp: i64,
 }
 
+/// Event emitted when a match between two orders of the same maker is blocked
+#[event]
+pub struct SelfTradePrevented {
+    /// Hash-based order ID (no sequential correlation)
+    pub buy_order_id: [u8; 16],
+    /// Hash-based order ID (no sequential correlation)
+    pub sell_order_id: [u8; 16],
+    pub maker: Pubkey,
+    /// Mode applied (taker's setting)
+    pub mode: SelfTradePrevention,
+    /// Whether the buy order was expired for refund
+    pub buy_cancelled: bool,
+    /// Whether the sell order was expired for refund
+    pub sell_cancelled: bool,
+    /// Coarse timestamp (hour precision)
+    pub timestamp: i64,
+}
+
 /// Event emitted after MPC callback confirms match and orders are updated
 #[event]
 pub struct TradeExecuted {
@@ -0,0 +1,246 @@
+use anchor_lang::prelude::*;
+
+use crate::cpi::arcium::{
+    queue_batch_calculate_fill, BatchFillPairData, MxeCpiAccounts, MAX_BATCH_MATCH_PAIRS,
+};
+use crate::error::ConfidexError;
+use crate::state::{ConfidentialOrder, ExchangeState, SelfTradePrevention, Side, TradingPair};
+
+/// Number of Arcium MXE accounts at the front of remaining_accounts
+const MXE_ACCOUNT_COUNT: usize = 11;
+
+/// Accounts required for batched order matching (up to 5 pairs per MPC call).
+///
+/// Orders are variable-count, so they are passed via remaining_accounts
+/// after the 11 MXE accounts (same layout as match_orders).
+#[derive(Accounts)]
+pub struct MatchOrdersBatch<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+        constraint = !exchange.paused @ ConfidexError::ExchangePaused
+    )]
+    pub exchange: Box<Account<'info, ExchangeState>>,
+
+    #[account(
+        seeds = [
+            TradingPair::SEED,
+            pair.base_mint.as_ref(),
+            pair.quote_mint.as_ref()
+        ],
+        bump = pair.bump
+    )]
+    pub pair: Box<Account<'info, TradingPair>>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Crank operator (payer for MPC fees - one fee for the whole batch)
+    #[account(mut)]
+    pub crank: Signer<'info>,
+
+    // =========================================================================
+    // REMAINING ACCOUNTS
+    //   0..10: Arcium MXE accounts (same order as match_orders)
+    //   11..:  order pairs, writable: buy_0, sell_0, buy_1, sell_1, ...
+    // =========================================================================
+}
+
+/// Input parameters for match_orders_batch instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MatchOrdersBatchParams {
+    /// Random seed for computation account derivation
+    pub computation_offset: u64,
+    /// X25519 public key for output encryption (from ephemeral keypair)
+    pub pub_key: [u8; 32],
+    /// Encryption nonce
+    pub nonce: u128,
+    /// Number of buy/sell pairs in remaining_accounts (1-5)
+    pub pair_count: u8,
+}
+
+/// Load and validate an order passed via remaining_accounts
+///
+/// Applies the same checks as the MatchOrders account constraints.
+fn load_matchable_order(
+    info: &AccountInfo,
+    pair: &Pubkey,
+    side: Side,
+) -> Result<ConfidentialOrder> {
+    require!(info.owner == &crate::ID, ConfidexError::InvalidOrder);
+    require!(info.is_writable, ConfidexError::InvalidOrder);
+
+    let order = {
+        let data = info.try_borrow_data()?;
+        ConfidentialOrder::try_deserialize(&mut &data[..])?
+    };
+
+    // Verify PDA derivation (seeds = [order, maker, order_nonce])
+    let expected = Pubkey::create_program_address(
+        &[
+            ConfidentialOrder::SEED,
+            order.maker.as_ref(),
+            &order.order_nonce,
+            &[order.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| error!(ConfidexError::InvalidOrder))?;
+    require!(expected == *info.key, ConfidexError::InvalidOrder);
+
+    require!(order.side == side, ConfidexError::InvalidOrderSide);
+    require!(order.is_active(), ConfidexError::OrderNotOpen);
+    require!(order.eligibility_proof_verified, ConfidexError::EligibilityNotVerified);
+    require!(!order.is_matching, ConfidexError::OrderAlreadyMatching);
+    require!(order.pair == *pair, ConfidexError::OrdersNotMatchable);
+
+    Ok(order)
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MatchOrdersBatch<'info>>,
+    params: MatchOrdersBatchParams,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let pair_key = ctx.accounts.pair.key();
+    let pair_count = params.pair_count as usize;
+
+    require!(
+        pair_count > 0 && pair_count <= MAX_BATCH_MATCH_PAIRS,
+        ConfidexError::InvalidAccountCount
+    );
+    require!(
+        ctx.remaining_accounts.len() >= MXE_ACCOUNT_COUNT + pair_count * 2,
+        ConfidexError::InvalidAccountCount
+    );
+
+    let order_infos = &ctx.remaining_accounts[MXE_ACCOUNT_COUNT..MXE_ACCOUNT_COUNT + pair_count * 2];
+
+    // Each order may only appear once in a batch
+    for (i, a) in order_infos.iter().enumerate() {
+        for b in order_infos.iter().skip(i + 1) {
+            require!(a.key != b.key, ConfidexError::OrdersNotMatchable);
+        }
+    }
+
+    // Validate every pair and collect the calculate_fill inputs
+    let mut orders = Vec::with_capacity(pair_count * 2);
+    let mut fill_pairs = Vec::with_capacity(pair_count);
+
+    let now = clock.unix_timestamp;
+
+    for chunk in order_infos.chunks(2) {
+        let buy_order = load_matchable_order(&chunk[0], &pair_key, Side::Buy)?;
+        let sell_order = load_matchable_order(&chunk[1], &pair_key, Side::Sell)?;
+
+        // Same expiry / time-in-force rules as match_orders
+        let buy_is_maker = buy_order.is_maker_against(&sell_order);
+        require!(
+            !buy_order.is_expired(now) && !sell_order.is_expired(now),
+            ConfidexError::OrderExpired
+        );
+        require!(
+            buy_order.can_match_at(now, buy_is_maker)
+                && sell_order.can_match_at(now, !buy_is_maker),
+            ConfidexError::TimeInForceViolation
+        );
+        // Self-trades must go through match_orders, which applies the cancel
+        // modes; only decrement (resolved at settlement) is allowed in a batch
+        if buy_order.maker == sell_order.maker {
+            let taker = if buy_is_maker { &sell_order } else { &buy_order };
+            require!(
+                taker.self_trade_prevention == SelfTradePrevention::Decrement,
+                ConfidexError::SelfTradePrevented
+            );
+        }
+
+        fill_pairs.push(BatchFillPairData {
+            buy_order: *chunk[0].key,
+            sell_order: *chunk[1].key,
+            buy_amount: buy_order.encrypted_amount,
+            buy_filled: buy_order.encrypted_filled,
+            sell_amount: sell_order.encrypted_amount,
+            sell_filled: sell_order.encrypted_filled,
+            buy_price: buy_order.encrypted_price,
+            sell_price: sell_order.encrypted_price,
+            buy_has_fills: buy_order.has_fills(),
+            sell_has_fills: sell_order.has_fills(),
+            buy_fill_or_kill: buy_order.is_fill_or_kill(),
+            sell_fill_or_kill: sell_order.is_fill_or_kill(),
+        });
+
+        orders.push(buy_order);
+        orders.push(sell_order);
+    }
+
+    // Store AccountInfo in local variables to avoid lifetime issues
+    let payer_info = ctx.accounts.crank.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+
+    let mxe_accounts = MxeCpiAccounts {
+        payer: &payer_info,
+        sign_pda_account: &ctx.remaining_accounts[0],
+        mxe_account: &ctx.remaining_accounts[1],
+        mempool_account: &ctx.remaining_accounts[2],
+        executing_pool: &ctx.remaining_accounts[3],
+        computation_account: &ctx.remaining_accounts[4],
+        comp_def_account: &ctx.remaining_accounts[5],
+        cluster_account: &ctx.remaining_accounts[6],
+        pool_account: &ctx.remaining_accounts[7],
+        clock_account: &ctx.remaining_accounts[8],
+        system_program: &system_program_info,
+        arcium_program: &ctx.remaining_accounts[9],
+        mxe_program: &ctx.remaining_accounts[10],
+    };
+
+    // Queue one batched fill calculation for all pairs
+    // Result comes back via finalize_match_batch callback from MXE
+    let queued = queue_batch_calculate_fill(
+        mxe_accounts,
+        params.computation_offset,
+        &fill_pairs,
+        &params.pub_key,
+        params.nonce,
+    )?;
+
+    // Store pending match state on every order for callback validation
+    for (info, order) in order_infos.iter().zip(orders.iter_mut()) {
+        order.pending_match_request = queued.request_id;
+        order.is_matching = true;
+
+        let mut data = info.try_borrow_mut_data()?;
+        let mut writer = &mut data[8..]; // Skip discriminator
+        order.serialize(&mut writer)?;
+    }
+
+    // Coarse timestamp for privacy (hour precision)
+    let coarse_time = ConfidentialOrder::coarse_timestamp(clock.unix_timestamp);
+
+    emit!(MatchBatchQueued {
+        pair: pair_key,
+        pair_count: params.pair_count,
+        request_id: queued.request_id,
+        computation_offset: params.computation_offset,
+        timestamp: coarse_time,
+    });
+
+    msg!(
+        "Batch match queued via MPC: {} pairs, computation_offset={}",
+        pair_count,
+        params.computation_offset
+    );
+
+    Ok(())
+}
+
+#[event]
+pub struct MatchBatchQueued {
+    pub pair: Pubkey,
+    /// Number of buy/sell pairs in the batch
+    pub pair_count: u8,
+    /// Request ID for tracking MPC computation
+    pub request_id: [u8; 32],
+    /// Computation offset used for account derivation
+    pub computation_offset: u64,
+    /// Coarse timestamp (hour precision)
+    pub timestamp: i64,
+}
@@ -1,15 +1,24 @@
 pub mod admin;
+pub mod amend_order;
+pub mod cancel_all_orders;
 pub mod cancel_order;
+pub mod cancel_orders_batch;
 pub mod create_pair;
+pub mod expire_order;
 pub mod initialize;
+pub mod initiate_cancel_order;
 pub mod match_orders;
+pub mod match_orders_batch;
 pub mod place_order;
+pub mod recover_pending_order;
 pub mod settle_order;
 pub mod unwrap_tokens;
 pub mod wrap_tokens;
 
 // ZK verification (Layer 1 of three-layer privacy)
 pub mod verify_eligibility;
+pub mod allowlist;
+pub mod solvency;
 
 // Perpetuals instructions (all at root level for Anchor compatibility)
 pub mod perp_init_market;
@@ -21,13 +30,19 @@ pub mod perp_add_margin;
 pub mod perp_remove_margin;
 pub mod perp_liquidate;
 pub mod perp_auto_deleverage;
+pub mod perp_adl_ranking;
+pub mod perp_insurance_fund;
 pub mod perp_settle_funding;
+pub mod perp_exit_triggers;
 pub mod check_liquidation_batch;
 
 // MPC callback handlers
 pub mod mpc_callback;
 pub mod settle_order_callback;
 pub mod cancel_order_callback;
+pub mod cancel_orders_batch_callback;
+pub mod place_order_callback;
+pub mod amend_order_callback;
 
 // ShadowWire settlement (Layer 4 - private transfer)
 pub mod finalize_settlement;
@@ -38,17 +53,26 @@ pub mod fail_settlement;
 pub mod expire_settlement;
 
 pub use admin::*;
+pub use amend_order::*;
+pub use cancel_all_orders::*;
 pub use cancel_order::*;
+pub use cancel_orders_batch::*;
 pub use create_pair::*;
+pub use expire_order::*;
 pub use initialize::*;
+pub use initiate_cancel_order::*;
 pub use match_orders::*;
+pub use match_orders_batch::*;
 pub use place_order::*;
+pub use recover_pending_order::*;
 pub use settle_order::*;
 pub use unwrap_tokens::*;
 pub use wrap_tokens::*;
 
 // ZK verification exports
 pub use verify_eligibility::*;
+pub use allowlist::*;
+pub use solvency::*;
 
 // Perpetuals exports
 pub use perp_init_market::*;
@@ -60,13 +84,19 @@ pub use perp_add_margin::*;
 pub use perp_remove_margin::*;
 pub use perp_liquidate::*;
 pub use perp_auto_deleverage::*;
+pub use perp_adl_ranking::*;
+pub use perp_insurance_fund::*;
 pub use perp_settle_funding::*;
+pub use perp_exit_triggers::*;
 pub use check_liquidation_batch::*;
 
 // MPC callback exports
 pub use mpc_callback::*;
 pub use settle_order_callback::*;
 pub use cancel_order_callback::*;
+pub use cancel_orders_batch_callback::*;
+pub use place_order_callback::*;
+pub use amend_order_callback::*;
 
 // ShadowWire settlement exports
 pub use finalize_settlement::*;
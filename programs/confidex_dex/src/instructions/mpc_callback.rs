@@ -4,7 +4,7 @@
 //! after MPC execution completes.
 //!
 //! Supported callback types:
-//! 1. Order matching (FinalizeMatch) - price comparison and fill calculation
+//! 1. Order matching (FinalizeMatch, FinalizeMatchBatch) - price comparison and fill calculation
 //! 2. Position verification (PositionVerificationCallback) - threshold computation
 //! 3. Margin operations (MarginOperationCallback) - add/remove collateral
 //! 4. Liquidation check (LiquidationCheckCallback) - batch liquidation status
@@ -65,9 +65,16 @@ pub struct FinalizeMatch<'info> {
     // MXE CPI only passes 3 accounts: mxe_authority, buy_order, sell_order
 }
 
-/// Simplified callback handler for MPC price comparison result
+/// Size of a calculate_fill result: fill, buy_filled, sell_filled (64 bytes each)
+/// followed by matched, buy_fully_filled and sell_fully_filled flags (1 byte each)
+const FILL_RESULT_LEN: usize = 64 * 3 + 3;
+
+/// Simplified callback handler for MPC match results
+///
+/// Called by the MXE after Arcium MPC either:
+/// - compares prices (compare_prices, 1-byte result), or
+/// - calculates the fill (calculate_fill, FILL_RESULT_LEN-byte result)
 ///
-/// Called by the MXE after Arcium MPC computes whether buy_price >= sell_price.
 /// Orders are passed directly via callback_account_1/2 stored in ComputationRequest.
 pub fn finalize_match(
     ctx: Context<FinalizeMatch>,
@@ -88,15 +95,16 @@ pub fn finalize_match(
     // the System Program, not the signing program. The signer+seeds constraint
     // is sufficient to prove this came from the authorized MXE.
 
-    // Parse result: single byte indicating match (1) or no match (0)
     // Security: Exact length validation prevents malformed callback data
-    require!(result.len() == 1, MpcCallbackError::InvalidResult);
-    let prices_match = result[0] == 1;
+    require!(
+        result.len() == 1 || result.len() == FILL_RESULT_LEN,
+        MpcCallbackError::InvalidResult
+    );
 
     msg!(
-        "MPC finalize_match: request {:?}, prices_match={}",
+        "MPC finalize_match: request {:?}, result_len={}",
         &request_id[0..8],
-        prices_match
+        result.len()
     );
 
     // Verify orders were waiting for this request
@@ -109,6 +117,158 @@ pub fn finalize_match(
         MpcCallbackError::InvalidRequestId
     );
 
+    let buy_key = buy_order.key();
+    let sell_key = sell_order.key();
+
+    if result.len() == FILL_RESULT_LEN {
+        apply_fill_result(
+            buy_order,
+            sell_order,
+            buy_key,
+            sell_key,
+            request_id,
+            &result,
+            clock.unix_timestamp,
+        );
+    } else {
+        // Legacy compare_prices result: single byte match (1) or no match (0)
+        apply_match_result(
+            buy_order,
+            sell_order,
+            buy_key,
+            sell_key,
+            request_id,
+            result[0] == 1,
+            clock.unix_timestamp,
+        );
+    }
+
+    Ok(())
+}
+
+/// Apply a calculate_fill result to a buy/sell order pair
+///
+/// The MPC has already added this match's fill to each order's cumulative
+/// encrypted_filled, so the new values are stored as-is. An order is only
+/// deactivated when the MPC reports it fully filled; otherwise it stays
+/// Active with its remaining size available for further matches.
+///
+/// Same-maker pairs only reach the MPC in decrement mode (the cancel modes
+/// are applied by match_orders / match_orders_batch before queuing), so their
+/// fill is recorded like any other; settlement releases it back to the maker
+/// instead of trading it.
+///
+/// Caller must have verified both orders were waiting for `request_id` and
+/// that `result.len() == FILL_RESULT_LEN`.
+fn apply_fill_result(
+    buy_order: &mut ConfidentialOrder,
+    sell_order: &mut ConfidentialOrder,
+    buy_key: Pubkey,
+    sell_key: Pubkey,
+    request_id: [u8; 32],
+    result: &[u8],
+    unix_timestamp: i64,
+) {
+    // Coarse timestamp for privacy
+    let coarse_time = ConfidentialOrder::coarse_timestamp(unix_timestamp);
+
+    let matched = result[192] == 1;
+    let buy_fully_filled = result[193] == 1;
+    let sell_fully_filled = result[194] == 1;
+
+    // Clear pending match state
+    buy_order.pending_match_request = [0u8; 32];
+    sell_order.pending_match_request = [0u8; 32];
+    buy_order.is_matching = false;
+    sell_order.is_matching = false;
+
+    // IOC/FOK: this was the order's only match attempt
+    buy_order.expire_if_immediate(unix_timestamp);
+    sell_order.expire_if_immediate(unix_timestamp);
+
+    if !matched {
+        // Prices don't overlap - orders remain Active, filled amounts unchanged
+        emit!(MatchFailedNoOverlap {
+            request_id,
+            buy_order: buy_key,
+            sell_order: sell_key,
+            timestamp: coarse_time,
+        });
+
+        msg!("No match: prices don't overlap. Orders remain Active.");
+        return;
+    }
+
+    // Encrypted fill for this match (consumed by settlement)
+    let mut encrypted_fill = [0u8; 64];
+    encrypted_fill.copy_from_slice(&result[0..64]);
+
+    // Cumulative filled amounts (previous filled + this fill, added in MPC)
+    buy_order.encrypted_filled.copy_from_slice(&result[64..128]);
+    sell_order.encrypted_filled.copy_from_slice(&result[128..192]);
+
+    // This match's fill is settled once; the orders can't match again until then
+    buy_order.record_unsettled_fill(encrypted_fill);
+    sell_order.record_unsettled_fill(encrypted_fill);
+
+    if buy_fully_filled {
+        buy_order.status = OrderStatus::Inactive;
+    }
+    // else: remains Active (partially filled)
+
+    if sell_fully_filled {
+        sell_order.status = OrderStatus::Inactive;
+    }
+    // else: remains Active (partially filled)
+
+    emit!(OrdersMatchedDirect {
+        request_id,
+        buy_order: buy_key,
+        sell_order: sell_key,
+        buy_fully_filled,
+        sell_fully_filled,
+        buy_is_maker: buy_order.is_maker_against(sell_order),
+        timestamp: coarse_time,
+    });
+
+    // Privacy: only the ciphertext is emitted, decrypted via decrypt_for_settlement
+    emit!(OrderFillRecorded {
+        request_id,
+        buy_order: buy_key,
+        sell_order: sell_key,
+        encrypted_fill,
+        timestamp: coarse_time,
+    });
+
+    msg!(
+        "Orders matched: buy={}, sell={}, buy_filled={}, sell_filled={}",
+        buy_key,
+        sell_key,
+        buy_fully_filled,
+        sell_fully_filled
+    );
+}
+
+/// Apply a revealed price comparison result to a buy/sell order pair
+///
+/// Used by finalize_match for compare_prices results. Caller must have
+/// verified both orders were waiting for `request_id`.
+fn apply_match_result(
+    buy_order: &mut ConfidentialOrder,
+    sell_order: &mut ConfidentialOrder,
+    buy_key: Pubkey,
+    sell_key: Pubkey,
+    request_id: [u8; 32],
+    prices_match: bool,
+    unix_timestamp: i64,
+) {
+    // Coarse timestamp for privacy
+    let coarse_time = ConfidentialOrder::coarse_timestamp(unix_timestamp);
+
+    // IOC/FOK: this was the order's only match attempt
+    buy_order.expire_if_immediate(unix_timestamp);
+    sell_order.expire_if_immediate(unix_timestamp);
+
     if prices_match {
         // Prices overlap - orders can be matched
         // V5: MPC computes fill amount. For now, mark orders for settlement.
@@ -147,22 +307,20 @@ pub fn finalize_match(
         buy_order.is_matching = false;
         sell_order.is_matching = false;
 
-        // Coarse timestamp for privacy
-        let coarse_time = ConfidentialOrder::coarse_timestamp(clock.unix_timestamp);
-
         emit!(OrdersMatchedDirect {
             request_id,
-            buy_order: buy_order.key(),
-            sell_order: sell_order.key(),
+            buy_order: buy_key,
+            sell_order: sell_key,
             buy_fully_filled,
             sell_fully_filled,
+            buy_is_maker: buy_order.is_maker_against(sell_order),
             timestamp: coarse_time,
         });
 
         msg!(
             "Orders matched: buy={}, sell={}, buy_filled={}, sell_filled={}",
-            buy_order.key(),
-            sell_order.key(),
+            buy_key,
+            sell_key,
             buy_fully_filled,
             sell_fully_filled
         );
@@ -178,13 +336,10 @@ pub fn finalize_match(
         buy_order.is_matching = false;
         sell_order.is_matching = false;
 
-        // Coarse timestamp for privacy
-        let coarse_time = ConfidentialOrder::coarse_timestamp(clock.unix_timestamp);
-
         emit!(MatchFailedNoOverlap {
             request_id,
-            buy_order: buy_order.key(),
-            sell_order: sell_order.key(),
+            buy_order: buy_key,
+            sell_order: sell_key,
             timestamp: coarse_time,
         });
 
@@ -192,6 +347,120 @@ pub fn finalize_match(
             "No match: prices don't overlap. Orders remain Active."
         );
     }
+}
+
+// ============================================================================
+// BATCH DIRECT CALLBACK (match_orders_batch)
+// ============================================================================
+
+/// Accounts for finalize_match_batch - batched version of finalize_match
+/// MXE passes the orders via remaining_accounts: buy_0, sell_0, buy_1, sell_1, ...
+#[derive(Accounts)]
+pub struct FinalizeMatchBatch<'info> {
+    /// MXE authority PDA - verifies this came from our MXE program
+    /// CHECK: Verified by seeds constraint - must be first account (signer from MXE CPI)
+    #[account(
+        signer,
+        seeds = [MXE_AUTHORITY_SEED],
+        bump,
+        seeds::program = ARCIUM_MXE_PROGRAM_ID
+    )]
+    pub mxe_authority: UncheckedAccount<'info>,
+    // Orders are passed via remaining_accounts to allow variable count
+}
+
+/// Callback handler for batched MPC fill results
+///
+/// Called by the MXE after batch_calculate_fill completes. `result` holds one
+/// FILL_RESULT_LEN-byte record per pair (same format as finalize_match), and
+/// each pair is applied like a single calculate_fill result. Every order in
+/// the batch is updated in this single instruction, so the batch either
+/// applies fully or not at all.
+pub fn finalize_match_batch(
+    ctx: Context<FinalizeMatchBatch>,
+    request_id: [u8; 32],
+    result: Vec<u8>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    // Security: mxe_authority verified via signer + seeds::program (see finalize_match)
+
+    let pair_count = ctx.remaining_accounts.len() / 2;
+    require!(
+        pair_count > 0 && pair_count <= 5 && ctx.remaining_accounts.len() % 2 == 0,
+        MpcCallbackError::InvalidResult
+    );
+    // Security: Exact length validation prevents malformed callback data
+    require!(
+        result.len() == pair_count * FILL_RESULT_LEN,
+        MpcCallbackError::InvalidResult
+    );
+
+    msg!(
+        "MPC finalize_match_batch: request {:?}, {} pairs",
+        &request_id[0..8],
+        pair_count
+    );
+
+    for (chunk, pair_result) in ctx
+        .remaining_accounts
+        .chunks(2)
+        .zip(result.chunks(FILL_RESULT_LEN))
+    {
+        let buy_info = &chunk[0];
+        let sell_info = &chunk[1];
+
+        require!(
+            buy_info.owner == &crate::ID && sell_info.owner == &crate::ID,
+            MpcCallbackError::InvalidOrder
+        );
+
+        let mut buy_data = buy_info.try_borrow_mut_data()?;
+        let mut sell_data = sell_info.try_borrow_mut_data()?;
+        let mut buy_order = ConfidentialOrder::try_deserialize(&mut &buy_data[..])?;
+        let mut sell_order = ConfidentialOrder::try_deserialize(&mut &sell_data[..])?;
+
+        // Same checks as the FinalizeMatch account constraints
+        require!(buy_order.is_in_matching(), MpcCallbackError::OrderNotMatching);
+        require!(sell_order.is_in_matching(), MpcCallbackError::OrderNotMatching);
+        require!(sell_order.pair == buy_order.pair, MpcCallbackError::InvalidOrder);
+
+        // Verify orders were waiting for this request
+        require!(
+            buy_order.pending_match_request == request_id,
+            MpcCallbackError::InvalidRequestId
+        );
+        require!(
+            sell_order.pending_match_request == request_id,
+            MpcCallbackError::InvalidRequestId
+        );
+
+        apply_fill_result(
+            &mut buy_order,
+            &mut sell_order,
+            *buy_info.key,
+            *sell_info.key,
+            request_id,
+            pair_result,
+            clock.unix_timestamp,
+        );
+
+        // Re-serialize both orders
+        let mut writer = &mut buy_data[8..]; // Skip discriminator
+        buy_order.serialize(&mut writer)?;
+        let mut writer = &mut sell_data[8..];
+        sell_order.serialize(&mut writer)?;
+    }
+
+    emit!(MatchBatchFinalized {
+        request_id,
+        pair_count: pair_count as u8,
+        matched_count: result
+            .chunks(FILL_RESULT_LEN)
+            .filter(|pair_result| pair_result[192] == 1)
+            .count() as u8,
+        timestamp: ConfidentialOrder::coarse_timestamp(clock.unix_timestamp),
+    });
 
     Ok(())
 }
@@ -382,6 +651,8 @@ pub fn receive_fill_result(
     // The encrypted fill amount is added to the encrypted_filled field
     buy_order.encrypted_filled = encrypted_fill;
     sell_order.encrypted_filled = encrypted_fill;
+    buy_order.record_unsettled_fill(encrypted_fill);
+    sell_order.record_unsettled_fill(encrypted_fill);
 
     // V2: Use Active/Inactive status
     if buy_fully_filled {
@@ -449,6 +720,22 @@ pub struct OrdersMatchedDirect {
     pub sell_order: Pubkey,
     pub buy_fully_filled: bool,
     pub sell_fully_filled: bool,
+    /// true if the buy order rested first (maker) and the sell order crossed (taker)
+    pub buy_is_maker: bool,
+    pub timestamp: i64,
+}
+
+/// Event emitted with the encrypted fill amount of a single match
+///
+/// The same ciphertext is kept on both orders as encrypted_unsettled_fill
+/// until settled; it is the decrypt_for_settlement input.
+#[event]
+pub struct OrderFillRecorded {
+    pub request_id: [u8; 32],
+    pub buy_order: Pubkey,
+    pub sell_order: Pubkey,
+    /// Encrypted fill amount for this match (64-byte Arcium format)
+    pub encrypted_fill: [u8; 64],
     pub timestamp: i64,
 }
 
@@ -461,6 +748,16 @@ pub struct MatchFailedNoOverlap {
     pub timestamp: i64,
 }
 
+/// Event emitted after finalize_match_batch updates every order in a batch
+/// Per-pair OrdersMatchedDirect / MatchFailedNoOverlap events are emitted too
+#[event]
+pub struct MatchBatchFinalized {
+    pub request_id: [u8; 32],
+    pub pair_count: u8,
+    pub matched_count: u8,
+    pub timestamp: i64,
+}
+
 // ============================================================================
 // EVENT-DRIVEN CALLBACK (Phase 3: Backend subscribes to MXE events)
 // ============================================================================
@@ -561,6 +858,12 @@ pub fn update_orders_from_result(
         buy_order.encrypted_filled = encrypted_fill;
         sell_order.encrypted_filled = encrypted_fill;
 
+        // Only a real fill ciphertext (not the price-match marker) is settled
+        if let Some(encrypted_fill) = params.encrypted_fill {
+            buy_order.record_unsettled_fill(encrypted_fill);
+            sell_order.record_unsettled_fill(encrypted_fill);
+        }
+
         // Update status based on fill result
         if params.buy_fully_filled {
             buy_order.status = OrderStatus::Inactive;
@@ -1114,6 +1417,217 @@ pub struct LiquidationBatchComplete {
     pub timestamp: i64,
 }
 
+// ============================================================================
+// ADL PNL CALLBACK (Auto-Deleverage Settlement)
+// ============================================================================
+
+/// Accounts for ADL settlement callback
+/// Called by MXE after calculate_adl MPC completes
+#[derive(Accounts)]
+#[instruction(request_id: [u8; 32])]
+pub struct AdlPnlCallback<'info> {
+    /// MXE authority PDA - verifies this came from our MXE program
+    /// CHECK: Verified by seeds constraint
+    #[account(
+        signer,
+        seeds = [MXE_AUTHORITY_SEED],
+        bump,
+        seeds::program = ARCIUM_MXE_PROGRAM_ID
+    )]
+    pub mxe_authority: UncheckedAccount<'info>,
+
+    /// Bankrupt position being deleveraged
+    #[account(
+        mut,
+        constraint = bankrupt_position.pending_mpc_request == request_id @ MpcCallbackError::InvalidRequestId,
+        constraint = bankrupt_position.is_open() @ MpcCallbackError::InvalidPositionState,
+        constraint = bankrupt_position.market == perp_market.key() @ ConfidexError::InvalidFundingState
+    )]
+    pub bankrupt_position: Box<Account<'info, ConfidentialPosition>>,
+
+    /// Opposite-side position taking over the bankrupt position's size
+    #[account(
+        mut,
+        constraint = target_position.pending_mpc_request == request_id @ MpcCallbackError::InvalidRequestId,
+        constraint = target_position.is_open() @ MpcCallbackError::InvalidPositionState,
+        constraint = target_position.market == perp_market.key() @ ConfidexError::InvalidFundingState
+    )]
+    pub target_position: Box<Account<'info, ConfidentialPosition>>,
+
+    /// Perpetual market (open interest is reduced by the ADL size)
+    #[account(
+        mut,
+        seeds = [PerpetualMarket::SEED, perp_market.underlying_mint.as_ref()],
+        bump = perp_market.bump,
+    )]
+    pub perp_market: Box<Account<'info, PerpetualMarket>>,
+
+    /// Target trader's collateral token account
+    #[account(
+        mut,
+        constraint = target_collateral_account.mint == perp_market.quote_mint @ ConfidexError::InvalidMint,
+        constraint = target_collateral_account.owner == target_position.trader @ ConfidexError::InvalidOwner
+    )]
+    pub target_collateral_account: Box<Account<'info, TokenAccount>>,
+
+    /// Market's collateral vault
+    #[account(
+        mut,
+        constraint = collateral_vault.key() == perp_market.collateral_vault @ ConfidexError::InvalidVault
+    )]
+    pub collateral_vault: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Vault authority PDA for signing transfers
+    #[account(
+        seeds = [b"vault", perp_market.key().as_ref()],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// SPL Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Parameters for ADL settlement callback
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AdlPnlParams {
+    /// MPC computation request ID
+    pub request_id: [u8; 32],
+    /// Remaining encrypted size of the bankrupt position
+    pub new_bankrupt_size: [u8; 64],
+    /// Remaining encrypted collateral of the bankrupt position
+    pub new_bankrupt_collateral: [u8; 64],
+    /// Remaining encrypted size of the target position
+    pub new_target_size: [u8; 64],
+    /// Remaining encrypted collateral of the target position
+    pub new_target_collateral: [u8; 64],
+    /// Collateral released to the target plus its PnL at the bankruptcy price
+    pub target_payout: u64,
+    /// Size closed on both positions (for open interest)
+    pub adl_size: u64,
+    /// Whether the bankrupt position has no size left
+    pub bankrupt_fully_closed: bool,
+    /// Whether the target position has no size left
+    pub target_fully_closed: bool,
+}
+
+/// Handle ADL settlement callback from MXE
+///
+/// Called after MPC closes min(bankrupt_size, target_size) of both positions
+/// at the bankruptcy price. Collateral is released pro rata on both sides,
+/// so leverage and liquidation thresholds are unchanged and no re-verification
+/// is needed. The target's payout leaves the collateral vault here; the
+/// bankrupt position's released collateral stays in the vault to fund it.
+///
+/// IMPORTANT: This function does NOT emit the payout or ADL size in events
+/// to preserve privacy.
+pub fn adl_pnl_callback(
+    ctx: Context<AdlPnlCallback>,
+    params: AdlPnlParams,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    msg!(
+        "ADL settlement callback: request_id={:?}, bankrupt_closed={}, target_closed={}",
+        &params.request_id[0..8],
+        params.bankrupt_fully_closed,
+        params.target_fully_closed
+    );
+
+    // Pay the target out of the collateral vault
+    if params.target_payout > 0 {
+        let market_key = ctx.accounts.perp_market.key();
+        let seeds = &[
+            b"vault".as_ref(),
+            market_key.as_ref(),
+            &[ctx.bumps.vault_authority],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.collateral_vault.to_account_info(),
+                    to: ctx.accounts.target_collateral_account.to_account_info(),
+                    authority: ctx.accounts.vault_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            params.target_payout,
+        )?;
+    }
+
+    let bankrupt_position = &mut ctx.accounts.bankrupt_position;
+    let target_position = &mut ctx.accounts.target_position;
+    let perp_market = &mut ctx.accounts.perp_market;
+
+    // Both sides lose the deleveraged size
+    perp_market.total_long_open_interest =
+        perp_market.total_long_open_interest.saturating_sub(params.adl_size);
+    perp_market.total_short_open_interest =
+        perp_market.total_short_open_interest.saturating_sub(params.adl_size);
+
+    let coarse_time = ConfidentialPosition::coarse_timestamp(clock.unix_timestamp);
+
+    bankrupt_position.encrypted_size = params.new_bankrupt_size;
+    bankrupt_position.encrypted_collateral = params.new_bankrupt_collateral;
+    bankrupt_position.clear_pending_mpc_request();
+    bankrupt_position.last_updated_hour = coarse_time;
+    if params.bankrupt_fully_closed {
+        bankrupt_position.status = PositionStatus::AutoDeleveraged;
+        bankrupt_position.is_liquidatable = false;
+        perp_market.record_position_closed();
+    }
+    // Otherwise the position stays liquidatable for the next ADL target
+
+    target_position.encrypted_size = params.new_target_size;
+    target_position.encrypted_collateral = params.new_target_collateral;
+    target_position.partial_close_count = target_position.partial_close_count.saturating_add(1);
+    target_position.clear_pending_mpc_request();
+    target_position.last_updated_hour = coarse_time;
+    if params.target_fully_closed {
+        target_position.status = PositionStatus::Closed;
+        perp_market.record_position_closed();
+    }
+
+    emit!(AutoDeleverageExecuted {
+        bankrupt_position_id: bankrupt_position.position_id,
+        bankrupt_trader: bankrupt_position.trader,
+        target_position_id: target_position.position_id,
+        target_trader: target_position.trader,
+        market: perp_market.key(),
+        request_id: params.request_id,
+        bankrupt_fully_closed: params.bankrupt_fully_closed,
+        target_fully_closed: params.target_fully_closed,
+        timestamp: coarse_time,
+    });
+
+    msg!(
+        "ADL settled: bankrupt position {} #{:?} covered by target position {} #{:?}",
+        bankrupt_position.trader,
+        bankrupt_position.position_id,
+        target_position.trader,
+        target_position.position_id
+    );
+
+    Ok(())
+}
+
+/// Event emitted when an ADL settlement is applied
+#[event]
+pub struct AutoDeleverageExecuted {
+    pub bankrupt_position_id: [u8; 16],
+    pub bankrupt_trader: Pubkey,
+    pub target_position_id: [u8; 16],
+    pub target_trader: Pubkey,
+    pub market: Pubkey,
+    pub request_id: [u8; 32],
+    pub bankrupt_fully_closed: bool,
+    pub target_fully_closed: bool,
+    pub timestamp: i64,
+}
+
 // ============================================================================
 // CLOSE POSITION CALLBACK (V7 - Async Close)
 // ============================================================================
@@ -1282,6 +1796,7 @@ pub fn close_position_callback(
     if position.pending_close_full {
         // Full close - mark position as closed
         position.status = PositionStatus::Closed;
+        ctx.accounts.perp_market.record_position_closed();
 
         emit!(PositionClosed {
             position: position.key(),
@@ -1542,3 +2057,106 @@ pub struct FundingSettlementFailed {
     pub request_id: [u8; 32],
     pub timestamp: i64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{OrderType, SelfTradePrevention, Side, TimeInForce};
+
+    const NOW: i64 = 1_704_103_200;
+
+    fn order(
+        maker: Pubkey,
+        side: Side,
+        sequence: u64,
+        self_trade_prevention: SelfTradePrevention,
+    ) -> ConfidentialOrder {
+        ConfidentialOrder {
+            maker,
+            pair: Pubkey::default(),
+            side,
+            order_type: OrderType::Limit,
+            encrypted_amount: [1u8; 64],
+            encrypted_price: [2u8; 64],
+            encrypted_filled: [0u8; 64],
+            status: OrderStatus::Active,
+            created_at_hour: NOW,
+            order_id: [0u8; 16],
+            order_nonce: sequence.to_le_bytes(),
+            eligibility_proof_verified: true,
+            pending_match_request: [7u8; 32],
+            is_matching: true,
+            bump: 255,
+            ephemeral_pubkey: [0u8; 32],
+            escrow_remaining: 1_000,
+            encrypted_unsettled_fill: [0u8; 64],
+            fill_pending_settlement: false,
+            time_in_force: TimeInForce::GoodTillCancel,
+            expires_at_hour: 0,
+            self_trade_prevention,
+            pending_queued_at: 0,
+            pending_encrypted_amount: [0u8; 64],
+            pending_encrypted_price: [0u8; 64],
+            pending_ephemeral_pubkey: [0u8; 32],
+        }
+    }
+
+    /// Same-maker pair: resting buy (older), crossing sell (taker) with `mode`
+    fn self_trade_pair(mode: SelfTradePrevention) -> (ConfidentialOrder, ConfidentialOrder) {
+        let maker = Pubkey::new_unique();
+        (
+            order(maker, Side::Buy, 1, SelfTradePrevention::CancelNewest),
+            order(maker, Side::Sell, 2, mode),
+        )
+    }
+
+    fn fill_result(matched: bool) -> Vec<u8> {
+        let mut result = vec![0u8; FILL_RESULT_LEN];
+        result[0..64].copy_from_slice(&[3u8; 64]);
+        result[64..128].copy_from_slice(&[4u8; 64]);
+        result[128..192].copy_from_slice(&[5u8; 64]);
+        result[192] = matched as u8;
+        result
+    }
+
+    fn apply(buy: &mut ConfidentialOrder, sell: &mut ConfidentialOrder, result: &[u8]) {
+        apply_fill_result(
+            buy,
+            sell,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            [7u8; 32],
+            result,
+            NOW,
+        );
+    }
+
+    #[test]
+    fn non_crossing_self_trade_leaves_orders_untouched() {
+        // Only decrement-mode self-trades are queued to the MPC
+        let (mut buy, mut sell) = self_trade_pair(SelfTradePrevention::Decrement);
+
+        apply(&mut buy, &mut sell, &fill_result(false));
+
+        for order in [&buy, &sell] {
+            assert!(!order.is_expired(NOW));
+            assert!(order.is_active());
+            assert!(!order.has_fills());
+            assert!(!order.fill_pending_settlement);
+            assert_eq!(order.escrow_remaining, 1_000);
+            assert!(!order.is_matching);
+        }
+    }
+
+    #[test]
+    fn crossing_self_trade_decrement_records_fill() {
+        let (mut buy, mut sell) = self_trade_pair(SelfTradePrevention::Decrement);
+
+        apply(&mut buy, &mut sell, &fill_result(true));
+
+        assert!(!buy.is_expired(NOW) && !sell.is_expired(NOW));
+        assert!(buy.shares_unsettled_fill(&sell));
+        assert_eq!(buy.encrypted_filled, [4u8; 64]);
+        assert_eq!(sell.encrypted_filled, [5u8; 64]);
+    }
+}
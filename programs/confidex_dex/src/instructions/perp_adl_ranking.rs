@@ -0,0 +1,465 @@
+use anchor_lang::prelude::*;
+
+use crate::cpi::arcium::{
+    queue_rank_adl_candidates, AdlRankPositionData, MxeCpiAccounts, ARCIUM_MXE_PROGRAM_ID,
+    MAX_ADL_RANK_POSITIONS,
+};
+use crate::error::ConfidexError;
+use crate::oracle::get_market_price;
+use crate::state::{
+    AdlRanking, AdlRoundProgress, ConfidentialPosition, ExchangeState, PerpetualMarket, PositionSide,
+};
+
+/// Number of Arcium MXE accounts at the front of remaining_accounts
+const MXE_ACCOUNT_COUNT: usize = 11;
+
+/// MXE authority PDA seed (must match MXE program)
+const MXE_AUTHORITY_SEED: &[u8] = b"mxe_authority";
+
+// ============================================================================
+// ADL RANKING (per-market ADL priority queue)
+// ============================================================================
+//
+// 1. Anyone calls refresh_adl_ranking() in chunks: the first chunk starts a
+//    round at the current mark price, and together the chunks must submit
+//    every open position of the market once, in ascending key order
+// 2. MPC rank_adl_candidates scores the round leaders plus each chunk's new
+//    positions by leverage * unrealized profit ratio and reveals only ranks
+// 3. adl_ranking_callback keeps the leaders of each side; once the round
+//    covers market.open_position_count positions it publishes the queues
+//    (fresh for MAX_AGE_SECS from completion, if the round finished within
+//    its time budget)
+// 4. execute_adl must target the head of the queue opposite the bankrupt
+//    position; advance_adl_ranking skips heads that have been closed
+
+/// Accounts for creating a market's ADL ranking account
+#[derive(Accounts)]
+pub struct InitializeAdlRanking<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+        has_one = authority @ ConfidexError::Unauthorized
+    )]
+    pub exchange: Account<'info, ExchangeState>,
+
+    #[account(
+        seeds = [PerpetualMarket::SEED, perp_market.underlying_mint.as_ref()],
+        bump = perp_market.bump
+    )]
+    pub perp_market: Account<'info, PerpetualMarket>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = AdlRanking::SIZE,
+        seeds = [AdlRanking::SEED, perp_market.key().as_ref()],
+        bump
+    )]
+    pub adl_ranking: Box<Account<'info, AdlRanking>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_adl_ranking_handler(ctx: Context<InitializeAdlRanking>) -> Result<()> {
+    let adl_ranking = &mut ctx.accounts.adl_ranking;
+
+    adl_ranking.market = ctx.accounts.perp_market.key();
+    adl_ranking.pending_request = [0u8; 32];
+    adl_ranking.candidates = [Pubkey::default(); AdlRanking::MAX_POSITIONS];
+    adl_ranking.candidate_is_long = [false; AdlRanking::MAX_POSITIONS];
+    adl_ranking.candidate_count = 0;
+    adl_ranking.pending_new_count = 0;
+    adl_ranking.pending_last_key = Pubkey::default();
+    adl_ranking.long_queue = [Pubkey::default(); AdlRanking::QUEUE_DEPTH];
+    adl_ranking.long_count = 0;
+    adl_ranking.long_head = 0;
+    adl_ranking.short_queue = [Pubkey::default(); AdlRanking::QUEUE_DEPTH];
+    adl_ranking.short_count = 0;
+    adl_ranking.short_head = 0;
+    adl_ranking.mark_price = 0;
+    adl_ranking.updated_at = 0;
+    adl_ranking.pending_queued_at = 0;
+    adl_ranking.start_round(0, 0, 0, 0);
+    adl_ranking.bump = ctx.bumps.adl_ranking;
+
+    msg!("ADL ranking initialized for market {}", adl_ranking.market);
+
+    Ok(())
+}
+
+/// Accounts for queueing one MPC chunk of a market's ADL ranking round
+///
+/// Permissionless: a round only publishes once it has covered every open
+/// position of the market, so the caller can't choose who gets ranked, and
+/// a round or chunk in flight can only be replaced once it has stalled.
+#[derive(Accounts)]
+pub struct RefreshAdlRanking<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+    )]
+    pub exchange: Box<Account<'info, ExchangeState>>,
+
+    #[account(
+        seeds = [PerpetualMarket::SEED, perp_market.underlying_mint.as_ref()],
+        bump = perp_market.bump
+    )]
+    pub perp_market: Box<Account<'info, PerpetualMarket>>,
+
+    /// CHECK: Primary oracle for current mark price (validated in oracle module)
+    pub oracle: AccountInfo<'info>,
+
+    /// CHECK: Secondary (fallback) oracle - required when the market configures one
+    pub secondary_oracle: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [AdlRanking::SEED, perp_market.key().as_ref()],
+        bump = adl_ranking.bump,
+    )]
+    pub adl_ranking: Box<Account<'info, AdlRanking>>,
+
+    /// Pays the MPC fee
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    // =========================================================================
+    // REMAINING ACCOUNTS
+    //   0..10: Arcium MXE accounts (same order as match_orders,
+    //          5: comp_def_account for rank_adl_candidates)
+    //   next:  the round leaders, in AdlRanking::round_leaders order
+    //   rest:  new open positions of this market, keys ascending and above
+    //          round_last_key (at least one; leaders + new <= 10)
+    // =========================================================================
+}
+
+/// Input parameters for refresh_adl_ranking instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RefreshAdlRankingParams {
+    /// Start a new round (restarts a round in progress only once it has
+    /// stalled for AdlRanking::CHUNK_TIMEOUT_SECS or can no longer complete)
+    pub new_round: bool,
+    /// Random seed for computation account derivation
+    pub computation_offset: u64,
+    /// X25519 public key for the position ciphertexts
+    pub pub_key: [u8; 32],
+    /// Encryption nonce
+    pub nonce: u128,
+}
+
+/// Load a position passed via remaining_accounts and check its market
+fn load_market_position(info: &AccountInfo, market: &Pubkey) -> Result<ConfidentialPosition> {
+    require!(info.owner == &crate::ID, ConfidexError::InvalidAccountData);
+
+    let position = {
+        let data = info.try_borrow_data()?;
+        ConfidentialPosition::try_deserialize(&mut &data[..])?
+    };
+
+    require!(position.market == *market, ConfidexError::InvalidFundingState);
+
+    Ok(position)
+}
+
+pub fn refresh_adl_ranking_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RefreshAdlRanking<'info>>,
+    params: RefreshAdlRankingParams,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let market_key = ctx.accounts.perp_market.key();
+    let open_position_count = ctx.accounts.perp_market.open_position_count;
+    let position_seed = ctx.accounts.perp_market.position_count;
+
+    require!(
+        ctx.accounts.adl_ranking.can_queue_chunk(clock.unix_timestamp),
+        ConfidexError::AdlRankingInFlight
+    );
+
+    if params.new_round {
+        require!(open_position_count > 0, ConfidexError::AdlRankingRoundInvalid);
+        require!(
+            ctx.accounts.adl_ranking.can_start_round(position_seed, clock.unix_timestamp),
+            ConfidexError::AdlRankingInFlight
+        );
+
+        // Every chunk of the round is ranked at this mark price (market price_decimals)
+        let mark_price = get_market_price(
+            &ctx.accounts.perp_market,
+            &ctx.accounts.oracle,
+            ctx.accounts.secondary_oracle.as_deref(),
+        )?;
+
+        ctx.accounts.adl_ranking.start_round(
+            open_position_count,
+            position_seed,
+            mark_price,
+            clock.unix_timestamp,
+        );
+    } else {
+        // A position opened since the round started could sort below
+        // round_last_key and be missed, so the round must restart
+        let adl_ranking = &ctx.accounts.adl_ranking;
+        require!(
+            adl_ranking.round_in_progress() && adl_ranking.round_position_seed == position_seed,
+            ConfidexError::AdlRankingRoundInvalid
+        );
+    }
+
+    let adl_ranking = &ctx.accounts.adl_ranking;
+    let leaders = adl_ranking.round_leaders();
+    let new_start = MXE_ACCOUNT_COUNT + leaders.len();
+
+    require!(
+        ctx.remaining_accounts.len() > new_start
+            && ctx.remaining_accounts.len() - MXE_ACCOUNT_COUNT <= MAX_ADL_RANK_POSITIONS,
+        ConfidexError::InvalidAccountCount
+    );
+
+    let leader_infos = &ctx.remaining_accounts[MXE_ACCOUNT_COUNT..new_start];
+    let new_infos = &ctx.remaining_accounts[new_start..];
+
+    require!(
+        adl_ranking.round_covered + new_infos.len() as u64 <= adl_ranking.round_open_count,
+        ConfidexError::AdlRankingCoverageInvalid
+    );
+
+    let mut candidates = Vec::with_capacity(MAX_ADL_RANK_POSITIONS);
+    let mut rank_inputs = Vec::with_capacity(MAX_ADL_RANK_POSITIONS);
+
+    // Leaders are re-ranked against the new positions; closed ones drop out
+    for (info, (leader, _)) in leader_infos.iter().zip(leaders.iter()) {
+        require!(info.key == leader, ConfidexError::AdlRankingCoverageInvalid);
+
+        let position = load_market_position(info, &market_key)?;
+        if !position.is_open() {
+            continue;
+        }
+
+        let is_long = matches!(position.side, PositionSide::Long);
+        rank_inputs.push(AdlRankPositionData {
+            encrypted_size: position.encrypted_size,
+            encrypted_entry_price: position.encrypted_entry_price,
+            encrypted_collateral: position.encrypted_collateral,
+            is_long,
+        });
+        candidates.push((*info.key, is_long));
+    }
+
+    // Strictly ascending keys make each position count once per round
+    let mut last_key = adl_ranking.round_last_key;
+    for info in new_infos.iter() {
+        require!(*info.key > last_key, ConfidexError::AdlRankingCoverageInvalid);
+
+        let position = load_market_position(info, &market_key)?;
+        require!(position.is_open(), ConfidexError::PositionNotOpen);
+
+        let is_long = matches!(position.side, PositionSide::Long);
+        rank_inputs.push(AdlRankPositionData {
+            encrypted_size: position.encrypted_size,
+            encrypted_entry_price: position.encrypted_entry_price,
+            encrypted_collateral: position.encrypted_collateral,
+            is_long,
+        });
+        candidates.push((*info.key, is_long));
+        last_key = *info.key;
+    }
+
+    let mark_price = adl_ranking.round_mark_price;
+    let adl_ranking_key = adl_ranking.key();
+
+    // Store AccountInfo in local variables to avoid lifetime issues
+    let payer_info = ctx.accounts.payer.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+
+    let mxe_accounts = MxeCpiAccounts {
+        payer: &payer_info,
+        sign_pda_account: &ctx.remaining_accounts[0],
+        mxe_account: &ctx.remaining_accounts[1],
+        mempool_account: &ctx.remaining_accounts[2],
+        executing_pool: &ctx.remaining_accounts[3],
+        computation_account: &ctx.remaining_accounts[4],
+        comp_def_account: &ctx.remaining_accounts[5],
+        cluster_account: &ctx.remaining_accounts[6],
+        pool_account: &ctx.remaining_accounts[7],
+        clock_account: &ctx.remaining_accounts[8],
+        system_program: &system_program_info,
+        arcium_program: &ctx.remaining_accounts[9],
+        mxe_program: &ctx.remaining_accounts[10],
+    };
+
+    // Result comes back via adl_ranking_callback from MXE
+    let queued = queue_rank_adl_candidates(
+        mxe_accounts,
+        params.computation_offset,
+        &rank_inputs,
+        mark_price,
+        &params.pub_key,
+        params.nonce,
+        &adl_ranking_key,
+    )?;
+
+    // The published queues stay usable until a round completes; a newer
+    // chunk only supersedes one in flight after CHUNK_TIMEOUT_SECS
+    let adl_ranking = &mut ctx.accounts.adl_ranking;
+    adl_ranking.set_pending(
+        queued.request_id,
+        &candidates,
+        new_infos.len() as u8,
+        last_key,
+        clock.unix_timestamp,
+    );
+
+    emit!(AdlRankingRefreshQueued {
+        market: market_key,
+        position_count: candidates.len() as u8,
+        mark_price,
+        request_id: queued.request_id,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "ADL ranking chunk queued via MPC: {} new positions ({}/{} covered) at mark price {}",
+        new_infos.len(),
+        adl_ranking.round_covered,
+        adl_ranking.round_open_count,
+        mark_price
+    );
+
+    Ok(())
+}
+
+/// Accounts for the MPC ADL ranking callback
+#[derive(Accounts)]
+pub struct AdlRankingCallback<'info> {
+    /// MXE authority PDA - must be the signer
+    /// This ensures only the MXE callback can reorder the ADL queues
+    #[account(
+        signer,
+        seeds = [MXE_AUTHORITY_SEED],
+        bump,
+        seeds::program = ARCIUM_MXE_PROGRAM_ID,
+    )]
+    pub mxe_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [AdlRanking::SEED, adl_ranking.market.as_ref()],
+        bump = adl_ranking.bump,
+    )]
+    pub adl_ranking: Box<Account<'info, AdlRanking>>,
+}
+
+/// Apply the MPC ranks of a ranking chunk to the round
+///
+/// ranks[i] is the queue position of the i-th submitted candidate
+/// (1 = deleveraged first, 0 = losing position, not eligible). Only the
+/// result of the latest chunk is accepted. The queues are published once
+/// the round has covered every open position, unless it overran
+/// AdlRanking::round_time_budget (its mark price is too old by then).
+pub fn adl_ranking_callback_handler(
+    ctx: Context<AdlRankingCallback>,
+    request_id: [u8; 32],
+    ranks: [u8; 10],
+) -> Result<()> {
+    let adl_ranking = &mut ctx.accounts.adl_ranking;
+    let clock = Clock::get()?;
+
+    require!(
+        adl_ranking.pending_request == request_id,
+        ConfidexError::InvalidMpcRequest
+    );
+
+    match adl_ranking.apply_ranks(&ranks, clock.unix_timestamp) {
+        AdlRoundProgress::Published => {}
+        AdlRoundProgress::InProgress => {
+            msg!(
+                "ADL ranking round progress: {}/{} positions",
+                adl_ranking.round_covered,
+                adl_ranking.round_open_count
+            );
+            return Ok(());
+        }
+        AdlRoundProgress::Expired => {
+            msg!("ADL ranking round overran its time budget; queues not updated");
+            return Ok(());
+        }
+    }
+
+    emit!(AdlRankingUpdated {
+        market: adl_ranking.market,
+        long_count: adl_ranking.long_count,
+        short_count: adl_ranking.short_count,
+        mark_price: adl_ranking.mark_price,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "ADL ranking updated: {} longs, {} shorts",
+        adl_ranking.long_count,
+        adl_ranking.short_count
+    );
+
+    Ok(())
+}
+
+/// Accounts for skipping a closed position at the head of an ADL queue
+/// Permissionless - the head can only be skipped once it is no longer open
+#[derive(Accounts)]
+#[instruction(is_long: bool)]
+pub struct AdvanceAdlRanking<'info> {
+    #[account(
+        mut,
+        seeds = [AdlRanking::SEED, adl_ranking.market.as_ref()],
+        bump = adl_ranking.bump,
+    )]
+    pub adl_ranking: Box<Account<'info, AdlRanking>>,
+
+    /// Current head of the queue
+    #[account(
+        constraint = adl_ranking.head(is_long) == Some(head_position.key()) @ ConfidexError::AdlTargetNotRanked,
+        constraint = !head_position.is_open() @ ConfidexError::AdlRankingHeadStillOpen
+    )]
+    pub head_position: Box<Account<'info, ConfidentialPosition>>,
+}
+
+pub fn advance_adl_ranking_handler(ctx: Context<AdvanceAdlRanking>, is_long: bool) -> Result<()> {
+    let adl_ranking = &mut ctx.accounts.adl_ranking;
+
+    adl_ranking.advance(is_long);
+
+    msg!(
+        "ADL ranking advanced past closed {} position {}",
+        if is_long { "long" } else { "short" },
+        ctx.accounts.head_position.key()
+    );
+
+    Ok(())
+}
+
+#[event]
+pub struct AdlRankingRefreshQueued {
+    pub market: Pubkey,
+    /// Number of positions submitted with the chunk (leaders + new)
+    pub position_count: u8,
+    /// Mark price the positions are ranked at
+    pub mark_price: u64,
+    /// Request ID for tracking MPC computation
+    pub request_id: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdlRankingUpdated {
+    pub market: Pubkey,
+    /// Number of ranked (profitable) long positions
+    pub long_count: u8,
+    /// Number of ranked (profitable) short positions
+    pub short_count: u8,
+    pub mark_price: u64,
+    pub timestamp: i64,
+}
@@ -1,9 +1,14 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
 
+use crate::cpi::arcium::{
+    queue_calculate_adl, AdlCallbackAccounts, AdlPositionData, MxeCpiAccounts,
+};
 use crate::error::ConfidexError;
-use crate::oracle::get_sol_usd_price;
+use crate::oracle::get_market_price;
 use crate::state::{
-    ConfidentialPosition, LiquidationConfig, PerpetualMarket, PositionSide, PositionStatus,
+    AdlRanking, ConfidentialPosition, LiquidationConfig, PerpetualMarket, PositionSide,
+    PositionStatus,
 };
 
 // ============================================================================
@@ -15,7 +20,11 @@ use crate::state::{
 // 1. Keeper periodically calls initiate_liquidation_check() via crank
 // 2. Crank triggers MPC batch_liquidation_check via MXE
 // 3. liquidation_check_callback sets position.is_liquidatable = true for each
-// 4. execute_adl reads the cached flag (no sync MPC needed at execution time)
+// 4. execute_adl reads the cached flag, requires the target to be at the head
+//    of the market's MPC-ranked ADL queue and queues MPC calculate_adl, which
+//    settles both positions at the bankrupt position's bankruptcy price
+// 5. adl_pnl_callback updates both positions' encrypted size/collateral,
+//    pays the target out of the collateral vault and updates open interest
 //
 // This is more efficient than per-position MPC calls during ADL execution.
 
@@ -43,7 +52,9 @@ pub struct ExecuteAdl<'info> {
         constraint = bankrupt_position.market == perp_market.key() @ ConfidexError::InvalidFundingState,
         constraint = bankrupt_position.is_open() @ ConfidexError::PositionNotOpen,
         // V6: Use cached liquidation status from MPC batch check
-        constraint = bankrupt_position.is_liquidatable @ ConfidexError::NotLiquidatable
+        constraint = bankrupt_position.is_liquidatable @ ConfidexError::NotLiquidatable,
+        constraint = !bankrupt_position.pending_close @ ConfidexError::PositionPendingClose,
+        constraint = !bankrupt_position.has_pending_mpc_request() @ ConfidexError::PositionHasPendingOperation
     )]
     pub bankrupt_position: Box<Account<'info, ConfidentialPosition>>,
 
@@ -60,7 +71,9 @@ pub struct ExecuteAdl<'info> {
         constraint = target_position.market == perp_market.key() @ ConfidexError::InvalidFundingState,
         constraint = target_position.is_open() @ ConfidexError::PositionNotOpen,
         // Target must be opposite side of bankrupt position
-        constraint = target_position.side != bankrupt_position.side @ ConfidexError::InvalidOrderSide
+        constraint = target_position.side != bankrupt_position.side @ ConfidexError::InvalidOrderSide,
+        constraint = !target_position.pending_close @ ConfidexError::PositionPendingClose,
+        constraint = !target_position.has_pending_mpc_request() @ ConfidexError::PositionHasPendingOperation
     )]
     pub target_position: Box<Account<'info, ConfidentialPosition>>,
 
@@ -71,63 +84,112 @@ pub struct ExecuteAdl<'info> {
     )]
     pub liquidation_config: Box<Account<'info, LiquidationConfig>>,
 
-    /// CHECK: Pyth oracle for current mark price
+    /// Market's ADL queue - the target must be at its head
     #[account(
-        constraint = oracle.key() == perp_market.oracle_price_feed @ ConfidexError::InvalidOraclePrice
+        seeds = [AdlRanking::SEED, perp_market.key().as_ref()],
+        bump = adl_ranking.bump,
     )]
+    pub adl_ranking: Box<Account<'info, AdlRanking>>,
+
+    /// CHECK: Primary oracle for current mark price (validated in oracle module)
     pub oracle: AccountInfo<'info>,
 
-    /// CHECK: Market's confidential collateral vault
+    /// CHECK: Secondary (fallback) oracle - required when the market configures one
+    pub secondary_oracle: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Market's confidential collateral vault (target payout source)
     #[account(
         mut,
         constraint = collateral_vault.key() == perp_market.collateral_vault @ ConfidexError::InvalidVault
     )]
     pub collateral_vault: AccountInfo<'info>,
 
-    /// CHECK: Insurance fund (must be depleted for ADL to trigger)
+    /// Market's insurance fund token account (must be depleted for ADL to trigger)
+    #[account(
+        constraint = insurance_fund.key() == perp_market.insurance_fund @ ConfidexError::InvalidVault,
+        constraint = insurance_fund.mint == perp_market.quote_mint @ ConfidexError::InvalidMint
+    )]
+    pub insurance_fund: Box<Account<'info, TokenAccount>>,
+
+    /// Target trader's collateral token account (receives the ADL payout)
+    #[account(
+        constraint = target_collateral_account.mint == perp_market.quote_mint @ ConfidexError::InvalidMint,
+        constraint = target_collateral_account.owner == target_position.trader @ ConfidexError::InvalidOwner
+    )]
+    pub target_collateral_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Vault authority PDA (signs the payout in adl_pnl_callback)
     #[account(
-        constraint = insurance_fund.key() == liquidation_config.insurance_fund @ ConfidexError::InvalidVault
+        seeds = [b"vault", perp_market.key().as_ref()],
+        bump
     )]
-    pub insurance_fund: AccountInfo<'info>,
+    pub vault_authority: UncheckedAccount<'info>,
 
-    /// Keeper/admin that triggers ADL
+    /// Keeper/admin that triggers ADL (also pays the MPC fee)
     #[account(mut)]
     pub keeper: Signer<'info>,
 
+    pub token_program: Program<'info, Token>,
+
     pub system_program: Program<'info, System>,
+
+    // =========================================================================
+    // ARCIUM MXE ACCOUNTS (11 accounts via remaining_accounts)
+    // Same layout as match_orders (5: comp_def_account for calculate_adl)
+    // =========================================================================
 }
 
-/// Execute auto-deleverage using cached liquidation status
+/// Input parameters for execute_adl instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ExecuteAdlParams {
+    /// Random seed for computation account derivation
+    pub computation_offset: u64,
+    /// MXE public key for encryption
+    pub mxe_pub_key: [u8; 32],
+    /// Nonce for MXE encryption
+    pub nonce: u128,
+}
+
+/// Checks and MPC queueing shared by execute_adl and the legacy handler
 ///
-/// V6: This instruction uses the is_liquidatable flag that was set by
-/// the batch liquidation check MPC callback. No sync MPC calls needed.
-pub fn execute_adl(ctx: Context<ExecuteAdl>) -> Result<()> {
+/// Both positions are locked with the request ID until adl_pnl_callback
+/// applies the settlement. The bankrupt position keeps is_liquidatable so a
+/// partially deleveraged position can be matched against further targets.
+fn queue_adl_settlement<'info>(
+    perp_market: &mut PerpetualMarket,
+    bankrupt_position: &mut ConfidentialPosition,
+    target_position: &mut ConfidentialPosition,
+    target_key: Pubkey,
+    liquidation_config: &LiquidationConfig,
+    adl_ranking: &AdlRanking,
+    insurance_balance: u64,
+    mark_price: u64,
+    mxe_accounts: MxeCpiAccounts<'_, 'info>,
+    callback: AdlCallbackAccounts,
+    params: &ExecuteAdlParams,
+) -> Result<()> {
     let clock = Clock::get()?;
-    let perp_market = &mut ctx.accounts.perp_market;
-    let bankrupt_position = &mut ctx.accounts.bankrupt_position;
-    let target_position = &mut ctx.accounts.target_position;
-    let liquidation_config = &ctx.accounts.liquidation_config;
-
-    // Verify insurance fund is below ADL trigger threshold
-    let insurance_balance = ctx.accounts.insurance_fund.lamports();
-    let adl_threshold = perp_market
-        .insurance_fund_target
-        .saturating_mul(liquidation_config.adl_trigger_threshold_bps as u64)
-        .saturating_div(10000);
+
+    // Refresh the snapshot from the fund's token balance
+    perp_market.insurance_fund_balance = insurance_balance;
+
+    // Verify insurance fund is below ADL trigger threshold (quote token units)
     require!(
-        insurance_balance < adl_threshold,
+        liquidation_config.should_trigger_adl(insurance_balance, perp_market.insurance_fund_target),
         ConfidexError::InsuranceFundNotDepleted
     );
 
-    // Verify target position has high ADL priority (most profitable)
+    // Target must be the most profitable remaining position on its side,
+    // as ranked by MPC rank_adl_candidates
     require!(
-        target_position.auto_deleverage_priority > 0,
-        ConfidexError::InvalidAdlThreshold
+        adl_ranking.is_fresh(clock.unix_timestamp),
+        ConfidexError::AdlRankingStale
+    );
+    let target_is_long = matches!(target_position.side, PositionSide::Long);
+    require!(
+        adl_ranking.head(target_is_long) == Some(target_key),
+        ConfidexError::AdlTargetNotRanked
     );
-
-    // Get mark price from oracle for settlement price (6 decimal precision)
-    let mark_price = get_sol_usd_price(&ctx.accounts.oracle)?;
-    msg!("ADL settlement mark price: {}", mark_price);
 
     // V6: Liquidation eligibility already verified via cached is_liquidatable flag
     // The constraint check above ensures bankrupt_position.is_liquidatable == true
@@ -135,33 +197,48 @@ pub fn execute_adl(ctx: Context<ExecuteAdl>) -> Result<()> {
         "ADL executing: bankrupt position is_liquidatable=true (verified by MPC batch check)"
     );
 
+    // Settlement happens at the bankruptcy price computed in MPC; the mark
+    // price is only recorded for monitoring
+    let queued = queue_calculate_adl(
+        mxe_accounts,
+        params.computation_offset,
+        &AdlPositionData {
+            encrypted_size: bankrupt_position.encrypted_size,
+            encrypted_entry_price: bankrupt_position.encrypted_entry_price,
+            encrypted_collateral: bankrupt_position.encrypted_collateral,
+        },
+        &AdlPositionData {
+            encrypted_size: target_position.encrypted_size,
+            encrypted_entry_price: target_position.encrypted_entry_price,
+            encrypted_collateral: target_position.encrypted_collateral,
+        },
+        matches!(bankrupt_position.side, PositionSide::Long),
+        &params.mxe_pub_key,
+        params.nonce,
+        &callback,
+    )?;
+
     let coarse_time = ConfidentialPosition::coarse_timestamp(clock.unix_timestamp);
 
-    // Mark bankrupt position as auto-deleveraged
-    bankrupt_position.status = PositionStatus::AutoDeleveraged;
+    bankrupt_position.pending_mpc_request = queued.request_id;
     bankrupt_position.last_updated_hour = coarse_time;
-    bankrupt_position.is_liquidatable = false; // Clear flag
 
-    // Update target position (partial close via ADL)
+    target_position.pending_mpc_request = queued.request_id;
     target_position.last_updated_hour = coarse_time;
-    target_position.partial_close_count = target_position.partial_close_count.saturating_add(1);
-
-    // NOTE: Full PnL calculation and collateral transfer would require another MPC call
-    // For hackathon, we use plaintext values to complete the transfer
-    // In production, this would be another async MPC flow
 
-    emit!(AutoDeleverageExecuted {
+    emit!(AutoDeleverageQueued {
         bankrupt_position_id: bankrupt_position.position_id,
         bankrupt_trader: bankrupt_position.trader,
         target_position_id: target_position.position_id,
         target_trader: target_position.trader,
-        market: perp_market.key(),
+        market: callback.perp_market,
         mark_price,
+        request_id: queued.request_id,
         timestamp: coarse_time,
     });
 
     msg!(
-        "ADL executed: bankrupt position {} #{:?} covered by target position {} #{:?}",
+        "ADL queued via MPC: bankrupt position {} #{:?} against target position {} #{:?}",
         bankrupt_position.trader,
         bankrupt_position.position_id,
         target_position.trader,
@@ -171,14 +248,87 @@ pub fn execute_adl(ctx: Context<ExecuteAdl>) -> Result<()> {
     Ok(())
 }
 
+/// Execute auto-deleverage using cached liquidation status
+///
+/// V6: This instruction uses the is_liquidatable flag that was set by
+/// the batch liquidation check MPC callback. The settlement itself (sizes,
+/// collateral, payout) is computed by MPC calculate_adl and applied by
+/// adl_pnl_callback.
+pub fn execute_adl<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteAdl<'info>>,
+    params: ExecuteAdlParams,
+) -> Result<()> {
+    require!(
+        ctx.remaining_accounts.len() >= 11,
+        ConfidexError::InvalidAccountCount
+    );
+
+    // Get mark price from oracle for monitoring (market price_decimals)
+    let mark_price = get_market_price(
+        &ctx.accounts.perp_market,
+        &ctx.accounts.oracle,
+        ctx.accounts.secondary_oracle.as_deref(),
+    )?;
+    msg!("ADL mark price: {}", mark_price);
+
+    let callback = AdlCallbackAccounts {
+        bankrupt_position: ctx.accounts.bankrupt_position.key(),
+        target_position: ctx.accounts.target_position.key(),
+        perp_market: ctx.accounts.perp_market.key(),
+        target_collateral_account: ctx.accounts.target_collateral_account.key(),
+        collateral_vault: ctx.accounts.collateral_vault.key(),
+        vault_authority: ctx.accounts.vault_authority.key(),
+        token_program: ctx.accounts.token_program.key(),
+    };
+    let insurance_balance = ctx.accounts.insurance_fund.amount;
+
+    // Store AccountInfo in local variables to avoid lifetime issues
+    let payer_info = ctx.accounts.keeper.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+
+    let mxe_accounts = MxeCpiAccounts {
+        payer: &payer_info,
+        sign_pda_account: &ctx.remaining_accounts[0],
+        mxe_account: &ctx.remaining_accounts[1],
+        mempool_account: &ctx.remaining_accounts[2],
+        executing_pool: &ctx.remaining_accounts[3],
+        computation_account: &ctx.remaining_accounts[4],
+        comp_def_account: &ctx.remaining_accounts[5],
+        cluster_account: &ctx.remaining_accounts[6],
+        pool_account: &ctx.remaining_accounts[7],
+        clock_account: &ctx.remaining_accounts[8],
+        system_program: &system_program_info,
+        arcium_program: &ctx.remaining_accounts[9],
+        mxe_program: &ctx.remaining_accounts[10],
+    };
+
+    queue_adl_settlement(
+        &mut ctx.accounts.perp_market,
+        &mut ctx.accounts.bankrupt_position,
+        &mut ctx.accounts.target_position,
+        callback.target_position,
+        &ctx.accounts.liquidation_config,
+        &ctx.accounts.adl_ranking,
+        insurance_balance,
+        mark_price,
+        mxe_accounts,
+        callback,
+        &params,
+    )
+}
+
+/// Emitted when ADL settlement is queued (result via adl_pnl_callback)
 #[event]
-pub struct AutoDeleverageExecuted {
+pub struct AutoDeleverageQueued {
     pub bankrupt_position_id: [u8; 16],
     pub bankrupt_trader: Pubkey,
     pub target_position_id: [u8; 16],
     pub target_trader: Pubkey,
     pub market: Pubkey,
+    /// Oracle mark price at queue time (settlement uses the bankruptcy price)
     pub mark_price: u64,
+    /// Request ID for tracking MPC computation
+    pub request_id: [u8; 32],
     pub timestamp: i64,
 }
 
@@ -195,12 +345,12 @@ pub struct InitiateLiquidationCheck<'info> {
     )]
     pub perp_market: Account<'info, PerpetualMarket>,
 
-    /// CHECK: Pyth oracle for current mark price
-    #[account(
-        constraint = oracle.key() == perp_market.oracle_price_feed @ ConfidexError::InvalidOraclePrice
-    )]
+    /// CHECK: Primary oracle for current mark price (validated in oracle module)
     pub oracle: AccountInfo<'info>,
 
+    /// CHECK: Secondary (fallback) oracle - required when the market configures one
+    pub secondary_oracle: Option<UncheckedAccount<'info>>,
+
     /// Keeper that triggers the check
     #[account(mut)]
     pub keeper: Signer<'info>,
@@ -217,7 +367,11 @@ pub fn initiate_liquidation_check(ctx: Context<InitiateLiquidationCheck>) -> Res
     let clock = Clock::get()?;
 
     // Get current mark price
-    let mark_price = get_sol_usd_price(&ctx.accounts.oracle)?;
+    let mark_price = get_market_price(
+        &ctx.accounts.perp_market,
+        &ctx.accounts.oracle,
+        ctx.accounts.secondary_oracle.as_deref(),
+    )?;
 
     // Generate request ID for this batch
     let request_id = ConfidentialPosition::generate_request_id(
@@ -300,7 +454,9 @@ pub struct AutoDeleverage<'info> {
         bump = bankrupt_position.bump,
         constraint = bankrupt_position.market == perp_market.key() @ ConfidexError::InvalidFundingState,
         constraint = bankrupt_position.is_open() @ ConfidexError::PositionNotOpen,
-        constraint = bankrupt_position.is_liquidatable @ ConfidexError::NotLiquidatable
+        constraint = bankrupt_position.is_liquidatable @ ConfidexError::NotLiquidatable,
+        constraint = !bankrupt_position.pending_close @ ConfidexError::PositionPendingClose,
+        constraint = !bankrupt_position.has_pending_mpc_request() @ ConfidexError::PositionHasPendingOperation
     )]
     pub bankrupt_position: Box<Account<'info, ConfidentialPosition>>,
 
@@ -315,7 +471,9 @@ pub struct AutoDeleverage<'info> {
         bump = target_position.bump,
         constraint = target_position.market == perp_market.key() @ ConfidexError::InvalidFundingState,
         constraint = target_position.is_open() @ ConfidexError::PositionNotOpen,
-        constraint = target_position.side != bankrupt_position.side @ ConfidexError::InvalidOrderSide
+        constraint = target_position.side != bankrupt_position.side @ ConfidexError::InvalidOrderSide,
+        constraint = !target_position.pending_close @ ConfidexError::PositionPendingClose,
+        constraint = !target_position.has_pending_mpc_request() @ ConfidexError::PositionHasPendingOperation
     )]
     pub target_position: Box<Account<'info, ConfidentialPosition>>,
 
@@ -326,12 +484,19 @@ pub struct AutoDeleverage<'info> {
     )]
     pub liquidation_config: Box<Account<'info, LiquidationConfig>>,
 
-    /// CHECK: Pyth oracle
+    /// Market's ADL queue - the target must be at its head
     #[account(
-        constraint = oracle.key() == perp_market.oracle_price_feed @ ConfidexError::InvalidOraclePrice
+        seeds = [AdlRanking::SEED, perp_market.key().as_ref()],
+        bump = adl_ranking.bump,
     )]
+    pub adl_ranking: Box<Account<'info, AdlRanking>>,
+
+    /// CHECK: Primary oracle (validated in oracle module)
     pub oracle: AccountInfo<'info>,
 
+    /// CHECK: Secondary (fallback) oracle - required when the market configures one
+    pub secondary_oracle: Option<UncheckedAccount<'info>>,
+
     /// CHECK: Collateral vault
     #[account(
         mut,
@@ -339,61 +504,92 @@ pub struct AutoDeleverage<'info> {
     )]
     pub collateral_vault: AccountInfo<'info>,
 
-    /// CHECK: Insurance fund
+    /// Market's insurance fund token account
+    #[account(
+        constraint = insurance_fund.key() == perp_market.insurance_fund @ ConfidexError::InvalidVault,
+        constraint = insurance_fund.mint == perp_market.quote_mint @ ConfidexError::InvalidMint
+    )]
+    pub insurance_fund: Box<Account<'info, TokenAccount>>,
+
     #[account(
-        constraint = insurance_fund.key() == liquidation_config.insurance_fund @ ConfidexError::InvalidVault
+        constraint = target_collateral_account.mint == perp_market.quote_mint @ ConfidexError::InvalidMint,
+        constraint = target_collateral_account.owner == target_position.trader @ ConfidexError::InvalidOwner
     )]
-    pub insurance_fund: AccountInfo<'info>,
+    pub target_collateral_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Vault authority PDA
+    #[account(
+        seeds = [b"vault", perp_market.key().as_ref()],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
 
     #[account(mut)]
     pub keeper: Signer<'info>,
 
+    pub token_program: Program<'info, Token>,
+
     pub system_program: Program<'info, System>,
+    // Arcium MXE accounts via remaining_accounts (same as ExecuteAdl)
 }
 
 /// Legacy handler - same logic as execute_adl
-pub fn handler(ctx: Context<AutoDeleverage>) -> Result<()> {
-    let clock = Clock::get()?;
-    let perp_market = &mut ctx.accounts.perp_market;
-    let bankrupt_position = &mut ctx.accounts.bankrupt_position;
-    let target_position = &mut ctx.accounts.target_position;
-    let liquidation_config = &ctx.accounts.liquidation_config;
-
-    // Same logic as execute_adl
-    let insurance_balance = ctx.accounts.insurance_fund.lamports();
-    let adl_threshold = perp_market
-        .insurance_fund_target
-        .saturating_mul(liquidation_config.adl_trigger_threshold_bps as u64)
-        .saturating_div(10000);
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AutoDeleverage<'info>>,
+    params: ExecuteAdlParams,
+) -> Result<()> {
     require!(
-        insurance_balance < adl_threshold,
-        ConfidexError::InsuranceFundNotDepleted
+        ctx.remaining_accounts.len() >= 11,
+        ConfidexError::InvalidAccountCount
     );
 
-    require!(
-        target_position.auto_deleverage_priority > 0,
-        ConfidexError::InvalidAdlThreshold
-    );
-
-    let mark_price = get_sol_usd_price(&ctx.accounts.oracle)?;
-    let coarse_time = ConfidentialPosition::coarse_timestamp(clock.unix_timestamp);
-
-    bankrupt_position.status = PositionStatus::AutoDeleveraged;
-    bankrupt_position.last_updated_hour = coarse_time;
-    bankrupt_position.is_liquidatable = false;
-
-    target_position.last_updated_hour = coarse_time;
-    target_position.partial_close_count = target_position.partial_close_count.saturating_add(1);
-
-    emit!(AutoDeleverageExecuted {
-        bankrupt_position_id: bankrupt_position.position_id,
-        bankrupt_trader: bankrupt_position.trader,
-        target_position_id: target_position.position_id,
-        target_trader: target_position.trader,
-        market: perp_market.key(),
+    let mark_price = get_market_price(
+        &ctx.accounts.perp_market,
+        &ctx.accounts.oracle,
+        ctx.accounts.secondary_oracle.as_deref(),
+    )?;
+
+    let callback = AdlCallbackAccounts {
+        bankrupt_position: ctx.accounts.bankrupt_position.key(),
+        target_position: ctx.accounts.target_position.key(),
+        perp_market: ctx.accounts.perp_market.key(),
+        target_collateral_account: ctx.accounts.target_collateral_account.key(),
+        collateral_vault: ctx.accounts.collateral_vault.key(),
+        vault_authority: ctx.accounts.vault_authority.key(),
+        token_program: ctx.accounts.token_program.key(),
+    };
+    let insurance_balance = ctx.accounts.insurance_fund.amount;
+
+    let payer_info = ctx.accounts.keeper.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+
+    let mxe_accounts = MxeCpiAccounts {
+        payer: &payer_info,
+        sign_pda_account: &ctx.remaining_accounts[0],
+        mxe_account: &ctx.remaining_accounts[1],
+        mempool_account: &ctx.remaining_accounts[2],
+        executing_pool: &ctx.remaining_accounts[3],
+        computation_account: &ctx.remaining_accounts[4],
+        comp_def_account: &ctx.remaining_accounts[5],
+        cluster_account: &ctx.remaining_accounts[6],
+        pool_account: &ctx.remaining_accounts[7],
+        clock_account: &ctx.remaining_accounts[8],
+        system_program: &system_program_info,
+        arcium_program: &ctx.remaining_accounts[9],
+        mxe_program: &ctx.remaining_accounts[10],
+    };
+
+    queue_adl_settlement(
+        &mut ctx.accounts.perp_market,
+        &mut ctx.accounts.bankrupt_position,
+        &mut ctx.accounts.target_position,
+        callback.target_position,
+        &ctx.accounts.liquidation_config,
+        &ctx.accounts.adl_ranking,
+        insurance_balance,
         mark_price,
-        timestamp: coarse_time,
-    });
-
-    Ok(())
+        mxe_accounts,
+        callback,
+        &params,
+    )
 }
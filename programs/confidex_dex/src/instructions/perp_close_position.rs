@@ -21,7 +21,7 @@ use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 
 use crate::cpi::arcium::{calculate_pnl, MxeCpiAccounts};
 use crate::error::ConfidexError;
-use crate::oracle::get_sol_usd_price;
+use crate::oracle::get_market_price;
 use crate::state::{ConfidentialPosition, PerpetualMarket, PositionSide, PositionStatus};
 
 /// Accounts for initiating position close (Phase 1)
@@ -52,12 +52,12 @@ pub struct InitiateClosePosition<'info> {
     )]
     pub position: Box<Account<'info, ConfidentialPosition>>,
 
-    /// CHECK: Pyth oracle for mark price / exit price
-    #[account(
-        constraint = oracle.key() == perp_market.oracle_price_feed @ ConfidexError::InvalidOraclePrice
-    )]
+    /// CHECK: Primary oracle for mark price / exit price (validated in oracle module)
     pub oracle: AccountInfo<'info>,
 
+    /// CHECK: Secondary (fallback) oracle - required when the market configures one
+    pub secondary_oracle: Option<UncheckedAccount<'info>>,
+
     #[account(mut)]
     pub trader: Signer<'info>,
 
@@ -132,13 +132,51 @@ pub struct InitiateClosePositionParams {
 pub fn initiate_close_position(
     ctx: Context<InitiateClosePosition>,
     params: InitiateClosePositionParams,
+) -> Result<()> {
+    // Build MXE CPI accounts
+    let payer_info = ctx.accounts.trader.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+    let mxe_accounts = MxeCpiAccounts {
+        payer: &payer_info,
+        sign_pda_account: &ctx.accounts.mxe_sign_pda,
+        mxe_account: &ctx.accounts.mxe_account,
+        mempool_account: &ctx.accounts.mempool_account,
+        executing_pool: &ctx.accounts.executing_pool,
+        computation_account: &ctx.accounts.computation_account,
+        comp_def_account: &ctx.accounts.comp_def_account,
+        cluster_account: &ctx.accounts.cluster_account,
+        pool_account: &ctx.accounts.pool_account,
+        clock_account: &ctx.accounts.clock_account,
+        system_program: &system_program_info,
+        arcium_program: &ctx.accounts.arcium_program,
+        mxe_program: &ctx.accounts.mxe_program,
+    };
+
+    queue_position_close(
+        &mut ctx.accounts.position,
+        &ctx.accounts.perp_market,
+        &ctx.accounts.oracle,
+        ctx.accounts.secondary_oracle.as_deref(),
+        mxe_accounts,
+        &params,
+    )
+}
+
+/// Capture the exit price, queue the MPC PnL calculation and mark the
+/// position pending close. Shared by trader-initiated closes and keeper
+/// closes of positions whose stop-loss / take-profit fired.
+pub(crate) fn queue_position_close<'info>(
+    position: &mut Account<'info, ConfidentialPosition>,
+    perp_market: &PerpetualMarket,
+    oracle: &AccountInfo<'info>,
+    secondary_oracle: Option<&AccountInfo<'info>>,
+    mxe_accounts: MxeCpiAccounts<'_, 'info>,
+    params: &InitiateClosePositionParams,
 ) -> Result<()> {
     let clock = Clock::get()?;
-    let position = &mut ctx.accounts.position;
-    let perp_market = &ctx.accounts.perp_market;
 
     // Fetch current oracle price for exit
-    let exit_price = get_sol_usd_price(&ctx.accounts.oracle)?;
+    let exit_price = get_market_price(perp_market, oracle, secondary_oracle)?;
 
     // Calculate funding owed since position was opened
     let current_cumulative_funding = match position.side {
@@ -155,23 +193,6 @@ pub fn initiate_close_position(
         _funding_delta
     );
 
-    // Build MXE CPI accounts
-    let mxe_accounts = MxeCpiAccounts {
-        payer: &ctx.accounts.trader.to_account_info(),
-        sign_pda_account: &ctx.accounts.mxe_sign_pda,
-        mxe_account: &ctx.accounts.mxe_account,
-        mempool_account: &ctx.accounts.mempool_account,
-        executing_pool: &ctx.accounts.executing_pool,
-        computation_account: &ctx.accounts.computation_account,
-        comp_def_account: &ctx.accounts.comp_def_account,
-        cluster_account: &ctx.accounts.cluster_account,
-        pool_account: &ctx.accounts.pool_account,
-        clock_account: &ctx.accounts.clock_account,
-        system_program: &ctx.accounts.system_program.to_account_info(),
-        arcium_program: &ctx.accounts.arcium_program,
-        mxe_program: &ctx.accounts.mxe_program,
-    };
-
     // Queue MPC PnL calculation
     let is_long = matches!(position.side, PositionSide::Long);
     let queued = calculate_pnl(
@@ -264,12 +285,12 @@ pub struct ClosePosition<'info> {
     )]
     pub position: Box<Account<'info, ConfidentialPosition>>,
 
-    /// CHECK: Pyth oracle for mark price / exit price
-    #[account(
-        constraint = oracle.key() == perp_market.oracle_price_feed @ ConfidexError::InvalidOraclePrice
-    )]
+    /// CHECK: Primary oracle for mark price / exit price (validated in oracle module)
     pub oracle: AccountInfo<'info>,
 
+    /// CHECK: Secondary (fallback) oracle - required when the market configures one
+    pub secondary_oracle: Option<UncheckedAccount<'info>>,
+
     /// Trader's USDC token account
     #[account(
         mut,
@@ -345,7 +366,11 @@ pub fn handler(ctx: Context<ClosePosition>, params: ClosePositionParams) -> Resu
     );
 
     // Fetch current oracle price for exit
-    let exit_price = get_sol_usd_price(&ctx.accounts.oracle)?;
+    let exit_price = get_market_price(
+        perp_market,
+        &ctx.accounts.oracle,
+        ctx.accounts.secondary_oracle.as_deref(),
+    )?;
 
     // Read plaintext position data
     let position_size = position.get_size_plaintext();
@@ -431,6 +456,7 @@ pub fn handler(ctx: Context<ClosePosition>, params: ClosePositionParams) -> Resu
 
     // Update position state
     position.status = PositionStatus::Closed;
+    ctx.accounts.perp_market.record_position_closed();
     position.set_realized_pnl_plaintext(pnl);
     let coarse_time = ConfidentialPosition::coarse_timestamp(clock.unix_timestamp);
     position.last_updated_hour = coarse_time;
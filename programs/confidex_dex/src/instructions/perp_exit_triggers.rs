@@ -0,0 +1,501 @@
+use anchor_lang::prelude::*;
+
+use crate::cpi::arcium::{
+    queue_batch_exit_trigger_check, BatchExitTriggerPositionData, MxeCpiAccounts,
+    ARCIUM_MXE_PROGRAM_ID, MAX_BATCH_EXIT_TRIGGER_POSITIONS,
+};
+use crate::error::ConfidexError;
+use crate::oracle::get_market_price;
+use crate::state::{ConfidentialPosition, PerpetualMarket, PositionSide};
+use super::perp_close_position::{queue_position_close, InitiateClosePositionParams};
+
+/// MXE authority PDA seed (must match MXE program)
+const MXE_AUTHORITY_SEED: &[u8] = b"mxe_authority";
+
+// ============================================================================
+// STOP-LOSS / TAKE-PROFIT TRIGGERS (async MPC)
+// ============================================================================
+//
+// 1. set_exit_triggers stores the trader's encrypted stop-loss / take-profit
+// 2. check_exit_triggers (permissionless) queues MPC batch_exit_trigger_check
+//    for up to 10 positions at the current mark price
+// 3. exit_trigger_callback marks positions whose trigger fired - only the
+//    fact that one fired is revealed, not which one or at what price
+// 4. execute_exit_trigger (permissionless) runs the regular full close flow
+//    for a fired position, with the keeper paying the MPC fees
+
+/// Accounts for setting or clearing a position's exit triggers (trader only)
+#[derive(Accounts)]
+pub struct SetExitTriggers<'info> {
+    #[account(
+        seeds = [PerpetualMarket::SEED, perp_market.underlying_mint.as_ref()],
+        bump = perp_market.bump,
+    )]
+    pub perp_market: Box<Account<'info, PerpetualMarket>>,
+
+    #[account(
+        mut,
+        seeds = [
+            ConfidentialPosition::SEED,
+            trader.key().as_ref(),
+            perp_market.key().as_ref(),
+            &position.position_seed.to_le_bytes()
+        ],
+        bump = position.bump,
+        constraint = position.trader == trader.key() @ ConfidexError::Unauthorized,
+        constraint = position.market == perp_market.key() @ ConfidexError::InvalidFundingState,
+        constraint = position.is_open() @ ConfidexError::PositionNotOpen,
+        constraint = !position.pending_close @ ConfidexError::PositionPendingClose
+    )]
+    pub position: Box<Account<'info, ConfidentialPosition>>,
+
+    pub trader: Signer<'info>,
+}
+
+/// Parameters for setting exit triggers
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetExitTriggersParams {
+    /// Encrypted stop-loss price (None removes it)
+    /// Must be encrypted under the position's ephemeral_pubkey
+    pub encrypted_stop_loss: Option<[u8; 64]>,
+    /// Encrypted take-profit price (None removes it)
+    /// Must be encrypted under the position's ephemeral_pubkey
+    pub encrypted_take_profit: Option<[u8; 64]>,
+}
+
+/// Replace the position's stop-loss and take-profit
+///
+/// Any check already in flight or trigger already fired is discarded, since
+/// it was evaluated against the old prices.
+pub fn set_exit_triggers_handler(
+    ctx: Context<SetExitTriggers>,
+    params: SetExitTriggersParams,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let position = &mut ctx.accounts.position;
+
+    position.clear_exit_triggers();
+
+    if let Some(stop_loss) = params.encrypted_stop_loss {
+        position.encrypted_stop_loss = stop_loss;
+        position.has_stop_loss = true;
+    }
+    if let Some(take_profit) = params.encrypted_take_profit {
+        position.encrypted_take_profit = take_profit;
+        position.has_take_profit = true;
+    }
+
+    let coarse_time = ConfidentialPosition::coarse_timestamp(clock.unix_timestamp);
+    position.last_updated_hour = coarse_time;
+
+    emit!(ExitTriggersUpdated {
+        position: position.key(),
+        trader: position.trader,
+        market: position.market,
+        has_stop_loss: position.has_stop_loss,
+        has_take_profit: position.has_take_profit,
+        timestamp: coarse_time,
+    });
+
+    msg!(
+        "Exit triggers updated: stop_loss={}, take_profit={}",
+        position.has_stop_loss,
+        position.has_take_profit
+    );
+
+    Ok(())
+}
+
+/// Accounts for queueing a batch exit trigger check (permissionless)
+///
+/// remaining_accounts layout:
+///   0..position_count: Position accounts to check (all on perp_market)
+///   position_count..position_count+11: MXE accounts (same order as MatchOrders)
+#[derive(Accounts)]
+pub struct CheckExitTriggers<'info> {
+    #[account(
+        seeds = [PerpetualMarket::SEED, perp_market.underlying_mint.as_ref()],
+        bump = perp_market.bump,
+    )]
+    pub perp_market: Box<Account<'info, PerpetualMarket>>,
+
+    /// CHECK: Primary oracle for current mark price (validated in oracle module)
+    pub oracle: AccountInfo<'info>,
+
+    /// CHECK: Secondary (fallback) oracle - required when the market configures one
+    pub secondary_oracle: Option<UncheckedAccount<'info>>,
+
+    /// Keeper (pays the MPC fees)
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    // =========================================================================
+    // ARCIUM MXE ACCOUNTS (11 accounts via remaining_accounts)
+    // Same layout as match_orders (5: comp_def_account for batch_exit_trigger_check)
+    // =========================================================================
+}
+
+/// Parameters for batch exit trigger check
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CheckExitTriggersParams {
+    /// Position pubkeys to check (up to 10)
+    pub position_keys: Vec<Pubkey>,
+    /// Random seed for computation account derivation
+    pub computation_offset: u64,
+    /// X25519 public key for output encryption
+    pub pub_key: [u8; 32],
+    /// Encryption nonce
+    pub nonce: u128,
+}
+
+pub fn check_exit_triggers_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CheckExitTriggers<'info>>,
+    params: CheckExitTriggersParams,
+) -> Result<()> {
+    let position_count = params.position_keys.len();
+    require!(
+        position_count > 0 && position_count <= MAX_BATCH_EXIT_TRIGGER_POSITIONS,
+        ConfidexError::InvalidAmount
+    );
+
+    let remaining_accounts = ctx.remaining_accounts;
+    require!(
+        remaining_accounts.len() >= position_count + 11,
+        ConfidexError::InvalidAccountCount
+    );
+
+    let mark_price = get_market_price(
+        &ctx.accounts.perp_market,
+        &ctx.accounts.oracle,
+        ctx.accounts.secondary_oracle.as_deref(),
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let market_key = ctx.accounts.perp_market.key();
+    let mut position_data: Vec<BatchExitTriggerPositionData> = Vec::with_capacity(position_count);
+
+    for (i, position_key) in params.position_keys.iter().enumerate() {
+        let info = &remaining_accounts[i];
+        require!(info.key() == *position_key, ConfidexError::OrderOwnerMismatch);
+        require!(info.owner == &crate::ID, ConfidexError::InvalidOwner);
+
+        let data = info.try_borrow_data()?;
+        let position = ConfidentialPosition::try_deserialize(&mut &data[..])?;
+
+        require!(position.market == market_key, ConfidexError::InvalidFundingState);
+        require!(position.is_open(), ConfidexError::PositionNotOpen);
+        require!(!position.pending_close, ConfidexError::PositionPendingClose);
+        require!(position.has_valid_mpc_encryption(), ConfidexError::InvalidPositionType);
+        require!(position.has_exit_triggers(), ConfidexError::NoExitTriggers);
+        require!(!position.exit_triggered, ConfidexError::ExitTriggerAlreadyFired);
+        // A check in flight can't be replaced until it times out, so keepers
+        // can't keep superseding a result that would fire the trigger
+        require!(position.can_queue_trigger_check(now), ConfidexError::OperationPending);
+
+        position_data.push(BatchExitTriggerPositionData {
+            position: *position_key,
+            encrypted_stop_loss: position.encrypted_stop_loss,
+            encrypted_take_profit: position.encrypted_take_profit,
+            has_stop_loss: position.has_stop_loss,
+            has_take_profit: position.has_take_profit,
+            is_long: position.side == PositionSide::Long,
+        });
+    }
+
+    // Extract MXE accounts from remaining_accounts (after position accounts)
+    // Store AccountInfo in local variables to avoid lifetime issues
+    let mxe_start = position_count;
+    let payer_info = ctx.accounts.keeper.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+
+    let mxe_accounts = MxeCpiAccounts {
+        payer: &payer_info,
+        sign_pda_account: &remaining_accounts[mxe_start],
+        mxe_account: &remaining_accounts[mxe_start + 1],
+        mempool_account: &remaining_accounts[mxe_start + 2],
+        executing_pool: &remaining_accounts[mxe_start + 3],
+        computation_account: &remaining_accounts[mxe_start + 4],
+        comp_def_account: &remaining_accounts[mxe_start + 5],
+        cluster_account: &remaining_accounts[mxe_start + 6],
+        pool_account: &remaining_accounts[mxe_start + 7],
+        clock_account: &remaining_accounts[mxe_start + 8],
+        system_program: &system_program_info,
+        arcium_program: &remaining_accounts[mxe_start + 9],
+        mxe_program: &remaining_accounts[mxe_start + 10],
+    };
+
+    let queued = queue_batch_exit_trigger_check(
+        mxe_accounts,
+        params.computation_offset,
+        &position_data,
+        mark_price,
+        &params.pub_key,
+        params.nonce,
+    )?;
+
+    // Record the request on each position so the callback only applies
+    // results computed from the positions' current triggers
+    for info in remaining_accounts.iter().take(position_count) {
+        let mut data = info.try_borrow_mut_data()?;
+        let mut position = ConfidentialPosition::try_deserialize(&mut &data[..])?;
+        position.pending_trigger_request = queued.request_id;
+        position.pending_trigger_queued_at = now;
+
+        let mut writer = &mut data[8..]; // Skip discriminator
+        position.serialize(&mut writer)?;
+    }
+
+    msg!(
+        "Queued exit trigger check for {} positions at mark price {}, computation_offset={}",
+        position_count,
+        mark_price,
+        params.computation_offset
+    );
+
+    Ok(())
+}
+
+/// Accounts for MPC-based exit trigger callback
+/// MXE passes the checked positions via remaining_accounts in batch order
+#[derive(Accounts)]
+pub struct ExitTriggerCallback<'info> {
+    /// MXE authority PDA - must be the signer
+    /// This ensures only the MXE callback can mark triggers as fired
+    #[account(
+        signer,
+        seeds = [MXE_AUTHORITY_SEED],
+        bump,
+        seeds::program = ARCIUM_MXE_PROGRAM_ID,
+    )]
+    pub mxe_authority: AccountInfo<'info>,
+    // Positions are passed via remaining_accounts to allow variable count
+}
+
+/// Record which positions' stop-loss / take-profit fired
+///
+/// fired[i] belongs to the i-th position in remaining_accounts. Positions
+/// whose triggers changed (or that were re-checked) since queueing no longer
+/// carry this request_id and are skipped.
+pub fn exit_trigger_callback_handler(
+    ctx: Context<ExitTriggerCallback>,
+    request_id: [u8; 32],
+    fired: Vec<bool>,
+) -> Result<()> {
+    require!(
+        !fired.is_empty() && fired.len() <= MAX_BATCH_EXIT_TRIGGER_POSITIONS,
+        ConfidexError::InvalidMpcRequest
+    );
+    require!(
+        ctx.remaining_accounts.len() == fired.len(),
+        ConfidexError::InvalidAccountCount
+    );
+
+    let coarse_time = ConfidentialPosition::coarse_timestamp(Clock::get()?.unix_timestamp);
+    let mut fired_count = 0usize;
+
+    for (info, &is_fired) in ctx.remaining_accounts.iter().zip(fired.iter()) {
+        require!(info.owner == &crate::ID, ConfidexError::InvalidOwner);
+
+        let mut data = info.try_borrow_mut_data()?;
+        let mut position = ConfidentialPosition::try_deserialize(&mut &data[..])?;
+
+        if position.pending_trigger_request != request_id {
+            msg!("Skipping stale exit trigger result for position {:?}", position.position_id);
+            continue;
+        }
+
+        position.pending_trigger_request = [0u8; 32];
+        position.pending_trigger_queued_at = 0;
+        if is_fired && position.is_open() && !position.pending_close {
+            position.exit_triggered = true;
+            fired_count += 1;
+
+            emit!(ExitTriggerFired {
+                position: info.key(),
+                trader: position.trader,
+                market: position.market,
+                timestamp: coarse_time,
+            });
+        }
+
+        let mut writer = &mut data[8..]; // Skip discriminator
+        position.serialize(&mut writer)?;
+    }
+
+    msg!("Exit trigger check complete: {}/{} positions fired", fired_count, fired.len());
+
+    Ok(())
+}
+
+/// Accounts for closing a position whose exit trigger fired (permissionless)
+/// Same accounts as InitiateClosePosition, with the keeper paying the MPC fees
+#[derive(Accounts)]
+pub struct ExecuteExitTrigger<'info> {
+    #[account(
+        mut,
+        seeds = [PerpetualMarket::SEED, perp_market.underlying_mint.as_ref()],
+        bump = perp_market.bump,
+    )]
+    pub perp_market: Box<Account<'info, PerpetualMarket>>,
+
+    #[account(
+        mut,
+        seeds = [
+            ConfidentialPosition::SEED,
+            position.trader.as_ref(),
+            perp_market.key().as_ref(),
+            &position.position_seed.to_le_bytes()
+        ],
+        bump = position.bump,
+        constraint = position.market == perp_market.key() @ ConfidexError::InvalidFundingState,
+        constraint = position.is_open() @ ConfidexError::PositionNotOpen,
+        constraint = position.exit_triggered @ ConfidexError::ExitTriggerNotFired,
+        constraint = !position.pending_close @ ConfidexError::PositionPendingClose,
+        constraint = !position.has_pending_margin_operation() @ ConfidexError::PositionHasPendingOperation
+    )]
+    pub position: Box<Account<'info, ConfidentialPosition>>,
+
+    /// CHECK: Primary oracle for mark price / exit price (validated in oracle module)
+    pub oracle: AccountInfo<'info>,
+
+    /// CHECK: Secondary (fallback) oracle - required when the market configures one
+    pub secondary_oracle: Option<UncheckedAccount<'info>>,
+
+    /// Keeper (pays the MPC fees)
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    // === MXE CPI ACCOUNTS ===
+    // Required to queue PnL computation
+
+    /// CHECK: MXE signer PDA
+    #[account(mut)]
+    pub mxe_sign_pda: AccountInfo<'info>,
+
+    /// CHECK: MXE account
+    #[account(mut)]
+    pub mxe_account: AccountInfo<'info>,
+
+    /// CHECK: Cluster mempool
+    #[account(mut)]
+    pub mempool_account: AccountInfo<'info>,
+
+    /// CHECK: Cluster executing pool
+    #[account(mut)]
+    pub executing_pool: AccountInfo<'info>,
+
+    /// CHECK: Computation account
+    #[account(mut)]
+    pub computation_account: AccountInfo<'info>,
+
+    /// CHECK: Computation definition for calculate_pnl circuit
+    pub comp_def_account: AccountInfo<'info>,
+
+    /// CHECK: Cluster account
+    #[account(mut)]
+    pub cluster_account: AccountInfo<'info>,
+
+    /// CHECK: Arcium fee pool
+    #[account(mut)]
+    pub pool_account: AccountInfo<'info>,
+
+    /// CHECK: Arcium clock
+    #[account(mut)]
+    pub clock_account: AccountInfo<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Arcium main program
+    pub arcium_program: AccountInfo<'info>,
+
+    /// CHECK: MXE program
+    pub mxe_program: AccountInfo<'info>,
+}
+
+/// Parameters for executing a fired exit trigger
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ExecuteExitTriggerParams {
+    /// Computation offset for MXE (unique per computation)
+    pub computation_offset: u64,
+    /// MXE public key for encryption
+    pub mxe_pub_key: [u8; 32],
+    /// Nonce for MXE encryption
+    pub nonce: u128,
+}
+
+/// Fully close a position whose stop-loss or take-profit fired
+///
+/// Runs the same flow as initiate_close_position; the payout still goes to
+/// the trader in close_position_callback. The triggers are consumed.
+pub fn execute_exit_trigger_handler(
+    ctx: Context<ExecuteExitTrigger>,
+    params: ExecuteExitTriggerParams,
+) -> Result<()> {
+    let payer_info = ctx.accounts.keeper.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+    let mxe_accounts = MxeCpiAccounts {
+        payer: &payer_info,
+        sign_pda_account: &ctx.accounts.mxe_sign_pda,
+        mxe_account: &ctx.accounts.mxe_account,
+        mempool_account: &ctx.accounts.mempool_account,
+        executing_pool: &ctx.accounts.executing_pool,
+        computation_account: &ctx.accounts.computation_account,
+        comp_def_account: &ctx.accounts.comp_def_account,
+        cluster_account: &ctx.accounts.cluster_account,
+        pool_account: &ctx.accounts.pool_account,
+        clock_account: &ctx.accounts.clock_account,
+        system_program: &system_program_info,
+        arcium_program: &ctx.accounts.arcium_program,
+        mxe_program: &ctx.accounts.mxe_program,
+    };
+
+    let close_params = InitiateClosePositionParams {
+        encrypted_close_size: [0u8; 64],
+        full_close: true,
+        computation_offset: params.computation_offset,
+        mxe_pub_key: params.mxe_pub_key,
+        nonce: params.nonce,
+    };
+
+    queue_position_close(
+        &mut ctx.accounts.position,
+        &ctx.accounts.perp_market,
+        &ctx.accounts.oracle,
+        ctx.accounts.secondary_oracle.as_deref(),
+        mxe_accounts,
+        &close_params,
+    )?;
+
+    ctx.accounts.position.clear_exit_triggers();
+
+    msg!("Exit trigger executed by keeper {}", ctx.accounts.keeper.key());
+
+    Ok(())
+}
+
+/// Emitted when a position's exit triggers are set or cleared
+/// Trigger prices are encrypted and never emitted
+#[event]
+pub struct ExitTriggersUpdated {
+    pub position: Pubkey,
+    pub trader: Pubkey,
+    pub market: Pubkey,
+    pub has_stop_loss: bool,
+    pub has_take_profit: bool,
+    /// Coarse timestamp (hour precision for privacy)
+    pub timestamp: i64,
+}
+
+/// Emitted when MPC finds a position's stop-loss or take-profit was hit
+/// Does not reveal which trigger fired
+#[event]
+pub struct ExitTriggerFired {
+    pub position: Pubkey,
+    pub trader: Pubkey,
+    pub market: Pubkey,
+    /// Coarse timestamp (hour precision for privacy)
+    pub timestamp: i64,
+}
@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
 
 use crate::error::ConfidexError;
-use crate::state::{ExchangeState, PerpetualMarket, FundingRateState};
+use crate::oracle::INTERNAL_PRICE_DECIMALS;
+use crate::state::{ExchangeState, PerpetualMarket, FundingRateState, OracleSource};
 
 /// Uses Box<Account<>> for large account types to reduce stack usage.
 #[derive(Accounts)]
@@ -42,13 +43,14 @@ pub struct InitializePerpMarket<'info> {
     /// CHECK: Quote/collateral token mint (e.g., USDC)
     pub quote_mint: AccountInfo<'info>,
 
-    /// CHECK: Pyth oracle price feed
+    /// CHECK: Primary oracle price feed (legacy Pyth account by default)
     pub oracle_price_feed: AccountInfo<'info>,
 
     /// CHECK: Confidential collateral vault (C-SPL)
     pub collateral_vault: AccountInfo<'info>,
 
     /// CHECK: Insurance fund token account
+    /// (replaced by the market's PDA via initialize_insurance_fund)
     pub insurance_fund: AccountInfo<'info>,
 
     /// CHECK: Fee recipient account
@@ -124,6 +126,7 @@ pub fn handler(
     perp_market.total_long_open_interest = 0;
     perp_market.total_short_open_interest = 0;
     perp_market.position_count = 0;
+    perp_market.open_position_count = 0;
     perp_market.index = ctx.accounts.exchange.pair_count; // Reuse pair_count as market index
     perp_market.last_funding_time = clock.unix_timestamp;
     perp_market.cumulative_funding_long = 0;
@@ -135,6 +138,13 @@ pub fn handler(
     perp_market.c_quote_mint = ctx.accounts.c_quote_mint.key();
     perp_market.active = true;
     perp_market.bump = ctx.bumps.perp_market;
+    perp_market.price_decimals = INTERNAL_PRICE_DECIMALS as u8;
+    // Primary defaults to the legacy Pyth account; sources and a secondary
+    // feed are configured via update_perp_market_config
+    perp_market.oracle_source = OracleSource::PythLegacy;
+    perp_market.secondary_oracle_source = OracleSource::PythLegacy;
+    perp_market.secondary_oracle_feed = Pubkey::default();
+    perp_market.max_oracle_divergence_bps = PerpetualMarket::DEFAULT_MAX_ORACLE_DIVERGENCE_BPS;
 
     // Initialize funding state
     let funding_state = &mut ctx.accounts.funding_state;
@@ -149,6 +159,18 @@ pub fn handler(
     funding_state.total_long_funding_paid = 0;
     funding_state.total_short_funding_paid = 0;
     funding_state.bump = ctx.bumps.funding_state;
+    // Premium funding model defaults (tunable via update_funding_config)
+    funding_state.interest_rate_bps = 0;
+    funding_state.premium_dampening_bps = FundingRateState::DEFAULT_PREMIUM_DAMPENING_BPS;
+    funding_state.mark_oracle_source = OracleSource::PythLegacy;
+    funding_state.mark_oracle_feed = Pubkey::default();
+    funding_state.mark_ema_window_seconds = FundingRateState::DEFAULT_MARK_EMA_WINDOW_SECS;
+    funding_state.mark_price_ema = 0;
+    funding_state.last_index_price = 0;
+    funding_state.index_price_ema = 0;
+    funding_state.mark_ema_remainder = 0;
+    funding_state.index_ema_remainder = 0;
+    funding_state.last_mark_update_time = clock.unix_timestamp;
 
     // Increment market count
     ctx.accounts.exchange.pair_count += 1;
@@ -0,0 +1,267 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::error::ConfidexError;
+use crate::state::{ExchangeState, PerpetualMarket};
+
+// ============================================================================
+// PER-MARKET INSURANCE FUND (SPL quote token)
+// ============================================================================
+//
+// Each perpetual market holds its insurance fund in a program-owned token
+// account (PDA seeds = [insurance_fund, perp_market]) denominated in the
+// market's quote token. The account is owned by the market's vault authority
+// PDA (seeds = [vault, perp_market]), the same signer used for the collateral
+// vault, so liquidation and ADL flows can move funds between the two.
+//
+// perp_market.insurance_fund_balance is a snapshot of the token balance,
+// refreshed whenever the program touches the fund. ADL eligibility is always
+// checked against the live token balance.
+
+/// Accounts for creating a market's insurance fund token account
+#[derive(Accounts)]
+pub struct InitializeInsuranceFund<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+        has_one = authority @ ConfidexError::Unauthorized
+    )]
+    pub exchange: Account<'info, ExchangeState>,
+
+    #[account(
+        mut,
+        seeds = [PerpetualMarket::SEED, perp_market.underlying_mint.as_ref()],
+        bump = perp_market.bump
+    )]
+    pub perp_market: Account<'info, PerpetualMarket>,
+
+    /// Quote token mint (USDC)
+    #[account(
+        address = perp_market.quote_mint @ ConfidexError::InvalidTokenMint
+    )]
+    pub quote_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [PerpetualMarket::INSURANCE_FUND_SEED, perp_market.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = vault_authority,
+    )]
+    pub insurance_fund: Account<'info, TokenAccount>,
+
+    /// CHECK: Vault authority PDA (owner of the insurance fund token account)
+    #[account(
+        seeds = [b"vault", perp_market.key().as_ref()],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_insurance_fund_handler(ctx: Context<InitializeInsuranceFund>) -> Result<()> {
+    let perp_market = &mut ctx.accounts.perp_market;
+
+    perp_market.insurance_fund = ctx.accounts.insurance_fund.key();
+    perp_market.insurance_fund_balance = 0;
+
+    msg!(
+        "Insurance fund initialized for market {}: {}",
+        perp_market.key(),
+        perp_market.insurance_fund
+    );
+
+    Ok(())
+}
+
+/// Accounts for depositing quote tokens into a market's insurance fund
+#[derive(Accounts)]
+pub struct DepositInsuranceFund<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+        has_one = authority @ ConfidexError::Unauthorized
+    )]
+    pub exchange: Account<'info, ExchangeState>,
+
+    #[account(
+        mut,
+        seeds = [PerpetualMarket::SEED, perp_market.underlying_mint.as_ref()],
+        bump = perp_market.bump
+    )]
+    pub perp_market: Account<'info, PerpetualMarket>,
+
+    #[account(
+        mut,
+        seeds = [PerpetualMarket::INSURANCE_FUND_SEED, perp_market.key().as_ref()],
+        bump,
+        constraint = insurance_fund.key() == perp_market.insurance_fund @ ConfidexError::InvalidVault
+    )]
+    pub insurance_fund: Account<'info, TokenAccount>,
+
+    /// Authority's quote token account (source of the deposit)
+    #[account(
+        mut,
+        constraint = authority_token_account.mint == perp_market.quote_mint @ ConfidexError::InvalidMint,
+        constraint = authority_token_account.owner == authority.key() @ ConfidexError::InvalidOwner
+    )]
+    pub authority_token_account: Account<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn deposit_insurance_fund_handler(
+    ctx: Context<DepositInsuranceFund>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, ConfidexError::InvalidAmount);
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.authority_token_account.to_account_info(),
+                to: ctx.accounts.insurance_fund.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    ctx.accounts.insurance_fund.reload()?;
+    let balance = ctx.accounts.insurance_fund.amount;
+    let perp_market = &mut ctx.accounts.perp_market;
+    perp_market.insurance_fund_balance = balance;
+
+    emit!(InsuranceFundDeposited {
+        market: perp_market.key(),
+        amount,
+        balance,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Insurance fund deposit: {} (balance {})", amount, balance);
+
+    Ok(())
+}
+
+/// Accounts for withdrawing quote tokens from a market's insurance fund
+#[derive(Accounts)]
+pub struct WithdrawInsuranceFund<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+        has_one = authority @ ConfidexError::Unauthorized
+    )]
+    pub exchange: Account<'info, ExchangeState>,
+
+    #[account(
+        mut,
+        seeds = [PerpetualMarket::SEED, perp_market.underlying_mint.as_ref()],
+        bump = perp_market.bump
+    )]
+    pub perp_market: Account<'info, PerpetualMarket>,
+
+    #[account(
+        mut,
+        seeds = [PerpetualMarket::INSURANCE_FUND_SEED, perp_market.key().as_ref()],
+        bump,
+        constraint = insurance_fund.key() == perp_market.insurance_fund @ ConfidexError::InvalidVault
+    )]
+    pub insurance_fund: Account<'info, TokenAccount>,
+
+    /// CHECK: Vault authority PDA (signs the withdrawal)
+    #[account(
+        seeds = [b"vault", perp_market.key().as_ref()],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Destination quote token account
+    #[account(
+        mut,
+        constraint = destination.mint == perp_market.quote_mint @ ConfidexError::InvalidMint
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn withdraw_insurance_fund_handler(
+    ctx: Context<WithdrawInsuranceFund>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, ConfidexError::InvalidAmount);
+    require!(
+        ctx.accounts.insurance_fund.amount >= amount,
+        ConfidexError::InsufficientBalance
+    );
+
+    let market_key = ctx.accounts.perp_market.key();
+    let seeds = &[
+        b"vault".as_ref(),
+        market_key.as_ref(),
+        &[ctx.bumps.vault_authority],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.insurance_fund.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    ctx.accounts.insurance_fund.reload()?;
+    let balance = ctx.accounts.insurance_fund.amount;
+    let perp_market = &mut ctx.accounts.perp_market;
+    perp_market.insurance_fund_balance = balance;
+
+    emit!(InsuranceFundWithdrawn {
+        market: market_key,
+        destination: ctx.accounts.destination.key(),
+        amount,
+        balance,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Insurance fund withdrawal: {} (balance {})", amount, balance);
+
+    Ok(())
+}
+
+#[event]
+pub struct InsuranceFundDeposited {
+    pub market: Pubkey,
+    pub amount: u64,
+    /// Fund token balance after the deposit
+    pub balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InsuranceFundWithdrawn {
+    pub market: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    /// Fund token balance after the withdrawal
+    pub balance: u64,
+    pub timestamp: i64,
+}
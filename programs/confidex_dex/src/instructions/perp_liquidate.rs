@@ -1,17 +1,45 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 
+use crate::cpi::arcium::{
+    queue_calculate_partial_liquidation, AdlPositionData, MxeCpiAccounts,
+    PartialLiquidationCallbackAccounts, PartialLiquidationParams, ARCIUM_MXE_PROGRAM_ID,
+};
 use crate::error::ConfidexError;
-use crate::oracle::get_sol_usd_price_for_liquidation;
+use crate::oracle::get_market_price;
 use crate::state::{
     ConfidentialPosition, LiquidationBatchRequest, LiquidationConfig,
     PerpetualMarket, PositionSide, PositionStatus, UserConfidentialBalance,
 };
 
+/// MXE authority PDA seed (must match MXE program)
+const MXE_AUTHORITY_SEED: &[u8] = b"mxe_authority";
+
+/// Cumulative funding is scaled by 1e18; MPC takes it in 1e-8 units
+const FUNDING_E8_DIVISOR: u128 = 10_000_000_000;
+
+// ============================================================================
+// PARTIAL LIQUIDATION (async MPC)
+// ============================================================================
+//
+// 1. check_liquidation_batch confirms via MPC that the position is below its
+//    encrypted liquidation threshold
+// 2. liquidate_position queues MPC calculate_partial_liquidation, which
+//    values equity at the mark price (unrealized PnL and accrued funding) and
+//    finds the minimum size reduction that restores maintenance margin,
+//    bounded by LiquidationConfig::max_liquidation_per_tx
+// 3. partial_liquidation_callback applies the new encrypted size, collateral
+//    and threshold, pays the liquidator bonus and - on a full close - splits
+//    the released collateral between the insurance fund and the trader
+//
+// The liquidator bonus only comes out of positive equity. A bankrupt
+// position is closed in full with no bonus and its deficit is covered by the
+// insurance fund; if the fund is too small, the position is left for ADL.
+
 /// Uses Box<Account<>> to move large account data to heap (avoids stack overflow)
 #[derive(Accounts)]
 pub struct LiquidatePosition<'info> {
     #[account(
-        mut,
         seeds = [PerpetualMarket::SEED, perp_market.underlying_mint.as_ref()],
         bump = perp_market.bump,
     )]
@@ -28,18 +56,22 @@ pub struct LiquidatePosition<'info> {
         bump = position.bump,
         constraint = position.market == perp_market.key() @ ConfidexError::InvalidFundingState,
         constraint = position.is_open() @ ConfidexError::PositionNotOpen,
-        constraint = position.threshold_verified @ ConfidexError::ThresholdNotVerified
+        constraint = position.threshold_verified @ ConfidexError::ThresholdNotVerified,
+        constraint = !position.pending_close @ ConfidexError::PositionPendingClose,
+        constraint = !position.has_pending_mpc_request() @ ConfidexError::PositionHasPendingOperation
     )]
     pub position: Box<Account<'info, ConfidentialPosition>>,
 
     /// Batch liquidation request that contains MPC verification results
-    /// This must have been verified by MPC before liquidation can proceed
+    /// The position's chunk must have been verified by MPC before liquidation can proceed
     #[account(
         mut,
-        seeds = [LiquidationBatchRequest::SEED, batch_request.request_id.as_ref()],
+        seeds = [
+            LiquidationBatchRequest::SEED,
+            batch_request.requester.as_ref(),
+            &batch_request.batch_id.to_le_bytes()
+        ],
         bump = batch_request.bump,
-        constraint = batch_request.completed @ ConfidexError::ThresholdNotVerified,
-        constraint = batch_request.market == perp_market.key() @ ConfidexError::InvalidFundingState
     )]
     pub batch_request: Box<Account<'info, LiquidationBatchRequest>>,
 
@@ -49,34 +81,44 @@ pub struct LiquidatePosition<'info> {
     )]
     pub liquidation_config: Box<Account<'info, LiquidationConfig>>,
 
-    /// CHECK: Pyth oracle for current mark price
-    #[account(
-        constraint = oracle.key() == perp_market.oracle_price_feed @ ConfidexError::InvalidOraclePrice
-    )]
+    /// CHECK: Primary oracle for current mark price (validated in oracle module)
     pub oracle: AccountInfo<'info>,
 
-    /// CHECK: Market's confidential collateral vault (C-SPL USDC)
+    /// CHECK: Secondary (fallback) oracle - required when the market configures one
+    pub secondary_oracle: Option<UncheckedAccount<'info>>,
+
+    /// Market's collateral vault (source of the insurance share)
     #[account(
-        mut,
         constraint = collateral_vault.key() == perp_market.collateral_vault @ ConfidexError::InvalidVault
     )]
-    pub collateral_vault: AccountInfo<'info>,
+    pub collateral_vault: Box<Account<'info, TokenAccount>>,
 
-    /// CHECK: Insurance fund account for socialized losses
+    /// Market's insurance fund token account (receives insurance share)
     #[account(
-        mut,
-        constraint = insurance_fund.key() == perp_market.insurance_fund @ ConfidexError::InvalidVault
+        constraint = insurance_fund.key() == perp_market.insurance_fund @ ConfidexError::InvalidVault,
+        constraint = insurance_fund.mint == perp_market.quote_mint @ ConfidexError::InvalidMint
     )]
-    pub insurance_fund: AccountInfo<'info>,
+    pub insurance_fund: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Vault authority PDA (signs the insurance share transfer in the callback)
+    #[account(
+        seeds = [b"vault", perp_market.key().as_ref()],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
 
     /// CHECK: Liquidator's collateral token account (receives liquidation bonus)
     #[account(mut)]
     pub liquidator_collateral_account: AccountInfo<'info>,
 
-    /// Anyone can liquidate - incentivized by liquidation bonus
+    /// Anyone can liquidate - incentivized by liquidation bonus (also pays the MPC fee)
     #[account(mut)]
     pub liquidator: Signer<'info>,
 
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
     // =========================================================================
     // UserConfidentialBalance accounts for payouts
     // In production: Replace with C-SPL confidential_transfer CPI
@@ -84,7 +126,6 @@ pub struct LiquidatePosition<'info> {
 
     /// Liquidator's quote balance (receives liquidation bonus)
     #[account(
-        mut,
         seeds = [
             UserConfidentialBalance::SEED,
             liquidator.key().as_ref(),
@@ -94,21 +135,8 @@ pub struct LiquidatePosition<'info> {
     )]
     pub liquidator_balance: Account<'info, UserConfidentialBalance>,
 
-    /// Insurance fund's quote balance (receives insurance share)
-    #[account(
-        mut,
-        seeds = [
-            UserConfidentialBalance::SEED,
-            liquidation_config.insurance_fund.as_ref(),
-            perp_market.quote_mint.as_ref()
-        ],
-        bump = insurance_balance.bump,
-    )]
-    pub insurance_balance: Account<'info, UserConfidentialBalance>,
-
     /// Trader's quote balance (receives remaining equity if any)
     #[account(
-        mut,
         seeds = [
             UserConfidentialBalance::SEED,
             position.trader.as_ref(),
@@ -117,159 +145,572 @@ pub struct LiquidatePosition<'info> {
         bump = trader_balance.bump,
     )]
     pub trader_balance: Account<'info, UserConfidentialBalance>,
+
+    // =========================================================================
+    // ARCIUM MXE ACCOUNTS (11 accounts via remaining_accounts)
+    // Same layout as match_orders (5: comp_def_account for calculate_partial_liquidation)
+    // =========================================================================
 }
 
-/// Index of the position within the batch request
+/// Input parameters for liquidate_position instruction
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct LiquidatePositionParams {
-    /// Index of this position in the batch request's results array
+    /// Index of this position in the batch request's entries
     pub batch_index: u8,
+    /// Random seed for computation account derivation
+    pub computation_offset: u64,
+    /// MXE public key for encryption
+    pub mxe_pub_key: [u8; 32],
+    /// Nonce for MXE encryption
+    pub nonce: u128,
 }
 
-pub fn handler(ctx: Context<LiquidatePosition>, params: LiquidatePositionParams) -> Result<()> {
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, LiquidatePosition<'info>>,
+    params: LiquidatePositionParams,
+) -> Result<()> {
     let clock = Clock::get()?;
     let batch_request = &ctx.accounts.batch_request;
     let liquidation_config = &ctx.accounts.liquidation_config;
 
-    // Fetch current mark price from Pyth oracle with strict validation (6 decimal precision)
+    require!(
+        ctx.remaining_accounts.len() >= 11,
+        ConfidexError::InvalidAccountCount
+    );
+
+    // Fetch current mark price from the market oracle with strict validation (market price_decimals)
     // This enforces:
     // - Price freshness < 60 seconds on mainnet (3600s on devnet)
     // - Confidence interval < 1% on mainnet (10% on devnet)
-    let mark_price = get_sol_usd_price_for_liquidation(&ctx.accounts.oracle)?;
+    let mark_price = get_market_price(
+        &ctx.accounts.perp_market,
+        &ctx.accounts.oracle,
+        ctx.accounts.secondary_oracle.as_deref(),
+    )?;
 
     // PRIVACY ENHANCEMENT (V2): Liquidation eligibility is verified via MPC batch check
     // The batch_request contains results from MPC comparing encrypted thresholds vs mark price
     // No public liquidation threshold is exposed
 
-    // Verify the batch was checked at the same mark price (within tolerance)
-    // This prevents stale batch results from being used
-    let price_tolerance = mark_price / 100; // 1% tolerance
+    // Verify this position is in the batch (before mutable borrow)
+    let entry = batch_request
+        .entries
+        .get(params.batch_index as usize)
+        .ok_or(ConfidexError::InvalidAmount)?;
     require!(
-        batch_request.mark_price >= mark_price.saturating_sub(price_tolerance)
-            && batch_request.mark_price <= mark_price.saturating_add(price_tolerance),
-        ConfidexError::StaleOraclePrice
+        entry.position == ctx.accounts.position.key(),
+        ConfidexError::OrderOwnerMismatch
+    );
+    require!(
+        entry.market == ctx.accounts.perp_market.key(),
+        ConfidexError::InvalidFundingState
     );
 
-    // Verify this position is in the batch and MPC confirmed liquidation
+    // Verify the position was checked at the same mark price (within tolerance)
+    // This prevents stale batch results from being used
+    let price_tolerance = mark_price / 100; // 1% tolerance
     require!(
-        (params.batch_index as usize) < batch_request.position_count as usize,
-        ConfidexError::InvalidAmount
+        entry.mark_price >= mark_price.saturating_sub(price_tolerance)
+            && entry.mark_price <= mark_price.saturating_add(price_tolerance),
+        ConfidexError::StaleOraclePrice
     );
 
-    // Check that the position pubkey matches the batch entry (before mutable borrow)
-    let position_key_bytes = ctx.accounts.position.key().to_bytes();
+    // Reject MPC results from a check queued too long ago
     require!(
-        batch_request.positions[params.batch_index as usize] == position_key_bytes,
-        ConfidexError::OrderOwnerMismatch
+        entry.is_fresh(clock.unix_timestamp),
+        ConfidexError::LiquidationResultExpired
     );
 
     // Verify MPC confirmed this position is liquidatable
     require!(
-        batch_request.results[params.batch_index as usize],
+        entry.liquidatable,
         ConfidexError::PositionNotLiquidatable
     );
 
-    // Now take mutable references
-    let perp_market = &mut ctx.accounts.perp_market;
+    let position = &ctx.accounts.position;
+    let perp_market = &ctx.accounts.perp_market;
+
+    // Funding accrued since the position's last settlement. Cumulative
+    // funding grows for the paying side, so a positive delta means the
+    // position owes funding.
+    let current_cumulative_funding = match position.side {
+        PositionSide::Long => perp_market.cumulative_funding_long,
+        PositionSide::Short => perp_market.cumulative_funding_short,
+    };
+    let funding_delta = current_cumulative_funding
+        .saturating_sub(position.entry_cumulative_funding);
+    let funding_e8 = u64::try_from(funding_delta.unsigned_abs() / FUNDING_E8_DIVISOR)
+        .unwrap_or(u64::MAX);
+
+    let liquidation_params = PartialLiquidationParams {
+        is_long: matches!(position.side, PositionSide::Long),
+        mark_price,
+        maintenance_margin_bps: perp_market.maintenance_margin_bps,
+        liquidation_bonus_bps: liquidation_config.liquidation_bonus_bps,
+        max_close_notional: liquidation_config.max_liquidation_per_tx,
+        min_remaining_notional: liquidation_config.min_liquidation_threshold,
+        funding_e8,
+        funding_is_paying: funding_delta > 0,
+    };
+
+    let callback = PartialLiquidationCallbackAccounts {
+        position: position.key(),
+        perp_market: perp_market.key(),
+        liquidation_config: liquidation_config.key(),
+        collateral_vault: ctx.accounts.collateral_vault.key(),
+        insurance_fund: ctx.accounts.insurance_fund.key(),
+        vault_authority: ctx.accounts.vault_authority.key(),
+        token_program: ctx.accounts.token_program.key(),
+        liquidator_balance: ctx.accounts.liquidator_balance.key(),
+        trader_balance: ctx.accounts.trader_balance.key(),
+    };
+
+    // Store AccountInfo in local variables to avoid lifetime issues
+    let payer_info = ctx.accounts.liquidator.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+
+    let mxe_accounts = MxeCpiAccounts {
+        payer: &payer_info,
+        sign_pda_account: &ctx.remaining_accounts[0],
+        mxe_account: &ctx.remaining_accounts[1],
+        mempool_account: &ctx.remaining_accounts[2],
+        executing_pool: &ctx.remaining_accounts[3],
+        computation_account: &ctx.remaining_accounts[4],
+        comp_def_account: &ctx.remaining_accounts[5],
+        cluster_account: &ctx.remaining_accounts[6],
+        pool_account: &ctx.remaining_accounts[7],
+        clock_account: &ctx.remaining_accounts[8],
+        system_program: &system_program_info,
+        arcium_program: &ctx.remaining_accounts[9],
+        mxe_program: &ctx.remaining_accounts[10],
+    };
+
+    // Size reduction, bonus and payout are computed in MPC; result comes
+    // back via partial_liquidation_callback
+    let queued = queue_calculate_partial_liquidation(
+        mxe_accounts,
+        params.computation_offset,
+        &AdlPositionData {
+            encrypted_size: position.encrypted_size,
+            encrypted_entry_price: position.encrypted_entry_price,
+            encrypted_collateral: position.encrypted_collateral,
+        },
+        &liquidation_params,
+        &params.mxe_pub_key,
+        params.nonce,
+        &callback,
+    )?;
+
+    // Each batch result can be used for a single liquidation
+    ctx.accounts.batch_request.entries[params.batch_index as usize].liquidatable = false;
+
+    let coarse_time = ConfidentialPosition::coarse_timestamp(clock.unix_timestamp);
     let position = &mut ctx.accounts.position;
+    position.pending_mpc_request = queued.request_id;
+    position.last_updated_hour = coarse_time;
+    // MPC settles the accrued funding into collateral, but it only counts as
+    // settled once the callback applies the result. Until then the snapshot
+    // is kept in threshold_commitment bytes 16-32 (same slot as settle_funding)
+    let mut funding_data = [0u8; 32];
+    funding_data[16..32].copy_from_slice(&current_cumulative_funding.to_le_bytes());
+    position.threshold_commitment = funding_data;
+
+    emit!(LiquidationQueued {
+        position_id: position.position_id,
+        trader: position.trader,
+        market: callback.perp_market,
+        liquidator: ctx.accounts.liquidator.key(),
+        side: position.side,
+        mark_price,
+        request_id: queued.request_id,
+        timestamp: coarse_time,
+    });
 
-    // ==========================================================================
-    // LIQUIDATION PAYOUT
-    //
-    // The MPC batch liquidation check has already verified this position is
-    // liquidatable. Now we distribute the remaining collateral:
-    // - Liquidator receives bonus (incentive)
-    // - Insurance fund receives its share
-    // - Trader receives any remaining equity
-    //
-    // NOTE: In V5/production, MPC would calculate encrypted PnL and funding.
-    // For hackathon, we use the plaintext collateral value stored in the
-    // position account (see UserConfidentialBalance pattern).
-    // ==========================================================================
-
-    // Get remaining equity using plaintext helper (hackathon only)
-    let remaining_equity_plaintext = position.get_collateral_plaintext();
-
-    // Calculate liquidator bonus: remaining_equity * liquidation_bonus_bps / 10000
-    let liquidator_bonus = remaining_equity_plaintext
-        .checked_mul(liquidation_config.liquidation_bonus_bps as u64)
-        .ok_or(ConfidexError::ArithmeticOverflow)?
-        .checked_div(10_000)
-        .ok_or(ConfidexError::ArithmeticOverflow)?;
-
-    // Calculate insurance fund share: remaining_equity * insurance_fund_share_bps / 10000
-    let insurance_share = remaining_equity_plaintext
-        .checked_mul(liquidation_config.insurance_fund_share_bps as u64)
-        .ok_or(ConfidexError::ArithmeticOverflow)?
-        .checked_div(10_000)
-        .ok_or(ConfidexError::ArithmeticOverflow)?;
-
-    // Calculate trader remainder (can be 0 or positive)
-    let trader_remainder = remaining_equity_plaintext
-        .saturating_sub(liquidator_bonus)
-        .saturating_sub(insurance_share);
-
-    msg!("Liquidation payout: remaining_equity={}, liquidator_bonus={}, insurance_share={}, trader_remainder={}",
-         remaining_equity_plaintext, liquidator_bonus, insurance_share, trader_remainder);
-
-    // Transfer liquidator bonus
-    let liquidator_balance = &mut ctx.accounts.liquidator_balance;
-    let liquidator_current = liquidator_balance.get_balance();
-    liquidator_balance.set_balance(liquidator_current + liquidator_bonus);
-
-    // Transfer insurance fund share
-    let insurance_balance = &mut ctx.accounts.insurance_balance;
-    let insurance_current = insurance_balance.get_balance();
-    insurance_balance.set_balance(insurance_current + insurance_share);
-
-    // Transfer trader remainder (if any positive equity remains)
-    if trader_remainder > 0 {
-        let trader_balance = &mut ctx.accounts.trader_balance;
-        let trader_current = trader_balance.get_balance();
-        trader_balance.set_balance(trader_current + trader_remainder);
-    }
+    msg!(
+        "Liquidation queued via MPC on market {}",
+        callback.perp_market
+    );
 
-    // Clear position collateral (already distributed)
-    position.set_collateral_plaintext(0);
+    Ok(())
+}
 
-    // Mark position as liquidated with coarse timestamp
+/// Accounts for partial liquidation callback
+/// Called by MXE after calculate_partial_liquidation MPC completes
+#[derive(Accounts)]
+#[instruction(request_id: [u8; 32])]
+pub struct PartialLiquidationCallback<'info> {
+    /// MXE authority PDA - verifies this came from our MXE program
+    /// CHECK: Verified by seeds constraint
+    #[account(
+        signer,
+        seeds = [MXE_AUTHORITY_SEED],
+        bump,
+        seeds::program = ARCIUM_MXE_PROGRAM_ID
+    )]
+    pub mxe_authority: UncheckedAccount<'info>,
+
+    /// Position being liquidated
+    #[account(
+        mut,
+        constraint = position.pending_mpc_request == request_id @ ConfidexError::InvalidMpcRequest,
+        constraint = position.is_open() @ ConfidexError::PositionNotOpen,
+        constraint = position.market == perp_market.key() @ ConfidexError::InvalidFundingState
+    )]
+    pub position: Box<Account<'info, ConfidentialPosition>>,
+
+    /// Perpetual market (open interest is reduced by the closed size)
+    #[account(
+        mut,
+        seeds = [PerpetualMarket::SEED, perp_market.underlying_mint.as_ref()],
+        bump = perp_market.bump,
+    )]
+    pub perp_market: Box<Account<'info, PerpetualMarket>>,
+
+    /// Liquidation config (liquidation and insurance payout totals)
+    #[account(
+        mut,
+        seeds = [LiquidationConfig::SEED],
+        bump = liquidation_config.bump,
+    )]
+    pub liquidation_config: Box<Account<'info, LiquidationConfig>>,
+
+    /// Market's collateral vault (source of the insurance share, receives bad debt cover)
+    #[account(
+        mut,
+        constraint = collateral_vault.key() == perp_market.collateral_vault @ ConfidexError::InvalidVault
+    )]
+    pub collateral_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Market's insurance fund token account (receives insurance share, covers bad debt)
+    #[account(
+        mut,
+        constraint = insurance_fund.key() == perp_market.insurance_fund @ ConfidexError::InvalidVault
+    )]
+    pub insurance_fund: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Vault authority PDA for signing transfers
+    #[account(
+        seeds = [b"vault", perp_market.key().as_ref()],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Liquidator's quote balance (fixed when the liquidation was queued)
+    #[account(
+        mut,
+        constraint = liquidator_balance.mint == perp_market.quote_mint @ ConfidexError::InvalidMint
+    )]
+    pub liquidator_balance: Box<Account<'info, UserConfidentialBalance>>,
+
+    /// Trader's quote balance
+    #[account(
+        mut,
+        seeds = [
+            UserConfidentialBalance::SEED,
+            position.trader.as_ref(),
+            perp_market.quote_mint.as_ref()
+        ],
+        bump = trader_balance.bump,
+    )]
+    pub trader_balance: Box<Account<'info, UserConfidentialBalance>>,
+}
+
+/// Parameters for partial liquidation callback
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PartialLiquidationCallbackParams {
+    /// MPC computation request ID
+    pub request_id: [u8; 32],
+    /// Remaining encrypted size
+    pub new_encrypted_size: [u8; 64],
+    /// Remaining encrypted collateral
+    pub new_encrypted_collateral: [u8; 64],
+    /// Encrypted liquidation threshold for the remaining position
+    pub new_encrypted_liq_threshold: [u8; 64],
+    /// Size closed (0 if the position is back above maintenance margin)
+    pub close_size: u64,
+    /// Liquidator bonus taken from the position's collateral
+    pub liquidator_bonus: u64,
+    /// Equity released by a full close (0 for a partial close)
+    pub released_collateral: u64,
+    /// Deficit of a bankrupt position, covered by the insurance fund
+    pub bad_debt: u64,
+    /// Whether the position has no size left
+    pub fully_closed: bool,
+}
+
+/// Handle partial liquidation callback from MXE
+///
+/// Applies the MPC result: the position keeps its entry price with the
+/// remaining size, collateral and recomputed liquidation threshold. A new
+/// batch check is needed before it can be liquidated again.
+///
+/// A bankrupt position's bad debt is moved from the insurance fund into the
+/// collateral vault. If the fund can't cover it, nothing is applied and the
+/// position is left for auto-deleverage.
+///
+/// The accrued funding is marked settled (entry_cumulative_funding moved to
+/// the snapshot taken when queueing) only when the result is applied.
+///
+/// IMPORTANT: This function does NOT emit the closed size or payouts in
+/// events to preserve privacy.
+pub fn callback_handler(
+    ctx: Context<PartialLiquidationCallback>,
+    params: PartialLiquidationCallbackParams,
+) -> Result<()> {
+    let clock = Clock::get()?;
     let coarse_time = ConfidentialPosition::coarse_timestamp(clock.unix_timestamp);
-    position.status = PositionStatus::Liquidated;
+
+    msg!(
+        "Partial liquidation callback: request_id={:?}, fully_closed={}",
+        &params.request_id[0..8],
+        params.fully_closed
+    );
+
+    let market_key = ctx.accounts.perp_market.key();
+    let seeds = &[
+        b"vault".as_ref(),
+        market_key.as_ref(),
+        &[ctx.bumps.vault_authority],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    // A bankrupt position's deficit (loss + funding beyond its collateral)
+    // is owed to the counterparties and comes out of the insurance fund
+    if params.bad_debt > 0 {
+        if ctx.accounts.insurance_fund.amount < params.bad_debt {
+            // Not enough to cover it - leave the position open and
+            // liquidatable so execute_adl can close it at its bankruptcy price
+            // Funding stays owed; restore the threshold commitment that held
+            // the funding snapshot
+            let maintenance_margin_bps = ctx.accounts.perp_market.maintenance_margin_bps;
+            let position = &mut ctx.accounts.position;
+            let is_long = matches!(position.side, PositionSide::Long);
+            position.threshold_commitment = ConfidentialPosition::compute_threshold_commitment(
+                &position.encrypted_entry_price,
+                position.leverage,
+                maintenance_margin_bps,
+                is_long,
+            );
+            position.is_liquidatable = true;
+            position.clear_pending_mpc_request();
+            position.last_updated_hour = coarse_time;
+
+            emit!(LiquidationDeferredToAdl {
+                position_id: position.position_id,
+                trader: position.trader,
+                market: market_key,
+                request_id: params.request_id,
+                timestamp: coarse_time,
+            });
+
+            msg!("Insurance fund cannot cover bad debt, position left for auto-deleverage");
+            return Ok(());
+        }
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.insurance_fund.to_account_info(),
+                    to: ctx.accounts.collateral_vault.to_account_info(),
+                    authority: ctx.accounts.vault_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            params.bad_debt,
+        )?;
+
+        ctx.accounts.insurance_fund.reload()?;
+
+        let liquidation_config = &mut ctx.accounts.liquidation_config;
+        liquidation_config.total_insurance_payouts = liquidation_config
+            .total_insurance_payouts
+            .saturating_add(params.bad_debt);
+    }
+
+    // Liquidator bonus (only ever taken from positive equity)
+    if params.liquidator_bonus > 0 {
+        let liquidator_balance = &mut ctx.accounts.liquidator_balance;
+        let liquidator_current = liquidator_balance.get_balance();
+        liquidator_balance.set_balance(
+            liquidator_current
+                .checked_add(params.liquidator_bonus)
+                .ok_or(ConfidexError::ArithmeticOverflow)?,
+        );
+    }
+
+    // A full close splits the remaining equity between the insurance fund
+    // and the trader; a partial close leaves it in the position
+    if params.fully_closed && params.released_collateral > 0 {
+        let insurance_share = params.released_collateral
+            .checked_mul(ctx.accounts.liquidation_config.insurance_fund_share_bps as u64)
+            .ok_or(ConfidexError::ArithmeticOverflow)?
+            .checked_div(10_000)
+            .ok_or(ConfidexError::ArithmeticOverflow)?;
+        let trader_remainder = params.released_collateral - insurance_share;
+
+        if insurance_share > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.collateral_vault.to_account_info(),
+                        to: ctx.accounts.insurance_fund.to_account_info(),
+                        authority: ctx.accounts.vault_authority.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                insurance_share,
+            )?;
+
+            ctx.accounts.insurance_fund.reload()?;
+        }
+
+        if trader_remainder > 0 {
+            let trader_balance = &mut ctx.accounts.trader_balance;
+            let trader_current = trader_balance.get_balance();
+            trader_balance.set_balance(
+                trader_current
+                    .checked_add(trader_remainder)
+                    .ok_or(ConfidexError::ArithmeticOverflow)?,
+            );
+        }
+    }
+
+    let perp_market = &mut ctx.accounts.perp_market;
+    let position = &mut ctx.accounts.position;
+
+    perp_market.insurance_fund_balance = ctx.accounts.insurance_fund.amount;
+
+    // Accrued funding was settled into collateral by MPC even when nothing
+    // was closed, so the new encrypted values are always applied
+    // (funding snapshot stored in threshold_commitment by the liquidate handler)
+    position.entry_cumulative_funding = i128::from_le_bytes(
+        position.threshold_commitment[16..32]
+            .try_into()
+            .map_err(|_| ConfidexError::InvalidAccountData)?,
+    );
+    position.encrypted_size = params.new_encrypted_size;
+    position.encrypted_collateral = params.new_encrypted_collateral;
+    position.is_liquidatable = false;
+    position.clear_pending_mpc_request();
     position.last_updated_hour = coarse_time;
 
-    // Update market statistics
-    // Note: Actual OI reduction happens via MPC callback since size is encrypted
+    if params.fully_closed {
+        position.status = PositionStatus::Liquidated;
+        perp_market.record_position_closed();
+    } else {
+        // Collateral (and for a partial close, size) changed - store the
+        // threshold MPC computed for the rest
+        match position.side {
+            PositionSide::Long => {
+                position.encrypted_liq_below = params.new_encrypted_liq_threshold;
+            }
+            PositionSide::Short => {
+                position.encrypted_liq_above = params.new_encrypted_liq_threshold;
+            }
+        }
+
+        let is_long = matches!(position.side, PositionSide::Long);
+        position.threshold_commitment = ConfidentialPosition::compute_threshold_commitment(
+            &position.encrypted_entry_price,
+            position.leverage,
+            perp_market.maintenance_margin_bps,
+            is_long,
+        );
+        position.last_threshold_update_hour = coarse_time;
+    }
+
+    if params.close_size == 0 {
+        // Back above maintenance margin at the mark price - nothing to close
+        msg!("Position no longer below maintenance margin, liquidation skipped");
+        return Ok(());
+    }
+
+    match position.side {
+        PositionSide::Long => {
+            perp_market.total_long_open_interest =
+                perp_market.total_long_open_interest.saturating_sub(params.close_size);
+        }
+        PositionSide::Short => {
+            perp_market.total_short_open_interest =
+                perp_market.total_short_open_interest.saturating_sub(params.close_size);
+        }
+    }
+
+    if !params.fully_closed {
+        position.partial_close_count = position.partial_close_count.saturating_add(1);
+    }
+
+    let liquidation_config = &mut ctx.accounts.liquidation_config;
+    liquidation_config.total_liquidations = liquidation_config.total_liquidations.saturating_add(1);
+    liquidation_config.last_liquidation_time = clock.unix_timestamp;
 
     // Log liquidation event (privacy-preserving: hash-based ID, no threshold, no amounts)
     msg!(
-        "Position liquidated on market {}",
-        perp_market.key()
+        "Position liquidated on market {} (fully_closed={})",
+        market_key,
+        params.fully_closed
     );
 
     // Emit liquidation event (privacy-preserving: no amounts, no threshold, hash-based ID)
     emit!(PositionLiquidated {
         position_id: position.position_id,
         trader: position.trader,
-        market: perp_market.key(),
-        liquidator: ctx.accounts.liquidator.key(),
+        market: market_key,
+        liquidator: ctx.accounts.liquidator_balance.owner,
         side: position.side,
-        mark_price,
+        request_id: params.request_id,
+        fully_closed: params.fully_closed,
         timestamp: coarse_time,
     });
 
     Ok(())
 }
 
+/// Emitted when a liquidation is queued (result via partial_liquidation_callback)
 #[event]
-pub struct PositionLiquidated {
+pub struct LiquidationQueued {
     /// Hash-based position ID (no sequential correlation)
     pub position_id: [u8; 16],
     pub trader: Pubkey,
     pub market: Pubkey,
     pub liquidator: Pubkey,
     pub side: PositionSide,
-    /// Public oracle price at liquidation time
+    /// Public oracle price the liquidation is computed at
     pub mark_price: u64,
+    /// Request ID for tracking MPC computation
+    pub request_id: [u8; 32],
+    /// Coarse timestamp (hour precision)
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PositionLiquidated {
+    /// Hash-based position ID (no sequential correlation)
+    pub position_id: [u8; 16],
+    pub trader: Pubkey,
+    pub market: Pubkey,
+    pub liquidator: Pubkey,
+    pub side: PositionSide,
+    pub request_id: [u8; 32],
+    /// Whether the whole position was closed (false = partial liquidation)
+    pub fully_closed: bool,
+    /// Coarse timestamp (hour precision)
+    pub timestamp: i64,
+}
+
+/// Emitted when a bankrupt position's deficit exceeds the insurance fund
+/// (the position stays liquidatable for execute_adl)
+#[event]
+pub struct LiquidationDeferredToAdl {
+    /// Hash-based position ID (no sequential correlation)
+    pub position_id: [u8; 16],
+    pub trader: Pubkey,
+    pub market: Pubkey,
+    pub request_id: [u8; 32],
     /// Coarse timestamp (hour precision)
     pub timestamp: i64,
 }
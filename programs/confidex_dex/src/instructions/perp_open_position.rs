@@ -2,8 +2,10 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 
 use crate::error::ConfidexError;
+use crate::instructions::allowlist::require_allowlist_credential;
+use crate::oracle::get_market_price;
 use crate::state::{
-    ConfidentialPosition, ExchangeState, PerpetualMarket, FundingRateState,
+    AllowlistCredential, BlacklistRootHistory, ConfidentialPosition, ExchangeState, PerpetualMarket, FundingRateState,
     PositionSide, PositionStatus, TraderEligibility
 };
 
@@ -13,18 +15,24 @@ use crate::state::{
 /// Uses Box<Account<>> to move large account data to heap
 #[derive(Accounts)]
 pub struct OpenPosition<'info> {
-    /// Exchange state for blacklist root (to validate eligibility is current)
+    /// Exchange state (blacklist root validity is checked via blacklist_history)
     #[account(
         seeds = [ExchangeState::SEED],
         bump = exchange.bump,
     )]
     pub exchange: Box<Account<'info, ExchangeState>>,
 
-    /// Trader's eligibility account (must be verified)
+    /// Recent blacklist roots (grace window for attestations)
+    #[account(
+        seeds = [BlacklistRootHistory::SEED],
+        bump = blacklist_history.bump
+    )]
+    pub blacklist_history: Box<Account<'info, BlacklistRootHistory>>,
+
+    /// Trader's eligibility account (must be verified and unexpired)
     #[account(
         seeds = [TraderEligibility::SEED, trader.key().as_ref()],
         bump = eligibility.bump,
-        constraint = eligibility.is_valid(&exchange.blacklist_root) @ ConfidexError::EligibilityNotVerified
     )]
     pub eligibility: Box<Account<'info, TraderEligibility>>,
 
@@ -42,6 +50,13 @@ pub struct OpenPosition<'info> {
     )]
     pub funding_state: Box<Account<'info, FundingRateState>>,
 
+    /// Trader's credential for allowlisted markets (required when perp_market.allowlist is enabled)
+    #[account(
+        seeds = [AllowlistCredential::SEED, perp_market.key().as_ref(), trader.key().as_ref()],
+        bump = allowlist_credential.bump
+    )]
+    pub allowlist_credential: Option<Box<Account<'info, AllowlistCredential>>>,
+
     #[account(
         init,
         payer = trader,
@@ -56,12 +71,12 @@ pub struct OpenPosition<'info> {
     )]
     pub position: Box<Account<'info, ConfidentialPosition>>,
 
-    /// CHECK: Pyth oracle for mark price verification
-    #[account(
-        constraint = oracle.key() == perp_market.oracle_price_feed @ ConfidexError::InvalidOraclePrice
-    )]
+    /// CHECK: Primary oracle for mark price verification (validated in oracle module)
     pub oracle: AccountInfo<'info>,
 
+    /// CHECK: Secondary (fallback) oracle - required when the market configures one
+    pub secondary_oracle: Option<UncheckedAccount<'info>>,
+
     /// Trader's USDC token account
     /// NOTE: Using standard SPL token transfer as fallback until C-SPL SDK is available.
     /// Collateral amounts are visible on-chain in this mode.
@@ -124,9 +139,20 @@ pub struct OpenPositionParams {
 
 pub fn handler(ctx: Context<OpenPosition>, params: OpenPositionParams) -> Result<()> {
     // === LAYER 1: ZK VERIFICATION (already done) ===
-    // The eligibility constraint validates that ZK proof was verified
-    // and the blacklist root hasn't changed since verification
-    // This is enforced by: constraint = eligibility.is_valid(&exchange.blacklist_root)
+    // The ZK proof was verified via verify_eligibility; the attestation must be
+    // unexpired and its root current or within the blacklist grace window
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.eligibility.is_valid(&ctx.accounts.blacklist_history, now),
+        ConfidexError::EligibilityNotVerified
+    );
+
+    // Allowlisted markets additionally require a credential for the current root
+    require_allowlist_credential(
+        &ctx.accounts.perp_market.allowlist,
+        ctx.accounts.allowlist_credential.as_deref().map(|c| &**c),
+        now,
+    )?;
 
     // Validate leverage (cheap check)
     require!(
@@ -146,6 +172,15 @@ pub fn handler(ctx: Context<OpenPosition>, params: OpenPositionParams) -> Result
         ConfidexError::OpenInterestLimitExceeded
     );
 
+    // Entry price is encrypted, but the market's oracle must be live (matching
+    // feed, fresh price) before new exposure is accepted
+    let mark_price = get_market_price(
+        &ctx.accounts.perp_market,
+        &ctx.accounts.oracle,
+        ctx.accounts.secondary_oracle.as_deref(),
+    )?;
+    msg!("Mark price at open: {}", mark_price);
+
     // Transfer collateral from trader to vault (SPL Token fallback)
     token::transfer(
         CpiContext::new(
@@ -256,10 +291,15 @@ pub fn handler(ctx: Context<OpenPosition>, params: OpenPositionParams) -> Result
         // V8: Store full ephemeral pubkey for MPC decryption
         // MPC needs this to compute: shared_secret = X25519(mxe_private, ephemeral_pubkey)
         position.ephemeral_pubkey = params.ephemeral_pubkey;
+
+        // V9: No exit triggers until the trader sets them
+        position.clear_exit_triggers();
     }
 
-    // Increment market position count
+    // Increment market position counts
     ctx.accounts.perp_market.position_count = ctx.accounts.perp_market.position_count.saturating_add(1);
+    ctx.accounts.perp_market.open_position_count =
+        ctx.accounts.perp_market.open_position_count.saturating_add(1);
 
     // Emit event for crank to detect and process
     emit!(PositionAwaitingVerification {
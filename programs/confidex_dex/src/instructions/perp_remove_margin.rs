@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 
 use crate::error::ConfidexError;
-use crate::oracle::get_sol_usd_price;
+use crate::oracle::get_market_price;
 use crate::state::{ConfidentialPosition, PerpetualMarket, PositionSide};
 
 /// Accounts for removing margin from a position (V6 - Async MPC)
@@ -37,12 +37,12 @@ pub struct RemoveMargin<'info> {
     )]
     pub position: Account<'info, ConfidentialPosition>,
 
-    /// CHECK: Pyth oracle for current mark price (safety check)
-    #[account(
-        constraint = oracle.key() == perp_market.oracle_price_feed @ ConfidexError::InvalidOraclePrice
-    )]
+    /// CHECK: Primary oracle for current mark price (safety check) (validated in oracle module)
     pub oracle: AccountInfo<'info>,
 
+    /// CHECK: Secondary (fallback) oracle - required when the market configures one
+    pub secondary_oracle: Option<UncheckedAccount<'info>>,
+
     #[account(mut)]
     pub trader: Signer<'info>,
 
@@ -91,7 +91,11 @@ pub fn handler(ctx: Context<RemoveMargin>, params: RemoveMarginParams) -> Result
     );
 
     // Get current mark price for safety check reference (logged for debugging)
-    let mark_price = get_sol_usd_price(&ctx.accounts.oracle)?;
+    let mark_price = get_market_price(
+        &ctx.accounts.perp_market,
+        &ctx.accounts.oracle,
+        ctx.accounts.secondary_oracle.as_deref(),
+    )?;
     msg!("Mark price for margin remove safety check: {}", mark_price);
 
     // Minimum collateral check (5% safety buffer)
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 
 use crate::error::ConfidexError;
+use crate::oracle::{get_market_price, get_price, OracleError};
 use crate::state::{PerpetualMarket, FundingRateState};
 
 /// Update funding rate for a perpetual market (keeper crank instruction)
@@ -21,9 +22,16 @@ pub struct UpdateFundingRate<'info> {
     )]
     pub funding_state: Account<'info, FundingRateState>,
 
-    /// CHECK: Pyth oracle for mark price
+    /// CHECK: Primary oracle for the index price (validated in oracle module)
     pub oracle: AccountInfo<'info>,
 
+    /// CHECK: Secondary (fallback) oracle - required when the market configures one
+    pub secondary_oracle: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Perp mark price feed (validated in oracle module) - required when
+    /// the funding state configures one
+    pub mark_oracle: Option<UncheckedAccount<'info>>,
+
     /// Anyone can crank the funding rate update
     pub keeper: Signer<'info>,
 }
@@ -33,25 +41,61 @@ pub fn handler(ctx: Context<UpdateFundingRate>) -> Result<()> {
     let funding_state = &mut ctx.accounts.funding_state;
     let perp_market = &mut ctx.accounts.perp_market;
 
-    // Check if funding needs to be updated
-    require!(
-        funding_state.needs_update(clock.unix_timestamp),
-        ConfidexError::FundingNotDue
-    );
+    // Funding accrues proportionally to elapsed time, so early cranks accrue
+    // a partial interval and late cranks catch up (capped)
+    let accrual_seconds = funding_state.accrual_seconds(clock.unix_timestamp);
+    require!(accrual_seconds > 0, ConfidexError::FundingNotDue);
+    let intervals_crossed = funding_state.intervals_crossed(clock.unix_timestamp);
 
-    // Calculate new funding rate based on open interest imbalance
-    let new_rate = FundingRateState::calculate_rate_from_oi(
-        perp_market.total_long_open_interest,
-        perp_market.total_short_open_interest,
-        100, // Base rate of 1% per funding interval when imbalanced
-    );
+    // Index price from the market oracle (primary with secondary fallback)
+    let index_price = get_market_price(
+        perp_market,
+        &ctx.accounts.oracle,
+        ctx.accounts.secondary_oracle.as_deref(),
+    )?;
 
-    // Clamp to max rate
-    let clamped_rate = funding_state.clamp_rate(new_rate);
+    // Mark price from the perp mark feed; without one the mark is the index
+    // and the rate reduces to the interest component. Mark and index are
+    // averaged over the same window, so the premium compares like with like
+    let mark_sample = if funding_state.has_mark_oracle() {
+        let mark_oracle = ctx
+            .accounts
+            .mark_oracle
+            .as_deref()
+            .ok_or(OracleError::MissingMarkOracle)?;
+        get_price(
+            mark_oracle,
+            funding_state.mark_oracle_source,
+            &funding_state.mark_oracle_feed,
+            perp_market.oracle_decimals(),
+        )?
+    } else {
+        index_price
+    };
+    funding_state.update_price_emas(mark_sample, index_price, clock.unix_timestamp);
+    funding_state.last_index_price = index_price;
+
+    // Premium rate per interval, recorded in the hourly ring buffer once per
+    // interval boundary crossed (missed intervals are backfilled with the
+    // current rate - no historical prices are available on-chain)
+    let premium_bps = funding_state.premium_bps();
+    let interval_rate = funding_state.clamp_rate(funding_state.calculate_premium_rate(premium_bps));
+    for _ in 0..intervals_crossed {
+        funding_state.add_hourly_rate(interval_rate);
+    }
+
+    // Applied rate is the 8h TWAP of premium rates (smooths single-interval spikes)
+    let clamped_rate = if funding_state.rates_filled == 0 {
+        interval_rate
+    } else {
+        funding_state.clamp_rate(funding_state.calculate_twap_8h())
+    };
 
-    // Update cumulative funding
+    // Update cumulative funding, pro-rated to the elapsed time
     // Cumulative funding is scaled by 1e18 for precision
-    let funding_delta = (clamped_rate as i128) * 1_000_000_000_000_000i128 / 10000; // Convert bps to scaled
+    let funding_delta = (clamped_rate as i128) * 1_000_000_000_000_000i128 / 10000 // Convert bps to scaled
+        * accrual_seconds as i128
+        / funding_state.funding_interval_seconds as i128;
 
     if clamped_rate > 0 {
         // Longs pay shorts
@@ -73,17 +117,19 @@ pub fn handler(ctx: Context<UpdateFundingRate>) -> Result<()> {
 
     // Update funding state
     funding_state.current_rate_bps = clamped_rate;
-    funding_state.add_hourly_rate(clamped_rate);
     funding_state.last_calculation_time = clock.unix_timestamp;
 
     // Update market last funding time
     perp_market.last_funding_time = clock.unix_timestamp;
 
     msg!(
-        "Funding rate updated: {}bps (long OI: {}, short OI: {})",
+        "Funding rate updated: {}bps over {}s, {} intervals recorded (premium: {}bps, mark avg: {}, index avg: {})",
         clamped_rate,
-        perp_market.total_long_open_interest,
-        perp_market.total_short_open_interest
+        accrual_seconds,
+        intervals_crossed,
+        premium_bps,
+        funding_state.mark_price_ema,
+        funding_state.index_price_ema
     );
 
     Ok(())
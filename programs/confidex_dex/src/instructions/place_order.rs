@@ -1,8 +1,14 @@
 use anchor_lang::prelude::*;
 
+use crate::cpi::arcium::{queue_check_order_balance, MxeCpiAccounts};
 use crate::cpi::verifier::{verify_eligibility_proof, GROTH16_PROOF_SIZE};
 use crate::error::ConfidexError;
-use crate::state::{ConfidentialOrder, ExchangeState, OrderStatus, OrderType, Side, TradingPair, UserConfidentialBalance};
+use crate::instructions::allowlist::require_allowlist_credential;
+use crate::state::{
+    AllowlistCredential, BlacklistRootHistory, ConfidentialOrder, ExchangeState, OrderStatus, OrderType,
+    SelfTradePrevention, Side, TimeInForce, TraderEligibility, TradingPair,
+    UserConfidentialBalance,
+};
 
 #[derive(Accounts)]
 #[instruction(side: Side)]
@@ -55,25 +61,87 @@ pub struct PlaceOrder<'info> {
     )]
     pub user_balance: Account<'info, UserConfidentialBalance>,
 
+    /// Recent blacklist roots (grace window for eligibility attestations)
+    #[account(
+        seeds = [BlacklistRootHistory::SEED],
+        bump = blacklist_history.bump
+    )]
+    pub blacklist_history: Box<Account<'info, BlacklistRootHistory>>,
+
+    /// Maker's eligibility record from verify_eligibility (optional)
+    /// When valid (unexpired, root within grace window) no per-order proof is needed
+    #[account(
+        seeds = [TraderEligibility::SEED, maker.key().as_ref()],
+        bump = eligibility.bump
+    )]
+    pub eligibility: Option<Box<Account<'info, TraderEligibility>>>,
+
+    /// Maker's credential for allowlisted pairs (required when pair.allowlist is enabled)
+    #[account(
+        seeds = [AllowlistCredential::SEED, pair.key().as_ref(), maker.key().as_ref()],
+        bump = allowlist_credential.bump
+    )]
+    pub allowlist_credential: Option<Box<Account<'info, AllowlistCredential>>>,
+
     /// CHECK: Sunspot ZK verifier program for eligibility proofs
-    /// Will be validated when CPI integration is complete
+    /// Only invoked when a per-order eligibility proof is supplied
     pub verifier_program: AccountInfo<'info>,
 
+    /// Maker (also payer for the MPC balance check)
     #[account(mut)]
     pub maker: Signer<'info>,
 
     pub system_program: Program<'info, System>,
+
+    // =========================================================================
+    // ARCIUM MXE ACCOUNTS (11 accounts via remaining_accounts to reduce stack)
+    // Same layout as match_orders:
+    //   0: sign_pda_account (mut)
+    //   1: mxe_account (mut)
+    //   2: mempool_account (mut)
+    //   3: executing_pool (mut)
+    //   4: computation_account (mut)
+    //   5: comp_def_account (check_order_balance)
+    //   6: cluster_account (mut)
+    //   7: pool_account (mut)
+    //   8: clock_account (mut)
+    //   9: arcium_program
+    //  10: mxe_program
+    // =========================================================================
 }
 
 /// Helper to get the token mint for the order side
 /// Buy orders spend quote (USDC), sell orders spend base (SOL)
-fn get_order_token_mint(pair: &TradingPair, side: Side) -> Pubkey {
+pub(crate) fn get_order_token_mint(pair: &TradingPair, side: Side) -> Pubkey {
     match side {
         Side::Buy => pair.quote_mint,
         Side::Sell => pair.base_mint,
     }
 }
 
+/// Resolve the stored (hour precision) expiry for a new order
+///
+/// A requested GTT expiry is floored to the hour and must still be in the
+/// future. IOC/FOK orders never rest, so they expire at the end of the next
+/// hour window at the latest (the MPC match is asynchronous).
+fn resolve_expiry(time_in_force: TimeInForce, expires_at_hour: i64, now: i64) -> Result<i64> {
+    let mut expiry = 0;
+
+    if expires_at_hour != 0 {
+        expiry = ConfidentialOrder::coarse_timestamp(expires_at_hour);
+        require!(expiry > now, ConfidexError::InvalidOrderExpiry);
+    }
+
+    if matches!(time_in_force, TimeInForce::ImmediateOrCancel | TimeInForce::FillOrKill) {
+        let immediate_expiry = ConfidentialOrder::coarse_timestamp(now) + 3600;
+        if expiry == 0 || expiry > immediate_expiry {
+            expiry = immediate_expiry;
+        }
+    }
+
+    Ok(expiry)
+}
+
 /// Parameters for placing an order (V2 - privacy enhanced)
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct PlaceOrderParams {
@@ -81,56 +149,84 @@ pub struct PlaceOrderParams {
     pub order_type: OrderType,
     pub encrypted_amount: [u8; 64],
     pub encrypted_price: [u8; 64],
-    pub eligibility_proof: [u8; GROTH16_PROOF_SIZE],
+    /// Per-order proof, only needed without a valid TraderEligibility account
+    pub eligibility_proof: Option<[u8; GROTH16_PROOF_SIZE]>,
     /// Client-provided nonce for hash-based order ID generation
     pub order_nonce: [u8; 8],
 }
 
-pub fn handler(
-    ctx: Context<PlaceOrder>,
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, PlaceOrder<'info>>,
     side: Side,
     order_type: OrderType,
     encrypted_amount: [u8; 64],
     encrypted_price: [u8; 64],
-    eligibility_proof: [u8; GROTH16_PROOF_SIZE],
+    eligibility_proof: Option<[u8; GROTH16_PROOF_SIZE]>,
     ephemeral_pubkey: [u8; 32],
+    computation_offset: u64,
+    nonce: u128,
+    time_in_force: TimeInForce,
+    expires_at_hour: i64,
+    self_trade_prevention: SelfTradePrevention,
 ) -> Result<()> {
     let exchange = &mut ctx.accounts.exchange;
     let pair = &mut ctx.accounts.pair;
     let order = &mut ctx.accounts.order;
-    let user_balance = &mut ctx.accounts.user_balance;
     let clock = Clock::get()?;
 
-    // Verify eligibility proof using Sunspot verifier CPI
-    let proof_valid = verify_eligibility_proof(
-        &ctx.accounts.verifier_program,
-        &eligibility_proof,
-        &exchange.blacklist_root,
-        &ctx.accounts.maker.key(),
+    // Eligibility: prefer the maker's TraderEligibility record (verified once
+    // via verify_eligibility, same as perps) and fall back to a per-order
+    // Sunspot proof only when the record is missing or stale
+    let eligibility_current = ctx
+        .accounts
+        .eligibility
+        .as_ref()
+        .is_some_and(|eligibility| {
+            eligibility.is_valid(&ctx.accounts.blacklist_history, clock.unix_timestamp)
+        });
+
+    if !eligibility_current {
+        let eligibility_proof =
+            eligibility_proof.ok_or(ConfidexError::EligibilityNotVerified)?;
+
+        let proof_valid = verify_eligibility_proof(
+            &ctx.accounts.verifier_program,
+            &eligibility_proof,
+            &exchange.blacklist_root,
+            &ctx.accounts.maker.key(),
+        )?;
+
+        require!(proof_valid, ConfidexError::EligibilityProofFailed);
+    }
+
+    // Allowlisted pairs additionally require a credential for the current root
+    require_allowlist_credential(
+        &pair.allowlist,
+        ctx.accounts.allowlist_credential.as_deref().map(|c| &**c),
+        clock.unix_timestamp,
     )?;
 
-    require!(proof_valid, ConfidexError::EligibilityProofFailed);
+    require!(
+        ctx.remaining_accounts.len() >= 11,
+        ConfidexError::InvalidAccountCount
+    );
 
     // PURE CIPHERTEXT FORMAT (V2):
-    // We cannot extract order amount/price from encrypted data anymore.
-    // Balance validation must be done via MPC.
-    //
-    // Flow for V2:
-    // 1. Order is placed with encrypted amount/price
-    // 2. Order status is set to "Active" until MPC validates balance
-    // 3. MPC computes: required = amount * price (for buys) or amount (for sells)
-    // 4. MPC compares: user_balance >= required
-    // 5. If valid: escrow via C-SPL confidential transfer
-    // 6. If invalid: status -> Inactive
-    //
-    // For now, we skip balance validation and trust MPC callback to handle it.
-    // The order will be placed but actual balance escrow happens via MPC/C-SPL.
-    //
-    // Security: Invalid orders will fail at match time when MPC tries to settle.
-    // Users who place orders they can't afford waste their own transaction fees.
-
-    // Mark balance as tracked (but not actually escrowed yet - MPC will do this)
-    let _user_balance_ref = user_balance;
+    // We cannot extract order amount/price from encrypted data, so the
+    // balance check runs in MPC (two-phase placement):
+    // 1. Order is created as PendingBalanceCheck (cannot be matched)
+    // 2. MPC computes: required = amount * price (buys) or amount (sells)
+    // 3. MPC compares: user_balance >= required
+    // 4. place_order_callback: escrow required amount and set Active,
+    //    or set Inactive if the balance is insufficient
+
+    // Market orders always take liquidity, so they can't be post-only
+    require!(
+        !(order_type == OrderType::Market && time_in_force == TimeInForce::PostOnly),
+        ConfidexError::InvalidTimeInForce
+    );
+
+    let expires_at_hour = resolve_expiry(time_in_force, expires_at_hour, clock.unix_timestamp)?;
 
     // Generate hash-based order ID using sequential count as nonce
     // This maintains backward compatibility while adding privacy
@@ -152,7 +248,7 @@ pub fn handler(
     order.encrypted_amount = encrypted_amount;
     order.encrypted_price = encrypted_price;
     order.encrypted_filled = [0u8; 64]; // Zero-encrypted
-    order.status = OrderStatus::Active;
+    order.status = OrderStatus::PendingBalanceCheck;
     order.created_at_hour = coarse_time;
     order.order_id = order_id;
     order.order_nonce = order_nonce; // Store for PDA reconstruction
@@ -163,6 +259,15 @@ pub fn handler(
 
     // Production MPC field: full ephemeral pubkey for Arcium decryption
     order.ephemeral_pubkey = ephemeral_pubkey;
+    order.escrow_remaining = 0; // Set by place_order_callback on activation
+    order.encrypted_unsettled_fill = [0u8; 64];
+    order.fill_pending_settlement = false;
+
+    order.time_in_force = time_in_force;
+    order.expires_at_hour = expires_at_hour;
+    order.self_trade_prevention = self_trade_prevention;
+    order.pending_queued_at = 0;
+    order.discard_staged_amend();
 
     exchange.order_count = exchange.order_count.checked_add(1)
         .ok_or(ConfidexError::ArithmeticOverflow)?;
@@ -170,6 +275,50 @@ pub fn handler(
     pair.open_order_count = pair.open_order_count.checked_add(1)
         .ok_or(ConfidexError::ArithmeticOverflow)?;
 
+    // Queue MPC balance check - result comes back via place_order_callback
+    let order_key = order.key();
+    let pair_key = pair.key();
+    let user_balance_key = ctx.accounts.user_balance.key();
+    let balance = ctx.accounts.user_balance.get_balance();
+
+    let payer_info = ctx.accounts.maker.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+
+    let mxe_accounts = MxeCpiAccounts {
+        payer: &payer_info,
+        sign_pda_account: &ctx.remaining_accounts[0],
+        mxe_account: &ctx.remaining_accounts[1],
+        mempool_account: &ctx.remaining_accounts[2],
+        executing_pool: &ctx.remaining_accounts[3],
+        computation_account: &ctx.remaining_accounts[4],
+        comp_def_account: &ctx.remaining_accounts[5],
+        cluster_account: &ctx.remaining_accounts[6],
+        pool_account: &ctx.remaining_accounts[7],
+        clock_account: &ctx.remaining_accounts[8],
+        system_program: &system_program_info,
+        arcium_program: &ctx.remaining_accounts[9],
+        mxe_program: &ctx.remaining_accounts[10],
+    };
+
+    let queued = queue_check_order_balance(
+        mxe_accounts,
+        computation_offset,
+        balance,
+        &encrypted_amount,
+        &encrypted_price,
+        side == Side::Buy,
+        &ephemeral_pubkey,
+        nonce,
+        &order_key,
+        &user_balance_key,
+        &pair_key,
+    )?;
+
+    // Only the queued computation's result may activate the order
+    let order = &mut ctx.accounts.order;
+    order.pending_match_request = queued.request_id;
+    order.pending_queued_at = clock.unix_timestamp;
+
     // Emit event (no amounts/prices, hash-based ID, coarse timestamp - privacy preserving)
     emit!(OrderPlaced {
         order_id,
@@ -177,10 +326,12 @@ pub fn handler(
         pair: order.pair,
         side: order.side,
         order_type: order.order_type,
+        time_in_force: order.time_in_force,
+        expires_at_hour: order.expires_at_hour,
         timestamp: coarse_time,
     });
 
-    msg!("Order placed: {:?} (side: {:?})", order_id, side);
+    msg!("Order placed (pending balance check): {:?} (side: {:?})", order_id, side);
 
     Ok(())
 }
@@ -193,6 +344,53 @@ pub struct OrderPlaced {
     pub pair: Pubkey,
     pub side: Side,
     pub order_type: OrderType,
+    pub time_in_force: TimeInForce,
+    /// GTT expiry (hour precision), 0 = no expiry
+    pub expires_at_hour: i64,
     /// Coarse timestamp (hour precision)
     pub timestamp: i64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-01-01 10:20:00 UTC
+    const NOW: i64 = 1_704_104_400;
+    const HOUR: i64 = 1_704_103_200;
+
+    #[test]
+    fn resolve_expiry_gtc_without_expiry() {
+        assert_eq!(resolve_expiry(TimeInForce::GoodTillCancel, 0, NOW).unwrap(), 0);
+        assert_eq!(resolve_expiry(TimeInForce::PostOnly, 0, NOW).unwrap(), 0);
+    }
+
+    #[test]
+    fn resolve_expiry_floors_gtt_to_the_hour() {
+        let requested = HOUR + 2 * 3600 + 1234;
+        assert_eq!(
+            resolve_expiry(TimeInForce::GoodTillCancel, requested, NOW).unwrap(),
+            HOUR + 2 * 3600
+        );
+    }
+
+    #[test]
+    fn resolve_expiry_rejects_past_expiry() {
+        // Floors to the current hour, which has already started
+        assert!(resolve_expiry(TimeInForce::GoodTillCancel, NOW + 60, NOW).is_err());
+        assert!(resolve_expiry(TimeInForce::GoodTillCancel, NOW - 3600, NOW).is_err());
+    }
+
+    #[test]
+    fn resolve_expiry_caps_immediate_orders_at_next_hour() {
+        let next_hour = HOUR + 3600;
+        assert_eq!(
+            resolve_expiry(TimeInForce::ImmediateOrCancel, 0, NOW).unwrap(),
+            next_hour
+        );
+        assert_eq!(
+            resolve_expiry(TimeInForce::FillOrKill, HOUR + 5 * 3600, NOW).unwrap(),
+            next_hour
+        );
+    }
+}
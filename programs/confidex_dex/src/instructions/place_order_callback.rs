@@ -0,0 +1,166 @@
+//! Place order callback from MXE
+//!
+//! This instruction receives the revealed balance check result and escrow
+//! amount from the MXE's check_order_balance callback. It either activates
+//! the pending order (locking the escrow amount from the maker's balance)
+//! or rejects it.
+//!
+//! SECURITY: Only the MXE authority PDA can call this instruction.
+//! The escrow_amount is computed via MPC and passed securely - it is NOT
+//! emitted in events to preserve privacy.
+
+use anchor_lang::prelude::*;
+
+use crate::error::ConfidexError;
+use crate::state::{ConfidentialOrder, OrderStatus, TradingPair, UserConfidentialBalance};
+use crate::cpi::arcium::ARCIUM_MXE_PROGRAM_ID;
+use super::place_order::get_order_token_mint;
+
+/// MXE authority PDA seed (must match MXE program)
+const MXE_AUTHORITY_SEED: &[u8] = b"mxe_authority";
+
+/// Accounts for MPC-based place order callback
+#[derive(Accounts)]
+pub struct PlaceOrderCallback<'info> {
+    /// MXE authority PDA - must be the signer
+    /// This ensures only the MXE callback can activate orders
+    #[account(
+        signer,
+        seeds = [MXE_AUTHORITY_SEED],
+        bump,
+        seeds::program = ARCIUM_MXE_PROGRAM_ID,
+    )]
+    pub mxe_authority: AccountInfo<'info>,
+
+    /// Pending order - will be marked Active or Inactive
+    #[account(
+        mut,
+        constraint = order.is_pending_balance_check() @ ConfidexError::OrderNotPendingBalanceCheck,
+        constraint = order.pair == pair.key() @ ConfidexError::InvalidOrder,
+    )]
+    pub order: Box<Account<'info, ConfidentialOrder>>,
+
+    /// Maker's balance for the token being sold
+    /// For buy orders: quote token (USDC) balance
+    /// For sell orders: base token (SOL) balance
+    #[account(
+        mut,
+        seeds = [
+            UserConfidentialBalance::SEED,
+            order.maker.as_ref(),
+            get_order_token_mint(&pair, order.side).as_ref()
+        ],
+        bump = user_balance.bump,
+    )]
+    pub user_balance: Box<Account<'info, UserConfidentialBalance>>,
+
+    /// Trading pair account (open_order_count decremented on rejection)
+    #[account(
+        mut,
+        seeds = [
+            TradingPair::SEED,
+            pair.base_mint.as_ref(),
+            pair.quote_mint.as_ref()
+        ],
+        bump = pair.bump,
+    )]
+    pub pair: Box<Account<'info, TradingPair>>,
+}
+
+/// Activate or reject a pending order using the MPC balance check result
+///
+/// This is called by the MXE's check_order_balance_callback with the
+/// revealed result. The escrow amount was computed via MPC as:
+///   buy:  amount * price / 1e9 (quote)
+///   sell: amount (base)
+///
+/// The balance may have changed between queueing and the callback
+/// (e.g. unwrap_tokens), so it is re-checked before escrowing.
+///
+/// The request_id must match the computation recorded on the order, so a
+/// balance check queued out-of-band (e.g. with a smaller amount) can't
+/// activate it.
+///
+/// IMPORTANT: This function does NOT emit the escrow_amount in events
+/// to preserve privacy. Only order ID and timestamp are emitted.
+pub fn handler(
+    ctx: Context<PlaceOrderCallback>,
+    request_id: [u8; 32],
+    sufficient: bool,
+    escrow_amount: u64,
+) -> Result<()> {
+    let order = &mut ctx.accounts.order;
+    let pair = &mut ctx.accounts.pair;
+    let user_balance = &mut ctx.accounts.user_balance;
+    let clock = Clock::get()?;
+
+    require!(
+        order.pending_match_request == request_id,
+        ConfidexError::InvalidMpcRequest
+    );
+    order.pending_match_request = [0u8; 32];
+    order.pending_queued_at = 0;
+
+    let current_balance = user_balance.get_balance();
+    let activated = sufficient && current_balance >= escrow_amount;
+
+    let coarse_time = ConfidentialOrder::coarse_timestamp(clock.unix_timestamp);
+
+    if activated {
+        // Lock funds: the order's escrow is debited by settlement and the
+        // remainder released by cancel/expiry or once fully filled
+        user_balance.set_balance(
+            current_balance.checked_sub(escrow_amount)
+                .ok_or(ConfidexError::InsufficientBalance)?
+        );
+        order.escrow_remaining = escrow_amount;
+        order.status = OrderStatus::Active;
+
+        msg!("Order activated after MPC balance check");
+
+        emit!(OrderActivated {
+            order_id: order.order_id,
+            maker: order.maker,
+            pair: order.pair,
+            timestamp: coarse_time,
+        });
+    } else {
+        order.status = OrderStatus::Inactive;
+
+        pair.open_order_count = pair.open_order_count.checked_sub(1)
+            .ok_or(ConfidexError::ArithmeticOverflow)?;
+
+        msg!("Order rejected: insufficient balance");
+
+        emit!(OrderRejected {
+            order_id: order.order_id,
+            maker: order.maker,
+            pair: order.pair,
+            timestamp: coarse_time,
+        });
+    }
+
+    Ok(())
+}
+
+/// Emitted when a pending order passes the MPC balance check
+#[event]
+pub struct OrderActivated {
+    /// Hash-based order ID (no sequential correlation)
+    pub order_id: [u8; 16],
+    pub maker: Pubkey,
+    pub pair: Pubkey,
+    /// Coarse timestamp (hour precision for privacy)
+    pub timestamp: i64,
+}
+
+/// Emitted when a pending order fails the MPC balance check
+#[event]
+pub struct OrderRejected {
+    /// Hash-based order ID (no sequential correlation)
+    pub order_id: [u8; 16],
+    pub maker: Pubkey,
+    pub pair: Pubkey,
+    /// Coarse timestamp (hour precision for privacy)
+    pub timestamp: i64,
+}
@@ -0,0 +1,153 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ConfidexError;
+use crate::state::{ConfidentialOrder, OrderStatus, Side, TradingPair, UserConfidentialBalance};
+
+/// Accounts for recovering an order whose MPC callback never arrived (maker only)
+///
+/// A PendingBalanceCheck, PendingCancel or PendingAmend order can't be matched,
+/// re-cancelled or expired, so if its MPC callback is lost the order (and any
+/// escrow) would stay locked. Once PENDING_MPC_TIMEOUT_SECS have passed the
+/// maker can release it directly, or put an amended order back on the book.
+#[derive(Accounts)]
+pub struct RecoverPendingOrder<'info> {
+    #[account(
+        mut,
+        seeds = [
+            TradingPair::SEED,
+            pair.base_mint.as_ref(),
+            pair.quote_mint.as_ref()
+        ],
+        bump = pair.bump
+    )]
+    pub pair: Box<Account<'info, TradingPair>>,
+
+    /// Stuck order - marked Inactive, or Active again if an amend was pending
+    #[account(
+        mut,
+        seeds = [
+            ConfidentialOrder::SEED,
+            order.maker.as_ref(),
+            &order.order_nonce
+        ],
+        bump = order.bump,
+        constraint = order.pair == pair.key() @ ConfidexError::InvalidOrder,
+        constraint = order.maker == maker.key() @ ConfidexError::OrderOwnerMismatch,
+        constraint = order.is_pending_balance_check()
+            || order.is_pending_cancel()
+            || order.is_pending_amend() @ ConfidexError::OrderNotOpen
+    )]
+    pub order: Box<Account<'info, ConfidentialOrder>>,
+
+    /// Maker's base token balance - refund target for sell orders
+    #[account(
+        mut,
+        seeds = [
+            UserConfidentialBalance::SEED,
+            maker.key().as_ref(),
+            pair.base_mint.as_ref()
+        ],
+        bump = user_base_balance.bump,
+    )]
+    pub user_base_balance: Box<Account<'info, UserConfidentialBalance>>,
+
+    /// Maker's quote token balance - refund target for buy orders
+    #[account(
+        mut,
+        seeds = [
+            UserConfidentialBalance::SEED,
+            maker.key().as_ref(),
+            pair.quote_mint.as_ref()
+        ],
+        bump = user_quote_balance.bump,
+    )]
+    pub user_quote_balance: Box<Account<'info, UserConfidentialBalance>>,
+
+    pub maker: Signer<'info>,
+}
+
+/// Recover an order whose MPC request timed out
+///
+/// A pending amend is abandoned: the staged values are dropped and the order
+/// returns to Active with its pre-amend amount, price and escrow. Otherwise
+/// the order's own plaintext escrow is released, so no MPC output is needed
+/// (a PendingBalanceCheck order has nothing escrowed yet), and the order is
+/// deactivated. Clearing pending_match_request means a late callback for the
+/// abandoned request is rejected.
+pub fn handler(ctx: Context<RecoverPendingOrder>) -> Result<()> {
+    let order = &mut ctx.accounts.order;
+    let pair = &mut ctx.accounts.pair;
+    let user_base_balance = &mut ctx.accounts.user_base_balance;
+    let user_quote_balance = &mut ctx.accounts.user_quote_balance;
+    let clock = Clock::get()?;
+
+    require!(
+        order.can_recover_pending(clock.unix_timestamp),
+        ConfidexError::PendingOrderNotTimedOut
+    );
+
+    let coarse_time = ConfidentialOrder::coarse_timestamp(clock.unix_timestamp);
+
+    if order.is_pending_amend() {
+        order.revert_pending_amend();
+
+        emit!(PendingOrderRecovered {
+            order_id: order.order_id,
+            maker: order.maker,
+            pair: order.pair,
+            reactivated: true,
+            timestamp: coarse_time,
+        });
+
+        msg!("Pending amend abandoned after MPC timeout: {:?}", order.order_id);
+
+        return Ok(());
+    }
+
+    let released = order.release_escrow();
+    if released > 0 {
+        let balance = match order.side {
+            Side::Buy => user_quote_balance,
+            Side::Sell => user_base_balance,
+        };
+        let current_balance = balance.get_balance();
+        balance.set_balance(
+            current_balance.checked_add(released)
+                .ok_or(ConfidexError::ArithmeticOverflow)?
+        );
+    }
+
+    order.status = OrderStatus::Inactive;
+    order.pending_match_request = [0u8; 32];
+    order.pending_queued_at = 0;
+
+    pair.open_order_count = pair.open_order_count.checked_sub(1)
+        .ok_or(ConfidexError::ArithmeticOverflow)?;
+
+    emit!(PendingOrderRecovered {
+        order_id: order.order_id,
+        maker: order.maker,
+        pair: order.pair,
+        reactivated: false,
+        timestamp: coarse_time,
+    });
+
+    msg!("Pending order recovered after MPC timeout: {:?}", order.order_id);
+
+    Ok(())
+}
+
+/// Emitted when a maker recovers an order whose MPC callback timed out
+/// (no amounts, as with OrderCancelledPrivate)
+#[event]
+pub struct PendingOrderRecovered {
+    /// Hash-based order ID (no sequential correlation)
+    pub order_id: [u8; 16],
+    pub maker: Pubkey,
+    pub pair: Pubkey,
+    /// Whether the order went back on the book (abandoned amend) rather
+    /// than being deactivated
+    pub reactivated: bool,
+    /// Coarse timestamp (hour precision for privacy)
+    pub timestamp: i64,
+}
@@ -11,7 +11,9 @@
 use anchor_lang::prelude::*;
 
 use crate::error::ConfidexError;
-use crate::state::{ConfidentialOrder, ExchangeState, Side, TradingPair, UserConfidentialBalance};
+use crate::state::{
+    ConfidentialOrder, ExchangeState, OrderStatus, Side, TradingPair, UserConfidentialBalance,
+};
 use crate::settlement::types::SettlementMethod;
 use crate::settlement::shadowwire::SHADOWWIRE_FEE_BPS;
 use crate::cpi::arcium::ARCIUM_MXE_PROGRAM_ID;
@@ -32,7 +34,7 @@ pub struct SettleOrderCallback<'info> {
     )]
     pub mxe_authority: AccountInfo<'info>,
 
-    /// Buy order - will have encrypted_filled cleared
+    /// Buy order - escrow (quote) debited by the fill value, unsettled fill cleared
     #[account(
         mut,
         constraint = buy_order.pair == pair.key() @ ConfidexError::InvalidOrder,
@@ -40,7 +42,7 @@ pub struct SettleOrderCallback<'info> {
     )]
     pub buy_order: Box<Account<'info, ConfidentialOrder>>,
 
-    /// Sell order - will have encrypted_filled cleared
+    /// Sell order - escrow (base) debited by the fill amount, unsettled fill cleared
     #[account(
         mut,
         constraint = sell_order.pair == pair.key() @ ConfidexError::InvalidOrder,
@@ -71,7 +73,7 @@ pub struct SettleOrderCallback<'info> {
     )]
     pub buyer_base_balance: Box<Account<'info, UserConfidentialBalance>>,
 
-    /// Buyer's quote token balance - will have tokens deducted
+    /// Buyer's quote token balance (funds already escrowed at placement)
     #[account(
         mut,
         seeds = [
@@ -83,7 +85,7 @@ pub struct SettleOrderCallback<'info> {
     )]
     pub buyer_quote_balance: Box<Account<'info, UserConfidentialBalance>>,
 
-    /// Seller's base token balance - will have tokens deducted
+    /// Seller's base token balance (funds already escrowed at placement)
     #[account(
         mut,
         seeds = [
@@ -107,7 +109,7 @@ pub struct SettleOrderCallback<'info> {
     )]
     pub seller_quote_balance: Box<Account<'info, UserConfidentialBalance>>,
 
-    /// Fee recipient's quote token balance
+    /// Fee recipient's quote token balance (fees charged to the seller)
     #[account(
         mut,
         seeds = [
@@ -119,7 +121,19 @@ pub struct SettleOrderCallback<'info> {
     )]
     pub fee_recipient_balance: Box<Account<'info, UserConfidentialBalance>>,
 
-    /// Exchange state (for fee_recipient pubkey and fee_bps)
+    /// Fee recipient's base token balance (fees charged to the buyer)
+    #[account(
+        mut,
+        seeds = [
+            UserConfidentialBalance::SEED,
+            exchange.fee_recipient.as_ref(),
+            pair.base_mint.as_ref()
+        ],
+        bump = fee_recipient_base_balance.bump,
+    )]
+    pub fee_recipient_base_balance: Box<Account<'info, UserConfidentialBalance>>,
+
+    /// Exchange state (for fee_recipient pubkey and maker/taker fee_bps)
     #[account(
         seeds = [ExchangeState::SEED],
         bump = exchange.bump,
@@ -133,6 +147,22 @@ pub struct SettleOrderCallback<'info> {
 /// revealed fill_amount and price. These values were decrypted via MPC
 /// and are passed securely (not read from storage).
 ///
+/// Both orders must carry the unsettled fill of the same match, which is
+/// cleared here so a settlement can't be replayed or pay an earlier fill
+/// twice. Each order's plaintext escrow is debited before anything is
+/// credited, and a fill larger than the remaining escrow is rejected, so a
+/// forged or replayed decryption can never pay out more than the makers locked.
+///
+/// Fees: the order placed first is the maker, the other the taker. Each side
+/// pays its fee in the token it receives (buyer: base, seller: quote). A
+/// negative maker fee is a rebate paid out of the taker fee, in the taker's
+/// fee token.
+///
+/// Self-trades (decrement mode) are not trades: nothing changes hands and no
+/// fee is charged. Both orders shrink by the overlap (MPC already counted it
+/// in encrypted_filled) and each side's escrow for it is released back to the
+/// balance it was locked from.
+///
 /// IMPORTANT: This function does NOT emit the fill_amount or price in events
 /// to preserve privacy. Only order IDs and timestamp are emitted.
 pub fn handler(
@@ -140,14 +170,23 @@ pub fn handler(
     fill_amount: u64,
     price: u64,
 ) -> Result<()> {
-    let buy_order = &ctx.accounts.buy_order;
-    let sell_order = &ctx.accounts.sell_order;
+    let buy_order = &mut ctx.accounts.buy_order;
+    let sell_order = &mut ctx.accounts.sell_order;
     let buyer_base_balance = &mut ctx.accounts.buyer_base_balance;
     let buyer_quote_balance = &mut ctx.accounts.buyer_quote_balance;
     let seller_base_balance = &mut ctx.accounts.seller_base_balance;
     let seller_quote_balance = &mut ctx.accounts.seller_quote_balance;
     let exchange = &ctx.accounts.exchange;
     let fee_recipient_balance = &mut ctx.accounts.fee_recipient_balance;
+    let fee_recipient_base_balance = &mut ctx.accounts.fee_recipient_base_balance;
+
+    // Only a match recorded by MPC and not yet settled can be paid out
+    require!(
+        buy_order.shares_unsettled_fill(sell_order),
+        ConfidexError::OrderNotFilled
+    );
+    buy_order.take_unsettled_fill();
+    sell_order.take_unsettled_fill();
 
     // Validate decrypted values
     require!(fill_amount > 0, ConfidexError::OrderNotFilled);
@@ -160,6 +199,53 @@ pub fn handler(
         .checked_div(1_000_000_000) // SOL decimals (9)
         .ok_or(ConfidexError::ArithmeticOverflow)? as u64;
 
+    if buy_order.maker == sell_order.maker {
+        // Self-trade prevention (decrement): release the overlap from both
+        // escrows, plus the leftover of an order that is now fully filled
+        buy_order.debit_escrow(fill_value)?;
+        sell_order.debit_escrow(fill_amount)?;
+        let mut quote_released = fill_value;
+        let mut base_released = fill_amount;
+        if buy_order.status == OrderStatus::Inactive {
+            quote_released = quote_released
+                .checked_add(buy_order.release_escrow())
+                .ok_or(ConfidexError::ArithmeticOverflow)?;
+        }
+        if sell_order.status == OrderStatus::Inactive {
+            base_released = base_released
+                .checked_add(sell_order.release_escrow())
+                .ok_or(ConfidexError::ArithmeticOverflow)?;
+        }
+
+        // One maker on both sides: buyer and seller balances are the same
+        // accounts, and each copy is written back on exit. Credit through
+        // the seller copies and mirror them into the buyer copies.
+        let seller_quote_current = seller_quote_balance.get_balance();
+        seller_quote_balance.set_balance(
+            seller_quote_current.checked_add(quote_released)
+                .ok_or(ConfidexError::ArithmeticOverflow)?
+        );
+        let seller_base_current = seller_base_balance.get_balance();
+        seller_base_balance.set_balance(
+            seller_base_current.checked_add(base_released)
+                .ok_or(ConfidexError::ArithmeticOverflow)?
+        );
+        buyer_quote_balance.set_balance(seller_quote_balance.get_balance());
+        buyer_base_balance.set_balance(seller_base_balance.get_balance());
+
+        msg!("Self-trade decremented");
+
+        emit!(SelfTradeDecremented {
+            buy_order_id: buy_order.order_id,
+            sell_order_id: sell_order.order_id,
+            maker: buy_order.maker,
+            pair: ctx.accounts.pair.key(),
+            timestamp: ConfidentialOrder::coarse_timestamp(Clock::get()?.unix_timestamp),
+        });
+
+        return Ok(());
+    }
+
     // Use ShadowWire as default settlement method (privacy-preserving)
     let settlement_fee = fill_value
         .checked_mul(SHADOWWIRE_FEE_BPS as u64)
@@ -167,51 +253,124 @@ pub fn handler(
         .checked_div(10_000)
         .ok_or(ConfidexError::ArithmeticOverflow)?;
 
-    // Calculate taker fee
-    let taker_fee = fill_value
-        .checked_mul(exchange.taker_fee_bps as u64)
-        .ok_or(ConfidexError::ArithmeticOverflow)?
-        .checked_div(10_000)
-        .ok_or(ConfidexError::ArithmeticOverflow)?;
+    // Maker = order that rested on the book first (lower placement sequence)
+    let buyer_is_maker = buy_order.is_maker_against(sell_order);
 
-    // Net to seller = fill_value - taker_fee - settlement_fee
-    let net_to_seller = fill_value
-        .checked_sub(taker_fee)
-        .ok_or(ConfidexError::ArithmeticOverflow)?
-        .checked_sub(settlement_fee)
+    // Notional each side receives: buyer gets base, seller gets quote
+    let (maker_notional, taker_notional) = if buyer_is_maker {
+        (fill_amount, fill_value)
+    } else {
+        (fill_value, fill_amount)
+    };
+
+    let taker_fee = fee_for(taker_notional, exchange.taker_fee_bps)?;
+
+    // Maker fee (charged in maker's token) or rebate (paid in taker's token)
+    let (maker_fee, maker_rebate) = if exchange.maker_fee_bps >= 0 {
+        (fee_for(maker_notional, exchange.maker_fee_bps as u16)?, 0)
+    } else {
+        (0, fee_for(taker_notional, exchange.maker_fee_bps.unsigned_abs())?)
+    };
+
+    // Rebate is funded from the taker fee (validate_fees guarantees it fits)
+    let taker_fee_net = taker_fee
+        .checked_sub(maker_rebate)
         .ok_or(ConfidexError::ArithmeticOverflow)?;
 
-    // Transfer base token: Seller → Buyer
-    let seller_base_current = seller_base_balance.get_balance();
+    let (buyer_fee, seller_fee) = if buyer_is_maker {
+        (maker_fee, taker_fee)
+    } else {
+        (taker_fee, maker_fee)
+    };
+
+    // Seller's base and buyer's quote were escrowed at placement
+    // (place_order_callback): debit both escrows before crediting anyone
+    buy_order.debit_escrow(fill_value)?;
+    sell_order.debit_escrow(fill_amount)?;
+
+    // Transfer base token: Seller escrow → Buyer (net of buyer fee)
     let buyer_base_current = buyer_base_balance.get_balance();
-    require!(
-        seller_base_current >= fill_amount,
-        ConfidexError::InsufficientBalance
+    buyer_base_balance.set_balance(
+        buyer_base_current
+            .checked_add(
+                fill_amount
+                    .checked_sub(buyer_fee)
+                    .ok_or(ConfidexError::ArithmeticOverflow)?,
+            )
+            .ok_or(ConfidexError::ArithmeticOverflow)?
     );
-    seller_base_balance.set_balance(seller_base_current - fill_amount);
-    buyer_base_balance.set_balance(buyer_base_current + fill_amount);
 
-    // Transfer quote token: Buyer → Seller (net of fees)
-    let buyer_quote_current = buyer_quote_balance.get_balance();
+    // Transfer quote token: Buyer escrow → Seller (net of seller fee and settlement fee)
+    let net_to_seller = fill_value
+        .checked_sub(seller_fee)
+        .ok_or(ConfidexError::ArithmeticOverflow)?
+        .checked_sub(settlement_fee)
+        .ok_or(ConfidexError::ArithmeticOverflow)?;
     let seller_quote_current = seller_quote_balance.get_balance();
-    require!(
-        buyer_quote_current >= fill_value,
-        ConfidexError::InsufficientBalance
+    seller_quote_balance.set_balance(
+        seller_quote_current.checked_add(net_to_seller)
+            .ok_or(ConfidexError::ArithmeticOverflow)?
     );
-    buyer_quote_balance.set_balance(buyer_quote_current - fill_value);
-    seller_quote_balance.set_balance(seller_quote_current + net_to_seller);
 
-    // Transfer fee to fee recipient
-    if taker_fee > 0 {
+    // Fully filled orders release leftover escrow (fills below the buyer's
+    // limit, rounding) back to the maker
+    if buy_order.status == OrderStatus::Inactive {
+        let released = buy_order.release_escrow();
+        let buyer_quote_current = buyer_quote_balance.get_balance();
+        buyer_quote_balance.set_balance(
+            buyer_quote_current.checked_add(released)
+                .ok_or(ConfidexError::ArithmeticOverflow)?
+        );
+    }
+    if sell_order.status == OrderStatus::Inactive {
+        let released = sell_order.release_escrow();
+        let seller_base_current = seller_base_balance.get_balance();
+        seller_base_balance.set_balance(
+            seller_base_current.checked_add(released)
+                .ok_or(ConfidexError::ArithmeticOverflow)?
+        );
+    }
+
+    // Fees collected per token (the rebate comes out of the taker's fee token)
+    let (base_fees, quote_fees) = if buyer_is_maker {
+        (maker_fee, taker_fee_net)
+    } else {
+        (taker_fee_net, maker_fee)
+    };
+
+    // Pay maker rebate in the taker's fee token
+    if maker_rebate > 0 {
+        let rebate_balance = if buyer_is_maker {
+            buyer_quote_balance
+        } else {
+            seller_base_balance
+        };
+        let rebate_current = rebate_balance.get_balance();
+        rebate_balance.set_balance(
+            rebate_current.checked_add(maker_rebate)
+                .ok_or(ConfidexError::ArithmeticOverflow)?
+        );
+    }
+
+    // Transfer fees to fee recipient
+    if quote_fees > 0 {
         let fee_recipient_current = fee_recipient_balance.get_balance();
-        fee_recipient_balance.set_balance(fee_recipient_current + taker_fee);
+        fee_recipient_balance.set_balance(
+            fee_recipient_current.checked_add(quote_fees)
+                .ok_or(ConfidexError::ArithmeticOverflow)?
+        );
+    }
+    if base_fees > 0 {
+        let fee_recipient_base_current = fee_recipient_base_balance.get_balance();
+        fee_recipient_base_balance.set_balance(
+            fee_recipient_base_current.checked_add(base_fees)
+                .ok_or(ConfidexError::ArithmeticOverflow)?
+        );
     }
 
-    // Mark orders as settled by clearing encrypted_filled
-    let buy_order = &mut ctx.accounts.buy_order;
-    let sell_order = &mut ctx.accounts.sell_order;
-    buy_order.encrypted_filled = [0u8; 64];
-    sell_order.encrypted_filled = [0u8; 64];
+    // NOTE: encrypted_filled is NOT cleared - it tracks the cumulative fill
+    // across matches so partially filled orders keep their remaining size.
+    // The per-match unsettled fill was cleared above.
 
     msg!("MPC settlement complete");
 
@@ -232,6 +391,27 @@ pub fn handler(
     Ok(())
 }
 
+/// Fee in basis points of `notional` (rounded down)
+fn fee_for(notional: u64, bps: u16) -> Result<u64> {
+    Ok(notional
+        .checked_mul(bps as u64)
+        .ok_or(ConfidexError::ArithmeticOverflow)?
+        .checked_div(10_000)
+        .ok_or(ConfidexError::ArithmeticOverflow)?)
+}
+
+/// Event emitted when a self-trade is decremented instead of settled
+///
+/// Like OrderSettledPrivate, no amount information is included.
+#[event]
+pub struct SelfTradeDecremented {
+    pub buy_order_id: [u8; 16],
+    pub sell_order_id: [u8; 16],
+    pub maker: Pubkey,
+    pub pair: Pubkey,
+    pub timestamp: i64,
+}
+
 /// Privacy-preserving settlement event
 ///
 /// Unlike OrderSettled in settle_order.rs, this event does NOT include
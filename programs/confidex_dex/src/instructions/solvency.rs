@@ -0,0 +1,434 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::cpi::verifier::{
+    current_eligibility_epoch, verify_solvency_proof, SolvencyPublicInputs, GROTH16_PROOF_SIZE,
+};
+use crate::error::ConfidexError;
+use crate::state::{ExchangeState, SolvencyAttestation, SolvencyConfig, TradingPair};
+
+// ============================================================================
+// PROOF OF SOLVENCY (circuits/solvency)
+// ============================================================================
+//
+// The exchange authority periodically proves, per token mint, that reserves
+// cover the total committed to by the liabilities Merkle-sum-tree root. The
+// proof is bound to the mint, the current epoch and the balance of the pair
+// vaults holding the mint (reserves can't exceed it), verified via Sunspot CPI
+// and recorded in a SolvencyAttestation. If auto-pause is enabled, anyone can
+// pause the exchange when a traded mint has no attestation within the
+// configured age.
+
+/// Basis points representing a 100% reserves/liabilities ratio
+const FULLY_SOLVENT_BPS: u64 = 10_000;
+
+// ============================================================================
+// Initialize Solvency Config
+// ============================================================================
+
+/// Accounts for creating the solvency configuration
+#[derive(Accounts)]
+pub struct InitializeSolvencyConfig<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+        has_one = authority @ ConfidexError::Unauthorized
+    )]
+    pub exchange: Account<'info, ExchangeState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = SolvencyConfig::SIZE,
+        seeds = [SolvencyConfig::SEED],
+        bump
+    )]
+    pub solvency_config: Account<'info, SolvencyConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_solvency_config_handler(
+    ctx: Context<InitializeSolvencyConfig>,
+    verifier: Pubkey,
+    max_attestation_age_secs: i64,
+    auto_pause: bool,
+) -> Result<()> {
+    require!(
+        ExchangeState::validate_program_id(&verifier)
+            && SolvencyConfig::validate_max_age(max_attestation_age_secs),
+        ConfidexError::InvalidSolvencyConfig
+    );
+
+    let config = &mut ctx.accounts.solvency_config;
+    config.verifier = verifier;
+    config.max_attestation_age_secs = max_attestation_age_secs;
+    config.auto_pause = auto_pause;
+    config.bump = ctx.bumps.solvency_config;
+
+    msg!(
+        "Solvency config initialized: verifier {}, max age {}s, auto-pause {}",
+        verifier,
+        max_attestation_age_secs,
+        auto_pause
+    );
+
+    Ok(())
+}
+
+// ============================================================================
+// Update Solvency Config
+// ============================================================================
+
+/// Accounts for updating the solvency configuration
+#[derive(Accounts)]
+pub struct UpdateSolvencyConfig<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+        has_one = authority @ ConfidexError::Unauthorized
+    )]
+    pub exchange: Account<'info, ExchangeState>,
+
+    #[account(
+        mut,
+        seeds = [SolvencyConfig::SEED],
+        bump = solvency_config.bump
+    )]
+    pub solvency_config: Account<'info, SolvencyConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+/// Parameters for updating the solvency configuration (None = unchanged)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UpdateSolvencyConfigParams {
+    pub verifier: Option<Pubkey>,
+    pub max_attestation_age_secs: Option<i64>,
+    pub auto_pause: Option<bool>,
+}
+
+pub fn update_solvency_config_handler(
+    ctx: Context<UpdateSolvencyConfig>,
+    params: UpdateSolvencyConfigParams,
+) -> Result<()> {
+    let config = &mut ctx.accounts.solvency_config;
+
+    if let Some(verifier) = params.verifier {
+        require!(
+            ExchangeState::validate_program_id(&verifier),
+            ConfidexError::InvalidSolvencyConfig
+        );
+        config.verifier = verifier;
+        msg!("Solvency verifier updated: {}", verifier);
+    }
+
+    if let Some(max_age) = params.max_attestation_age_secs {
+        require!(
+            SolvencyConfig::validate_max_age(max_age),
+            ConfidexError::InvalidSolvencyConfig
+        );
+        config.max_attestation_age_secs = max_age;
+        msg!("Solvency max attestation age updated: {}s", max_age);
+    }
+
+    if let Some(auto_pause) = params.auto_pause {
+        config.auto_pause = auto_pause;
+        msg!("Solvency auto-pause: {}", auto_pause);
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Submit Solvency Proof
+// ============================================================================
+
+/// Accounts for submitting a proof of solvency for a mint
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct SubmitSolvencyProof<'info> {
+    #[account(
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+        has_one = authority @ ConfidexError::Unauthorized
+    )]
+    pub exchange: Account<'info, ExchangeState>,
+
+    #[account(
+        seeds = [SolvencyConfig::SEED],
+        bump = solvency_config.bump
+    )]
+    pub solvency_config: Account<'info, SolvencyConfig>,
+
+    /// Latest attestation for the mint (created on first submission)
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = SolvencyAttestation::SIZE,
+        seeds = [SolvencyAttestation::SEED, mint.as_ref()],
+        bump
+    )]
+    pub attestation: Account<'info, SolvencyAttestation>,
+
+    /// CHECK: Sunspot verifier for the solvency circuit (must match solvency_config)
+    #[account(
+        constraint = verifier_program.key() == solvency_config.verifier @ ConfidexError::InvalidSolvencyVerifier
+    )]
+    pub verifier_program: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    // =========================================================================
+    // REMAINING ACCOUNTS
+    //   for each pair trading the mint, in ascending pair key order:
+    //          the TradingPair, followed by its vault token account for the mint
+    // =========================================================================
+}
+
+/// Public statement and proof for a solvency attestation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SubmitSolvencyProofParams {
+    /// Groth16 proof from the solvency circuit (324 bytes)
+    pub proof: [u8; GROTH16_PROOF_SIZE],
+    /// Merkle-sum-tree root of user liabilities
+    pub liabilities_root: [u8; 32],
+    /// Sum of all user balances at the tree root (committed to by the root)
+    pub total_liabilities: u64,
+    /// Poseidon commitment to the reserves
+    pub reserves_commitment: [u8; 32],
+    /// Reserves/liabilities ratio in basis points
+    pub solvency_ratio_bps: u64,
+}
+
+pub fn submit_solvency_proof_handler(
+    ctx: Context<SubmitSolvencyProof>,
+    mint: Pubkey,
+    params: SubmitSolvencyProofParams,
+) -> Result<()> {
+    // The circuit enforces reserves >= liabilities; reject the claim early
+    require!(
+        params.solvency_ratio_bps >= FULLY_SOLVENT_BPS,
+        ConfidexError::InvalidSolvencyRatio
+    );
+
+    let clock = Clock::get()?;
+    let epoch = current_eligibility_epoch()?;
+    let vault_balance = sum_vault_balances(ctx.remaining_accounts, &mint)?;
+    require!(
+        SolvencyAttestation::validate_liabilities(params.total_liabilities, vault_balance),
+        ConfidexError::InvalidSolvencyLiabilities
+    );
+
+    let public_inputs = SolvencyPublicInputs::new(
+        &params.liabilities_root,
+        params.total_liabilities,
+        &params.reserves_commitment,
+        params.solvency_ratio_bps,
+        vault_balance,
+        &mint,
+        epoch,
+    );
+
+    let proof_valid = verify_solvency_proof(
+        &ctx.accounts.verifier_program.to_account_info(),
+        &params.proof,
+        &public_inputs,
+    )?;
+
+    require!(proof_valid, ConfidexError::SolvencyProofFailed);
+
+    let attestation = &mut ctx.accounts.attestation;
+    attestation.mint = mint;
+    attestation.liabilities_root = params.liabilities_root;
+    attestation.total_liabilities = params.total_liabilities;
+    attestation.reserves_commitment = params.reserves_commitment;
+    attestation.solvency_ratio_bps = params.solvency_ratio_bps;
+    attestation.vault_balance = vault_balance;
+    attestation.attested_at = clock.unix_timestamp;
+    attestation.epoch = epoch;
+    attestation.attestation_count = attestation.attestation_count.saturating_add(1);
+    attestation.bump = ctx.bumps.attestation;
+
+    emit!(SolvencyAttested {
+        mint,
+        liabilities_root: params.liabilities_root,
+        total_liabilities: params.total_liabilities,
+        solvency_ratio_bps: params.solvency_ratio_bps,
+        vault_balance,
+        epoch,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Solvency attested for mint {}: ratio {} bps, vault balance {} (attestation #{})",
+        mint,
+        params.solvency_ratio_bps,
+        vault_balance,
+        attestation.attestation_count
+    );
+
+    Ok(())
+}
+
+/// Sum the mint's vault balances over the [pair, vault] groups in remaining_accounts
+///
+/// Each vault must be the pair's base or quote vault for the mint. Pairs are
+/// required in strictly ascending key order so no vault is counted twice.
+fn sum_vault_balances(remaining_accounts: &[AccountInfo], mint: &Pubkey) -> Result<u64> {
+    require!(
+        !remaining_accounts.is_empty() && remaining_accounts.len() % 2 == 0,
+        ConfidexError::InvalidAccountCount
+    );
+
+    let mut total: u64 = 0;
+    let mut last_pair: Option<Pubkey> = None;
+    for group in remaining_accounts.chunks(2) {
+        let (pair_info, vault_info) = (&group[0], &group[1]);
+
+        if let Some(last) = last_pair {
+            require!(*pair_info.key > last, ConfidexError::InvalidSolvencyVaults);
+        }
+        last_pair = Some(*pair_info.key);
+
+        require!(pair_info.owner == &crate::ID, ConfidexError::InvalidAccountData);
+        let pair = {
+            let data = pair_info.try_borrow_data()?;
+            TradingPair::try_deserialize(&mut &data[..])?
+        };
+        let expected_vault = if pair.base_mint == *mint {
+            pair.c_base_vault
+        } else if pair.quote_mint == *mint {
+            pair.c_quote_vault
+        } else {
+            return err!(ConfidexError::InvalidSolvencyMint);
+        };
+        require!(*vault_info.key == expected_vault, ConfidexError::InvalidVault);
+
+        require!(
+            vault_info.owner == &anchor_spl::token::ID,
+            ConfidexError::InvalidVault
+        );
+        let vault = {
+            let data = vault_info.try_borrow_data()?;
+            TokenAccount::try_deserialize(&mut &data[..])?
+        };
+        require!(vault.mint == *mint, ConfidexError::InvalidVault);
+
+        total = total
+            .checked_add(vault.amount)
+            .ok_or(ConfidexError::ArithmeticOverflow)?;
+    }
+
+    Ok(total)
+}
+
+// ============================================================================
+// Enforce Solvency Attestation (permissionless)
+// ============================================================================
+
+/// Accounts for pausing the exchange when a traded mint's attestation is stale
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct EnforceSolvencyAttestation<'info> {
+    #[account(
+        mut,
+        seeds = [ExchangeState::SEED],
+        bump = exchange.bump,
+    )]
+    pub exchange: Account<'info, ExchangeState>,
+
+    #[account(
+        seeds = [SolvencyConfig::SEED],
+        bump = solvency_config.bump,
+        constraint = solvency_config.auto_pause @ ConfidexError::SolvencyAutoPauseDisabled
+    )]
+    pub solvency_config: Account<'info, SolvencyConfig>,
+
+    /// Active pair trading the mint (keeps the check to mints the exchange holds)
+    #[account(
+        seeds = [TradingPair::SEED, pair.base_mint.as_ref(), pair.quote_mint.as_ref()],
+        bump = pair.bump,
+        constraint = pair.active @ ConfidexError::PairNotActive,
+        constraint = (pair.base_mint == mint || pair.quote_mint == mint) @ ConfidexError::InvalidSolvencyMint
+    )]
+    pub pair: Account<'info, TradingPair>,
+
+    /// CHECK: Attestation PDA for the mint; may not exist yet (treated as stale)
+    #[account(
+        seeds = [SolvencyAttestation::SEED, mint.as_ref()],
+        bump
+    )]
+    pub attestation: UncheckedAccount<'info>,
+}
+
+pub fn enforce_solvency_attestation_handler(
+    ctx: Context<EnforceSolvencyAttestation>,
+    mint: Pubkey,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let max_age = ctx.accounts.solvency_config.max_attestation_age_secs;
+
+    let attestation_info = ctx.accounts.attestation.to_account_info();
+    let last_attested_at = if attestation_info.data_is_empty() {
+        None
+    } else {
+        require!(
+            attestation_info.owner == &crate::ID,
+            ConfidexError::InvalidAccountData
+        );
+        let data = attestation_info.try_borrow_data()?;
+        let attestation = SolvencyAttestation::try_deserialize(&mut &data[..])?;
+        require!(
+            !attestation.is_fresh(max_age, now),
+            ConfidexError::SolvencyAttestationFresh
+        );
+        Some(attestation.attested_at)
+    };
+
+    let exchange = &mut ctx.accounts.exchange;
+    if exchange.paused {
+        msg!("Exchange already paused");
+        return Ok(());
+    }
+    exchange.paused = true;
+
+    emit!(SolvencyAutoPaused {
+        mint,
+        last_attested_at: last_attested_at.unwrap_or(0),
+        timestamp: now,
+    });
+
+    msg!(
+        "Exchange paused: no solvency attestation for mint {} within {}s",
+        mint,
+        max_age
+    );
+
+    Ok(())
+}
+
+#[event]
+pub struct SolvencyAttested {
+    pub mint: Pubkey,
+    pub liabilities_root: [u8; 32],
+    pub total_liabilities: u64,
+    pub solvency_ratio_bps: u64,
+    pub vault_balance: u64,
+    pub epoch: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SolvencyAutoPaused {
+    pub mint: Pubkey,
+    /// Timestamp of the stale attestation (0 = never attested)
+    pub last_attested_at: i64,
+    pub timestamp: i64,
+}
@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 
 use crate::cpi::verifier::{verify_eligibility_proof, GROTH16_PROOF_SIZE};
 use crate::error::ConfidexError;
-use crate::state::{ExchangeState, TraderEligibility};
+use crate::state::{BlacklistRootHistory, ExchangeState, TraderEligibility};
 
 /// Accounts for verifying trader eligibility via ZK proof
 /// This is a separate instruction to avoid stack overflow from large proof in position params
@@ -15,6 +15,13 @@ pub struct VerifyEligibility<'info> {
     )]
     pub exchange: Account<'info, ExchangeState>,
 
+    /// Blacklist root history (attestation lifetime)
+    #[account(
+        seeds = [BlacklistRootHistory::SEED],
+        bump = blacklist_history.bump
+    )]
+    pub blacklist_history: Account<'info, BlacklistRootHistory>,
+
     /// Trader's eligibility account (created if doesn't exist)
     #[account(
         init_if_needed,
@@ -66,12 +73,93 @@ pub fn handler(ctx: Context<VerifyEligibility>, params: VerifyEligibilityParams)
     eligibility.verified_at = clock.unix_timestamp;
     eligibility.verification_count = eligibility.verification_count.saturating_add(1);
     eligibility.bump = ctx.bumps.eligibility;
+    eligibility.valid_until = clock
+        .unix_timestamp
+        .saturating_add(ctx.accounts.blacklist_history.attestation_ttl_secs);
 
     msg!(
-        "Trader eligibility verified: {} (proof #{}, blacklist root: {:?})",
+        "Trader eligibility verified: {} (proof #{}, blacklist root: {:?}, valid until {})",
         ctx.accounts.trader.key(),
         eligibility.verification_count,
-        &ctx.accounts.exchange.blacklist_root[0..8]
+        &ctx.accounts.exchange.blacklist_root[0..8],
+        eligibility.valid_until
+    );
+
+    Ok(())
+}
+
+// ============================================================================
+// Migrate Trader Eligibility (V1 → V2)
+// ============================================================================
+
+/// Accounts for growing a trader's pre-V2 eligibility record
+///
+/// V1 records (86 bytes) predate `valid_until` and no longer deserialize, so
+/// verify_eligibility can't refresh them. The trader grows their own record;
+/// the appended `valid_until` is zero, so the attestation must be re-proven.
+#[derive(Accounts)]
+pub struct MigrateTraderEligibility<'info> {
+    /// CHECK: Old-layout record; owner, size and discriminator checked in the handler
+    #[account(
+        mut,
+        seeds = [TraderEligibility::SEED, trader.key().as_ref()],
+        bump
+    )]
+    pub eligibility: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub trader: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_trader_eligibility_handler(ctx: Context<MigrateTraderEligibility>) -> Result<()> {
+    let eligibility_info = &ctx.accounts.eligibility;
+
+    require!(eligibility_info.owner == &crate::ID, ConfidexError::InvalidAccountData);
+
+    let current_size = eligibility_info.data_len();
+    if current_size == TraderEligibility::SIZE {
+        msg!("Eligibility already at current size ({}), no migration needed", TraderEligibility::SIZE);
+        return Ok(());
+    }
+
+    require!(
+        current_size == TraderEligibility::V1_SIZE,
+        ConfidexError::InvalidAccountSize
+    );
+
+    {
+        let data = eligibility_info.try_borrow_data()?;
+        require!(
+            &data[..8] == TraderEligibility::DISCRIMINATOR,
+            ConfidexError::InvalidAccountData
+        );
+    }
+
+    // Transfer additional rent from the trader
+    let additional_rent = Rent::get()?
+        .minimum_balance(TraderEligibility::SIZE)
+        .saturating_sub(eligibility_info.lamports());
+
+    if additional_rent > 0 {
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.trader.to_account_info(),
+                to: eligibility_info.clone(),
+            },
+        );
+        anchor_lang::system_program::transfer(cpi_context, additional_rent)?;
+    }
+
+    // Appended valid_until is zero-initialized: expired until re-verified
+    eligibility_info.resize(TraderEligibility::SIZE)?;
+
+    msg!(
+        "Trader eligibility migrated to {} bytes: {} (re-verification required)",
+        TraderEligibility::SIZE,
+        ctx.accounts.trader.key()
     );
 
     Ok(())
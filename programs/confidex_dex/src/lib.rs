@@ -18,7 +18,7 @@ pub mod confidex_dex {
     /// Initialize the exchange with admin settings
     pub fn initialize(
         ctx: Context<Initialize>,
-        maker_fee_bps: u16,
+        maker_fee_bps: i16,
         taker_fee_bps: u16,
     ) -> Result<()> {
         instructions::initialize::handler(ctx, maker_fee_bps, taker_fee_bps)
@@ -45,14 +45,32 @@ pub mod confidex_dex {
 
     /// Place a confidential order with ZK eligibility proof (V5 - no plaintext)
     /// All order values are encrypted; settlement uses MPC-computed results
-    pub fn place_order(
-        ctx: Context<PlaceOrder>,
+    ///
+    /// Eligibility comes from the maker's TraderEligibility account when it is
+    /// still valid (see BlacklistRootHistory); `eligibility_proof` is only
+    /// verified (Sunspot CPI) when that account is absent or stale.
+    ///
+    /// The order starts as PendingBalanceCheck and is activated (funds escrowed)
+    /// or rejected by place_order_callback once the MPC balance check completes.
+    /// The 11 Arcium accounts are passed via remaining_accounts (same as match_orders).
+    ///
+    /// `time_in_force` selects GTC/IOC/FOK/PostOnly; `expires_at_hour` is an
+    /// optional good-till-time expiry (0 = none, floored to the hour).
+    /// `self_trade_prevention` applies when this order would take against
+    /// the same maker's resting order.
+    pub fn place_order<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlaceOrder<'info>>,
         side: state::Side,
         order_type: state::OrderType,
         encrypted_amount: [u8; 64],
         encrypted_price: [u8; 64],
-        eligibility_proof: [u8; 324],
+        eligibility_proof: Option<[u8; 324]>,
         ephemeral_pubkey: [u8; 32],
+        computation_offset: u64,
+        nonce: u128,
+        time_in_force: state::TimeInForce,
+        expires_at_hour: i64,
+        self_trade_prevention: state::SelfTradePrevention,
     ) -> Result<()> {
         instructions::place_order::handler(
             ctx,
@@ -62,14 +80,79 @@ pub mod confidex_dex {
             encrypted_price,
             eligibility_proof,
             ephemeral_pubkey,
+            computation_offset,
+            nonce,
+            time_in_force,
+            expires_at_hour,
+            self_trade_prevention,
         )
     }
 
     /// Cancel an open order
+    ///
+    /// DEPRECATED: always fails. Use initiate_cancel_order.
     pub fn cancel_order(ctx: Context<CancelOrder>) -> Result<()> {
         instructions::cancel_order::handler(ctx)
     }
 
+    /// Cancel an open order (maker only)
+    ///
+    /// Marks the order PendingCancel (no longer matchable) and queues MPC
+    /// calculate_refund; the unfilled escrow is released by
+    /// cancel_order_callback.
+    pub fn initiate_cancel_order<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitiateCancelOrder<'info>>,
+        params: initiate_cancel_order::InitiateCancelOrderParams,
+    ) -> Result<()> {
+        instructions::initiate_cancel_order::handler(ctx, params)
+    }
+
+    /// Recover an order whose balance check, cancel/expiry refund or amend
+    /// check never came back (maker only)
+    ///
+    /// Once the pending MPC request has timed out, releases the order's
+    /// remaining escrow and marks it Inactive. An order with a pending amend
+    /// is instead reactivated with its pre-amend values and escrow.
+    pub fn recover_pending_order(ctx: Context<RecoverPendingOrder>) -> Result<()> {
+        instructions::recover_pending_order::handler(ctx)
+    }
+
+    /// Cancel any number of the maker's orders on one pair (maker only)
+    ///
+    /// Arcium accounts go first in remaining_accounts, then one extra
+    /// computation account per additional chunk, then the orders. Each chunk
+    /// of 5 orders is one MPC batch_calculate_refund; the escrows are
+    /// released by cancel_orders_batch_callback.
+    pub fn cancel_orders_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelOrdersBatch<'info>>,
+        params: cancel_orders_batch::CancelOrdersBatchParams,
+    ) -> Result<()> {
+        instructions::cancel_orders_batch::handler(ctx, params)
+    }
+
+    /// Cancel the maker's orders across several pairs (maker only)
+    ///
+    /// Same chunking as cancel_orders_batch, with each pair passed in
+    /// remaining_accounts ahead of its orders.
+    pub fn cancel_all_orders<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelAllOrders<'info>>,
+        params: cancel_all_orders::CancelAllOrdersParams,
+    ) -> Result<()> {
+        instructions::cancel_all_orders::handler(ctx, params)
+    }
+
+    /// Amend a resting order's amount and price in place (maker only)
+    ///
+    /// Keeps the order account, order_id and eligibility proof. Only unfilled
+    /// GTC/post-only orders can be amended. The escrow is re-sized via MPC
+    /// check_order_amend and applied by amend_order_callback.
+    pub fn amend_order<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AmendOrder<'info>>,
+        params: amend_order::AmendOrderParams,
+    ) -> Result<()> {
+        instructions::amend_order::handler(ctx, params)
+    }
+
     /// Match two orders via MPC price comparison
     ///
     /// All 12 Arcium accounts must be provided by the client using Arcium SDK derivation.
@@ -81,6 +164,30 @@ pub mod confidex_dex {
         instructions::match_orders::handler(ctx, params)
     }
 
+    /// Match up to 5 buy/sell order pairs with a single MPC computation
+    ///
+    /// Arcium accounts go first in remaining_accounts (same as match_orders),
+    /// followed by the orders as buy_0, sell_0, buy_1, sell_1, ...
+    /// Results come back atomically via finalize_match_batch.
+    pub fn match_orders_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MatchOrdersBatch<'info>>,
+        params: match_orders_batch::MatchOrdersBatchParams,
+    ) -> Result<()> {
+        instructions::match_orders_batch::handler(ctx, params)
+    }
+
+    /// Expire an order whose good-till-time has passed (permissionless)
+    ///
+    /// Also used to release the unfilled remainder of IOC/FOK orders after
+    /// their match attempt. Queues MPC calculate_refund; the escrow is
+    /// released by cancel_order_callback.
+    pub fn expire_order<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExpireOrder<'info>>,
+        params: expire_order::ExpireOrderParams,
+    ) -> Result<()> {
+        instructions::expire_order::handler(ctx, params)
+    }
+
     /// Settle matched orders by transferring tokens between users
     /// Called after orders have been matched via MPC (status = Inactive, filled > 0)
     ///
@@ -108,13 +215,60 @@ pub mod confidex_dex {
     /// Cancel order callback from MXE
     ///
     /// Called by the MXE's calculate_refund_callback with decrypted
-    /// refund_amount. Only the MXE authority PDA can invoke this.
+    /// refund_amount. Only the MXE authority PDA can invoke this, and only
+    /// for the refund request queued by initiate_cancel_order / expire_order.
     /// This is the production MPC-based cancellation that doesn't read plaintext.
     pub fn cancel_order_callback(
         ctx: Context<CancelOrderCallback>,
+        request_id: [u8; 32],
         refund_amount: u64,
     ) -> Result<()> {
-        instructions::cancel_order_callback::handler(ctx, refund_amount)
+        instructions::cancel_order_callback::handler(ctx, request_id, refund_amount)
+    }
+
+    /// Batch cancel callback from MXE
+    ///
+    /// Called by the MXE's batch_calculate_refund_callback with the decrypted
+    /// refund amounts. Only the MXE authority PDA can invoke this, and only
+    /// for the batch queued by cancel_orders_batch.
+    /// Orders are passed via remaining_accounts in batch order.
+    pub fn cancel_orders_batch_callback(
+        ctx: Context<CancelOrdersBatchCallback>,
+        request_id: [u8; 32],
+        refund_amounts: Vec<u64>,
+    ) -> Result<()> {
+        instructions::cancel_orders_batch_callback::handler(ctx, request_id, refund_amounts)
+    }
+
+    /// Place order callback from MXE
+    ///
+    /// Called by the MXE's check_order_balance_callback with the revealed
+    /// balance check result and escrow amount. Only the MXE authority PDA can
+    /// invoke this, and only for the balance check queued by place_order.
+    /// Activates the pending order (escrowing funds) or rejects it.
+    pub fn place_order_callback(
+        ctx: Context<PlaceOrderCallback>,
+        request_id: [u8; 32],
+        sufficient: bool,
+        escrow_amount: u64,
+    ) -> Result<()> {
+        instructions::place_order_callback::handler(ctx, request_id, sufficient, escrow_amount)
+    }
+
+    /// Amend order callback from MXE
+    ///
+    /// Called by the MXE's check_order_amend_callback with the revealed
+    /// result and new escrow amount. Only the MXE authority PDA can invoke
+    /// this, and only for the amend request queued by amend_order. Reactivates
+    /// the order with the amended values, or with its original values if the
+    /// new escrow can't be covered.
+    pub fn amend_order_callback(
+        ctx: Context<AmendOrderCallback>,
+        request_id: [u8; 32],
+        sufficient: bool,
+        escrow_amount: u64,
+    ) -> Result<()> {
+        instructions::amend_order_callback::handler(ctx, request_id, sufficient, escrow_amount)
     }
 
     /// Pause trading (admin only)
@@ -127,20 +281,47 @@ pub mod confidex_dex {
         instructions::admin::unpause_handler(ctx)
     }
 
-    /// Update fee rates (admin only)
+    /// Update fee rates (admin only). A negative maker fee is a maker rebate.
     pub fn update_fees(
         ctx: Context<UpdateFees>,
-        maker_fee_bps: u16,
+        maker_fee_bps: i16,
         taker_fee_bps: u16,
     ) -> Result<()> {
         instructions::admin::update_fees_handler(ctx, maker_fee_bps, taker_fee_bps)
     }
 
     /// Update blacklist merkle root (admin only)
+    /// The previous root stays acceptable for the configured grace window
     pub fn update_blacklist(ctx: Context<UpdateBlacklist>, new_root: [u8; 32]) -> Result<()> {
         instructions::admin::update_blacklist_handler(ctx, new_root)
     }
 
+    /// Create the blacklist root history (admin only)
+    pub fn initialize_blacklist_history(
+        ctx: Context<InitializeBlacklistHistory>,
+        grace_period_secs: i64,
+        attestation_ttl_secs: i64,
+    ) -> Result<()> {
+        instructions::admin::initialize_blacklist_history_handler(
+            ctx,
+            grace_period_secs,
+            attestation_ttl_secs,
+        )
+    }
+
+    /// Update the blacklist grace window and attestation lifetime (admin only)
+    pub fn update_blacklist_history_config(
+        ctx: Context<UpdateBlacklistHistoryConfig>,
+        grace_period_secs: i64,
+        attestation_ttl_secs: i64,
+    ) -> Result<()> {
+        instructions::admin::update_blacklist_history_config_handler(
+            ctx,
+            grace_period_secs,
+            attestation_ttl_secs,
+        )
+    }
+
     /// Set vault addresses for a trading pair (admin only)
     pub fn set_pair_vaults(ctx: Context<SetPairVaults>) -> Result<()> {
         instructions::admin::set_pair_vaults_handler(ctx)
@@ -175,6 +356,41 @@ pub mod confidex_dex {
         instructions::admin::migrate_exchange_handler(ctx)
     }
 
+    /// Migrate a PerpetualMarket account to the current layout (admin only)
+    /// Grows the account and initializes appended fields with defaults;
+    /// open_position_count seeds the V5 counter (ignored for V5+ markets)
+    pub fn migrate_perp_market(
+        ctx: Context<MigratePerpMarket>,
+        open_position_count: u64,
+    ) -> Result<()> {
+        instructions::admin::migrate_perp_market_handler(ctx, open_position_count)
+    }
+
+    /// Migrate a ConfidentialPosition account to the current layout (admin only)
+    /// Grows pre-exit-trigger positions; appended fields start with no triggers set
+    pub fn migrate_position(ctx: Context<MigratePosition>) -> Result<()> {
+        instructions::admin::migrate_position_handler(ctx)
+    }
+
+    /// Migrate a TradingPair account to the current layout (admin only)
+    /// Grows the account; the appended allowlist starts disabled
+    pub fn migrate_trading_pair(ctx: Context<MigrateTradingPair>) -> Result<()> {
+        instructions::admin::migrate_trading_pair_handler(ctx)
+    }
+
+    /// Update the premium funding model parameters of a market (admin only)
+    pub fn update_funding_config(
+        ctx: Context<UpdateFundingConfig>,
+        params: UpdateFundingConfigParams,
+    ) -> Result<()> {
+        instructions::admin::update_funding_config_handler(ctx, params)
+    }
+
+    /// Migrate a FundingRateState account to the premium funding layout (admin only)
+    pub fn migrate_funding_state(ctx: Context<MigrateFundingState>) -> Result<()> {
+        instructions::admin::migrate_funding_state_handler(ctx)
+    }
+
     /// Update program IDs stored in ExchangeState (admin only)
     /// Allows switching MXE or verifier programs without redeploying DEX
     pub fn update_program_ids(
@@ -184,6 +400,13 @@ pub mod confidex_dex {
         instructions::admin::update_program_ids_handler(ctx, params)
     }
 
+    /// Migrate a ConfidentialOrder account to the current layout (admin only)
+    /// Grows the account; orders from before escrow tracking are expired, as
+    /// are orders whose amend replaced the ciphertexts in place (pre-V10)
+    pub fn migrate_order(ctx: Context<MigrateOrder>) -> Result<()> {
+        instructions::admin::migrate_order_handler(ctx)
+    }
+
     /// Admin force-close a broken or legacy position (admin only)
     ///
     /// EMERGENCY function for positions that cannot be closed via MPC because:
@@ -243,6 +466,70 @@ pub mod confidex_dex {
         instructions::verify_eligibility::handler(ctx, params)
     }
 
+    /// Grow a pre-V2 TraderEligibility record to the current layout
+    /// Signed by the trader; the migrated attestation must be re-proven
+    pub fn migrate_trader_eligibility(ctx: Context<MigrateTraderEligibility>) -> Result<()> {
+        instructions::verify_eligibility::migrate_trader_eligibility_handler(ctx)
+    }
+
+    /// Configure a pair's or perp market's allowlist
+    /// The exchange authority sets issuer/verifier/root; the issuer may rotate the root
+    pub fn set_allowlist(ctx: Context<SetAllowlist>, params: SetAllowlistParams) -> Result<()> {
+        instructions::allowlist::set_allowlist_handler(ctx, params)
+    }
+
+    /// Verify allowlist membership via ZK proof for an allowlisted market
+    /// Stores an AllowlistCredential checked by place_order / open_position
+    pub fn verify_allowlist(
+        ctx: Context<VerifyAllowlist>,
+        params: VerifyAllowlistParams,
+    ) -> Result<()> {
+        instructions::allowlist::verify_allowlist_handler(ctx, params)
+    }
+
+    /// Create the proof-of-solvency configuration (admin only)
+    pub fn initialize_solvency_config(
+        ctx: Context<InitializeSolvencyConfig>,
+        verifier: Pubkey,
+        max_attestation_age_secs: i64,
+        auto_pause: bool,
+    ) -> Result<()> {
+        instructions::solvency::initialize_solvency_config_handler(
+            ctx,
+            verifier,
+            max_attestation_age_secs,
+            auto_pause,
+        )
+    }
+
+    /// Update the proof-of-solvency configuration (admin only)
+    pub fn update_solvency_config(
+        ctx: Context<UpdateSolvencyConfig>,
+        params: UpdateSolvencyConfigParams,
+    ) -> Result<()> {
+        instructions::solvency::update_solvency_config_handler(ctx, params)
+    }
+
+    /// Verify a solvency proof for a mint and record a SolvencyAttestation (admin only)
+    /// The proof is bound to the mint, the current day and the mint's pair vault balances
+    /// (remaining_accounts: [pair, vault] per pair, ascending pair key order)
+    pub fn submit_solvency_proof(
+        ctx: Context<SubmitSolvencyProof>,
+        mint: Pubkey,
+        params: SubmitSolvencyProofParams,
+    ) -> Result<()> {
+        instructions::solvency::submit_solvency_proof_handler(ctx, mint, params)
+    }
+
+    /// Pause the exchange if a traded mint has no fresh solvency attestation
+    /// Permissionless; only available when auto-pause is enabled
+    pub fn enforce_solvency_attestation(
+        ctx: Context<EnforceSolvencyAttestation>,
+        mint: Pubkey,
+    ) -> Result<()> {
+        instructions::solvency::enforce_solvency_attestation_handler(ctx, mint)
+    }
+
     // === Perpetuals Instructions ===
 
     /// Initialize a perpetual futures market
@@ -298,6 +585,7 @@ pub mod confidex_dex {
     }
 
     /// Update funding rate for a perpetual market (keeper crank)
+    /// Accrues funding for the time elapsed since the last update (capped)
     pub fn update_funding_rate(ctx: Context<UpdateFundingRate>) -> Result<()> {
         instructions::perp_update_funding::handler(ctx)
     }
@@ -339,6 +627,42 @@ pub mod confidex_dex {
         instructions::mpc_callback::close_position_callback(ctx, params)
     }
 
+    /// Set or clear a position's encrypted stop-loss / take-profit (trader only)
+    pub fn set_exit_triggers(
+        ctx: Context<SetExitTriggers>,
+        params: SetExitTriggersParams,
+    ) -> Result<()> {
+        instructions::perp_exit_triggers::set_exit_triggers_handler(ctx, params)
+    }
+
+    /// Queue MPC stop-loss / take-profit check for up to 10 positions (permissionless)
+    /// Reveals only whether each position's trigger fired at the current mark price
+    pub fn check_exit_triggers<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CheckExitTriggers<'info>>,
+        params: CheckExitTriggersParams,
+    ) -> Result<()> {
+        instructions::perp_exit_triggers::check_exit_triggers_handler(ctx, params)
+    }
+
+    /// Callback for exit trigger check MPC result
+    /// Marks positions whose trigger fired; positions are passed via remaining_accounts
+    pub fn exit_trigger_callback(
+        ctx: Context<ExitTriggerCallback>,
+        request_id: [u8; 32],
+        fired: Vec<bool>,
+    ) -> Result<()> {
+        instructions::perp_exit_triggers::exit_trigger_callback_handler(ctx, request_id, fired)
+    }
+
+    /// Fully close a position whose exit trigger fired (permissionless keeper)
+    /// Runs the initiate_close_position flow; payout still goes to the trader
+    pub fn execute_exit_trigger(
+        ctx: Context<ExecuteExitTrigger>,
+        params: ExecuteExitTriggerParams,
+    ) -> Result<()> {
+        instructions::perp_exit_triggers::execute_exit_trigger_handler(ctx, params)
+    }
+
     /// Callback for funding settlement MPC result (V7)
     /// Receives computed funding payment and updates encrypted collateral
     pub fn funding_settlement_callback(
@@ -366,8 +690,18 @@ pub mod confidex_dex {
 
     // === Liquidation Instructions ===
 
+    /// Allocate a liquidation batch for up to `capacity` positions (max 64)
+    pub fn create_liquidation_batch(
+        ctx: Context<CreateLiquidationBatch>,
+        batch_id: u64,
+        capacity: u8,
+    ) -> Result<()> {
+        instructions::check_liquidation_batch::create_handler(ctx, batch_id, capacity)
+    }
+
     /// Queue batch liquidation check via MPC
-    /// Checks up to 10 positions in a single MPC call for efficiency
+    /// Positions may span multiple markets, each checked at its own mark price;
+    /// one MPC call is queued per 10 positions and results aggregate on the batch
     /// Required before liquidation since thresholds are now encrypted
     pub fn check_liquidation_batch<'info>(
         ctx: Context<'_, '_, 'info, 'info, CheckLiquidationBatch<'info>>,
@@ -386,25 +720,80 @@ pub mod confidex_dex {
 
     /// Liquidate an underwater position (V2: requires prior MPC batch verification)
     /// The batch_request must have verified this position is liquidatable via MPC
+    /// Queues MPC calculate_partial_liquidation to close only the size needed
+    /// to restore maintenance margin; result via partial_liquidation_callback
     /// Anyone can call this - incentivized by liquidation bonus
-    pub fn liquidate_position(
-        ctx: Context<LiquidatePosition>,
+    pub fn liquidate_position<'info>(
+        ctx: Context<'_, '_, 'info, 'info, LiquidatePosition<'info>>,
         params: LiquidatePositionParams,
     ) -> Result<()> {
         instructions::perp_liquidate::handler(ctx, params)
     }
 
+    /// Callback for partial liquidation from MXE
+    /// Updates encrypted size/collateral/threshold, pays the liquidator bonus
+    /// and splits released equity between insurance fund and trader on a full close
+    /// Bad debt of a bankrupt position is covered by the insurance fund
+    pub fn partial_liquidation_callback(
+        ctx: Context<PartialLiquidationCallback>,
+        params: PartialLiquidationCallbackParams,
+    ) -> Result<()> {
+        instructions::perp_liquidate::callback_handler(ctx, params)
+    }
+
     /// Auto-deleverage when insurance fund is depleted (legacy handler)
     /// Force-closes profitable positions to cover underwater liquidations
     /// Note: Uses cached is_liquidatable flag from batch MPC check (V6)
-    pub fn auto_deleverage(ctx: Context<AutoDeleverage>) -> Result<()> {
-        instructions::perp_auto_deleverage::handler(ctx)
+    pub fn auto_deleverage<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AutoDeleverage<'info>>,
+        params: ExecuteAdlParams,
+    ) -> Result<()> {
+        instructions::perp_auto_deleverage::handler(ctx, params)
     }
 
     /// Execute auto-deleverage using cached liquidation status (V6)
     /// Requires position.is_liquidatable = true from prior batch MPC check
-    pub fn execute_adl(ctx: Context<ExecuteAdl>) -> Result<()> {
-        instructions::perp_auto_deleverage::execute_adl(ctx)
+    /// Queues MPC calculate_adl; settlement happens in adl_pnl_callback
+    pub fn execute_adl<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteAdl<'info>>,
+        params: ExecuteAdlParams,
+    ) -> Result<()> {
+        instructions::perp_auto_deleverage::execute_adl(ctx, params)
+    }
+
+    /// Create a market's ADL ranking account (per-market ADL queue)
+    pub fn initialize_adl_ranking(ctx: Context<InitializeAdlRanking>) -> Result<()> {
+        instructions::perp_adl_ranking::initialize_adl_ranking_handler(ctx)
+    }
+
+    /// Queue one chunk of a market's ADL ranking round (permissionless)
+    /// Positions are ranked by leverage * unrealized profit ratio; the queues
+    /// are published by adl_ranking_callback once every open position is covered
+    pub fn refresh_adl_ranking<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefreshAdlRanking<'info>>,
+        params: RefreshAdlRankingParams,
+    ) -> Result<()> {
+        instructions::perp_adl_ranking::refresh_adl_ranking_handler(ctx, params)
+    }
+
+    /// Skip a closed position at the head of an ADL queue (permissionless)
+    pub fn advance_adl_ranking(ctx: Context<AdvanceAdlRanking>, is_long: bool) -> Result<()> {
+        instructions::perp_adl_ranking::advance_adl_ranking_handler(ctx, is_long)
+    }
+
+    /// Create a market's insurance fund token account (quote token PDA)
+    pub fn initialize_insurance_fund(ctx: Context<InitializeInsuranceFund>) -> Result<()> {
+        instructions::perp_insurance_fund::initialize_insurance_fund_handler(ctx)
+    }
+
+    /// Deposit quote tokens into a market's insurance fund (admin only)
+    pub fn deposit_insurance_fund(ctx: Context<DepositInsuranceFund>, amount: u64) -> Result<()> {
+        instructions::perp_insurance_fund::deposit_insurance_fund_handler(ctx, amount)
+    }
+
+    /// Withdraw quote tokens from a market's insurance fund (admin only)
+    pub fn withdraw_insurance_fund(ctx: Context<WithdrawInsuranceFund>, amount: u64) -> Result<()> {
+        instructions::perp_insurance_fund::withdraw_insurance_fund_handler(ctx, amount)
     }
 
     /// Initiate batch liquidation check for multiple positions (V6)
@@ -421,7 +810,7 @@ pub mod confidex_dex {
     // === MPC Callback Instructions ===
 
     /// Finalize order match from Arcium MPC callback (simplified production flow)
-    /// Called by the MXE after MPC price comparison completes.
+    /// Called by the MXE after MPC price comparison or fill calculation completes.
     /// Orders are passed directly via callback_account_1/2 stored in ComputationRequest.
     pub fn finalize_match(
         ctx: Context<FinalizeMatch>,
@@ -431,6 +820,17 @@ pub mod confidex_dex {
         instructions::mpc_callback::finalize_match(ctx, request_id, result)
     }
 
+    /// Finalize a batch of order matches from Arcium MPC callback
+    /// Called by the MXE after batch_calculate_fill completes.
+    /// Orders are passed via remaining_accounts (buy_0, sell_0, buy_1, sell_1, ...).
+    pub fn finalize_match_batch(
+        ctx: Context<FinalizeMatchBatch>,
+        request_id: [u8; 32],
+        result: Vec<u8>,
+    ) -> Result<()> {
+        instructions::mpc_callback::finalize_match_batch(ctx, request_id, result)
+    }
+
     /// Receive price comparison result from Arcium MPC (legacy PendingMatch flow)
     /// Called by the MXE after MPC execution completes
     pub fn receive_compare_result(
@@ -493,6 +893,27 @@ pub mod confidex_dex {
         instructions::mpc_callback::liquidation_check_callback(ctx, params)
     }
 
+    /// Callback for auto-deleverage settlement from MXE
+    /// Called after calculate_adl MPC settles both positions at the bankruptcy price
+    /// Updates encrypted size/collateral, pays the target and reduces open interest
+    pub fn adl_pnl_callback(
+        ctx: Context<AdlPnlCallback>,
+        params: AdlPnlParams,
+    ) -> Result<()> {
+        instructions::mpc_callback::adl_pnl_callback(ctx, params)
+    }
+
+    /// Callback for ADL candidate ranking from MXE
+    /// Called after rank_adl_candidates MPC ranks positions by profitability
+    /// Carries the round leaders forward and publishes the queues when the round completes
+    pub fn adl_ranking_callback(
+        ctx: Context<AdlRankingCallback>,
+        request_id: [u8; 32],
+        ranks: [u8; 10],
+    ) -> Result<()> {
+        instructions::perp_adl_ranking::adl_ranking_callback_handler(ctx, request_id, ranks)
+    }
+
     // === ShadowWire Settlement (Layer 4 - Private Transfer) ===
 
     /// Initiate ShadowWire settlement for matched orders
@@ -1,15 +1,23 @@
-//! Pyth Oracle Integration
+//! Oracle Integration (Pyth legacy, Pyth pull, Switchboard On-Demand)
 //!
-//! Provides helpers for fetching SOL/USD price from Pyth Network.
-//! Used by perpetuals for mark price, entry/exit validation, and liquidations.
+//! Provides asset-agnostic helpers for fetching prices from Pyth Network
+//! and Switchboard. Markets may configure a secondary feed that is used when
+//! the primary is stale or too uncertain.
+//! Each perpetual market supplies its own feed (`oracle_price_feed`) and
+//! price precision (`price_decimals`), so SOL, BTC and ETH markets share
+//! the same code paths for mark price, exit price, and liquidations.
 //!
 //! Reference: https://docs.pyth.network/price-feeds/solana
 
 use anchor_lang::prelude::*;
 
+use crate::state::{OracleSource, PerpetualMarket};
+
 /// Maximum age for price data (production - 60 seconds per PRD-001)
 /// This is the CRITICAL threshold for liquidation safety.
 /// Stale prices can lead to incorrect liquidations.
+/// Applies to every price read, so a trader can't pick an old (but still
+/// valid) price update to open or close at.
 #[cfg(feature = "devnet")]
 pub const MAX_PRICE_AGE_SECS: u64 = 3600; // 1 hour for devnet (Pyth feeds update infrequently)
 
@@ -28,21 +36,84 @@ pub const MAX_CONFIDENCE_BPS: u64 = 100; // 1% for mainnet (tight for production
 /// Pyth price precision (8 decimals typically)
 pub const PYTH_PRICE_DECIMALS: u32 = 8;
 
-/// Our internal price precision (6 decimals for USDC compatibility)
+/// Default internal price precision (6 decimals for USDC compatibility)
 pub const INTERNAL_PRICE_DECIMALS: u32 = 6;
 
-/// Get SOL/USD price from Pyth price feed
-///
-/// Returns price in 6 decimal precision (micro-dollars).
-/// For example, $100.50 = 100_500_000
-///
-/// # Arguments
-/// * `price_feed` - The Pyth price feed account
+/// Pyth Solana receiver program (owner of PriceUpdateV2 accounts)
+/// Base58: rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    0x0c, 0xb7, 0xfa, 0xbb, 0x52, 0xf7, 0xa6, 0x48,
+    0xbb, 0x5b, 0x31, 0x7d, 0x9a, 0x01, 0x8b, 0x90,
+    0x57, 0xcb, 0x02, 0x47, 0x74, 0xfa, 0xfe, 0x01,
+    0xe6, 0xc4, 0xdf, 0x98, 0xcc, 0x38, 0x58, 0x81,
+]);
+
+/// Switchboard On-Demand program (devnet)
+/// Base58: Aio4gaXjXzJNVLtzwtNVmSqGKpANtXhybbkhtAC94ji2
+#[cfg(feature = "devnet")]
+pub const SWITCHBOARD_ON_DEMAND_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    0x90, 0x6e, 0x14, 0x64, 0xc5, 0xf8, 0xb7, 0x63,
+    0x3c, 0xc0, 0x5a, 0x42, 0x4c, 0xdd, 0xb3, 0xae,
+    0xcd, 0x6d, 0xab, 0xb8, 0xae, 0xc7, 0x47, 0xbc,
+    0x4f, 0x3e, 0x11, 0x30, 0x1e, 0x40, 0x63, 0xcb,
+]);
+
+/// Switchboard On-Demand program (mainnet)
+/// Base58: SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv
+#[cfg(not(feature = "devnet"))]
+pub const SWITCHBOARD_ON_DEMAND_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    0x06, 0x73, 0xbd, 0x46, 0xf2, 0xe4, 0x7e, 0x04,
+    0xf1, 0x2b, 0xd9, 0x2f, 0xb7, 0x31, 0x96, 0x8e,
+    0xcd, 0x9d, 0x97, 0x57, 0xc2, 0x74, 0xda, 0x87,
+    0x47, 0x6f, 0x46, 0x5c, 0x04, 0x0c, 0x65, 0x73,
+]);
+
+/// Anchor discriminator of PriceUpdateV2 (sha256("account:PriceUpdateV2")[..8])
+const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [0x22, 0xf1, 0x23, 0x63, 0x9d, 0x7e, 0xf4, 0xcd];
+
+/// Anchor discriminator of PullFeedAccountData (sha256("account:PullFeedAccountData")[..8])
+const PULL_FEED_DISCRIMINATOR: [u8; 8] = [0xc4, 0x1b, 0x6c, 0xc4, 0x0a, 0xd7, 0xdb, 0x28];
+
+/// PriceUpdateV2 layout (borsh):
+/// discriminator (8) | write_authority (32) | verification_level (1 or 2) | PriceFeedMessage
+const PRICE_UPDATE_V2_LEVEL_OFFSET: usize = 8 + 32;
+
+/// PriceFeedMessage: feed_id (32) | price i64 | conf u64 | exponent i32 | publish_time i64 | ...
+const PRICE_FEED_MESSAGE_LEN: usize = 84;
+
+/// PullFeedAccountData (repr(C), offsets include the 8-byte discriminator)
+/// 32 submissions (64 bytes each) precede the feed metadata
+const SB_LAST_UPDATE_TIMESTAMP_OFFSET: usize = 8 + 2208;
+/// CurrentResult.value (i128, 18 decimals)
+const SB_RESULT_VALUE_OFFSET: usize = 8 + 2256;
+/// CurrentResult.std_dev (i128, 18 decimals)
+const SB_RESULT_STD_DEV_OFFSET: usize = 8 + 2272;
+
+/// Switchboard results are fixed-point with 18 decimals
+const SWITCHBOARD_DECIMALS: i32 = 18;
+
+/// Raw oracle reading: value = price * 10^expo
+struct RawPrice {
+    price: i128,
+    conf: u128,
+    expo: i32,
+    publish_time: i64,
+}
+
+fn read_bytes<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N]> {
+    let end = offset.checked_add(N).ok_or(OracleError::InvalidOracleAccount)?;
+    let bytes = data
+        .get(offset..end)
+        .ok_or(OracleError::InvalidOracleAccount)?;
+    Ok(bytes.try_into().map_err(|_| OracleError::InvalidOracleAccount)?)
+}
+
+/// Load the current price from a legacy Pyth price account
 ///
-/// # Returns
-/// * `Ok(u64)` - Price in micro-dollars (6 decimals)
-/// * `Err(_)` - If price feed is stale, invalid, or parsing fails
-pub fn get_sol_usd_price(price_feed: &AccountInfo) -> Result<u64> {
+/// Legacy price accounts are identified by their address.
+fn load_pyth_legacy_price(price_feed: &AccountInfo, expected_feed_id: &Pubkey) -> Result<RawPrice> {
+    require_keys_eq!(price_feed.key(), *expected_feed_id, OracleError::InvalidFeedId);
+
     // Load price account from raw data using pyth_sdk_solana::state module
     // This avoids AccountInfo type mismatches between Anchor 0.32.1 and pyth-sdk-solana
     // SolanaPriceAccount = GenericPriceAccount<32, ()>
@@ -50,88 +121,135 @@ pub fn get_sol_usd_price(price_feed: &AccountInfo) -> Result<u64> {
     let price_account = pyth_sdk_solana::state::load_price_account::<32, ()>(&data)
         .map_err(|_| OracleError::InvalidFeedId)?;
 
-    // Manually create PriceFeed to avoid Pubkey type mismatch between
-    // Anchor 0.32.1 (solana_pubkey) and pyth-sdk-solana (solana_program::pubkey)
-    let price_id = pyth_sdk_solana::PriceIdentifier::new(price_feed.key.to_bytes());
+    // Use the aggregate price while trading, otherwise fall back to the last
+    // trading price (publish_time still reflects the account timestamp)
     let status = price_account.agg.status;
-    let price = if status == pyth_sdk_solana::state::PriceStatus::Trading {
-        pyth_sdk_solana::Price {
-            conf: price_account.agg.conf,
-            expo: price_account.expo,
-            price: price_account.agg.price,
-            publish_time: price_account.timestamp,
-        }
+    let (price, conf) = if status == pyth_sdk_solana::state::PriceStatus::Trading {
+        (price_account.agg.price, price_account.agg.conf)
     } else {
-        pyth_sdk_solana::Price {
-            conf: price_account.prev_conf,
-            expo: price_account.expo,
-            price: price_account.prev_price,
-            publish_time: price_account.timestamp,
-        }
+        (price_account.prev_price, price_account.prev_conf)
     };
-    let ema_price = pyth_sdk_solana::Price {
-        conf: price_account.ema_conf.val as u64,
+
+    Ok(RawPrice {
+        price: price as i128,
+        conf: conf as u128,
         expo: price_account.expo,
-        price: price_account.ema_price.val,
         publish_time: price_account.timestamp,
+    })
+}
+
+/// Load the price from a Pyth pull oracle PriceUpdateV2 account
+///
+/// Update accounts are posted by anyone, so the account is validated by
+/// owner + discriminator and the embedded feed ID is matched against the market.
+/// Only fully verified (all guardian signatures) updates are accepted.
+fn load_pyth_pull_price(price_feed: &AccountInfo, expected_feed_id: &Pubkey) -> Result<RawPrice> {
+    require_keys_eq!(*price_feed.owner, PYTH_RECEIVER_PROGRAM_ID, OracleError::InvalidOracleAccount);
+
+    let data = price_feed.try_borrow_data()?;
+    require!(
+        read_bytes::<8>(&data, 0)? == PRICE_UPDATE_V2_DISCRIMINATOR,
+        OracleError::InvalidOracleAccount
+    );
+
+    // VerificationLevel: Partial { num_signatures: u8 } = 0, Full = 1
+    let offset = match read_bytes::<1>(&data, PRICE_UPDATE_V2_LEVEL_OFFSET)?[0] {
+        1 => PRICE_UPDATE_V2_LEVEL_OFFSET + 1,
+        0 => return Err(OracleError::UnverifiedPriceUpdate.into()),
+        _ => return Err(OracleError::InvalidOracleAccount.into()),
     };
-    let price_feed_data = pyth_sdk_solana::PriceFeed::new(price_id, price, ema_price);
+    require!(
+        data.len() >= offset + PRICE_FEED_MESSAGE_LEN,
+        OracleError::InvalidOracleAccount
+    );
 
-    let clock = Clock::get()?;
-    let current_time = clock.unix_timestamp;
+    let feed_id = read_bytes::<32>(&data, offset)?;
+    require!(feed_id == expected_feed_id.to_bytes(), OracleError::InvalidFeedId);
 
-    // On devnet, Pyth price feeds may be extremely stale (months old)
-    // Use a very large age threshold to effectively disable staleness checking
-    // On mainnet, use a strict 120-second threshold
-    #[cfg(feature = "devnet")]
-    let age_threshold: u64 = u64::MAX / 2; // Effectively disable staleness check
-
-    #[cfg(not(feature = "devnet"))]
-    let age_threshold: u64 = MAX_PRICE_AGE_SECS;
-
-    let price = price_feed_data
-        .get_price_no_older_than(current_time, age_threshold)
-        .ok_or(OracleError::StalePrice)?;
-
-    // Pyth returns price with variable exponent (usually -8)
-    // We need to convert to our 6-decimal precision
-    //
-    // Example: SOL at $100.12345678
-    // - Pyth: price = 10012345678, expo = -8
-    // - Our format: 100_123_456 (6 decimals)
-    //
-    // Conversion: price * 10^(6 - |expo|)
-    // If expo = -8: price * 10^(6-8) = price / 100
+    Ok(RawPrice {
+        price: i64::from_le_bytes(read_bytes(&data, offset + 32)?) as i128,
+        conf: u64::from_le_bytes(read_bytes(&data, offset + 40)?) as u128,
+        expo: i32::from_le_bytes(read_bytes(&data, offset + 48)?),
+        publish_time: i64::from_le_bytes(read_bytes(&data, offset + 52)?),
+    })
+}
 
-    // Ensure price is positive
-    require!(price.price > 0, OracleError::InvalidPrice);
-
-    let expo_abs = (-price.expo) as u32;
-
-    let normalized_price = if expo_abs > INTERNAL_PRICE_DECIMALS {
-        // Pyth has more precision, divide to reduce
-        let divisor = 10u64.pow(expo_abs - INTERNAL_PRICE_DECIMALS);
-        (price.price as u64) / divisor
-    } else if expo_abs < INTERNAL_PRICE_DECIMALS {
-        // Pyth has less precision, multiply to increase
-        let multiplier = 10u64.pow(INTERNAL_PRICE_DECIMALS - expo_abs);
-        (price.price as u64).saturating_mul(multiplier)
+/// Load the latest result from a Switchboard On-Demand pull feed
+///
+/// Feed accounts are identified by their address. The standard deviation of
+/// the oracle samples is used as the confidence interval.
+fn load_switchboard_price(price_feed: &AccountInfo, expected_feed_id: &Pubkey) -> Result<RawPrice> {
+    require_keys_eq!(price_feed.key(), *expected_feed_id, OracleError::InvalidFeedId);
+    require_keys_eq!(
+        *price_feed.owner,
+        SWITCHBOARD_ON_DEMAND_PROGRAM_ID,
+        OracleError::InvalidOracleAccount
+    );
+
+    let data = price_feed.try_borrow_data()?;
+    require!(
+        read_bytes::<8>(&data, 0)? == PULL_FEED_DISCRIMINATOR,
+        OracleError::InvalidOracleAccount
+    );
+
+    let std_dev = i128::from_le_bytes(read_bytes(&data, SB_RESULT_STD_DEV_OFFSET)?);
+
+    Ok(RawPrice {
+        price: i128::from_le_bytes(read_bytes(&data, SB_RESULT_VALUE_OFFSET)?),
+        conf: std_dev.unsigned_abs(),
+        expo: -SWITCHBOARD_DECIMALS,
+        publish_time: i64::from_le_bytes(read_bytes(&data, SB_LAST_UPDATE_TIMESTAMP_OFFSET)?),
+    })
+}
+
+fn load_raw_price(
+    price_feed: &AccountInfo,
+    source: OracleSource,
+    expected_feed_id: &Pubkey,
+) -> Result<RawPrice> {
+    match source {
+        OracleSource::PythLegacy => load_pyth_legacy_price(price_feed, expected_feed_id),
+        OracleSource::PythPull => load_pyth_pull_price(price_feed, expected_feed_id),
+        OracleSource::SwitchboardOnDemand => load_switchboard_price(price_feed, expected_feed_id),
+    }
+}
+
+/// Rescale an oracle value (value * 10^expo) to `decimals` fixed-point precision
+///
+/// Example: BTC at $65,432.10 with expo = -8 and decimals = 6
+/// - Pyth: price = 6543210000000, expo = -8
+/// - Result: 65_432_100_000 (6 decimals)
+fn scale_to_decimals(value: u128, expo: i32, decimals: u32) -> Result<u64> {
+    // Target exponent is -decimals; shift = expo + decimals
+    let shift = expo
+        .checked_add(decimals as i32)
+        .ok_or(OracleError::ArithmeticOverflow)?;
+
+    let scaled = if shift >= 0 {
+        // Feed has less precision than requested, multiply to increase
+        let multiplier = 10u128
+            .checked_pow(shift as u32)
+            .ok_or(OracleError::ArithmeticOverflow)?;
+        value
+            .checked_mul(multiplier)
+            .ok_or(OracleError::ArithmeticOverflow)?
     } else {
-        // Same precision
-        price.price as u64
+        // Feed has more precision than requested, divide to reduce
+        let divisor = 10u128
+            .checked_pow(shift.unsigned_abs())
+            .ok_or(OracleError::ArithmeticOverflow)?;
+        value / divisor
     };
 
-    msg!("Pyth SOL/USD price: {} (6 decimals)", normalized_price);
-
-    Ok(normalized_price)
+    Ok(u64::try_from(scaled).map_err(|_| OracleError::ArithmeticOverflow)?)
 }
 
 /// Price data with metadata for validation
 #[derive(Clone, Copy, Debug)]
 pub struct PriceWithMetadata {
-    /// Price in 6-decimal precision (micro-dollars)
+    /// Price in the requested fixed-point precision
     pub price: u64,
-    /// Confidence interval in 6-decimal precision
+    /// Confidence interval in the same precision
     pub confidence: u64,
     /// Unix timestamp when price was published
     pub publish_time: i64,
@@ -139,145 +257,198 @@ pub struct PriceWithMetadata {
     pub age_seconds: u64,
 }
 
-/// Get SOL/USD price with full metadata for validation
-///
-/// This function is used for critical operations like liquidations where
-/// both price freshness AND confidence interval must be validated.
+impl PriceWithMetadata {
+    /// Confidence interval as basis points of price
+    pub fn confidence_bps(&self) -> Result<u64> {
+        // confidence_bps = (confidence * 10000) / price
+        Ok(self
+            .confidence
+            .checked_mul(10000)
+            .ok_or(OracleError::ArithmeticOverflow)?
+            .checked_div(self.price)
+            .ok_or(OracleError::ArithmeticOverflow)?)
+    }
+
+    /// Fresh enough and tight enough to be trusted
+    fn is_healthy(&self) -> Result<bool> {
+        Ok(self.age_seconds <= MAX_PRICE_AGE_SECS && self.confidence_bps()? <= MAX_CONFIDENCE_BPS)
+    }
+}
+
+/// Get an asset price with full metadata for validation
 ///
 /// # Arguments
-/// * `price_feed` - The Pyth price feed account
+/// * `price_feed` - The oracle account
+/// * `source` - Oracle account format
+/// * `expected_feed_id` - Feed configured on the market
+/// * `decimals` - Output precision for price and confidence
 ///
 /// # Returns
 /// * `Ok(PriceWithMetadata)` - Price with timestamp and confidence
-/// * `Err(_)` - If price feed is invalid or parsing fails
+/// * `Err(_)` - If the feed doesn't match, is invalid or parsing fails
 ///
 /// # Note
 /// This function does NOT enforce staleness - caller must validate `age_seconds`.
-/// Use `get_sol_usd_price()` for standard price fetching with staleness check.
-pub fn get_sol_usd_price_with_metadata(price_feed: &AccountInfo) -> Result<PriceWithMetadata> {
-    // Load price account from raw data using pyth_sdk_solana::state module
-    // This avoids AccountInfo type mismatches between Anchor 0.32.1 and pyth-sdk-solana
-    // SolanaPriceAccount = GenericPriceAccount<32, ()>
-    let data = price_feed.try_borrow_data()?;
-    let price_account = pyth_sdk_solana::state::load_price_account::<32, ()>(&data)
-        .map_err(|_| OracleError::InvalidFeedId)?;
-
-    // Manually create PriceFeed to avoid Pubkey type mismatch between
-    // Anchor 0.32.1 (solana_pubkey) and pyth-sdk-solana (solana_program::pubkey)
-    let price_id = pyth_sdk_solana::PriceIdentifier::new(price_feed.key.to_bytes());
-    let status = price_account.agg.status;
-    let price = if status == pyth_sdk_solana::state::PriceStatus::Trading {
-        pyth_sdk_solana::Price {
-            conf: price_account.agg.conf,
-            expo: price_account.expo,
-            price: price_account.agg.price,
-            publish_time: price_account.timestamp,
-        }
-    } else {
-        pyth_sdk_solana::Price {
-            conf: price_account.prev_conf,
-            expo: price_account.expo,
-            price: price_account.prev_price,
-            publish_time: price_account.timestamp,
-        }
-    };
-    let ema_price = pyth_sdk_solana::Price {
-        conf: price_account.ema_conf.val as u64,
-        expo: price_account.expo,
-        price: price_account.ema_price.val,
-        publish_time: price_account.timestamp,
-    };
-    let price_feed_data = pyth_sdk_solana::PriceFeed::new(price_id, price, ema_price);
+/// Use `get_price()` for standard price fetching with staleness check.
+pub fn get_price_with_metadata(
+    price_feed: &AccountInfo,
+    source: OracleSource,
+    expected_feed_id: &Pubkey,
+    decimals: u32,
+) -> Result<PriceWithMetadata> {
+    let raw = load_raw_price(price_feed, source, expected_feed_id)?;
 
     let clock = Clock::get()?;
     let current_time = clock.unix_timestamp;
 
-    // Get current price - caller will validate staleness via age_seconds
-    // We use get_price_no_older_than with a large threshold to get the price
-    // but the actual staleness validation happens in the caller
-    let price = price_feed_data
-        .get_price_no_older_than(current_time, u64::MAX / 2)
-        .ok_or(OracleError::InvalidPrice)?;
-
     // Ensure price is positive
-    require!(price.price > 0, OracleError::InvalidPrice);
+    require!(raw.price > 0, OracleError::InvalidPrice);
 
-    let expo_abs = (-price.expo) as u32;
-
-    // Convert price to 6-decimal precision
-    let normalized_price = if expo_abs > INTERNAL_PRICE_DECIMALS {
-        let divisor = 10u64.pow(expo_abs - INTERNAL_PRICE_DECIMALS);
-        (price.price as u64) / divisor
-    } else if expo_abs < INTERNAL_PRICE_DECIMALS {
-        let multiplier = 10u64.pow(INTERNAL_PRICE_DECIMALS - expo_abs);
-        (price.price as u64).saturating_mul(multiplier)
-    } else {
-        price.price as u64
-    };
-
-    // Convert confidence to 6-decimal precision
-    let normalized_confidence = if expo_abs > INTERNAL_PRICE_DECIMALS {
-        let divisor = 10u64.pow(expo_abs - INTERNAL_PRICE_DECIMALS);
-        price.conf / divisor
-    } else if expo_abs < INTERNAL_PRICE_DECIMALS {
-        let multiplier = 10u64.pow(INTERNAL_PRICE_DECIMALS - expo_abs);
-        price.conf.saturating_mul(multiplier)
-    } else {
-        price.conf
-    };
+    // Price and confidence share the feed exponent
+    let normalized_price = scale_to_decimals(raw.price as u128, raw.expo, decimals)?;
+    let normalized_confidence = scale_to_decimals(raw.conf, raw.expo, decimals)?;
+    require!(normalized_price > 0, OracleError::InvalidPrice);
 
     // Calculate age
-    let age_seconds = (current_time - price.publish_time).max(0) as u64;
+    let age_seconds = (current_time - raw.publish_time).max(0) as u64;
 
     Ok(PriceWithMetadata {
         price: normalized_price,
         confidence: normalized_confidence,
-        publish_time: price.publish_time,
+        publish_time: raw.publish_time,
         age_seconds,
     })
 }
 
-/// Get SOL/USD price for liquidations with strict validation
+/// Get the price of any asset from a single oracle feed
 ///
-/// This function enforces:
-/// 1. Price must be < MAX_PRICE_AGE_SECS old (60s mainnet, 3600s devnet)
-/// 2. Confidence interval must be < MAX_CONFIDENCE_BPS of price (1% mainnet, 10% devnet)
+/// Returns price in `decimals` fixed-point precision.
+/// For example, $100.50 with 6 decimals = 100_500_000
 ///
 /// # Arguments
-/// * `price_feed` - The Pyth price feed account
+/// * `price_feed` - The oracle account
+/// * `source` - Oracle account format
+/// * `expected_feed_id` - Feed configured on the market
+/// * `decimals` - Output precision
 ///
 /// # Returns
-/// * `Ok(u64)` - Validated price in micro-dollars (6 decimals)
-/// * `Err(StalePrice)` - If price is too old
-/// * `Err(ConfidenceTooWide)` - If confidence interval is too wide
-pub fn get_sol_usd_price_for_liquidation(price_feed: &AccountInfo) -> Result<u64> {
-    let price_data = get_sol_usd_price_with_metadata(price_feed)?;
+/// * `Ok(u64)` - Price in `decimals` precision
+/// * `Err(_)` - If the feed doesn't match, is stale, invalid, or parsing fails
+pub fn get_price(
+    price_feed: &AccountInfo,
+    source: OracleSource,
+    expected_feed_id: &Pubkey,
+    decimals: u32,
+) -> Result<u64> {
+    let price_data = get_price_with_metadata(price_feed, source, expected_feed_id, decimals)?;
 
-    // Validate freshness
     require!(
         price_data.age_seconds <= MAX_PRICE_AGE_SECS,
         OracleError::StalePrice
     );
 
-    // Validate confidence interval
-    // confidence_bps = (confidence * 10000) / price
-    let confidence_bps = price_data
-        .confidence
-        .checked_mul(10000)
-        .ok_or(OracleError::ArithmeticOverflow)?
-        .checked_div(price_data.price)
-        .ok_or(OracleError::ArithmeticOverflow)?;
+    msg!("Oracle price: {} ({} decimals)", price_data.price, decimals);
 
-    require!(
-        confidence_bps <= MAX_CONFIDENCE_BPS,
-        OracleError::ConfidenceTooWide
+    Ok(price_data.price)
+}
+
+/// Read a market's price from its primary oracle, falling back to the
+/// secondary when the primary is unreadable, stale or too uncertain
+///
+/// When both sources are healthy their prices must agree within
+/// `max_oracle_divergence_bps` (0 = no divergence check).
+fn get_aggregated_price(
+    market: &PerpetualMarket,
+    oracle: &AccountInfo,
+    secondary_oracle: Option<&AccountInfo>,
+) -> Result<PriceWithMetadata> {
+    let decimals = market.oracle_decimals();
+    let primary = get_price_with_metadata(
+        oracle,
+        market.oracle_source,
+        &market.oracle_price_feed,
+        decimals,
     );
 
+    if !market.has_secondary_oracle() {
+        let price_data = primary?;
+        require!(price_data.age_seconds <= MAX_PRICE_AGE_SECS, OracleError::StalePrice);
+        require!(
+            price_data.confidence_bps()? <= MAX_CONFIDENCE_BPS,
+            OracleError::ConfidenceTooWide
+        );
+        return Ok(price_data);
+    }
+
+    let secondary_info = secondary_oracle.ok_or(OracleError::MissingSecondaryOracle)?;
+    let secondary = get_price_with_metadata(
+        secondary_info,
+        market.secondary_oracle_source,
+        &market.secondary_oracle_feed,
+        decimals,
+    );
+
+    let primary_ok = match &primary {
+        Ok(price_data) => price_data.is_healthy()?,
+        Err(_) => false,
+    };
+    let secondary_ok = match &secondary {
+        Ok(price_data) => price_data.is_healthy()?,
+        Err(_) => false,
+    };
+
+    match (primary_ok, secondary_ok) {
+        (true, true) => {
+            let (primary, secondary) = (primary?, secondary?);
+            if market.max_oracle_divergence_bps > 0 {
+                require!(
+                    validate_price_deviation(
+                        secondary.price,
+                        primary.price,
+                        market.max_oracle_divergence_bps
+                    )?,
+                    OracleError::OracleDivergence
+                );
+            }
+            Ok(primary)
+        }
+        (true, false) => {
+            msg!("Secondary oracle unhealthy, using primary");
+            primary
+        }
+        (false, true) => {
+            msg!("Primary oracle unhealthy, using secondary");
+            secondary
+        }
+        (false, false) => Err(OracleError::NoHealthyOracle.into()),
+    }
+}
+
+/// Get a market's mark price (primary oracle with secondary fallback)
+///
+/// Used for opening, closing, liquidations and ADL settlement. Enforces, for
+/// whichever source is used:
+/// 1. Price must be < MAX_PRICE_AGE_SECS old (60s mainnet, 3600s devnet)
+/// 2. Confidence interval must be < MAX_CONFIDENCE_BPS of price (1% mainnet, 10% devnet)
+/// 3. Primary and secondary must not diverge beyond `max_oracle_divergence_bps`
+///
+/// # Returns
+/// * `Ok(u64)` - Validated price in the market's `price_decimals` precision
+/// * `Err(StalePrice)` / `Err(ConfidenceTooWide)` - Single source unhealthy
+/// * `Err(NoHealthyOracle)` - Neither primary nor secondary usable
+/// * `Err(OracleDivergence)` - Sources disagree
+pub fn get_market_price(
+    market: &PerpetualMarket,
+    oracle: &AccountInfo,
+    secondary_oracle: Option<&AccountInfo>,
+) -> Result<u64> {
+    let price_data = get_aggregated_price(market, oracle, secondary_oracle)?;
+
     msg!(
-        "Liquidation price: {} (age: {}s, confidence: {} bps)",
+        "Oracle price: {} (age: {}s, confidence: {} bps)",
         price_data.price,
         price_data.age_seconds,
-        confidence_bps
+        price_data.confidence_bps()?
     );
 
     Ok(price_data.price)
@@ -362,7 +533,7 @@ pub fn calculate_liquidation_price(
 
 #[error_code]
 pub enum OracleError {
-    #[msg("Invalid Pyth feed ID")]
+    #[msg("Invalid Pyth feed ID or oracle account does not match the market feed")]
     InvalidFeedId,
     #[msg("Price data is stale (older than 60 seconds on mainnet)")]
     StalePrice,
@@ -374,4 +545,16 @@ pub enum OracleError {
     ArithmeticOverflow,
     #[msg("Oracle confidence interval too wide (>1% on mainnet) - price unreliable for liquidations")]
     ConfidenceTooWide,
+    #[msg("Oracle account has unexpected owner or layout")]
+    InvalidOracleAccount,
+    #[msg("Pyth price update is only partially verified")]
+    UnverifiedPriceUpdate,
+    #[msg("Market has a secondary oracle configured but it was not provided")]
+    MissingSecondaryOracle,
+    #[msg("Primary and secondary oracle prices diverge beyond the market limit")]
+    OracleDivergence,
+    #[msg("Neither primary nor secondary oracle is fresh with acceptable confidence")]
+    NoHealthyOracle,
+    #[msg("Funding state has a mark price feed configured but it was not provided")]
+    MissingMarkOracle,
 }
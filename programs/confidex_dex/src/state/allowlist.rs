@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+
+/// Per-market allowlist configuration (stored on TradingPair / PerpetualMarket)
+///
+/// When `root` is non-zero the market is restricted to traders holding a
+/// credential in the issuer's tree (KYC'd or jurisdiction-permitted), proven
+/// via the allowlist circuit and recorded in an AllowlistCredential account.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllowlistConfig {
+    /// Root of the issuer's credential tree ([0; 32] = allowlist disabled)
+    pub root: [u8; 32],
+    /// Credential issuer; may rotate `root` without the exchange authority
+    pub issuer: Pubkey,
+    /// Sunspot verifier program for the allowlist circuit
+    pub verifier: Pubkey,
+}
+
+impl AllowlistConfig {
+    pub const SIZE: usize = 32 + // root
+        32 + // issuer
+        32;  // verifier
+
+    /// Whether the market requires an allowlist credential
+    pub fn is_enabled(&self) -> bool {
+        self.root != [0u8; 32]
+    }
+}
+
+/// Trader's allowlist credential for a single market
+///
+/// Created by verify_allowlist after a successful Sunspot proof of membership
+/// in the market's credential tree. Unlike blacklist attestations there is no
+/// grace window: rotating the allowlist root revokes every credential proven
+/// against the old root immediately.
+#[account]
+pub struct AllowlistCredential {
+    /// Trader holding the credential
+    pub trader: Pubkey,
+    /// TradingPair or PerpetualMarket the credential applies to
+    pub market: Pubkey,
+    /// Allowlist root the membership proof was verified against
+    pub verified_allowlist_root: [u8; 32],
+    /// Unix timestamp of the last verification
+    pub verified_at: i64,
+    /// Unix timestamp after which the credential must be re-proven
+    pub valid_until: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl AllowlistCredential {
+    pub const SIZE: usize = 8 +  // discriminator
+        32 + // trader
+        32 + // market
+        32 + // verified_allowlist_root
+        8 +  // verified_at
+        8 +  // valid_until
+        1;   // bump
+    // Total: 121 bytes
+
+    pub const SEED: &'static [u8] = b"allowlist_credential";
+
+    /// Check the credential against the market's current allowlist root
+    pub fn is_valid(&self, allowlist: &AllowlistConfig, now: i64) -> bool {
+        allowlist.is_enabled()
+            && self.verified_allowlist_root == allowlist.root
+            && now < self.valid_until
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROOT: [u8; 32] = [7u8; 32];
+
+    fn allowlist(root: [u8; 32]) -> AllowlistConfig {
+        AllowlistConfig {
+            root,
+            issuer: Pubkey::new_unique(),
+            verifier: Pubkey::new_unique(),
+        }
+    }
+
+    fn credential(root: [u8; 32], valid_until: i64) -> AllowlistCredential {
+        AllowlistCredential {
+            trader: Pubkey::new_unique(),
+            market: Pubkey::new_unique(),
+            verified_allowlist_root: root,
+            verified_at: 0,
+            valid_until,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_credential_valid_until_expiry() {
+        let credential = credential(ROOT, 1_000);
+
+        assert!(credential.is_valid(&allowlist(ROOT), 999));
+        assert!(!credential.is_valid(&allowlist(ROOT), 1_000));
+    }
+
+    #[test]
+    fn test_root_rotation_revokes_credential() {
+        let credential = credential(ROOT, 1_000);
+
+        assert!(!credential.is_valid(&allowlist([8u8; 32]), 0));
+    }
+
+    #[test]
+    fn test_disabled_allowlist_never_validates() {
+        // A zeroed credential must not match a disabled (zero-root) allowlist
+        let credential = credential([0u8; 32], i64::MAX);
+
+        assert!(!credential.is_valid(&allowlist([0u8; 32]), 0));
+    }
+}
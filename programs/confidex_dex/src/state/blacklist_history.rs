@@ -0,0 +1,179 @@
+use anchor_lang::prelude::*;
+
+/// Ring buffer of recent blacklist merkle roots (companion to ExchangeState)
+///
+/// Eligibility attestations are tied to the root they were proven against.
+/// Instead of invalidating every attestation the moment `update_blacklist`
+/// runs, a superseded root stays acceptable for `grace_period_secs` after the
+/// root that replaced it was activated. The current root (always equal to
+/// `exchange.blacklist_root`) is accepted indefinitely.
+#[account]
+pub struct BlacklistRootHistory {
+    /// Recent roots; `roots[head]` is the current root
+    pub roots: [[u8; 32]; BlacklistRootHistory::MAX_ROOTS],
+    /// Activation timestamp of each root (unix seconds)
+    pub activated_at: [i64; BlacklistRootHistory::MAX_ROOTS],
+    /// Index of the current root
+    pub head: u8,
+    /// Number of populated slots (<= MAX_ROOTS)
+    pub count: u8,
+    /// How long a superseded root stays acceptable (seconds)
+    pub grace_period_secs: i64,
+    /// Lifetime of a TraderEligibility attestation (seconds)
+    pub attestation_ttl_secs: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl BlacklistRootHistory {
+    pub const MAX_ROOTS: usize = 8;
+
+    /// Upper bound for the grace window (7 days)
+    pub const MAX_GRACE_PERIOD_SECS: i64 = 7 * 86_400;
+
+    /// Upper bound for attestation lifetime (30 days)
+    pub const MAX_ATTESTATION_TTL_SECS: i64 = 30 * 86_400;
+
+    pub const SIZE: usize = 8 + // discriminator
+        32 * Self::MAX_ROOTS + // roots
+        8 * Self::MAX_ROOTS +  // activated_at
+        1 +  // head
+        1 +  // count
+        8 +  // grace_period_secs
+        8 +  // attestation_ttl_secs
+        1;   // bump
+    // Total: 347 bytes
+
+    pub const SEED: &'static [u8] = b"blacklist_history";
+
+    /// Validate grace window and attestation lifetime
+    pub fn validate_config(grace_period_secs: i64, attestation_ttl_secs: i64) -> bool {
+        (0..=Self::MAX_GRACE_PERIOD_SECS).contains(&grace_period_secs)
+            && (1..=Self::MAX_ATTESTATION_TTL_SECS).contains(&attestation_ttl_secs)
+    }
+
+    /// Current (most recently activated) root
+    pub fn current_root(&self) -> [u8; 32] {
+        self.roots[self.head as usize]
+    }
+
+    /// Record a newly activated root
+    pub fn push(&mut self, root: [u8; 32], now: i64) {
+        if self.count == 0 {
+            self.head = 0;
+        } else {
+            self.head = ((self.head as usize + 1) % Self::MAX_ROOTS) as u8;
+        }
+        self.roots[self.head as usize] = root;
+        self.activated_at[self.head as usize] = now;
+        self.count = (self.count + 1).min(Self::MAX_ROOTS as u8);
+    }
+
+    /// Time until which `root` is acceptable, if it is in the history
+    ///
+    /// Returns `i64::MAX` for the current root. For a superseded root this is
+    /// the activation time of its successor plus the grace window. Roots that
+    /// were re-activated later take their most recent window.
+    pub fn root_accepted_until(&self, root: &[u8; 32]) -> Option<i64> {
+        // Walk newest -> oldest; the successor of slot i is the slot visited before it
+        let mut successor_activated_at: Option<i64> = None;
+        for step in 0..self.count as usize {
+            let idx = (self.head as usize + Self::MAX_ROOTS - step) % Self::MAX_ROOTS;
+            if self.roots[idx] == *root {
+                return Some(match successor_activated_at {
+                    None => i64::MAX,
+                    Some(superseded_at) => superseded_at.saturating_add(self.grace_period_secs),
+                });
+            }
+            successor_activated_at = Some(self.activated_at[idx]);
+        }
+        None
+    }
+
+    /// Whether an attestation proven against `root` is still acceptable
+    pub fn is_root_accepted(&self, root: &[u8; 32], now: i64) -> bool {
+        self.root_accepted_until(root)
+            .is_some_and(|accepted_until| now < accepted_until)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRACE: i64 = 3_600;
+
+    fn history() -> BlacklistRootHistory {
+        BlacklistRootHistory {
+            roots: [[0u8; 32]; BlacklistRootHistory::MAX_ROOTS],
+            activated_at: [0; BlacklistRootHistory::MAX_ROOTS],
+            head: 0,
+            count: 0,
+            grace_period_secs: GRACE,
+            attestation_ttl_secs: 86_400,
+            bump: 255,
+        }
+    }
+
+    fn root(n: u8) -> [u8; 32] {
+        [n; 32]
+    }
+
+    #[test]
+    fn test_push_wraps_ring_and_evicts_oldest() {
+        let mut h = history();
+        let total = BlacklistRootHistory::MAX_ROOTS as u8 + 2;
+        for n in 1..=total {
+            h.push(root(n), n as i64 * 100);
+        }
+
+        assert_eq!(h.count as usize, BlacklistRootHistory::MAX_ROOTS);
+        assert_eq!(h.head, 1);
+        assert_eq!(h.current_root(), root(total));
+
+        // The two oldest roots were overwritten
+        assert_eq!(h.root_accepted_until(&root(1)), None);
+        assert_eq!(h.root_accepted_until(&root(2)), None);
+        // The oldest surviving root is graced from its successor's activation
+        assert_eq!(h.root_accepted_until(&root(3)), Some(400 + GRACE));
+        assert_eq!(h.root_accepted_until(&root(total)), Some(i64::MAX));
+    }
+
+    #[test]
+    fn test_reactivated_root_takes_latest_window() {
+        let mut h = history();
+        h.push(root(1), 100);
+        h.push(root(2), 200);
+        h.push(root(1), 300);
+
+        // Root 1 is current again
+        assert_eq!(h.root_accepted_until(&root(1)), Some(i64::MAX));
+
+        h.push(root(3), 400);
+        assert_eq!(h.root_accepted_until(&root(1)), Some(400 + GRACE));
+        assert_eq!(h.root_accepted_until(&root(2)), Some(300 + GRACE));
+    }
+
+    #[test]
+    fn test_grace_boundary() {
+        let mut h = history();
+        h.push(root(1), 100);
+        h.push(root(2), 1_000);
+
+        assert!(h.is_root_accepted(&root(1), 1_000 + GRACE - 1));
+        assert!(!h.is_root_accepted(&root(1), 1_000 + GRACE));
+        assert!(h.is_root_accepted(&root(2), i64::MAX - 1));
+        assert!(!h.is_root_accepted(&root(9), 0));
+    }
+
+    #[test]
+    fn test_zero_grace_rejects_superseded_root() {
+        let mut h = history();
+        h.grace_period_secs = 0;
+        h.push(root(1), 100);
+        h.push(root(2), 200);
+
+        assert!(h.is_root_accepted(&root(1), 199));
+        assert!(!h.is_root_accepted(&root(1), 200));
+    }
+}
@@ -17,7 +17,9 @@ pub struct ExchangeState {
     pub fee_recipient: Pubkey,
 
     /// Maker fee in basis points (e.g., 10 = 0.10%)
-    pub maker_fee_bps: u16,
+    /// Negative values are maker rebates (e.g., -2 = 0.02% rebate), paid out
+    /// of the taker fee. Same 2-byte layout as the former u16 field.
+    pub maker_fee_bps: i16,
 
     /// Taker fee in basis points (e.g., 30 = 0.30%)
     pub taker_fee_bps: u16,
@@ -99,8 +101,14 @@ impl ExchangeState {
 
     pub const SEED: &'static [u8] = b"exchange";
 
-    pub fn validate_fees(maker_fee_bps: u16, taker_fee_bps: u16) -> bool {
-        maker_fee_bps <= 10000 && taker_fee_bps <= 10000
+    /// Validate fee configuration
+    ///
+    /// A maker rebate (negative maker fee) must be fully funded by the taker
+    /// fee, so |maker_fee_bps| may not exceed taker_fee_bps.
+    pub fn validate_fees(maker_fee_bps: i16, taker_fee_bps: u16) -> bool {
+        taker_fee_bps <= 10000
+            && maker_fee_bps <= 10000
+            && (maker_fee_bps >= 0 || maker_fee_bps.unsigned_abs() <= taker_fee_bps)
     }
 
     /// Validate program ID is non-zero (basic sanity check)
@@ -108,3 +116,35 @@ impl ExchangeState {
         *program_id != Pubkey::default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_fees_accepts_standard_fees() {
+        assert!(ExchangeState::validate_fees(10, 30));
+        assert!(ExchangeState::validate_fees(0, 0));
+        assert!(ExchangeState::validate_fees(10000, 10000));
+    }
+
+    #[test]
+    fn validate_fees_rejects_fees_over_100_percent() {
+        assert!(!ExchangeState::validate_fees(10001, 30));
+        assert!(!ExchangeState::validate_fees(10, 10001));
+    }
+
+    #[test]
+    fn validate_fees_accepts_rebate_funded_by_taker_fee() {
+        assert!(ExchangeState::validate_fees(-2, 30));
+        // Rebate equal to the taker fee leaves the exchange with nothing, but is funded
+        assert!(ExchangeState::validate_fees(-30, 30));
+    }
+
+    #[test]
+    fn validate_fees_rejects_unfunded_rebate() {
+        assert!(!ExchangeState::validate_fees(-31, 30));
+        assert!(!ExchangeState::validate_fees(-1, 0));
+        assert!(!ExchangeState::validate_fees(i16::MIN, 10000));
+    }
+}
@@ -1,8 +1,20 @@
 use anchor_lang::prelude::*;
 
+use super::OracleSource;
+
 /// Funding rate state for a perpetual market
 /// Tracks current funding rate and historical data for TWAP calculation
-/// Size: 8 (discriminator) + 168 = 176 bytes
+///
+/// Funding follows a mark-vs-index premium model. Each update samples the
+/// perp mark price from the market's mark feed (where the perp actually
+/// trades) and the spot index from the market oracle. Both are folded into
+/// time-weighted averages over the same window and with the same weights,
+/// so the premium (mark average vs index average) reflects where the perp
+/// trades against spot, not index moves the averages haven't caught up with.
+/// Each interval's rate is
+///   premium + clamp(interest - premium, -dampening, +dampening)
+/// The applied rate is the 8h TWAP of those samples (hourly_rates).
+/// Size: 8 (discriminator) + 257 = 265 bytes (V2)
 #[account]
 #[derive(Default)]
 pub struct FundingRateState {
@@ -39,6 +51,44 @@ pub struct FundingRateState {
 
     /// PDA bump seed
     pub bump: u8,
+
+    // === V2 fields (premium funding model) ===
+
+    /// Interest component in basis points per funding interval (signed)
+    pub interest_rate_bps: i16,
+
+    /// Dampening band: interest - premium is clamped to +/- this value
+    pub premium_dampening_bps: u16,
+
+    /// Format of the mark price feed
+    pub mark_oracle_source: OracleSource,
+
+    /// Perp mark price feed, account address or Pyth feed ID depending on
+    /// `mark_oracle_source` (Pubkey::default() = no mark feed: the mark is
+    /// taken to be the index and the rate reduces to the interest component)
+    pub mark_oracle_feed: Pubkey,
+
+    /// Time window of the mark and index price averages in seconds
+    pub mark_ema_window_seconds: u32,
+
+    /// Time-weighted average of the mark price (market price_decimals)
+    pub mark_price_ema: u64,
+
+    /// Oracle index price at the last funding update (market price_decimals)
+    pub last_index_price: u64,
+
+    /// Unix timestamp of the last mark/index average update
+    pub last_mark_update_time: i64,
+
+    /// Time-weighted average of the oracle index price, over the same window
+    /// and samples as `mark_price_ema` (market price_decimals)
+    pub index_price_ema: u64,
+
+    /// Fractional part of `mark_price_ema`, in 1/mark_ema_window_seconds units
+    pub mark_ema_remainder: i64,
+
+    /// Fractional part of `index_price_ema`, in 1/mark_ema_window_seconds units
+    pub index_ema_remainder: i64,
 }
 
 impl FundingRateState {
@@ -53,8 +103,32 @@ impl FundingRateState {
         1 +   // rates_filled
         8 +   // total_long_funding_paid
         8 +   // total_short_funding_paid
-        1;    // bump
-    // Total: 176 bytes
+        1 +   // bump
+        2 +   // interest_rate_bps (V2)
+        2 +   // premium_dampening_bps (V2)
+        1 +   // mark_oracle_source (V2)
+        32 +  // mark_oracle_feed (V2)
+        4 +   // mark_ema_window_seconds (V2)
+        8 +   // mark_price_ema (V2)
+        8 +   // last_index_price (V2)
+        8 +   // last_mark_update_time (V2)
+        8 +   // index_price_ema (V2)
+        8 +   // mark_ema_remainder (V2)
+        8;    // index_ema_remainder (V2)
+    // Total: 265 bytes
+
+    /// V1 size (OI-only funding) for migration
+    pub const V1_SIZE: usize = 176;
+
+    /// Default dampening band (0.05%, so small premiums fund at the interest rate)
+    pub const DEFAULT_PREMIUM_DAMPENING_BPS: u16 = 5;
+
+    /// Default mark/index average window (8 hours)
+    pub const DEFAULT_MARK_EMA_WINDOW_SECS: u32 = 8 * 3600;
+
+    /// Maximum number of funding intervals accrued (and recorded in
+    /// hourly_rates) by a single late update
+    pub const MAX_CATCHUP_INTERVALS: u64 = 24;
 
     pub const SEED: &'static [u8] = b"funding";
 
@@ -63,6 +137,33 @@ impl FundingRateState {
         current_time >= self.last_calculation_time + self.funding_interval_seconds as i64
     }
 
+    /// Seconds of funding to accrue since the last calculation
+    /// (capped at MAX_CATCHUP_INTERVALS intervals)
+    pub fn accrual_seconds(&self, current_time: i64) -> u64 {
+        let elapsed = (current_time - self.last_calculation_time).max(0) as u64;
+        elapsed.min(
+            self.funding_interval_seconds
+                .saturating_mul(Self::MAX_CATCHUP_INTERVALS),
+        )
+    }
+
+    /// Number of interval boundaries crossed since the last calculation
+    /// (intervals are aligned to the unix epoch, capped at MAX_CATCHUP_INTERVALS)
+    pub fn intervals_crossed(&self, current_time: i64) -> u64 {
+        if self.funding_interval_seconds == 0 {
+            return 0;
+        }
+        let interval = self.funding_interval_seconds as i64;
+        let crossed = current_time.div_euclid(interval)
+            - self.last_calculation_time.div_euclid(interval);
+        (crossed.max(0) as u64).min(Self::MAX_CATCHUP_INTERVALS)
+    }
+
+    /// Whether a mark price feed is configured
+    pub fn has_mark_oracle(&self) -> bool {
+        self.mark_oracle_feed != Pubkey::default()
+    }
+
     /// Calculate time until next funding
     pub fn time_until_next_funding(&self, current_time: i64) -> i64 {
         let next_funding = self.last_calculation_time + self.funding_interval_seconds as i64;
@@ -105,27 +206,211 @@ impl FundingRateState {
         rate.clamp(-max, max)
     }
 
-    /// Calculate funding rate based on open interest imbalance
-    /// Returns positive if longs pay shorts, negative if shorts pay longs
-    pub fn calculate_rate_from_oi(
-        long_oi: u64,
-        short_oi: u64,
-        base_rate_bps: i32,
-    ) -> i32 {
-        if long_oi == 0 && short_oi == 0 {
-            return 0;
+    /// Move a time-weighted average towards `sample` by elapsed / window
+    ///
+    /// The division remainder is carried (as the average's fractional part),
+    /// so a crank every few seconds still moves the average instead of each
+    /// step truncating to zero. Returns the new average and remainder.
+    fn ema_step(ema: u64, remainder: i64, sample: u64, elapsed: i64, window: u32) -> (u64, i64) {
+        if ema == 0 || window == 0 {
+            return (sample, 0);
+        }
+        let window = window as i128;
+        let elapsed = elapsed.max(0) as i128;
+        if elapsed >= window {
+            return (sample, 0);
         }
+        let scaled = (sample as i128 - ema as i128) * elapsed + remainder as i128;
+        let delta = scaled / window;
+        let carry = scaled % window;
+        ((ema as i128 + delta).max(0) as u64, carry as i64)
+    }
 
-        let total_oi = long_oi.saturating_add(short_oi);
-        let imbalance = if long_oi > short_oi {
-            // More longs than shorts - longs pay
-            ((long_oi - short_oi) as i128 * 10000 / total_oi as i128) as i32
+    /// Fold a mark and an index sample into their time-weighted averages
+    ///
+    /// Both averages use the same weight, so they lag price moves equally and
+    /// their difference is the (averaged) mark premium over the index.
+    pub fn update_price_emas(&mut self, mark_sample: u64, index_sample: u64, current_time: i64) {
+        let elapsed = current_time - self.last_mark_update_time;
+        // Seed both together so neither average starts ahead of the other
+        if self.mark_price_ema == 0 || self.index_price_ema == 0 {
+            self.mark_price_ema = mark_sample;
+            self.index_price_ema = index_sample;
+            self.mark_ema_remainder = 0;
+            self.index_ema_remainder = 0;
         } else {
-            // More shorts than longs - shorts pay (negative rate)
-            -((short_oi - long_oi) as i128 * 10000 / total_oi as i128) as i32
-        };
+            (self.mark_price_ema, self.mark_ema_remainder) = Self::ema_step(
+                self.mark_price_ema,
+                self.mark_ema_remainder,
+                mark_sample,
+                elapsed,
+                self.mark_ema_window_seconds,
+            );
+            (self.index_price_ema, self.index_ema_remainder) = Self::ema_step(
+                self.index_price_ema,
+                self.index_ema_remainder,
+                index_sample,
+                elapsed,
+                self.mark_ema_window_seconds,
+            );
+        }
+        self.last_mark_update_time = current_time;
+    }
+
+    /// Premium of the mark average over the index average, in basis points
+    pub fn premium_bps(&self) -> i64 {
+        if self.index_price_ema == 0 {
+            return 0;
+        }
+        let premium = (self.mark_price_ema as i128 - self.index_price_ema as i128) * 10000
+            / self.index_price_ema as i128;
+        premium.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+
+    /// Funding rate for one interval from the premium:
+    /// premium + clamp(interest - premium, -dampening, +dampening)
+    /// Returns positive if longs pay shorts, negative if shorts pay longs
+    pub fn calculate_premium_rate(&self, premium_bps: i64) -> i32 {
+        let interest = self.interest_rate_bps as i64;
+        let dampening = self.premium_dampening_bps as i64;
+        let rate = premium_bps.saturating_add(
+            interest.saturating_sub(premium_bps).clamp(-dampening, dampening),
+        );
+        rate.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const T0: i64 = 1_704_067_200;
+    const WINDOW: u32 = 8 * 3600;
+
+    fn state() -> FundingRateState {
+        FundingRateState {
+            funding_interval_seconds: 3600,
+            max_funding_rate_bps: 100,
+            premium_dampening_bps: 5,
+            mark_ema_window_seconds: WINDOW,
+            last_mark_update_time: T0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn first_update_seeds_both_averages() {
+        let mut s = state();
+        s.update_price_emas(101_000_000, 100_000_000, T0);
+        assert_eq!(s.mark_price_ema, 101_000_000);
+        assert_eq!(s.index_price_ema, 100_000_000);
+        assert_eq!(s.premium_bps(), 100);
+    }
+
+    #[test]
+    fn ema_step_moves_by_elapsed_fraction_of_window() {
+        // A quarter of the window moves a quarter of the way
+        assert_eq!(FundingRateState::ema_step(100, 0, 200, WINDOW as i64 / 4, WINDOW), (125, 0));
+        // Elapsed beyond the window jumps to the sample
+        assert_eq!(FundingRateState::ema_step(100, 0, 200, WINDOW as i64 * 3, WINDOW), (200, 0));
+        // No time elapsed, or time going backwards, leaves the average unchanged
+        assert_eq!(FundingRateState::ema_step(100, 0, 200, 0, WINDOW), (100, 0));
+        assert_eq!(FundingRateState::ema_step(100, 7, 200, -60, WINDOW), (100, 7));
+        // A step too small to move the average is carried, not dropped
+        assert_eq!(FundingRateState::ema_step(100, 0, 200, 1, WINDOW), (100, 100));
+        assert_eq!(
+            FundingRateState::ema_step(100, WINDOW as i64 - 100, 200, 1, WINDOW),
+            (101, 0)
+        );
+    }
+
+    #[test]
+    fn one_second_cranks_converge() {
+        let mut s = state();
+        s.update_price_emas(100_000_000, 100_000_000, T0);
+        // Mark moves 1% over the index; crank every second for 20 windows
+        let mut now = T0;
+        for _ in 0..20 * WINDOW {
+            now += 1;
+            s.update_price_emas(101_000_000, 100_000_000, now);
+        }
+        // Without the carried remainder every step would truncate to zero
+        // once the gap fell below the window, stalling ~28,800 units short
+        assert!(101_000_000 - s.mark_price_ema <= 1, "mark avg {}", s.mark_price_ema);
+        assert_eq!(s.index_price_ema, 100_000_000);
+        assert!((s.premium_bps() - 100).abs() <= 1);
+    }
+
+    #[test]
+    fn index_move_tracked_by_mark_has_no_premium() {
+        let mut s = state();
+        s.update_price_emas(100_000_000, 100_000_000, T0);
+        // Index jumps 10% and the perp trades with it: no premium
+        s.update_price_emas(110_000_000, 110_000_000, T0 + 3600);
+        assert_eq!(s.premium_bps(), 0);
+    }
+
+    #[test]
+    fn persistent_mark_premium_survives_index_trend() {
+        let mut s = state();
+        // Perp trades 1% over spot while spot trends up
+        let mut index = 100_000_000u64;
+        s.update_price_emas(index * 101 / 100, index, T0);
+        for hour in 1..=4 {
+            index += 2_000_000;
+            s.update_price_emas(index * 101 / 100, index, T0 + hour * 3600);
+            // Both averages lag by the same weight, so the premium stays the
+            // mark's 100 bps (up to rounding) while the index trends
+            assert!((s.premium_bps() - 100).abs() <= 1, "premium {}", s.premium_bps());
+        }
+    }
+
+    #[test]
+    fn mark_premium_moves_averages_apart() {
+        let mut s = state();
+        s.update_price_emas(100_000_000, 100_000_000, T0);
+        // Perp starts trading 2% under spot at a flat index
+        s.update_price_emas(98_000_000, 100_000_000, T0 + WINDOW as i64 / 4);
+        assert_eq!(s.premium_bps(), -50);
+        s.update_price_emas(98_000_000, 100_000_000, T0 + WINDOW as i64 * 2);
+        assert_eq!(s.premium_bps(), -200);
+    }
+
+    #[test]
+    fn premium_rate_applies_interest_within_dampening_band() {
+        let mut s = state();
+        s.interest_rate_bps = 1;
+        // Small premium: interest - premium is inside the band, rate = interest
+        assert_eq!(s.calculate_premium_rate(3), 1);
+        assert_eq!(s.calculate_premium_rate(-2), 1);
+        // Large premium: rate = premium - dampening (or + dampening)
+        assert_eq!(s.calculate_premium_rate(50), 45);
+        assert_eq!(s.calculate_premium_rate(-50), -45);
+    }
+
+    #[test]
+    fn accrual_seconds_is_proportional_and_capped() {
+        let s = FundingRateState { last_calculation_time: T0, ..state() };
+        assert_eq!(s.accrual_seconds(T0), 0);
+        assert_eq!(s.accrual_seconds(T0 + 900), 900);
+        assert_eq!(s.accrual_seconds(T0 - 60), 0);
+        // Catch-up is capped at MAX_CATCHUP_INTERVALS intervals
+        assert_eq!(s.accrual_seconds(T0 + 100 * 3600), 24 * 3600);
+    }
+
+    #[test]
+    fn intervals_crossed_counts_epoch_aligned_boundaries() {
+        // Last update 10 minutes into an hour
+        let s = FundingRateState { last_calculation_time: T0 + 600, ..state() };
+        assert_eq!(s.intervals_crossed(T0 + 3599), 0);
+        assert_eq!(s.intervals_crossed(T0 + 3600), 1);
+        // Less than one interval elapsed, but one boundary crossed
+        assert_eq!(s.intervals_crossed(T0 + 3600 + 60), 1);
+        assert_eq!(s.intervals_crossed(T0 + 3 * 3600 + 1), 3);
+        assert_eq!(s.intervals_crossed(T0 + 100 * 3600), FundingRateState::MAX_CATCHUP_INTERVALS);
+        assert_eq!(s.intervals_crossed(T0), 0);
 
-        // Scale by base rate
-        (imbalance as i64 * base_rate_bps as i64 / 10000) as i32
+        let no_interval = FundingRateState { funding_interval_seconds: 0, ..s };
+        assert_eq!(no_interval.intervals_crossed(T0 + 3600), 0);
     }
 }
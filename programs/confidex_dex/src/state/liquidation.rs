@@ -29,8 +29,9 @@ pub struct LiquidationConfig {
     /// ADL force-closes profitable positions when insurance fund is depleted
     pub adl_enabled: bool,
 
-    /// Insurance fund depletion threshold that triggers ADL (basis points of total)
-    /// e.g., 1000 = ADL triggers when insurance fund drops to 10% of initial
+    /// Insurance fund depletion threshold that triggers ADL (basis points of
+    /// the market's insurance_fund_target)
+    /// e.g., 1000 = ADL triggers when insurance fund drops to 10% of target
     pub adl_trigger_threshold_bps: u16,
 
     /// Total number of liquidations performed
@@ -45,7 +46,8 @@ pub struct LiquidationConfig {
     /// Unix timestamp of last liquidation
     pub last_liquidation_time: i64,
 
-    /// Insurance fund token account
+    /// Global insurance fund account (legacy - each market now holds its own
+    /// fund in PerpetualMarket.insurance_fund)
     pub insurance_fund: Pubkey,
 
     /// PDA bump seed
@@ -87,13 +89,14 @@ impl LiquidationConfig {
         position_value <= self.max_liquidation_per_tx
     }
 
-    /// Check if ADL should be triggered based on insurance fund balance
-    pub fn should_trigger_adl(&self, current_balance: u64, initial_balance: u64) -> bool {
-        if !self.adl_enabled || initial_balance == 0 {
+    /// Check if ADL should be triggered based on insurance fund token balance
+    /// relative to the market's target balance (both in quote token units)
+    pub fn should_trigger_adl(&self, current_balance: u64, target_balance: u64) -> bool {
+        if !self.adl_enabled || target_balance == 0 {
             return false;
         }
 
-        let threshold = (initial_balance as u128 * self.adl_trigger_threshold_bps as u128 / 10000) as u64;
+        let threshold = (target_balance as u128 * self.adl_trigger_threshold_bps as u128 / 10000) as u64;
         current_balance < threshold
     }
 }
@@ -115,3 +118,552 @@ impl AdlPriority {
         (leverage as u64) * (profit_ratio_bps as u64)
     }
 }
+
+/// Per-market auto-deleverage queue
+/// Refreshed by the MPC rank_adl_candidates circuit; execute_adl may only
+/// deleverage the head of the queue on the opposite side of the bankrupt
+/// position, so keepers cannot pick ADL targets themselves.
+///
+/// A refresh is a round of chunks covering every open position of the
+/// market exactly once (new positions are submitted in ascending key order).
+/// Each chunk re-ranks the round's current leaders together with new
+/// positions, so after the last chunk the round queues hold the most
+/// profitable positions of the whole market. Only a complete round replaces
+/// the published queues.
+///
+/// Refreshes are permissionless, so a round in progress can't be restarted
+/// and a chunk in flight can't be superseded until it has stalled for
+/// CHUNK_TIMEOUT_SECS.
+#[account]
+pub struct AdlRanking {
+    /// Market this ranking belongs to
+    pub market: Pubkey,
+
+    /// MPC request of the chunk in flight (zero when idle)
+    pub pending_request: [u8; 32],
+
+    /// Positions submitted with the pending chunk (round leaders, then new positions)
+    pub candidates: [Pubkey; 10],
+
+    /// Side of each candidate (true = long)
+    pub candidate_is_long: [bool; 10],
+
+    /// Number of candidates in the pending chunk
+    pub candidate_count: u8,
+
+    /// Number of new (not carried) positions in the pending chunk
+    pub pending_new_count: u8,
+
+    /// Highest new position key in the pending chunk
+    pub pending_last_key: Pubkey,
+
+    /// Ranked long positions (most profitable first)
+    pub long_queue: [Pubkey; AdlRanking::QUEUE_DEPTH],
+
+    /// Number of ranked long positions
+    pub long_count: u8,
+
+    /// Index of the next long position to deleverage
+    pub long_head: u8,
+
+    /// Ranked short positions (most profitable first)
+    pub short_queue: [Pubkey; AdlRanking::QUEUE_DEPTH],
+
+    /// Number of ranked short positions
+    pub short_count: u8,
+
+    /// Index of the next short position to deleverage
+    pub short_head: u8,
+
+    /// Mark price the current queues were ranked at
+    pub mark_price: u64,
+
+    /// Completion of the round that produced the current queues (0 = never)
+    pub updated_at: i64,
+
+    /// Long leaders of the round in progress
+    pub round_long: [Pubkey; AdlRanking::QUEUE_DEPTH],
+
+    /// Number of long leaders of the round in progress
+    pub round_long_count: u8,
+
+    /// Short leaders of the round in progress
+    pub round_short: [Pubkey; AdlRanking::QUEUE_DEPTH],
+
+    /// Number of short leaders of the round in progress
+    pub round_short_count: u8,
+
+    /// Market open_position_count when the round started
+    pub round_open_count: u64,
+
+    /// Market position_count when the round started (any new position restarts the round)
+    pub round_position_seed: u64,
+
+    /// Positions ranked so far in the round
+    pub round_covered: u64,
+
+    /// Highest position key ranked so far in the round
+    pub round_last_key: Pubkey,
+
+    /// Mark price every chunk of the round is ranked at
+    pub round_mark_price: u64,
+
+    /// Unix timestamp the round started (0 = no round)
+    pub round_started_at: i64,
+
+    /// Unix timestamp the round last started or applied a chunk
+    pub round_progress_at: i64,
+
+    /// Unix timestamp the chunk in flight was queued
+    pub pending_queued_at: i64,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl AdlRanking {
+    pub const SIZE: usize = 8 +   // discriminator
+        32 +  // market
+        32 +  // pending_request
+        320 + // candidates (32 * 10)
+        10 +  // candidate_is_long
+        1 +   // candidate_count
+        1 +   // pending_new_count
+        32 +  // pending_last_key
+        32 * Self::QUEUE_DEPTH + // long_queue
+        1 +   // long_count
+        1 +   // long_head
+        32 * Self::QUEUE_DEPTH + // short_queue
+        1 +   // short_count
+        1 +   // short_head
+        8 +   // mark_price
+        8 +   // updated_at
+        32 * Self::QUEUE_DEPTH + // round_long
+        1 +   // round_long_count
+        32 * Self::QUEUE_DEPTH + // round_short
+        1 +   // round_short_count
+        8 +   // round_open_count
+        8 +   // round_position_seed
+        8 +   // round_covered
+        32 +  // round_last_key
+        8 +   // round_mark_price
+        8 +   // round_started_at
+        8 +   // round_progress_at
+        8 +   // pending_queued_at
+        1;    // bump
+    // Total: 931 bytes
+
+    pub const SEED: &'static [u8] = b"adl_ranking";
+
+    /// Positions per rank_adl_candidates computation (fixed by the circuit)
+    pub const MAX_POSITIONS: usize = 10;
+
+    /// Leaders kept per side; they are re-ranked with every chunk, so a chunk
+    /// has room for MAX_POSITIONS - 2 * QUEUE_DEPTH new positions
+    pub const QUEUE_DEPTH: usize = 3;
+
+    /// New positions per chunk (the rest of the chunk carries the leaders)
+    pub const NEW_PER_CHUNK: usize = Self::MAX_POSITIONS - 2 * Self::QUEUE_DEPTH;
+
+    /// Rankings completed longer ago than this can't be used for ADL (prices move)
+    pub const MAX_AGE_SECS: i64 = 600;
+
+    /// Time allowed per chunk when bounding how long a round may run
+    pub const SECS_PER_CHUNK: i64 = 30;
+
+    /// How long a round or chunk may stall before anyone can replace it
+    pub const CHUNK_TIMEOUT_SECS: i64 = 300;
+
+    /// Next position to deleverage on the given side, if any
+    pub fn head(&self, is_long: bool) -> Option<Pubkey> {
+        let (queue, count, head) = if is_long {
+            (&self.long_queue, self.long_count, self.long_head)
+        } else {
+            (&self.short_queue, self.short_count, self.short_head)
+        };
+        if head < count {
+            Some(queue[head as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Move past the head of the given side's queue
+    pub fn advance(&mut self, is_long: bool) {
+        if is_long {
+            self.long_head = self.long_head.saturating_add(1).min(self.long_count);
+        } else {
+            self.short_head = self.short_head.saturating_add(1).min(self.short_count);
+        }
+    }
+
+    /// Whether the queues were ranked recently enough to be used for ADL
+    pub fn is_fresh(&self, now: i64) -> bool {
+        self.updated_at > 0 && now.saturating_sub(self.updated_at) <= Self::MAX_AGE_SECS
+    }
+
+    /// Longest a round covering `open_count` positions may run: every chunk
+    /// is ranked at the mark price from the round start, so larger markets
+    /// get proportionally longer, but never unbounded
+    pub fn round_time_budget(open_count: u64) -> i64 {
+        let chunks = open_count.div_ceil(Self::NEW_PER_CHUNK as u64);
+        Self::MAX_AGE_SECS
+            .saturating_add((chunks as i64).saturating_mul(Self::SECS_PER_CHUNK))
+    }
+
+    /// Whether a round has started and still has positions to cover
+    pub fn round_in_progress(&self) -> bool {
+        self.round_started_at > 0 && self.round_covered < self.round_open_count
+    }
+
+    /// Whether a new chunk may be queued: none is in flight, or the one in
+    /// flight has gone unanswered for CHUNK_TIMEOUT_SECS
+    pub fn can_queue_chunk(&self, now: i64) -> bool {
+        self.pending_request == [0u8; 32]
+            || now.saturating_sub(self.pending_queued_at) >= Self::CHUNK_TIMEOUT_SECS
+    }
+
+    /// Whether a new round may replace the current one: no round is in
+    /// progress, a position opened since it started (it can no longer
+    /// complete), or it has stalled for CHUNK_TIMEOUT_SECS
+    pub fn can_start_round(&self, position_seed: u64, now: i64) -> bool {
+        !self.round_in_progress()
+            || self.round_position_seed != position_seed
+            || (self.can_queue_chunk(now)
+                && now.saturating_sub(self.round_progress_at) >= Self::CHUNK_TIMEOUT_SECS)
+    }
+
+    /// Start a round that must cover `open_count` positions at `mark_price`
+    pub fn start_round(&mut self, open_count: u64, position_seed: u64, mark_price: u64, now: i64) {
+        self.round_long = [Pubkey::default(); Self::QUEUE_DEPTH];
+        self.round_long_count = 0;
+        self.round_short = [Pubkey::default(); Self::QUEUE_DEPTH];
+        self.round_short_count = 0;
+        self.round_open_count = open_count;
+        self.round_position_seed = position_seed;
+        self.round_covered = 0;
+        self.round_last_key = Pubkey::default();
+        self.round_mark_price = mark_price;
+        self.round_started_at = now;
+        self.round_progress_at = now;
+    }
+
+    /// Leaders of the round in progress (longs first), re-ranked with the next chunk
+    pub fn round_leaders(&self) -> Vec<(Pubkey, bool)> {
+        self.round_long[..self.round_long_count as usize]
+            .iter()
+            .map(|key| (*key, true))
+            .chain(
+                self.round_short[..self.round_short_count as usize]
+                    .iter()
+                    .map(|key| (*key, false)),
+            )
+            .collect()
+    }
+
+    /// Record the candidates of a queued chunk
+    pub fn set_pending(
+        &mut self,
+        request_id: [u8; 32],
+        candidates: &[(Pubkey, bool)],
+        new_count: u8,
+        last_key: Pubkey,
+        now: i64,
+    ) {
+        self.pending_request = request_id;
+        self.pending_queued_at = now;
+        self.candidates = [Pubkey::default(); Self::MAX_POSITIONS];
+        self.candidate_is_long = [false; Self::MAX_POSITIONS];
+        for (i, (key, is_long)) in candidates.iter().enumerate() {
+            self.candidates[i] = *key;
+            self.candidate_is_long[i] = *is_long;
+        }
+        self.candidate_count = candidates.len() as u8;
+        self.pending_new_count = new_count;
+        self.pending_last_key = last_key;
+    }
+
+    /// Apply the MPC ranks of the pending chunk to the round
+    /// ranks[i] is the rank of candidates[i] (1 = first, 0 = not eligible).
+    /// A round that overran its time budget is dropped instead of published.
+    pub fn apply_ranks(&mut self, ranks: &[u8; 10], now: i64) -> AdlRoundProgress {
+        self.round_long = [Pubkey::default(); Self::QUEUE_DEPTH];
+        self.round_short = [Pubkey::default(); Self::QUEUE_DEPTH];
+        self.round_long_count = 0;
+        self.round_short_count = 0;
+
+        for rank in 1..=Self::MAX_POSITIONS as u8 {
+            for i in 0..self.candidate_count as usize {
+                if ranks[i] != rank {
+                    continue;
+                }
+                if self.candidate_is_long[i] {
+                    if (self.round_long_count as usize) < Self::QUEUE_DEPTH {
+                        self.round_long[self.round_long_count as usize] = self.candidates[i];
+                        self.round_long_count += 1;
+                    }
+                } else if (self.round_short_count as usize) < Self::QUEUE_DEPTH {
+                    self.round_short[self.round_short_count as usize] = self.candidates[i];
+                    self.round_short_count += 1;
+                }
+            }
+        }
+
+        self.round_covered = self.round_covered.saturating_add(self.pending_new_count as u64);
+        self.round_last_key = self.pending_last_key;
+        self.round_progress_at = now;
+        self.pending_request = [0u8; 32];
+        self.pending_queued_at = 0;
+        self.pending_new_count = 0;
+
+        if self.round_covered < self.round_open_count {
+            return AdlRoundProgress::InProgress;
+        }
+
+        let elapsed = now.saturating_sub(self.round_started_at);
+        if elapsed > Self::round_time_budget(self.round_open_count) {
+            self.round_started_at = 0;
+            return AdlRoundProgress::Expired;
+        }
+
+        self.long_queue = self.round_long;
+        self.long_count = self.round_long_count;
+        self.long_head = 0;
+        self.short_queue = self.round_short;
+        self.short_count = self.round_short_count;
+        self.short_head = 0;
+        self.mark_price = self.round_mark_price;
+        self.updated_at = now;
+        AdlRoundProgress::Published
+    }
+}
+
+/// Outcome of applying a ranking chunk to the round in progress
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AdlRoundProgress {
+    /// The round still has positions to cover
+    InProgress,
+    /// The round covered every open position and replaced the queues
+    Published,
+    /// The round completed after its time budget and was dropped
+    Expired,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_704_104_400;
+
+    fn ranking() -> AdlRanking {
+        AdlRanking {
+            market: Pubkey::new_unique(),
+            pending_request: [0u8; 32],
+            candidates: [Pubkey::default(); 10],
+            candidate_is_long: [false; 10],
+            candidate_count: 0,
+            pending_new_count: 0,
+            pending_last_key: Pubkey::default(),
+            long_queue: [Pubkey::default(); AdlRanking::QUEUE_DEPTH],
+            long_count: 0,
+            long_head: 0,
+            short_queue: [Pubkey::default(); AdlRanking::QUEUE_DEPTH],
+            short_count: 0,
+            short_head: 0,
+            mark_price: 0,
+            updated_at: 0,
+            round_long: [Pubkey::default(); AdlRanking::QUEUE_DEPTH],
+            round_long_count: 0,
+            round_short: [Pubkey::default(); AdlRanking::QUEUE_DEPTH],
+            round_short_count: 0,
+            round_open_count: 0,
+            round_position_seed: 0,
+            round_covered: 0,
+            round_last_key: Pubkey::default(),
+            round_mark_price: 0,
+            round_started_at: 0,
+            round_progress_at: 0,
+            pending_queued_at: 0,
+            bump: 255,
+        }
+    }
+
+    fn ranks(values: &[u8]) -> [u8; 10] {
+        let mut ranks = [0u8; 10];
+        ranks[..values.len()].copy_from_slice(values);
+        ranks
+    }
+
+    #[test]
+    fn single_chunk_round_publishes_queues_in_rank_order() {
+        let mut adl = ranking();
+        let keys: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        adl.start_round(4, 4, 150_000_000, NOW);
+        adl.set_pending(
+            [1u8; 32],
+            &[(keys[0], true), (keys[1], false), (keys[2], true), (keys[3], true)],
+            4,
+            keys[3],
+            NOW,
+        );
+
+        // keys[3] is losing (rank 0) and never enters the queue
+        assert_eq!(adl.apply_ranks(&ranks(&[2, 3, 1, 0]), NOW), AdlRoundProgress::Published);
+
+        assert_eq!(adl.long_count, 2);
+        assert_eq!(adl.long_queue[..2], [keys[2], keys[0]]);
+        assert_eq!(adl.short_count, 1);
+        assert_eq!(adl.short_queue[0], keys[1]);
+        assert_eq!(adl.mark_price, 150_000_000);
+        assert_eq!(adl.updated_at, NOW);
+        assert_eq!(adl.pending_request, [0u8; 32]);
+        assert!(!adl.round_in_progress());
+    }
+
+    #[test]
+    fn queue_keeps_only_queue_depth_per_side() {
+        let mut adl = ranking();
+        let keys: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let candidates: Vec<(Pubkey, bool)> = keys.iter().map(|key| (*key, true)).collect();
+        adl.start_round(5, 5, 1, NOW);
+        adl.set_pending([1u8; 32], &candidates, 5, keys[4], NOW);
+
+        assert_eq!(adl.apply_ranks(&ranks(&[5, 4, 3, 2, 1]), NOW), AdlRoundProgress::Published);
+        assert_eq!(adl.long_count as usize, AdlRanking::QUEUE_DEPTH);
+        assert_eq!(adl.long_queue, [keys[4], keys[3], keys[2]]);
+    }
+
+    #[test]
+    fn round_publishes_only_after_every_open_position_is_covered() {
+        let mut adl = ranking();
+        let previous = Pubkey::new_unique();
+        adl.long_queue[0] = previous;
+        adl.long_count = 1;
+        adl.updated_at = NOW - 60;
+
+        let first: Vec<Pubkey> = (0..2).map(|_| Pubkey::new_unique()).collect();
+        let second = Pubkey::new_unique();
+        adl.start_round(3, 7, 1, NOW);
+
+        adl.set_pending([1u8; 32], &[(first[0], true), (first[1], true)], 2, first[1], NOW);
+        assert_eq!(adl.apply_ranks(&ranks(&[2, 1]), NOW), AdlRoundProgress::InProgress);
+        assert!(adl.round_in_progress());
+        assert_eq!(adl.round_covered, 2);
+        assert_eq!(adl.round_last_key, first[1]);
+        // The published queue is untouched until the round completes
+        assert_eq!(adl.head(true), Some(previous));
+        assert_eq!(adl.updated_at, NOW - 60);
+
+        // Leaders are carried into the next chunk ahead of the new position
+        let mut candidates = adl.round_leaders();
+        assert_eq!(candidates, vec![(first[1], true), (first[0], true)]);
+        candidates.push((second, true));
+        adl.set_pending([2u8; 32], &candidates, 1, second, NOW + 10);
+
+        assert_eq!(adl.apply_ranks(&ranks(&[2, 0, 1]), NOW + 30), AdlRoundProgress::Published);
+        assert_eq!(adl.long_count, 2);
+        assert_eq!(adl.long_queue[..2], [second, first[1]]);
+        assert_eq!(adl.updated_at, NOW + 30);
+    }
+
+    #[test]
+    fn head_and_advance_walk_each_side_independently() {
+        let mut adl = ranking();
+        let longs = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let short = Pubkey::new_unique();
+        adl.long_queue[..2].copy_from_slice(&longs);
+        adl.long_count = 2;
+        adl.short_queue[0] = short;
+        adl.short_count = 1;
+
+        assert_eq!(adl.head(true), Some(longs[0]));
+        adl.advance(true);
+        assert_eq!(adl.head(true), Some(longs[1]));
+        assert_eq!(adl.head(false), Some(short));
+
+        adl.advance(true);
+        adl.advance(true);
+        assert_eq!(adl.head(true), None);
+        assert_eq!(adl.long_head, 2);
+
+        adl.advance(false);
+        assert_eq!(adl.head(false), None);
+    }
+
+    #[test]
+    fn is_fresh_requires_a_completed_round_within_max_age() {
+        let mut adl = ranking();
+        assert!(!adl.is_fresh(NOW));
+
+        adl.updated_at = NOW;
+        assert!(adl.is_fresh(NOW));
+        assert!(adl.is_fresh(NOW + AdlRanking::MAX_AGE_SECS));
+        assert!(!adl.is_fresh(NOW + AdlRanking::MAX_AGE_SECS + 1));
+    }
+
+    #[test]
+    fn in_flight_round_is_protected_until_it_stalls() {
+        let mut adl = ranking();
+        adl.start_round(8, 8, 1, NOW);
+        adl.set_pending([1u8; 32], &[(Pubkey::new_unique(), true)], 1, Pubkey::new_unique(), NOW);
+
+        let timeout = AdlRanking::CHUNK_TIMEOUT_SECS;
+        assert!(!adl.can_queue_chunk(NOW + timeout - 1));
+        assert!(!adl.can_start_round(8, NOW + timeout - 1));
+        assert!(adl.can_queue_chunk(NOW + timeout));
+        assert!(adl.can_start_round(8, NOW + timeout));
+        // A position opened since the round started, so it can't complete
+        assert!(adl.can_start_round(9, NOW + 1));
+
+        // Applying a chunk is progress: the stall timer restarts
+        adl.apply_ranks(&ranks(&[1]), NOW + 100);
+        assert!(adl.can_queue_chunk(NOW + 100));
+        assert!(!adl.can_start_round(8, NOW + 100 + timeout - 1));
+        assert!(adl.can_start_round(8, NOW + 100 + timeout));
+    }
+
+    #[test]
+    fn large_market_round_publishes_and_stays_fresh_from_completion() {
+        let mut adl = ranking();
+        let open_count = 1_000u64;
+        let per_chunk = AdlRanking::NEW_PER_CHUNK;
+        adl.start_round(open_count, open_count, 1, NOW);
+
+        // 250 chunks at 20s each take far longer than MAX_AGE_SECS
+        let mut now = NOW;
+        let mut last_key = Pubkey::default();
+        let mut progress = AdlRoundProgress::InProgress;
+        for chunk in 0..open_count as usize / per_chunk {
+            let mut candidates = adl.round_leaders();
+            let new: Vec<Pubkey> = (0..per_chunk).map(|_| Pubkey::new_unique()).collect();
+            candidates.extend(new.iter().map(|key| (*key, chunk % 2 == 0)));
+            last_key = new[per_chunk - 1];
+            now += 20;
+            adl.set_pending([1u8; 32], &candidates, per_chunk as u8, last_key, now);
+
+            let chunk_ranks: Vec<u8> = (1..=candidates.len() as u8).collect();
+            progress = adl.apply_ranks(&ranks(&chunk_ranks), now);
+        }
+
+        assert!(now - NOW > AdlRanking::MAX_AGE_SECS);
+        assert_eq!(progress, AdlRoundProgress::Published);
+        assert_eq!(adl.round_last_key, last_key);
+        assert_eq!(adl.updated_at, now);
+        assert!(adl.is_fresh(now + AdlRanking::MAX_AGE_SECS));
+        assert!(!adl.is_fresh(now + AdlRanking::MAX_AGE_SECS + 1));
+    }
+
+    #[test]
+    fn round_over_its_time_budget_is_dropped() {
+        let mut adl = ranking();
+        let key = Pubkey::new_unique();
+        adl.start_round(1, 1, 1, NOW);
+        adl.set_pending([1u8; 32], &[(key, true)], 1, key, NOW);
+
+        let late = NOW + AdlRanking::round_time_budget(1) + 1;
+        assert_eq!(adl.apply_ranks(&ranks(&[1]), late), AdlRoundProgress::Expired);
+        assert_eq!(adl.updated_at, 0);
+        assert_eq!(adl.head(true), None);
+        assert!(adl.can_start_round(1, late));
+    }
+}
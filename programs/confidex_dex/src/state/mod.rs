@@ -3,6 +3,9 @@ pub mod order;
 pub mod pair;
 pub mod user_balance;
 pub mod trader_eligibility;
+pub mod blacklist_history;
+pub mod allowlist;
+pub mod solvency;
 
 // Perpetuals state
 pub mod perp_market;
@@ -22,6 +25,9 @@ pub use order::*;
 pub use pair::*;
 pub use user_balance::*;
 pub use trader_eligibility::*;
+pub use blacklist_history::*;
+pub use allowlist::*;
+pub use solvency::*;
 
 // Perpetuals exports
 pub use perp_market::*;
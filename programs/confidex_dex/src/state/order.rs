@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
 use solana_sha256_hasher::hash;
 
+use crate::error::ConfidexError;
+
 /// Order side (buy or sell)
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
 pub enum Side {
@@ -17,15 +19,75 @@ pub enum OrderType {
     Market,
 }
 
+/// Time-in-force policy
+///
+/// Enforced when matching (match_orders / finalize_match), since fills are
+/// computed asynchronously via MPC rather than at placement.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum TimeInForce {
+    /// Rests on the book until filled, cancelled or `expires_at_hour` (GTT)
+    #[default]
+    GoodTillCancel,
+    /// Takes liquidity once; any unfilled remainder expires after the first match attempt
+    ImmediateOrCancel,
+    /// Takes liquidity once and must be fully filled by that match, or not at all
+    FillOrKill,
+    /// May only be matched as the maker (never crosses against an older order)
+    PostOnly,
+}
+
+/// Self-trade prevention mode
+///
+/// Applied when both orders in a match belong to the same maker. The taker's
+/// (newer order's) mode decides. Cancel modes act before MPC is queued:
+/// cancelled orders are expired in place and refunded via expire_order.
+/// Decrement goes through the fill calculation and is resolved at settlement.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum SelfTradePrevention {
+    /// Cancel the newer (taker) order, keep the resting order
+    #[default]
+    CancelNewest,
+    /// Cancel the older (maker) order, keep the incoming order
+    CancelOldest,
+    /// Cancel both orders
+    CancelBoth,
+    /// Reduce both orders by the overlapping size and release its escrow
+    /// back to the maker at settlement (fee-free, no trade)
+    Decrement,
+}
+
+impl SelfTradePrevention {
+    /// Orders a same-maker match cancels under this (taker's) mode, as
+    /// (cancel_buy, cancel_sell); Decrement cancels neither
+    pub fn cancels(self, buy_is_maker: bool) -> (bool, bool) {
+        match self {
+            SelfTradePrevention::CancelNewest => (!buy_is_maker, buy_is_maker),
+            SelfTradePrevention::CancelOldest => (buy_is_maker, !buy_is_maker),
+            SelfTradePrevention::CancelBoth => (true, true),
+            SelfTradePrevention::Decrement => (false, false),
+        }
+    }
+}
+
 /// Order status - simplified for privacy
 /// Internally we track detailed states, but externally we only expose Active/Inactive
+/// (plus a transient pending state while MPC verifies the maker's balance)
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
 pub enum OrderStatus {
     #[default]
     /// Order is active and can be matched (includes open, partially filled, matching)
     Active,
-    /// Order is no longer active (filled or cancelled)
+    /// Order is no longer active (filled, cancelled or rejected)
     Inactive,
+    /// Order is placed but waiting for the MPC balance check callback
+    /// Cannot be matched until activated (funds escrowed) by place_order_callback
+    PendingBalanceCheck,
+    /// Cancellation/expiry queued; waiting for the MPC refund callback
+    /// Cannot be matched; set Inactive by cancel_order_callback
+    PendingCancel,
+    /// Amend (new amount/price) queued; waiting for the MPC escrow check
+    /// Cannot be matched; set Active or Inactive by amend_order_callback
+    PendingAmend,
 }
 
 /// Internal order state for matching logic
@@ -63,7 +125,8 @@ pub struct ConfidentialOrder {
     /// Encrypted limit price (64 bytes via Arcium)
     pub encrypted_price: [u8; 64],
 
-    /// Encrypted filled amount (64 bytes via Arcium)
+    /// Encrypted cumulative filled amount (64 bytes via Arcium)
+    /// Updated by MPC on every match; all zeros until the first fill
     pub encrypted_filled: [u8; 64],
 
     /// Current order status (Active/Inactive only - privacy preserving)
@@ -98,11 +161,49 @@ pub struct ConfidentialOrder {
     /// Required for Full Arcium MXE to decrypt encrypted values via MPC
     /// This is the full 32-byte key (V2 format stores truncated 16-byte version)
     pub ephemeral_pubkey: [u8; 32],
+
+    /// Funds still locked for this order (quote for buys, base for sells)
+    /// Set from the MPC escrow amount on activation, debited by settlement
+    /// and released to the maker on cancel/expiry or once fully filled
+    pub escrow_remaining: u64,
+
+    /// Encrypted fill of the latest match, awaiting settlement (64 bytes)
+    /// encrypted_filled is cumulative, so settlement pays out this value
+    pub encrypted_unsettled_fill: [u8; 64],
+
+    /// Whether `encrypted_unsettled_fill` still has to be settled
+    /// Set when MPC records a fill, cleared once it is settled
+    pub fill_pending_settlement: bool,
+
+    /// Time-in-force policy (GTC/IOC/FOK/PostOnly)
+    pub time_in_force: TimeInForce,
+
+    /// Good-till-time expiry (hour precision for privacy), 0 = no expiry
+    /// Expired orders can no longer be matched and can be expired by anyone
+    pub expires_at_hour: i64,
+
+    /// Self-trade prevention mode (used when this order is the taker)
+    pub self_trade_prevention: SelfTradePrevention,
+
+    /// Unix timestamp when the pending balance check, cancel/expiry refund or
+    /// amend was queued
+    /// The maker can recover the order once PENDING_MPC_TIMEOUT_SECS have passed
+    pub pending_queued_at: i64,
+
+    /// Replacement amount awaiting the MPC amend check (64 bytes)
+    /// Only copied over encrypted_amount once amend_order_callback accepts it
+    pub pending_encrypted_amount: [u8; 64],
+
+    /// Replacement limit price awaiting the MPC amend check (64 bytes)
+    pub pending_encrypted_price: [u8; 64],
+
+    /// Ephemeral X25519 public key of the replacement values (32 bytes)
+    pub pending_ephemeral_pubkey: [u8; 32],
 }
 
 impl ConfidentialOrder {
-    /// V5 account size - no plaintext fields (privacy hardened)
-    /// Reduced from 390 bytes to 366 bytes
+    /// V10 account size - stages amend ciphertexts until the MPC check passes
+    /// Increased from 457 bytes (V9) to 617 bytes
     pub const SIZE: usize = 8 +  // discriminator
         32 + // maker
         32 + // pair
@@ -119,14 +220,40 @@ impl ConfidentialOrder {
         32 + // pending_match_request
         1 +  // is_matching
         1 +  // bump
-        32;  // ephemeral_pubkey (for production MPC)
-    // Total: 366 bytes (8 + 358)
+        32 + // ephemeral_pubkey (for production MPC)
+        8 +  // escrow_remaining (V6)
+        64 + // encrypted_unsettled_fill (V7)
+        1 +  // fill_pending_settlement (V7)
+        1 +  // time_in_force (V8)
+        8 +  // expires_at_hour (V8)
+        1 +  // self_trade_prevention (V8)
+        8 +  // pending_queued_at (V9)
+        64 + // pending_encrypted_amount (V10)
+        64 + // pending_encrypted_price (V10)
+        32;  // pending_ephemeral_pubkey (V10)
+    // Total: 617 bytes (8 + 609)
+
+    /// V5 size (before escrow tracking), the oldest layout migrate_order accepts
+    pub const V5_SIZE: usize = 366;
+
+    /// V6 size (first layout with escrow tracking); smaller orders never locked funds
+    pub const V6_SIZE: usize = 374;
+
+    /// V9 size (last layout that replaced ciphertexts in place on amend)
+    pub const V9_SIZE: usize = 457;
 
     pub const SEED: &'static [u8] = b"order";
 
+    /// How long a pending refund or amend may stay unanswered before the
+    /// maker can recover the order's escrow without MPC
+    pub const PENDING_MPC_TIMEOUT_SECS: i64 = 300;
+
     /// Check if order is active and can participate in matching
+    /// An unsettled fill must be settled first (its escrow is still owed)
     pub fn is_active(&self) -> bool {
-        matches!(self.status, OrderStatus::Active) && !self.is_matching
+        matches!(self.status, OrderStatus::Active)
+            && !self.is_matching
+            && !self.fill_pending_settlement
     }
 
     /// Check if order is currently in MPC matching flow
@@ -134,6 +261,174 @@ impl ConfidentialOrder {
         self.is_matching && matches!(self.status, OrderStatus::Active)
     }
 
+    /// Check if order is waiting for the MPC balance check callback
+    pub fn is_pending_balance_check(&self) -> bool {
+        matches!(self.status, OrderStatus::PendingBalanceCheck)
+    }
+
+    /// Debit a settled fill from the order's escrow
+    ///
+    /// Fails if the fill exceeds what is still locked, so a settlement can
+    /// never pay out more than the maker escrowed at placement.
+    pub fn debit_escrow(&mut self, amount: u64) -> Result<()> {
+        self.escrow_remaining = self
+            .escrow_remaining
+            .checked_sub(amount)
+            .ok_or(ConfidexError::InsufficientBalance)?;
+        Ok(())
+    }
+
+    /// Release all remaining escrow, returning the amount to credit the maker
+    pub fn release_escrow(&mut self) -> u64 {
+        std::mem::take(&mut self.escrow_remaining)
+    }
+
+    /// Record the encrypted fill of a match for settlement
+    pub fn record_unsettled_fill(&mut self, encrypted_fill: [u8; 64]) {
+        self.encrypted_unsettled_fill = encrypted_fill;
+        self.fill_pending_settlement = true;
+    }
+
+    /// Check if both orders carry the same unsettled fill (i.e. one match)
+    pub fn shares_unsettled_fill(&self, other: &ConfidentialOrder) -> bool {
+        self.fill_pending_settlement
+            && other.fill_pending_settlement
+            && self.encrypted_unsettled_fill == other.encrypted_unsettled_fill
+    }
+
+    /// Mark the unsettled fill as settled, returning its ciphertext
+    pub fn take_unsettled_fill(&mut self) -> [u8; 64] {
+        self.fill_pending_settlement = false;
+        std::mem::replace(&mut self.encrypted_unsettled_fill, [0u8; 64])
+    }
+
+    /// Check if a cancellation/expiry refund is waiting for the MPC callback
+    pub fn is_pending_cancel(&self) -> bool {
+        matches!(self.status, OrderStatus::PendingCancel)
+    }
+
+    /// Whether the maker may recover the order without MPC: a balance check,
+    /// cancel/expiry refund or amend check has gone unanswered for
+    /// PENDING_MPC_TIMEOUT_SECS
+    pub fn can_recover_pending(&self, now: i64) -> bool {
+        (self.is_pending_balance_check() || self.is_pending_cancel() || self.is_pending_amend())
+            && now.saturating_sub(self.pending_queued_at) >= Self::PENDING_MPC_TIMEOUT_SECS
+    }
+
+    /// Take the MPC cancel refund out of the escrow, returning the amount
+    /// to credit the maker
+    ///
+    /// Fails if the refund exceeds what is still locked. Escrow left after
+    /// the refund is price improvement from fills below the limit price and
+    /// is released with it, as settlement does for a fully filled order.
+    pub fn take_cancel_refund(&mut self, refund_amount: u64) -> Result<u64> {
+        self.debit_escrow(refund_amount)?;
+        refund_amount
+            .checked_add(self.release_escrow())
+            .ok_or(ConfidexError::ArithmeticOverflow.into())
+    }
+
+    /// Check if an amend is waiting for the MPC escrow check callback
+    pub fn is_pending_amend(&self) -> bool {
+        matches!(self.status, OrderStatus::PendingAmend)
+    }
+
+    /// Hold replacement ciphertexts until the MPC amend check passes
+    pub fn stage_amend(
+        &mut self,
+        encrypted_amount: [u8; 64],
+        encrypted_price: [u8; 64],
+        ephemeral_pubkey: [u8; 32],
+    ) {
+        self.pending_encrypted_amount = encrypted_amount;
+        self.pending_encrypted_price = encrypted_price;
+        self.pending_ephemeral_pubkey = ephemeral_pubkey;
+    }
+
+    /// Replace the live ciphertexts with the staged amend
+    pub fn apply_staged_amend(&mut self) {
+        self.encrypted_amount = std::mem::replace(&mut self.pending_encrypted_amount, [0u8; 64]);
+        self.encrypted_price = std::mem::replace(&mut self.pending_encrypted_price, [0u8; 64]);
+        self.ephemeral_pubkey = std::mem::take(&mut self.pending_ephemeral_pubkey);
+    }
+
+    /// Drop the staged amend, leaving the live ciphertexts untouched
+    pub fn discard_staged_amend(&mut self) {
+        self.stage_amend([0u8; 64], [0u8; 64], [0u8; 32]);
+    }
+
+    /// Abandon a timed-out amend check: drop the staged values and put the
+    /// order back on the book with its pre-amend amount, price and escrow
+    pub fn revert_pending_amend(&mut self) {
+        self.discard_staged_amend();
+        self.status = OrderStatus::Active;
+        self.pending_match_request = [0u8; 32];
+        self.pending_queued_at = 0;
+    }
+
+    /// Check if the order's good-till-time has passed
+    pub fn is_expired(&self, unix_timestamp: i64) -> bool {
+        self.expires_at_hour != 0 && unix_timestamp >= self.expires_at_hour
+    }
+
+    /// Check if the order may take part in a match at `unix_timestamp`
+    /// as the maker (`is_maker`) or the taker
+    pub fn can_match_at(&self, unix_timestamp: i64, is_maker: bool) -> bool {
+        if self.is_expired(unix_timestamp) {
+            return false;
+        }
+        match self.time_in_force {
+            TimeInForce::PostOnly => is_maker,
+            // IOC/FOK only ever take liquidity, they never rest on the book
+            TimeInForce::ImmediateOrCancel | TimeInForce::FillOrKill => !is_maker,
+            TimeInForce::GoodTillCancel => true,
+        }
+    }
+
+    /// Check if the order must be fully filled by a single match
+    pub fn is_fill_or_kill(&self) -> bool {
+        matches!(self.time_in_force, TimeInForce::FillOrKill)
+    }
+
+    /// Check if any unfilled remainder expires after one match attempt
+    pub fn is_immediate(&self) -> bool {
+        matches!(
+            self.time_in_force,
+            TimeInForce::ImmediateOrCancel | TimeInForce::FillOrKill
+        )
+    }
+
+    /// IOC/FOK orders get a single match attempt: expire any unfilled
+    /// remainder so it can't be matched again and can be refunded
+    pub fn expire_if_immediate(&mut self, unix_timestamp: i64) {
+        if self.is_immediate() {
+            self.mark_expired(unix_timestamp);
+        }
+    }
+
+    /// Expire the order now: it can no longer be matched and expire_order
+    /// can refund its unfilled escrow
+    pub fn mark_expired(&mut self, unix_timestamp: i64) {
+        self.expires_at_hour = Self::coarse_timestamp(unix_timestamp);
+    }
+
+    /// Check if MPC has recorded any fill for this order
+    /// (encrypted_filled is all zeros until the first match)
+    pub fn has_fills(&self) -> bool {
+        self.encrypted_filled != [0u8; 64]
+    }
+
+    /// Global placement sequence (exchange.order_count when the order was placed)
+    pub fn sequence(&self) -> u64 {
+        u64::from_le_bytes(self.order_nonce)
+    }
+
+    /// Check if this order rested on the book before `other` (i.e. is the maker)
+    /// The order placed later is the one that crossed the spread (taker)
+    pub fn is_maker_against(&self, other: &ConfidentialOrder) -> bool {
+        self.sequence() < other.sequence()
+    }
+
     pub fn has_pending_match(&self) -> bool {
         self.pending_match_request != [0u8; 32]
     }
@@ -167,3 +462,165 @@ impl ConfidentialOrder {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 1_704_103_200;
+
+    fn order(time_in_force: TimeInForce, expires_at_hour: i64) -> ConfidentialOrder {
+        ConfidentialOrder {
+            maker: Pubkey::new_unique(),
+            pair: Pubkey::new_unique(),
+            side: Side::Buy,
+            order_type: OrderType::Limit,
+            encrypted_amount: [0u8; 64],
+            encrypted_price: [0u8; 64],
+            encrypted_filled: [0u8; 64],
+            status: OrderStatus::Active,
+            created_at_hour: HOUR,
+            order_id: [0u8; 16],
+            order_nonce: [0u8; 8],
+            eligibility_proof_verified: true,
+            pending_match_request: [0u8; 32],
+            is_matching: false,
+            bump: 255,
+            ephemeral_pubkey: [0u8; 32],
+            escrow_remaining: 0,
+            encrypted_unsettled_fill: [0u8; 64],
+            fill_pending_settlement: false,
+            time_in_force,
+            expires_at_hour,
+            self_trade_prevention: SelfTradePrevention::CancelNewest,
+            pending_queued_at: 0,
+            pending_encrypted_amount: [0u8; 64],
+            pending_encrypted_price: [0u8; 64],
+            pending_ephemeral_pubkey: [0u8; 32],
+        }
+    }
+
+    #[test]
+    fn can_match_at_respects_time_in_force() {
+        let gtc = order(TimeInForce::GoodTillCancel, 0);
+        assert!(gtc.can_match_at(HOUR, true));
+        assert!(gtc.can_match_at(HOUR, false));
+
+        let post_only = order(TimeInForce::PostOnly, 0);
+        assert!(post_only.can_match_at(HOUR, true));
+        assert!(!post_only.can_match_at(HOUR, false));
+
+        for tif in [TimeInForce::ImmediateOrCancel, TimeInForce::FillOrKill] {
+            let immediate = order(tif, HOUR + 3600);
+            assert!(!immediate.can_match_at(HOUR, true));
+            assert!(immediate.can_match_at(HOUR, false));
+        }
+    }
+
+    #[test]
+    fn can_match_at_stops_at_expiry() {
+        let gtt = order(TimeInForce::GoodTillCancel, HOUR + 3600);
+        assert!(gtt.can_match_at(HOUR + 3599, false));
+        assert!(!gtt.can_match_at(HOUR + 3600, false));
+        assert!(!gtt.can_match_at(HOUR + 3600, true));
+    }
+
+    #[test]
+    fn mark_expired_stops_matching_immediately() {
+        let mut ioc = order(TimeInForce::ImmediateOrCancel, HOUR + 3600);
+        ioc.expire_if_immediate(HOUR + 10);
+        assert!(ioc.is_expired(HOUR + 10));
+        assert!(!ioc.can_match_at(HOUR + 10, false));
+
+        let mut gtc = order(TimeInForce::GoodTillCancel, 0);
+        gtc.expire_if_immediate(HOUR + 10);
+        assert!(gtc.can_match_at(HOUR + 10, false));
+    }
+
+    #[test]
+    fn self_trade_cancels_per_taker_mode() {
+        // Resting buy is the maker, crossing sell is the taker
+        assert_eq!(SelfTradePrevention::CancelNewest.cancels(true), (false, true));
+        assert_eq!(SelfTradePrevention::CancelOldest.cancels(true), (true, false));
+        // Resting sell is the maker, crossing buy is the taker
+        assert_eq!(SelfTradePrevention::CancelNewest.cancels(false), (true, false));
+        assert_eq!(SelfTradePrevention::CancelOldest.cancels(false), (false, true));
+
+        assert_eq!(SelfTradePrevention::CancelBoth.cancels(true), (true, true));
+        assert_eq!(SelfTradePrevention::Decrement.cancels(true), (false, false));
+    }
+
+    #[test]
+    fn pending_cancel_recoverable_after_timeout() {
+        let mut pending = order(TimeInForce::GoodTillCancel, 0);
+        assert!(!pending.can_recover_pending(HOUR + 3600));
+
+        pending.status = OrderStatus::PendingCancel;
+        pending.pending_queued_at = HOUR;
+        let timeout = ConfidentialOrder::PENDING_MPC_TIMEOUT_SECS;
+        assert!(!pending.can_recover_pending(HOUR + timeout - 1));
+        assert!(pending.can_recover_pending(HOUR + timeout));
+
+        pending.status = OrderStatus::PendingAmend;
+        assert!(pending.can_recover_pending(HOUR + timeout));
+
+        pending.status = OrderStatus::PendingBalanceCheck;
+        assert!(!pending.can_recover_pending(HOUR + timeout - 1));
+        assert!(pending.can_recover_pending(HOUR + timeout));
+    }
+
+    #[test]
+    fn reverted_amend_keeps_pre_amend_order() {
+        let mut amended = order(TimeInForce::GoodTillCancel, 0);
+        amended.escrow_remaining = 1_000;
+        let live_amount = amended.encrypted_amount;
+        let live_price = amended.encrypted_price;
+        amended.stage_amend([9u8; 64], [9u8; 64], [9u8; 32]);
+        amended.status = OrderStatus::PendingAmend;
+        amended.pending_match_request = [7u8; 32];
+        amended.pending_queued_at = HOUR;
+
+        amended.revert_pending_amend();
+
+        assert!(amended.is_active());
+        assert_eq!(amended.encrypted_amount, live_amount);
+        assert_eq!(amended.encrypted_price, live_price);
+        assert_eq!(amended.pending_encrypted_amount, [0u8; 64]);
+        assert_eq!(amended.escrow_remaining, 1_000);
+        assert_eq!(amended.pending_match_request, [0u8; 32]);
+        assert_eq!(amended.pending_queued_at, 0);
+    }
+
+    #[test]
+    fn cancel_refund_is_bounded_by_escrow() {
+        let mut cancelled = order(TimeInForce::GoodTillCancel, 0);
+        cancelled.escrow_remaining = 1_000;
+        assert!(cancelled.take_cancel_refund(1_001).is_err());
+        assert_eq!(cancelled.escrow_remaining, 1_000);
+
+        // Price improvement left over after the refund goes back too
+        assert_eq!(cancelled.take_cancel_refund(900).unwrap(), 1_000);
+        assert_eq!(cancelled.escrow_remaining, 0);
+    }
+
+    #[test]
+    fn staged_amend_replaces_ciphertexts_only_when_applied() {
+        let mut amended = order(TimeInForce::GoodTillCancel, 0);
+        amended.encrypted_amount = [1u8; 64];
+        amended.encrypted_price = [2u8; 64];
+
+        amended.stage_amend([3u8; 64], [4u8; 64], [5u8; 32]);
+        let mut rejected = amended.clone();
+
+        rejected.discard_staged_amend();
+        assert_eq!(rejected.encrypted_amount, [1u8; 64]);
+        assert_eq!(rejected.encrypted_price, [2u8; 64]);
+        assert_eq!(rejected.pending_encrypted_amount, [0u8; 64]);
+
+        amended.apply_staged_amend();
+        assert_eq!(amended.encrypted_amount, [3u8; 64]);
+        assert_eq!(amended.encrypted_price, [4u8; 64]);
+        assert_eq!(amended.ephemeral_pubkey, [5u8; 32]);
+        assert_eq!(amended.pending_encrypted_price, [0u8; 64]);
+    }
+}
@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
 
+use super::AllowlistConfig;
+
 /// Trading pair configuration account
-/// Size: 8 (discriminator) + 226 = 234 bytes
+/// Size: 8 (discriminator) + 322 = 330 bytes (V2)
 #[account]
 #[derive(Default)]
 pub struct TradingPair {
@@ -40,6 +42,11 @@ pub struct TradingPair {
 
     /// PDA bump seed
     pub bump: u8,
+
+    // === V2 fields (appended for migration compatibility) ===
+
+    /// Optional allowlist restricting the pair to credentialed traders
+    pub allowlist: AllowlistConfig,
 }
 
 impl TradingPair {
@@ -55,8 +62,12 @@ impl TradingPair {
         1 +  // active
         8 +  // open_order_count
         8 +  // index
-        1;   // bump
-    // Total: 234 bytes
+        1 +  // bump
+        AllowlistConfig::SIZE; // allowlist (V2)
+    // Total: 330 bytes
+
+    /// V1 size (before allowlist) for migration
+    pub const V1_SIZE: usize = 234;
 
     pub const SEED: &'static [u8] = b"pair";
 }
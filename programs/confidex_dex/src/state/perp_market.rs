@@ -1,7 +1,26 @@
 use anchor_lang::prelude::*;
 
+use super::AllowlistConfig;
+
+/// Oracle account format used for a market price feed
+///
+/// The feed identifier stored on the market depends on the source:
+/// - PythLegacy / SwitchboardOnDemand: address of the feed account
+/// - PythPull: 32-byte Pyth feed ID inside the PriceUpdateV2 account
+///   (update accounts are posted per transaction, so the address varies)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum OracleSource {
+    /// Legacy pyth_sdk_solana price account (being deprecated by Pyth)
+    #[default]
+    PythLegacy,
+    /// Pyth push/pull oracle PriceUpdateV2 account (pyth-solana-receiver)
+    PythPull,
+    /// Switchboard On-Demand pull feed account
+    SwitchboardOnDemand,
+}
+
 /// Perpetual market configuration account
-/// Size: 8 (discriminator) + 342 = 350 bytes
+/// Size: 8 (discriminator) + 531 = 539 bytes (V6)
 #[account]
 #[derive(Default)]
 pub struct PerpetualMarket {
@@ -46,7 +65,7 @@ pub struct PerpetualMarket {
     /// Total short open interest (PUBLIC for funding calculation)
     pub total_short_open_interest: u64,
 
-    /// Number of open positions in this market
+    /// Number of positions ever opened in this market (position PDA seed)
     pub position_count: u64,
 
     /// Sequential market identifier
@@ -61,7 +80,8 @@ pub struct PerpetualMarket {
     /// Cumulative funding for short positions (scaled by 1e18)
     pub cumulative_funding_short: i128,
 
-    /// Pyth oracle price feed account
+    /// Primary oracle feed (any asset; validated on every price read)
+    /// Account address or Pyth feed ID depending on `oracle_source`
     pub oracle_price_feed: Pubkey,
 
     /// Confidential collateral vault (C-SPL USDC)
@@ -88,6 +108,44 @@ pub struct PerpetualMarket {
 
     /// PDA bump seed
     pub bump: u8,
+
+    // === V2 fields (appended for migration compatibility) ===
+
+    /// Fixed-point precision of prices in this market (oracle prices are
+    /// rescaled to this many decimals; 6 for USDC-quoted markets)
+    pub price_decimals: u8,
+
+    // === V3 fields ===
+
+    /// Format of the primary oracle feed
+    pub oracle_source: OracleSource,
+
+    /// Format of the secondary (fallback) oracle feed
+    pub secondary_oracle_source: OracleSource,
+
+    /// Secondary oracle feed, used when the primary is stale or its confidence
+    /// interval is too wide (Pubkey::default() = no secondary)
+    pub secondary_oracle_feed: Pubkey,
+
+    /// Maximum allowed divergence between primary and secondary prices (bps)
+    pub max_oracle_divergence_bps: u16,
+
+    // === V4 fields ===
+
+    /// Last observed token balance of the insurance fund (quote token units)
+    /// Refreshed on deposit/withdraw, liquidation and ADL
+    pub insurance_fund_balance: u64,
+
+    // === V5 fields ===
+
+    /// Number of positions not yet closed, liquidated or auto-deleveraged
+    /// (an ADL ranking round must cover exactly this many positions)
+    pub open_position_count: u64,
+
+    // === V6 fields ===
+
+    /// Optional allowlist restricting the market to credentialed traders
+    pub allowlist: AllowlistConfig,
 }
 
 impl PerpetualMarket {
@@ -118,11 +176,53 @@ impl PerpetualMarket {
         32 +  // c_quote_mint
         32 +  // arcium_cluster
         1 +   // active
-        1;    // bump
-    // Total: 390 bytes
+        1 +   // bump
+        1 +   // price_decimals (V2)
+        1 +   // oracle_source (V3)
+        1 +   // secondary_oracle_source (V3)
+        32 +  // secondary_oracle_feed (V3)
+        2 +   // max_oracle_divergence_bps (V3)
+        8 +   // insurance_fund_balance (V4)
+        8 +   // open_position_count (V5)
+        AllowlistConfig::SIZE; // allowlist (V6)
+    // Total: 539 bytes
+
+    /// V1 size (before price_decimals) for migration
+    pub const V1_SIZE: usize = 390;
+
+    /// V2 size (before oracle sources) for migration
+    pub const V2_SIZE: usize = 391;
+
+    /// V3 size (before insurance fund balance snapshot) for migration
+    pub const V3_SIZE: usize = 427;
+
+    /// V4 size (before open position count) for migration
+    pub const V4_SIZE: usize = 435;
+
+    /// V5 size (before allowlist) for migration
+    pub const V5_SIZE: usize = 443;
+
+    /// Default max primary/secondary oracle divergence (2%)
+    pub const DEFAULT_MAX_ORACLE_DIVERGENCE_BPS: u16 = 200;
+
+    /// Maximum supported price precision
+    pub const MAX_PRICE_DECIMALS: u8 = 12;
 
     pub const SEED: &'static [u8] = b"perp_market";
 
+    /// Seed for the market's insurance fund token account PDA
+    pub const INSURANCE_FUND_SEED: &'static [u8] = b"insurance_fund";
+
+    /// Price precision used for oracle reads
+    pub fn oracle_decimals(&self) -> u32 {
+        self.price_decimals as u32
+    }
+
+    /// Whether a secondary (fallback) oracle is configured
+    pub fn has_secondary_oracle(&self) -> bool {
+        self.secondary_oracle_feed != Pubkey::default()
+    }
+
     /// Validate leverage is within bounds
     pub fn validate_leverage(&self, leverage: u8) -> bool {
         leverage >= 1 && leverage <= self.max_leverage
@@ -140,6 +240,11 @@ impl PerpetualMarket {
         (notional_value as u128 * self.maintenance_margin_bps as u128 / 10000) as u64
     }
 
+    /// Record a position leaving the market for good (closed, liquidated or ADL'd)
+    pub fn record_position_closed(&mut self) {
+        self.open_position_count = self.open_position_count.saturating_sub(1);
+    }
+
     /// Check if market can accept more open interest
     pub fn can_increase_open_interest(&self, additional: u64, is_long: bool) -> bool {
         let current = if is_long {
@@ -24,7 +24,7 @@ pub enum PositionStatus {
 /// Confidential perpetual position account
 /// Core position data (size, entry price, collateral, PnL) is encrypted via Arcium
 /// Liquidation thresholds are now ENCRYPTED for full privacy
-/// Size: 8 (discriminator) + 553 = 561 bytes
+/// Size: 8 (discriminator) + 887 = 895 bytes
 #[account]
 pub struct ConfidentialPosition {
     /// Position owner's public key
@@ -166,11 +166,41 @@ pub struct ConfidentialPosition {
     /// in each 64-byte encrypted blob. This caused MPC decryption failures.
     /// Now we store the full 32-byte key once per position.
     pub ephemeral_pubkey: [u8; 32],
+
+    // === V9: ENCRYPTED EXIT TRIGGERS (stop-loss / take-profit) ===
+    // Encrypted under ephemeral_pubkey; checked against the mark price via
+    // MPC batch_exit_trigger_check, which only reveals whether one fired.
+
+    /// Encrypted stop-loss price (64 bytes via Arcium)
+    pub encrypted_stop_loss: [u8; 64],
+
+    /// Encrypted take-profit price (64 bytes via Arcium)
+    pub encrypted_take_profit: [u8; 64],
+
+    /// Whether a stop-loss is set (PUBLIC)
+    pub has_stop_loss: bool,
+
+    /// Whether a take-profit is set (PUBLIC)
+    pub has_take_profit: bool,
+
+    /// Pending exit trigger check request ID (all zeros if none)
+    /// Kept separate from pending_mpc_request so checks don't block other operations
+    pub pending_trigger_request: [u8; 32],
+
+    /// Set by exit_trigger_callback when a trigger fired
+    /// Lets any keeper initiate the close
+    pub exit_triggered: bool,
+
+    // === V10 fields ===
+
+    /// Unix timestamp the pending trigger check was queued
+    /// A new check can replace it once TRIGGER_CHECK_TIMEOUT_SECS have passed
+    pub pending_trigger_queued_at: i64,
 }
 
 impl ConfidentialPosition {
-    /// V8 account size - includes ephemeral pubkey for MPC decryption
-    /// Increased from 692 bytes (V7) to 724 bytes (+32 for ephemeral_pubkey)
+    /// V10 account size - includes the exit trigger check timestamp
+    /// Increased from 887 bytes (V9) to 895 bytes (+8)
     pub const SIZE: usize = 8 +   // discriminator
         32 +  // trader
         32 +  // market
@@ -208,11 +238,29 @@ impl ConfidentialPosition {
         1 +   // pending_close_full
         64 +  // pending_close_size
         // V8 fields (MPC decryption fix):
-        32;   // ephemeral_pubkey
-    // Total: 724 bytes
+        32 +  // ephemeral_pubkey
+        // V9 fields (exit triggers):
+        64 +  // encrypted_stop_loss
+        64 +  // encrypted_take_profit
+        1 +   // has_stop_loss
+        1 +   // has_take_profit
+        32 +  // pending_trigger_request
+        1 +   // exit_triggered
+        // V10 fields:
+        8;    // pending_trigger_queued_at
+    // Total: 895 bytes
+
+    /// V8 size (before exit triggers) for migration
+    pub const V8_SIZE: usize = 724;
+
+    /// V9 size (before the trigger check timestamp) for migration
+    pub const V9_SIZE: usize = 887;
 
     pub const SEED: &'static [u8] = b"position";
 
+    /// How long a trigger check may stay unanswered before it can be replaced
+    pub const TRIGGER_CHECK_TIMEOUT_SECS: i64 = 300;
+
     /// Generate a hash-based position ID from trader, market, and nonce
     /// Uses fixed-size array to avoid heap allocation
     pub fn generate_position_id(trader: &Pubkey, market: &Pubkey, nonce: &[u8; 8]) -> [u8; 16] {
@@ -430,6 +478,38 @@ impl ConfidentialPosition {
         self.clear_pending_mpc_request();
     }
 
+    // =========================================================================
+    // EXIT TRIGGER HELPERS (V9)
+    // =========================================================================
+
+    /// Check if position has a stop-loss or take-profit set
+    pub fn has_exit_triggers(&self) -> bool {
+        self.has_stop_loss || self.has_take_profit
+    }
+
+    /// Check if position has a pending exit trigger check
+    pub fn has_pending_trigger_check(&self) -> bool {
+        self.pending_trigger_request != [0u8; 32]
+    }
+
+    /// Whether a new trigger check may be queued: none is pending, or the
+    /// pending one has gone unanswered for TRIGGER_CHECK_TIMEOUT_SECS
+    pub fn can_queue_trigger_check(&self, now: i64) -> bool {
+        !self.has_pending_trigger_check()
+            || now.saturating_sub(self.pending_trigger_queued_at) >= Self::TRIGGER_CHECK_TIMEOUT_SECS
+    }
+
+    /// Remove both triggers and discard any pending or fired check
+    pub fn clear_exit_triggers(&mut self) {
+        self.encrypted_stop_loss = [0u8; 64];
+        self.encrypted_take_profit = [0u8; 64];
+        self.has_stop_loss = false;
+        self.has_take_profit = false;
+        self.pending_trigger_request = [0u8; 32];
+        self.pending_trigger_queued_at = 0;
+        self.exit_triggered = false;
+    }
+
     // =========================================================================
     // LEGACY/BROKEN POSITION DETECTION (V7)
     // =========================================================================
@@ -529,29 +609,63 @@ impl ConfidentialPosition {
     }
 }
 
+/// Position entry in a batch liquidation check
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct LiquidationBatchEntry {
+    /// Position account being checked
+    pub position: Pubkey,
+    /// Market the position belongs to
+    pub market: Pubkey,
+    /// Market mark price the position was checked at (public oracle price)
+    pub mark_price: u64,
+    /// Result from MPC (filled by callback): true = liquidatable
+    pub liquidatable: bool,
+    /// Index of the chunk (MPC computation) that checks this position
+    pub chunk: u8,
+    /// Unix timestamp when the chunk's MPC check was queued
+    pub queued_at: i64,
+}
+
+impl LiquidationBatchEntry {
+    pub const SIZE: usize = 32 + 32 + 8 + 1 + 1 + 8;
+    /// Oldest MPC result (measured from queue time) that can be recorded
+    /// or acted on
+    pub const RESULT_MAX_AGE_SECS: i64 = 300;
+
+    /// Whether a result for this entry is still recent enough to use
+    pub fn is_fresh(&self, now: i64) -> bool {
+        now.saturating_sub(self.queued_at) < Self::RESULT_MAX_AGE_SECS
+    }
+}
+
 /// Batch liquidation check request account
 /// Used to queue multiple positions for MPC liquidation eligibility check
+///
+/// Positions may span several markets; each is checked against its own
+/// market's mark price. Positions are checked in chunks of up to
+/// CHUNK_SIZE (one MPC batch_liquidation_check per chunk) and the chunk
+/// results are aggregated here.
 #[account]
 pub struct LiquidationBatchRequest {
-    /// Request ID for MPC tracking
-    pub request_id: [u8; 32],
+    /// Keeper that created the batch
+    pub requester: Pubkey,
 
-    /// Market for all positions in this batch
-    pub market: Pubkey,
+    /// Requester-chosen batch ID (PDA seed)
+    pub batch_id: u64,
 
-    /// Current mark price (public oracle price)
-    pub mark_price: u64,
+    /// Maximum number of positions (fixed at creation, sizes the account)
+    pub capacity: u8,
 
-    /// Number of positions in this batch
-    pub position_count: u8,
+    /// Positions queued so far, in chunk order
+    pub entries: Vec<LiquidationBatchEntry>,
 
-    /// Position pubkeys being checked (up to 10)
-    pub positions: [[u8; 32]; 10],
+    /// MPC request ID of each queued chunk (indexed by entry.chunk)
+    pub chunk_requests: Vec<[u8; 32]>,
 
-    /// Results from MPC (filled by callback): true = liquidatable
-    pub results: [bool; 10],
+    /// Bitmask of chunks whose MPC results have landed
+    pub chunks_completed: u8,
 
-    /// Whether MPC has returned results
+    /// Whether every queued chunk has returned results
     pub completed: bool,
 
     /// Unix timestamp when batch was created
@@ -562,18 +676,38 @@ pub struct LiquidationBatchRequest {
 }
 
 impl LiquidationBatchRequest {
-    pub const SIZE: usize = 8 +   // discriminator
-        32 +  // request_id
-        32 +  // market
-        8 +   // mark_price
-        1 +   // position_count
-        320 + // positions (32 * 10)
-        10 +  // results (10 bools)
+    pub const SEED: &'static [u8] = b"liq_batch";
+    /// Largest configurable batch
+    pub const MAX_POSITIONS: usize = 64;
+    /// Positions per MPC batch_liquidation_check (fixed by the circuit)
+    pub const CHUNK_SIZE: usize = 10;
+    /// Chunks needed for MAX_POSITIONS (bounded by the u8 completion bitmask)
+    pub const MAX_CHUNKS: usize = 7;
+
+    /// Account size for a batch of `capacity` positions
+    /// Chunk slots are sized for MAX_CHUNKS since a keeper may fill the
+    /// batch over several partial chunks.
+    pub fn space(capacity: usize) -> usize {
+        8 +   // discriminator
+        32 +  // requester
+        8 +   // batch_id
+        1 +   // capacity
+        4 + capacity * LiquidationBatchEntry::SIZE + // entries
+        4 + Self::MAX_CHUNKS * 32 + // chunk_requests
+        1 +   // chunks_completed
         1 +   // completed
         8 +   // created_at
-        1;    // bump
-    // Total: 421 bytes
+        1     // bump
+    }
 
-    pub const SEED: &'static [u8] = b"liq_batch";
-    pub const MAX_POSITIONS: usize = 10;
+    /// Index of the chunk queued with this MPC request ID
+    pub fn chunk_index(&self, request_id: &[u8; 32]) -> Option<usize> {
+        self.chunk_requests.iter().position(|r| r == request_id)
+    }
+
+    /// Whether every queued chunk has returned results
+    pub fn all_chunks_completed(&self) -> bool {
+        let queued = self.chunk_requests.len();
+        queued > 0 && self.chunks_completed.count_ones() as usize == queued
+    }
 }
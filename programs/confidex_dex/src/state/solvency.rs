@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+
+/// Proof-of-solvency configuration (singleton)
+///
+/// Holds the Sunspot verifier for the solvency circuit and the freshness
+/// policy applied by enforce_solvency_attestation.
+#[account]
+pub struct SolvencyConfig {
+    /// Sunspot verifier program for the solvency circuit
+    pub verifier: Pubkey,
+    /// Maximum age of an attestation before it is considered stale (seconds)
+    pub max_attestation_age_secs: i64,
+    /// Pause the exchange when a traded mint has no fresh attestation
+    pub auto_pause: bool,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl SolvencyConfig {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // verifier
+        8 +  // max_attestation_age_secs
+        1 +  // auto_pause
+        1;   // bump
+    // Total: 50 bytes
+
+    pub const SEED: &'static [u8] = b"solvency_config";
+
+    /// Attestations are proven per epoch (1 day), so allow 1 hour to 30 days
+    pub const MIN_ATTESTATION_AGE_SECS: i64 = 3_600;
+    pub const MAX_ATTESTATION_AGE_SECS: i64 = 30 * 86_400;
+
+    pub fn validate_max_age(max_attestation_age_secs: i64) -> bool {
+        (Self::MIN_ATTESTATION_AGE_SECS..=Self::MAX_ATTESTATION_AGE_SECS)
+            .contains(&max_attestation_age_secs)
+    }
+}
+
+/// Latest verified solvency proof for a token mint
+///
+/// Only the public statement is stored: the liabilities root users can check
+/// their balance inclusion against, the total liabilities the root commits
+/// to, the reserves commitment, the ratio and the vault balance the proven
+/// reserves were capped by. The reserve amount itself is not recorded.
+#[account]
+pub struct SolvencyAttestation {
+    /// Token mint the attestation covers
+    pub mint: Pubkey,
+    /// Merkle-sum-tree root of user liabilities
+    pub liabilities_root: [u8; 32],
+    /// Sum of user liabilities at the tree root (bound to the root by the circuit)
+    pub total_liabilities: u64,
+    /// Poseidon commitment to the reserves
+    pub reserves_commitment: [u8; 32],
+    /// Proven reserves/liabilities ratio in basis points (>= 10000)
+    pub solvency_ratio_bps: u64,
+    /// Summed vault token balance for the mint at verification
+    pub vault_balance: u64,
+    /// Unix timestamp of verification
+    pub attested_at: i64,
+    /// Epoch (unix_timestamp / 86400) the proof is bound to
+    pub epoch: u64,
+    /// Number of attestations submitted for this mint
+    pub attestation_count: u32,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl SolvencyAttestation {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // mint
+        32 + // liabilities_root
+        8 +  // total_liabilities
+        32 + // reserves_commitment
+        8 +  // solvency_ratio_bps
+        8 +  // vault_balance
+        8 +  // attested_at
+        8 +  // epoch
+        4 +  // attestation_count
+        1;   // bump
+    // Total: 149 bytes
+
+    pub const SEED: &'static [u8] = b"solvency";
+
+    /// Zero liabilities can only be claimed while the vaults hold nothing;
+    /// otherwise the deposits backing the vault balance are missing from the tree
+    pub fn validate_liabilities(total_liabilities: u64, vault_balance: u64) -> bool {
+        total_liabilities > 0 || vault_balance == 0
+    }
+
+    /// Whether the attestation is younger than the configured maximum age
+    pub fn is_fresh(&self, max_age_secs: i64, now: i64) -> bool {
+        self.attestation_count > 0 && now.saturating_sub(self.attested_at) <= max_age_secs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_AGE: i64 = 86_400;
+
+    fn attestation(attested_at: i64, attestation_count: u32) -> SolvencyAttestation {
+        SolvencyAttestation {
+            mint: Pubkey::new_unique(),
+            liabilities_root: [1u8; 32],
+            total_liabilities: 800_000,
+            reserves_commitment: [2u8; 32],
+            solvency_ratio_bps: 12_500,
+            vault_balance: 1_000_000,
+            attested_at,
+            epoch: 0,
+            attestation_count,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_fresh_until_max_age_inclusive() {
+        let attestation = attestation(1_000, 1);
+
+        assert!(attestation.is_fresh(MAX_AGE, 1_000));
+        assert!(attestation.is_fresh(MAX_AGE, 1_000 + MAX_AGE));
+        assert!(!attestation.is_fresh(MAX_AGE, 1_000 + MAX_AGE + 1));
+    }
+
+    #[test]
+    fn test_never_attested_is_stale() {
+        // A zeroed account (no submissions yet) is never fresh
+        let attestation = attestation(0, 0);
+
+        assert!(!attestation.is_fresh(MAX_AGE, 0));
+    }
+
+    #[test]
+    fn test_zero_liabilities_require_empty_vaults() {
+        assert!(SolvencyAttestation::validate_liabilities(0, 0));
+        assert!(SolvencyAttestation::validate_liabilities(800_000, 1_000_000));
+        assert!(!SolvencyAttestation::validate_liabilities(0, 1_000_000));
+    }
+
+    #[test]
+    fn test_max_age_bounds() {
+        assert!(SolvencyConfig::validate_max_age(SolvencyConfig::MIN_ATTESTATION_AGE_SECS));
+        assert!(SolvencyConfig::validate_max_age(SolvencyConfig::MAX_ATTESTATION_AGE_SECS));
+        assert!(!SolvencyConfig::validate_max_age(SolvencyConfig::MIN_ATTESTATION_AGE_SECS - 1));
+        assert!(!SolvencyConfig::validate_max_age(SolvencyConfig::MAX_ATTESTATION_AGE_SECS + 1));
+    }
+}
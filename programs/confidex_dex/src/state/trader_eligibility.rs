@@ -1,8 +1,13 @@
 use anchor_lang::prelude::*;
 
+use super::BlacklistRootHistory;
+
 /// Trader's ZK eligibility status
 /// Tracks whether a trader has been verified as not on blacklist
 /// This allows splitting ZK verification from position operations to avoid stack overflow
+///
+/// V2: Added valid_until. The attestation also survives blacklist updates for
+/// the grace window configured in BlacklistRootHistory.
 #[account]
 pub struct TraderEligibility {
     /// Owner of this eligibility record
@@ -10,7 +15,7 @@ pub struct TraderEligibility {
     /// Whether the trader's eligibility proof has been verified
     pub is_verified: bool,
     /// The blacklist root at time of verification
-    /// If exchange blacklist root changes, verification is invalidated
+    /// Stays acceptable until its successor's grace window ends
     pub verified_blacklist_root: [u8; 32],
     /// Unix timestamp when eligibility was verified (second precision)
     pub verified_at: i64,
@@ -18,6 +23,8 @@ pub struct TraderEligibility {
     pub verification_count: u32,
     /// PDA bump
     pub bump: u8,
+    /// Unix timestamp after which the attestation must be re-proven (V2)
+    pub valid_until: i64,
 }
 
 impl TraderEligibility {
@@ -27,13 +34,22 @@ impl TraderEligibility {
         32 + // verified_blacklist_root
         8 +  // verified_at
         4 +  // verification_count
-        1;   // bump
-    // Total: 86 bytes
+        1 +  // bump
+        8;   // valid_until (V2)
+    // Total: 94 bytes
+
+    /// V1 size (before valid_until) for migration
+    pub const V1_SIZE: usize = 86;
 
     pub const SEED: &'static [u8] = b"trader_eligibility";
 
-    /// Check if eligibility is still valid for the current blacklist root
-    pub fn is_valid(&self, current_blacklist_root: &[u8; 32]) -> bool {
-        self.is_verified && self.verified_blacklist_root == *current_blacklist_root
+    /// Check if eligibility is still valid
+    ///
+    /// Requires an unexpired attestation whose root is either the current
+    /// blacklist root or a recently superseded one still within its grace window.
+    pub fn is_valid(&self, history: &BlacklistRootHistory, now: i64) -> bool {
+        self.is_verified
+            && now < self.valid_until
+            && history.is_root_accepted(&self.verified_blacklist_root, now)
     }
 }
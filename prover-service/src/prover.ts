@@ -5,6 +5,7 @@ import { join, dirname } from 'path';
 import { randomBytes, createHash } from 'crypto';
 import { existsSync } from 'fs';
 import { fileURLToPath } from 'url';
+import bs58 from 'bs58';
 
 const execAsync = promisify(exec);
 
@@ -16,6 +17,13 @@ const __dirname = dirname(__filename);
 // Layout: A(64) + B(128) + C(64) + num_commitments(4) + commitment_pok(64) = 324 bytes
 export const PROOF_SIZE = 324;
 
+// Eligibility epoch length - must match ELIGIBILITY_EPOCH_SECS in the DEX verifier
+export const ELIGIBILITY_EPOCH_SECS = 86_400;
+
+export function currentEligibilityEpoch(nowMs: number = Date.now()): number {
+  return Math.floor(nowMs / 1000 / ELIGIBILITY_EPOCH_SECS);
+}
+
 // Strict proof mode
 const IS_PRODUCTION = process.env.NODE_ENV === 'production';
 const STRICT_PROOFS_ENV = process.env.STRICT_PROOFS;
@@ -54,12 +62,12 @@ class ProofLRUCache {
     this.ttlMs = ttlMs;
   }
 
-  private makeKey(address: string, blacklistRoot: string): string {
-    return `${address}:${blacklistRoot}`;
+  private makeKey(address: string, blacklistRoot: string, epoch: number): string {
+    return `${address}:${blacklistRoot}:${epoch}`;
   }
 
-  get(address: string, blacklistRoot: string): Buffer | null {
-    const key = this.makeKey(address, blacklistRoot);
+  get(address: string, blacklistRoot: string, epoch: number = currentEligibilityEpoch()): Buffer | null {
+    const key = this.makeKey(address, blacklistRoot, epoch);
     const entry = this.cache.get(key);
 
     if (!entry) return null;
@@ -76,8 +84,8 @@ class ProofLRUCache {
     return entry.proof;
   }
 
-  set(address: string, blacklistRoot: string, proof: Buffer): void {
-    const key = this.makeKey(address, blacklistRoot);
+  set(address: string, blacklistRoot: string, proof: Buffer, epoch: number = currentEligibilityEpoch()): void {
+    const key = this.makeKey(address, blacklistRoot, epoch);
 
     if (this.cache.size >= this.maxSize) {
       const oldestKey = this.cache.keys().next().value;
@@ -118,8 +126,11 @@ export interface ProofInputs {
 export async function generateEligibilityProof(inputs: ProofInputs): Promise<Buffer> {
   const startTime = Date.now();
 
+  // Proofs are bound to the signer and epoch
+  const epoch = currentEligibilityEpoch();
+
   // Check cache first
-  const cached = proofCache.get(inputs.address, inputs.blacklistRoot);
+  const cached = proofCache.get(inputs.address, inputs.blacklistRoot, epoch);
   if (cached) {
     console.log(`[Prover] Cache hit for ${inputs.address.slice(0, 8)}...`);
     return cached;
@@ -139,6 +150,8 @@ export async function generateEligibilityProof(inputs: ProofInputs): Promise<Buf
     // Write Prover.toml
     const proverToml = generateProverToml({
       blacklistRoot: inputs.blacklistRoot,
+      address: inputs.address,
+      epoch,
       merklePath: inputs.merklePath,
       pathIndices: inputs.pathIndices,
     });
@@ -245,7 +258,7 @@ export async function generateEligibilityProof(inputs: ProofInputs): Promise<Buf
     }
 
     // Cache
-    proofCache.set(inputs.address, inputs.blacklistRoot, rawProof);
+    proofCache.set(inputs.address, inputs.blacklistRoot, rawProof, epoch);
 
     console.log(`[Prover] Generated proof in ${Date.now() - startTime}ms`);
     return rawProof;
@@ -268,9 +281,16 @@ export async function generateEligibilityProof(inputs: ProofInputs): Promise<Buf
 
 function generateProverToml(inputs: {
   blacklistRoot: string;
+  address: string;
+  epoch: number;
   merklePath: string[];
   pathIndices: number[];
 }): string {
+  // Pubkey split into two 16-byte field elements, matching the on-chain verifier
+  const addressBytes = Buffer.from(bs58.decode(inputs.address));
+  const addressHi = `0x${addressBytes.subarray(0, 16).toString('hex')}`;
+  const addressLo = `0x${addressBytes.subarray(16, 32).toString('hex')}`;
+
   const pathArray = inputs.merklePath
     .map((p) => `    "${p}"`)
     .join(',\n');
@@ -281,6 +301,9 @@ function generateProverToml(inputs: {
 
   return `# Auto-generated prover inputs
 blacklist_root = "${inputs.blacklistRoot}"
+address_hi = "${addressHi}"
+address_lo = "${addressLo}"
+epoch = "${inputs.epoch}"
 merkle_path = [
 ${pathArray}
 ]